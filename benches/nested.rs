@@ -0,0 +1,143 @@
+//! Benchmarks for encoding deeply nested messages.
+//!
+//! The types and codecs are generated by `protobuf_codec::codegen` from `testdata/nested.proto`.
+//! In the `tree` group, `pre_encode` is a hand-written encoder that embeds the children by `PreEncode`
+//! which copies the encoded bytes at every level, and `generated` is the generated `NodeEncoder`
+//! that computes the sizes of all the subtrees once before encoding.
+use bytecodec::combinator::PreEncode;
+use bytecodec::{ByteCount, Encode, EncodeExt, Eos, Result, SizedEncode};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use protobuf_codec::field::num::{F1, F2};
+use protobuf_codec::field::{FieldEncoder, Fields, MaybeDefault, MessageFieldEncoder, Repeated};
+use protobuf_codec::message::{MessageEncode, MessageEncoder};
+use protobuf_codec::scalar::StringEncoder;
+use std::hint::black_box;
+
+#[allow(dead_code)]
+mod generated {
+    include!("testdata/nested.rs");
+}
+use self::generated::nested::{Chain, ChainEncoder, Node, NodeEncoder};
+
+// Makes a tree of the given depth in which every node has `width` children
+fn tree(depth: usize, width: usize) -> Node {
+    Node {
+        name: format!("node-{}", depth),
+        children: if depth > 1 {
+            (0..width).map(|_| tree(depth - 1, width)).collect()
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+fn chain(depth: usize) -> Chain {
+    Chain {
+        name: format!("chain-{}", depth),
+        next: if depth > 1 {
+            Some(Box::new(chain(depth - 1)))
+        } else {
+            None
+        },
+    }
+}
+
+// The inner encoder is allocated lazily since the encoder is recursive
+#[derive(Debug, Default)]
+#[allow(clippy::type_complexity)]
+struct PreEncodeNodeEncoder(
+    Option<
+        Box<
+            PreEncode<
+                MessageEncoder<
+                    Fields<(
+                        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+                        Repeated<MessageFieldEncoder<F2, PreEncodeNodeEncoder>, Vec<Node>>,
+                    )>,
+                >,
+            >,
+        >,
+    >,
+);
+impl Encode for PreEncodeNodeEncoder {
+    type Item = Node;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        match self.0 {
+            Some(ref mut inner) => inner.encode(buf, eos),
+            None => Ok(0),
+        }
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        self.0
+            .get_or_insert_with(Default::default)
+            .start_encoding((item.name, item.children))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.as_ref().is_none_or(|inner| inner.is_idle())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        ByteCount::Finite(self.exact_requiring_bytes())
+    }
+}
+impl SizedEncode for PreEncodeNodeEncoder {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0
+            .as_ref()
+            .map_or(0, |inner| inner.exact_requiring_bytes())
+    }
+}
+impl MessageEncode for PreEncodeNodeEncoder {}
+
+fn bench_tree(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tree");
+    for &(depth, width) in &[(30, 1), (300, 1), (10, 3)] {
+        let node = tree(depth, width);
+        let id = format!("depth={},width={}", depth, width);
+
+        let mut pre_encode = PreEncodeNodeEncoder::default();
+        let mut generated = NodeEncoder::default();
+        assert_eq!(
+            pre_encode.encode_into_bytes(node.clone()).unwrap(),
+            generated.encode_into_bytes(node.clone()).unwrap()
+        );
+
+        group.bench_with_input(BenchmarkId::new("pre_encode", &id), &node, |b, node| {
+            b.iter_batched(
+                || node.clone(),
+                |node| black_box(pre_encode.encode_into_bytes(node).unwrap()),
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("generated", &id), &node, |b, node| {
+            b.iter_batched(
+                || node.clone(),
+                |node| black_box(generated.encode_into_bytes(node).unwrap()),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chain");
+    for &depth in &[30, 300, 3000] {
+        let chain = chain(depth);
+        let mut encoder = ChainEncoder::default();
+        group.bench_with_input(BenchmarkId::new("generated", depth), &chain, |b, chain| {
+            b.iter_batched(
+                || chain.clone(),
+                |chain| black_box(encoder.encode_into_bytes(chain).unwrap()),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_tree, bench_chain);
+criterion_main!(benches);
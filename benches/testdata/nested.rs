@@ -0,0 +1,203 @@
+// This file is generated by `protobuf_codec::codegen`. Do not edit.
+
+/// `nested` package.
+pub mod nested {
+    /// `Node` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Node {
+        /// `string name = 1;`
+        pub name: String,
+        /// `repeated Node children = 2;`
+        pub children: Vec<Node>,
+    }
+
+    /// Decoder for `Node` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct NodeDecoder {
+        inner: ::protobuf_codec::message::MessageDecoder<
+            ::protobuf_codec::field::Fields<(
+                ::protobuf_codec::field::MaybeDefault<::protobuf_codec::field::FieldDecoder<::protobuf_codec::field::num::F1, ::protobuf_codec::scalar::StringDecoder>>,
+                ::protobuf_codec::field::Repeated<::protobuf_codec::field::MessageFieldDecoder<::protobuf_codec::field::num::F2, ::protobuf_codec::message::BoxedMessageDecoder<NodeDecoder>>, Vec<Node>>,
+            )>
+        >,
+    }
+    impl NodeDecoder {
+        /// Makes a new `NodeDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for NodeDecoder {
+        type Item = Node;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (f0, f1) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(Node {
+                name: f0,
+                children: f1,
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl ::protobuf_codec::message::MessageDecode for NodeDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            ::protobuf_codec::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `Node` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct NodeEncoder {
+        inner: ::protobuf_codec::message::MessageEncoder<
+            ::protobuf_codec::field::Fields<(
+                ::protobuf_codec::field::MaybeDefault<::protobuf_codec::field::FieldEncoder<::protobuf_codec::field::num::F1, ::protobuf_codec::scalar::StringEncoder>>,
+                ::protobuf_codec::field::SizedRepeated<::protobuf_codec::field::MessageFieldEncoder<::protobuf_codec::field::num::F2, ::protobuf_codec::message::BoxedMessageEncoder<NodeEncoder>>, Vec<Node>>,
+            )>
+        >,
+    }
+    impl NodeEncoder {
+        /// Makes a new `NodeEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for NodeEncoder {
+        type Item = Node;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let item = (item.name, item.children);
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for NodeEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl ::protobuf_codec::message::MessageEncode for NodeEncoder {}
+
+    /// `Chain` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Chain {
+        /// `string name = 1;`
+        pub name: String,
+        /// `Chain next = 2;`
+        pub next: Option<Box<Chain>>,
+    }
+
+    /// Decoder for `Chain` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct ChainDecoder {
+        inner: ::protobuf_codec::message::MessageDecoder<
+            ::protobuf_codec::field::Fields<(
+                ::protobuf_codec::field::MaybeDefault<::protobuf_codec::field::FieldDecoder<::protobuf_codec::field::num::F1, ::protobuf_codec::scalar::StringDecoder>>,
+                ::protobuf_codec::field::Optional<::protobuf_codec::field::MessageFieldDecoder<::protobuf_codec::field::num::F2, ::protobuf_codec::message::BoxedMessageDecoder<ChainDecoder>>>,
+            )>
+        >,
+    }
+    impl ChainDecoder {
+        /// Makes a new `ChainDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for ChainDecoder {
+        type Item = Chain;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (f0, f1) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(Chain {
+                name: f0,
+                next: f1.map(Box::new),
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl ::protobuf_codec::message::MessageDecode for ChainDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            ::protobuf_codec::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `Chain` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct ChainEncoder {
+        inner: ::protobuf_codec::message::MessageEncoder<
+            ::protobuf_codec::field::Fields<(
+                ::protobuf_codec::field::MaybeDefault<::protobuf_codec::field::FieldEncoder<::protobuf_codec::field::num::F1, ::protobuf_codec::scalar::StringEncoder>>,
+                ::protobuf_codec::field::Optional<::protobuf_codec::field::MessageFieldEncoder<::protobuf_codec::field::num::F2, ::protobuf_codec::message::BoxedMessageEncoder<ChainEncoder>>>,
+            )>
+        >,
+    }
+    impl ChainEncoder {
+        /// Makes a new `ChainEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for ChainEncoder {
+        type Item = Chain;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let next = item.next.map(|v| *v);
+            let item = (item.name, next);
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for ChainEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl ::protobuf_codec::message::MessageEncode for ChainEncoder {}
+}
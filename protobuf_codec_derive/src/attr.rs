@@ -0,0 +1,246 @@
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::{Attribute, Error, Lit, LitStr, Meta, NestedMeta, Path, Result};
+
+const MAX_FIELD_NUM: u32 = (1 << 29) - 1;
+
+/// Attributes of a struct or an enum.
+pub struct TypeAttrs {
+    /// Path of `protobuf_codec` crate.
+    pub crate_path: Path,
+}
+impl TypeAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut crate_path = syn::parse_str("::protobuf_codec")?;
+        for meta in protobuf_metas(attrs)? {
+            match meta {
+                NestedMeta::Meta(Meta::NameValue(ref m)) if m.path.is_ident("crate") => {
+                    crate_path = lit_str(&m.lit)?.parse()?;
+                }
+                _ => return Err(Error::new(meta.span(), "Unknown attribute")),
+            }
+        }
+        Ok(TypeAttrs { crate_path })
+    }
+}
+
+/// Label of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+    /// No label (i.e., a proto3 singular field).
+    Implicit,
+    Optional,
+    Required,
+    Repeated,
+    Packed,
+}
+
+/// Type of a field value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// Scalar type.
+    ///
+    /// The string is the prefix of the names of the corresponding decoder and encoder (e.g., `Int32`).
+    Scalar(&'static str),
+    Enum,
+    Message,
+}
+impl ValueType {
+    fn parse(s: &LitStr) -> Result<Self> {
+        Ok(match s.value().as_str() {
+            "double" => ValueType::Scalar("Double"),
+            "float" => ValueType::Scalar("Float"),
+            "int32" => ValueType::Scalar("Int32"),
+            "int64" => ValueType::Scalar("Int64"),
+            "uint32" => ValueType::Scalar("Uint32"),
+            "uint64" => ValueType::Scalar("Uint64"),
+            "sint32" => ValueType::Scalar("Sint32"),
+            "sint64" => ValueType::Scalar("Sint64"),
+            "fixed32" => ValueType::Scalar("Fixed32"),
+            "fixed64" => ValueType::Scalar("Fixed64"),
+            "sfixed32" => ValueType::Scalar("Sfixed32"),
+            "sfixed64" => ValueType::Scalar("Sfixed64"),
+            "bool" => ValueType::Scalar("Bool"),
+            "string" => ValueType::Scalar("String"),
+            "bytes" => ValueType::Scalar("Bytes"),
+            "enum" => ValueType::Enum,
+            "message" => ValueType::Message,
+            _ => return Err(Error::new(s.span(), "Unknown field type")),
+        })
+    }
+
+    pub fn is_numeric(self) -> bool {
+        match self {
+            ValueType::Scalar(name) => name != "String" && name != "Bytes",
+            ValueType::Enum => true,
+            ValueType::Message => false,
+        }
+    }
+
+    pub fn is_map_key(self) -> bool {
+        match self {
+            ValueType::Scalar(name) => name != "Double" && name != "Float" && name != "Bytes",
+            _ => false,
+        }
+    }
+}
+
+/// Attributes of a struct field or an enum variant.
+#[derive(Debug)]
+pub struct FieldAttrs {
+    pub num: Option<u32>,
+    pub ty: Option<ValueType>,
+    pub label: Label,
+    pub key: Option<ValueType>,
+    pub value: Option<ValueType>,
+    pub oneof: bool,
+    pub unknown_fields: bool,
+}
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute], span: Span) -> Result<Self> {
+        let mut this = FieldAttrs {
+            num: None,
+            ty: None,
+            label: Label::Implicit,
+            key: None,
+            value: None,
+            oneof: false,
+            unknown_fields: false,
+        };
+        for meta in protobuf_metas(attrs)? {
+            match meta {
+                NestedMeta::Meta(Meta::NameValue(ref m)) => {
+                    let name = m.path.get_ident().map(|x| x.to_string());
+                    match name.as_deref() {
+                        Some("num") => this.num = Some(field_num(&m.lit)?),
+                        Some("ty") => this.ty = Some(ValueType::parse(lit_str(&m.lit)?)?),
+                        Some("key") => this.key = Some(ValueType::parse(lit_str(&m.lit)?)?),
+                        Some("value") => this.value = Some(ValueType::parse(lit_str(&m.lit)?)?),
+                        _ => return Err(Error::new(meta.span(), "Unknown attribute")),
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(ref p)) => {
+                    let name = p.get_ident().map(|x| x.to_string());
+                    let label = match name.as_deref() {
+                        Some("optional") => Label::Optional,
+                        Some("required") => Label::Required,
+                        Some("repeated") => Label::Repeated,
+                        Some("packed") => Label::Packed,
+                        Some("oneof") => {
+                            this.oneof = true;
+                            continue;
+                        }
+                        Some("unknown_fields") => {
+                            this.unknown_fields = true;
+                            continue;
+                        }
+                        _ => return Err(Error::new(meta.span(), "Unknown attribute")),
+                    };
+                    if this.label != Label::Implicit && this.label != label {
+                        return Err(Error::new(meta.span(), "Conflicting labels"));
+                    }
+                    this.label = label;
+                }
+                _ => return Err(Error::new(meta.span(), "Unknown attribute")),
+            }
+        }
+        validate(&this, span)?;
+        Ok(this)
+    }
+
+    pub fn is_map(&self) -> bool {
+        self.key.is_some() || self.value.is_some()
+    }
+}
+
+fn validate(attrs: &FieldAttrs, span: Span) -> Result<()> {
+    if attrs.unknown_fields {
+        if attrs.oneof
+            || attrs.num.is_some()
+            || attrs.ty.is_some()
+            || attrs.is_map()
+            || attrs.label != Label::Implicit
+        {
+            return Err(Error::new(
+                span,
+                "`unknown_fields` fields cannot have other attributes",
+            ));
+        }
+        return Ok(());
+    }
+    if attrs.oneof {
+        if attrs.num.is_some() || attrs.ty.is_some() || attrs.is_map() {
+            return Err(Error::new(
+                span,
+                "`oneof` fields cannot have `num`, `ty`, `key` and `value` attributes",
+            ));
+        }
+        if attrs.label != Label::Implicit {
+            return Err(Error::new(span, "`oneof` fields cannot have labels"));
+        }
+        return Ok(());
+    }
+
+    if attrs.num.is_none() {
+        return Err(Error::new(span, "`num` attribute is required"));
+    }
+    if attrs.is_map() {
+        match (attrs.key, attrs.value) {
+            (Some(key), Some(_)) if key.is_map_key() => {}
+            (Some(_), Some(_)) => return Err(Error::new(span, "Invalid map key type")),
+            _ => {
+                return Err(Error::new(
+                    span,
+                    "Map fields must have both `key` and `value` attributes",
+                ))
+            }
+        }
+        if attrs.ty.is_some() || attrs.label != Label::Implicit {
+            return Err(Error::new(
+                span,
+                "Map fields cannot have `ty` attribute and labels",
+            ));
+        }
+        return Ok(());
+    }
+
+    match attrs.ty {
+        None => return Err(Error::new(span, "`ty` attribute is required")),
+        Some(ty) if attrs.label == Label::Packed && !ty.is_numeric() => {
+            return Err(Error::new(span, "Only numeric fields can be packed"));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn protobuf_metas(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut metas = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("protobuf")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            meta => return Err(Error::new(meta.span(), "Expected `#[protobuf(...)]`")),
+        }
+    }
+    Ok(metas)
+}
+
+fn lit_str(lit: &Lit) -> Result<&LitStr> {
+    if let Lit::Str(ref s) = *lit {
+        Ok(s)
+    } else {
+        Err(Error::new(lit.span(), "Expected a string literal"))
+    }
+}
+
+fn field_num(lit: &Lit) -> Result<u32> {
+    let n = if let Lit::Int(ref n) = *lit {
+        n.base10_parse::<u32>()?
+    } else {
+        return Err(Error::new(lit.span(), "Expected an integer literal"));
+    };
+    if n == 0 || n > MAX_FIELD_NUM || (19_000..20_000).contains(&n) {
+        return Err(Error::new(lit.span(), "Invalid field number"));
+    }
+    Ok(n)
+}
@@ -0,0 +1,268 @@
+use crate::attr::{FieldAttrs, Label, ValueType};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Error, GenericArgument, Path, PathArguments, Result, Type};
+
+pub const MAX_TUPLE_LEN: usize = 32;
+
+/// Decoder and encoder types of a field.
+///
+/// The encoder always implements `SizedEncode`.
+#[derive(Debug)]
+pub struct FieldCodec {
+    pub decoder: TokenStream,
+    pub encoder: TokenStream,
+}
+
+/// Builder of the types of field decoders and encoders.
+pub struct CodecBuilder<'a> {
+    rt: &'a Path,
+}
+impl<'a> CodecBuilder<'a> {
+    pub fn new(rt: &'a Path) -> Self {
+        CodecBuilder { rt }
+    }
+
+    /// Makes the codec of a struct field.
+    pub fn field(&self, attrs: &FieldAttrs, ty: &Type) -> Result<FieldCodec> {
+        let rt = self.rt;
+        if attrs.unknown_fields {
+            return Ok(FieldCodec {
+                decoder: quote! { #rt::field::UnknownFieldsDecoder },
+                encoder: quote! { #rt::field::UnknownFieldsEncoder },
+            });
+        }
+        if attrs.oneof {
+            return Ok(if let Some(inner) = option_inner(ty) {
+                let decoder = codec_path(inner, "Decoder")?;
+                let encoder = codec_path(inner, "Encoder")?;
+                FieldCodec {
+                    decoder: quote! { #rt::field::Optional<#decoder> },
+                    encoder: quote! { #rt::field::Optional<#encoder> },
+                }
+            } else {
+                FieldCodec {
+                    decoder: codec_path(ty, "Decoder")?,
+                    encoder: codec_path(ty, "Encoder")?,
+                }
+            });
+        }
+
+        let num = self.field_num(attrs.num.expect("Never fails"));
+        if let (Some(key), Some(value)) = (attrs.key, attrs.value) {
+            let (key_item, value_item) = map_types(ty)?;
+            let key_decoder = self.value_decoder(key, key_item)?;
+            let key_encoder = self.value_encoder(key, key_item)?;
+            let value_decoder = self.value_decoder(value, value_item)?;
+            let value_encoder = self.value_encoder(value, value_item)?;
+            let (decoder, encoder) = if value == ValueType::Message {
+                (
+                    quote! { MapMessageFieldDecoder },
+                    quote! { SizedMapMessageFieldEncoder },
+                )
+            } else {
+                (quote! { MapFieldDecoder }, quote! { SizedMapFieldEncoder })
+            };
+            return Ok(FieldCodec {
+                decoder: quote! {
+                    #rt::field::#decoder<#num, #key_decoder, #value_decoder, #ty>
+                },
+                encoder: quote! {
+                    #rt::field::#encoder<#num, #key_encoder, #value_encoder, #ty>
+                },
+            });
+        }
+
+        let value = attrs.ty.expect("Never fails");
+        let item = match attrs.label {
+            Label::Optional | Label::Repeated | Label::Packed => element_type(ty)?,
+            Label::Implicit if value == ValueType::Message => element_type(ty)?,
+            _ => ty,
+        };
+        if attrs.label == Label::Packed {
+            let decoder = self.value_decoder(value, item)?;
+            let encoder = self.value_encoder(value, item)?;
+            return Ok(FieldCodec {
+                decoder: quote! { #rt::field::PackedFieldDecoder<#num, #decoder, #ty> },
+                encoder: quote! { #rt::field::PackedFieldEncoder<#num, #encoder, #ty> },
+            });
+        }
+
+        let (decoder, encoder) = self.single_field(num, value, item)?;
+        Ok(match attrs.label {
+            Label::Required => FieldCodec { decoder, encoder },
+            Label::Repeated => FieldCodec {
+                decoder: quote! { #rt::field::Repeated<#decoder, #ty> },
+                encoder: quote! { #rt::field::SizedRepeated<#encoder, #ty> },
+            },
+            Label::Optional => FieldCodec {
+                decoder: quote! { #rt::field::Optional<#decoder> },
+                encoder: quote! { #rt::field::Optional<#encoder> },
+            },
+            Label::Implicit if value == ValueType::Message => FieldCodec {
+                decoder: quote! { #rt::field::Optional<#decoder> },
+                encoder: quote! { #rt::field::Optional<#encoder> },
+            },
+            _ => FieldCodec {
+                decoder: quote! { #rt::field::MaybeDefault<#decoder> },
+                encoder: quote! { #rt::field::MaybeDefault<#encoder> },
+            },
+        })
+    }
+
+    /// Makes the codec of an enum variant (i.e., a oneof field).
+    pub fn variant(&self, attrs: &FieldAttrs, ty: &Type, span: Span) -> Result<FieldCodec> {
+        if attrs.oneof || attrs.unknown_fields || attrs.is_map() || attrs.label != Label::Implicit {
+            return Err(Error::new(
+                span,
+                "Oneof fields can only have `num` and `ty` attributes",
+            ));
+        }
+        let num = self.field_num(attrs.num.expect("Never fails"));
+        let (decoder, encoder) = self.single_field(num, attrs.ty.expect("Never fails"), ty)?;
+        Ok(FieldCodec { decoder, encoder })
+    }
+
+    fn single_field(
+        &self,
+        num: TokenStream,
+        value: ValueType,
+        item: &Type,
+    ) -> Result<(TokenStream, TokenStream)> {
+        let rt = self.rt;
+        let decoder = self.value_decoder(value, item)?;
+        let encoder = self.value_encoder(value, item)?;
+        Ok(if value == ValueType::Message {
+            (
+                quote! { #rt::field::MessageFieldDecoder<#num, #decoder> },
+                quote! { #rt::field::MessageFieldEncoder<#num, #encoder> },
+            )
+        } else {
+            (
+                quote! { #rt::field::FieldDecoder<#num, #decoder> },
+                quote! { #rt::field::FieldEncoder<#num, #encoder> },
+            )
+        })
+    }
+
+    fn value_decoder(&self, value: ValueType, item: &Type) -> Result<TokenStream> {
+        let rt = self.rt;
+        Ok(match value {
+            ValueType::Scalar(name) => {
+                let decoder = format_ident!("{}Decoder", name);
+                quote! { #rt::scalar::#decoder }
+            }
+            ValueType::Enum => quote! { #rt::scalar::EnumDecoder<#item> },
+            ValueType::Message => codec_path(item, "Decoder")?,
+        })
+    }
+
+    // The encoders of embedded messages are used as they are,
+    // because the encoders derived by `ProtobufMessage` always implement `SizedEncode`.
+    fn value_encoder(&self, value: ValueType, item: &Type) -> Result<TokenStream> {
+        let rt = self.rt;
+        Ok(match value {
+            ValueType::Scalar(name) => {
+                let encoder = format_ident!("{}Encoder", name);
+                quote! { #rt::scalar::#encoder }
+            }
+            ValueType::Enum => quote! { #rt::scalar::EnumEncoder<#item> },
+            ValueType::Message => codec_path(item, "Encoder")?,
+        })
+    }
+
+    fn field_num(&self, n: u32) -> TokenStream {
+        let rt = self.rt;
+        if n <= 16 {
+            let num = format_ident!("F{}", n);
+            quote! { #rt::field::num::#num }
+        } else {
+            quote! { #rt::field::num::Fnum<#n> }
+        }
+    }
+}
+
+/// Makes a (possibly nested) tuple which has at most `MAX_TUPLE_LEN` elements.
+///
+/// `f` is applied to each nested tuple.
+pub fn nest(items: &[TokenStream], f: &dyn Fn(TokenStream) -> TokenStream) -> TokenStream {
+    if items.len() <= MAX_TUPLE_LEN {
+        f(quote! { (#(#items,)*) })
+    } else {
+        let head = &items[..MAX_TUPLE_LEN - 1];
+        let rest = nest(&items[MAX_TUPLE_LEN - 1..], f);
+        f(quote! { (#(#head,)* #rest,) })
+    }
+}
+
+// `Foo` => `FooDecoder`, `foo::Bar` => `foo::BarDecoder`
+fn codec_path(ty: &Type, suffix: &str) -> Result<TokenStream> {
+    if let Type::Path(ref p) = *ty {
+        if p.qself.is_none() {
+            let mut path = p.path.clone();
+            let last = path.segments.last_mut().expect("Never fails");
+            if last.arguments.is_empty() {
+                last.ident = format_ident!("{}{}", last.ident, suffix);
+                return Ok(quote! { #path });
+            }
+        }
+    }
+    Err(Error::new(
+        ty.span(),
+        "Expected a non-generic type that derives `ProtobufMessage` or `ProtobufOneof`",
+    ))
+}
+
+fn type_args(ty: &Type) -> Vec<&Type> {
+    if let Type::Path(ref p) = *ty {
+        if let Some(last) = p.path.segments.last() {
+            if let PathArguments::AngleBracketed(ref args) = last.arguments {
+                return args
+                    .args
+                    .iter()
+                    .filter_map(|a| {
+                        if let GenericArgument::Type(ref t) = *a {
+                            Some(t)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+            }
+        }
+    }
+    Vec::new()
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    if let Type::Path(ref p) = *ty {
+        let last = p.path.segments.last()?;
+        if last.ident == "Option" {
+            return type_args(ty).last().cloned();
+        }
+    }
+    None
+}
+
+// `Option<T>` => `T`, `Vec<T>` => `T`
+fn element_type(ty: &Type) -> Result<&Type> {
+    type_args(ty).last().cloned().ok_or_else(|| {
+        Error::new(
+            ty.span(),
+            "Expected a generic type such as `Option<T>` or `Vec<T>`",
+        )
+    })
+}
+
+// `HashMap<K, V>` => `(K, V)`
+fn map_types(ty: &Type) -> Result<(&Type, &Type)> {
+    let args = type_args(ty);
+    if args.len() < 2 {
+        return Err(Error::new(
+            ty.span(),
+            "Expected a map type such as `HashMap<K, V>`",
+        ));
+    }
+    Ok((args[args.len() - 2], args[args.len() - 1]))
+}
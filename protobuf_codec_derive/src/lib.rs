@@ -0,0 +1,156 @@
+//! This crate provides `ProtobufMessage` and `ProtobufOneof` derive macros.
+//!
+//! The macros generate the decoder and encoder types for a struct or an enum
+//! from the `#[protobuf(...)]` attributes of the fields.
+//! The generated codecs are built from the components of [protobuf_codec] crate
+//! and their `Item` is the annotated type itself.
+//!
+//! The generated code depends on `protobuf_codec` and `bytecodec` crates.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! # #[macro_use]
+//! # extern crate protobuf_codec_derive;
+//! use bytecodec::EncodeExt;
+//! use bytecodec::io::{IoDecodeExt, IoEncodeExt};
+//!
+//! // syntax = "proto3";
+//! //
+//! // message SearchRequest {
+//! //   string query = 1;
+//! //   int32 page_number = 2;
+//! //   repeated sint32 samples = 3;
+//! //   oneof source {
+//! //     string url = 4;
+//! //     Project project = 5;
+//! //   }
+//! // }
+//! //
+//! // message Project {
+//! //   string name = 1;
+//! // }
+//! #[derive(Debug, Default, PartialEq, ProtobufMessage)]
+//! struct SearchRequest {
+//!     #[protobuf(num = 1, ty = "string")]
+//!     query: String,
+//!
+//!     #[protobuf(num = 2, ty = "int32")]
+//!     page_number: i32,
+//!
+//!     #[protobuf(num = 3, ty = "sint32", packed)]
+//!     samples: Vec<i32>,
+//!
+//!     #[protobuf(oneof)]
+//!     source: Option<Source>,
+//! }
+//!
+//! #[derive(Debug, PartialEq, ProtobufOneof)]
+//! enum Source {
+//!     #[protobuf(num = 4, ty = "string")]
+//!     Url(String),
+//!
+//!     #[protobuf(num = 5, ty = "message")]
+//!     Project(Project),
+//! }
+//!
+//! #[derive(Debug, Default, PartialEq, ProtobufMessage)]
+//! struct Project {
+//!     #[protobuf(num = 1, ty = "string")]
+//!     name: String,
+//! }
+//!
+//! # fn main() {
+//! let request = SearchRequest {
+//!     query: "foo".to_owned(),
+//!     page_number: 3,
+//!     samples: vec![1, -1],
+//!     source: Some(Source::Url("bar".to_owned())),
+//! };
+//!
+//! let mut buf = Vec::new();
+//! let mut encoder = SearchRequestEncoder::with_item(request).unwrap();
+//! encoder.encode_all(&mut buf).unwrap();
+//! assert_eq!(buf, [10, 3, 102, 111, 111, 16, 3, 26, 2, 2, 1, 34, 3, 98, 97, 114]);
+//!
+//! let mut decoder = SearchRequestDecoder::new();
+//! let request = decoder.decode_exact(&buf[..]).unwrap();
+//! assert_eq!(request.source, Some(Source::Url("bar".to_owned())));
+//! # }
+//! ```
+//!
+//! # Attributes
+//!
+//! Struct fields:
+//!
+//! | Attribute                                   | Field                  | Rust type      |
+//! |---------------------------------------------|------------------------|----------------|
+//! | `num = 1, ty = "int32"`                     | singular (proto3)      | `T`            |
+//! | `num = 1, ty = "int32", optional`           | `optional`             | `Option<T>`    |
+//! | `num = 1, ty = "int32", required`           | `required` (proto2)    | `T`            |
+//! | `num = 1, ty = "int32", repeated`           | `repeated`             | `Vec<T>`       |
+//! | `num = 1, ty = "int32", packed`             | `repeated` (packed)    | `Vec<T>`       |
+//! | `num = 1, ty = "message"`                   | singular message       | `Option<T>`    |
+//! | `num = 1, key = "string", value = "int32"`  | `map<string, int32>`   | `HashMap<K, V>`|
+//! | `oneof`                                     | `oneof`                | `Option<T>`    |
+//! | `unknown_fields`                            | unknown fields         | `UnknownFields`|
+//!
+//! `ty`, `key` and `value` take the name of a scalar type (e.g., `"sint64"` or `"bytes"`),
+//! `"enum"` or `"message"`.
+//! The types of enum fields must implement `protobuf_codec::scalar::Enum`, and
+//! the types of message fields must derive `ProtobufMessage`
+//! (i.e., the codecs of a message type `foo::Bar` are `foo::BarDecoder` and `foo::BarEncoder`).
+//!
+//! `repeated`, `packed` and map fields can be any collection type `C` that satisfies
+//! `C: Default + Extend<T> + IntoIterator<Item = T>`.
+//!
+//! The types of `oneof` fields must derive `ProtobufOneof`.
+//! Each variant of the enum must have exactly one unnamed field and the `num` and `ty` attributes.
+//! If the type of a `oneof` field is not `Option<T>`, the field is regarded as required.
+//!
+//! An `unknown_fields` field preserves the fields that are not defined in the struct
+//! so that the encoder can re-emit them byte-exactly.
+//! Its type must be `protobuf_codec::field::UnknownFields` and it must be the last field of the struct.
+//!
+//! If the name of `protobuf_codec` crate is not `protobuf_codec`,
+//! specify its path with `#[protobuf(crate = "path::to::protobuf_codec")]`.
+//!
+//! # Limitations
+//!
+//! - Generic types are not supported
+//!
+//! [protobuf_codec]: https://docs.rs/protobuf_codec
+#![recursion_limit = "128"]
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attr;
+mod codec;
+mod message;
+mod oneof;
+
+/// Derives the decoder and encoder for a message.
+///
+/// See [the crate documentation](./index.html) for details.
+#[proc_macro_derive(ProtobufMessage, attributes(protobuf))]
+pub fn derive_protobuf_message(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    message::expand(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives the decoder and encoder for a oneof field.
+///
+/// See [the crate documentation](./index.html) for details.
+#[proc_macro_derive(ProtobufOneof, attributes(protobuf))]
+pub fn derive_protobuf_oneof(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    oneof::expand(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,152 @@
+use crate::attr::{FieldAttrs, TypeAttrs};
+use crate::codec::{nest, CodecBuilder};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, Result};
+
+pub fn expand(ast: &DeriveInput) -> Result<TokenStream> {
+    let fields = match ast.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Named(ref fields) => fields.named.iter().collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(Error::new(
+                    ast.span(),
+                    "`ProtobufMessage` cannot be derived for tuple structs",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                ast.span(),
+                "`ProtobufMessage` can only be derived for structs",
+            ))
+        }
+    };
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new(
+            ast.generics.span(),
+            "`ProtobufMessage` cannot be derived for generic structs",
+        ));
+    }
+
+    let type_attrs = TypeAttrs::parse(&ast.attrs)?;
+    let rt = &type_attrs.crate_path;
+    let vis = &ast.vis;
+    let item = &ast.ident;
+    let decoder = format_ident!("{}Decoder", item);
+    let encoder = format_ident!("{}Encoder", item);
+
+    let builder = CodecBuilder::new(rt);
+    let mut names = Vec::new();
+    let mut decoders = Vec::new();
+    let mut encoders = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let attrs = FieldAttrs::parse(&field.attrs, field.span())?;
+        // `UnknownFieldsDecoder` accepts any tags, so it must be the last field
+        if attrs.unknown_fields && i != fields.len() - 1 {
+            return Err(Error::new(
+                field.span(),
+                "`unknown_fields` field must be the last field",
+            ));
+        }
+        let codec = builder.field(&attrs, &field.ty)?;
+        names.push(field.ident.clone().expect("Never fails"));
+        decoders.push(codec.decoder);
+        encoders.push(codec.encoder);
+    }
+
+    let fields_type = |t| quote! { #rt::field::Fields<#t> };
+    let decoder_fields = nest(&decoders, &fields_type);
+    let encoder_fields = nest(&encoders, &fields_type);
+    let vars = names
+        .iter()
+        .map(|name| quote! { #name })
+        .collect::<Vec<_>>();
+    let pattern = nest(&vars, &|t| t);
+
+    let decoder_doc = format!("Decoder for `{}`.", item);
+    let encoder_doc = format!("Encoder for `{}`.", item);
+    let decoder_new_doc = format!("Makes a new `{}` instance.", decoder);
+    let encoder_new_doc = format!("Makes a new `{}` instance.", encoder);
+
+    Ok(quote! {
+        #[doc = #decoder_doc]
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        #vis struct #decoder {
+            inner: #rt::message::MessageDecoder<#decoder_fields>,
+        }
+        impl #decoder {
+            #[doc = #decoder_new_doc]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Decode for #decoder {
+            type Item = #item;
+
+            fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+            }
+
+            fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+                let #pattern = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+                Ok(#item { #(#names),* })
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Decode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Decode::is_idle(&self.inner)
+            }
+        }
+        impl #rt::message::MessageDecode for #decoder {
+            fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+                #rt::message::MessageDecode::resume_decoding(&mut self.inner)
+            }
+        }
+
+        #[doc = #encoder_doc]
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        #vis struct #encoder {
+            inner: #rt::message::MessageEncoder<#encoder_fields>,
+        }
+        impl #encoder {
+            #[doc = #encoder_new_doc]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Encode for #encoder {
+            type Item = #item;
+
+            fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+            }
+
+            fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+                let #item { #(#names),* } = item;
+                ::bytecodec::Encode::start_encoding(&mut self.inner, #pattern)
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Encode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Encode::is_idle(&self.inner)
+            }
+        }
+        impl ::bytecodec::SizedEncode for #encoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+            }
+        }
+        impl #rt::message::MessageEncode for #encoder {}
+    })
+}
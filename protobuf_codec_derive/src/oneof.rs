@@ -0,0 +1,201 @@
+use crate::attr::{FieldAttrs, TypeAttrs};
+use crate::codec::{nest, CodecBuilder, MAX_TUPLE_LEN};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Path, Result};
+
+pub fn expand(ast: &DeriveInput) -> Result<TokenStream> {
+    let variants = match ast.data {
+        Data::Enum(ref e) => e.variants.iter().collect::<Vec<_>>(),
+        _ => {
+            return Err(Error::new(
+                ast.span(),
+                "`ProtobufOneof` can only be derived for enums",
+            ))
+        }
+    };
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new(
+            ast.generics.span(),
+            "`ProtobufOneof` cannot be derived for generic enums",
+        ));
+    }
+    if variants.is_empty() {
+        return Err(Error::new(
+            ast.span(),
+            "`ProtobufOneof` enums must have at least one variant",
+        ));
+    }
+
+    let type_attrs = TypeAttrs::parse(&ast.attrs)?;
+    let rt = &type_attrs.crate_path;
+    let vis = &ast.vis;
+    let item = &ast.ident;
+    let decoder = format_ident!("{}Decoder", item);
+    let encoder = format_ident!("{}Encoder", item);
+
+    let builder = CodecBuilder::new(rt);
+    let mut names = Vec::new();
+    let mut decoders = Vec::new();
+    let mut encoders = Vec::new();
+    for variant in &variants {
+        let ty = match variant.fields {
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+            _ => {
+                return Err(Error::new(
+                    variant.span(),
+                    "Variants must have exactly one unnamed field",
+                ))
+            }
+        };
+        let attrs = FieldAttrs::parse(&variant.attrs, variant.span())?;
+        let codec = builder.variant(&attrs, ty, variant.span())?;
+        names.push(&variant.ident);
+        decoders.push(codec.decoder);
+        encoders.push(codec.encoder);
+    }
+
+    // A oneof that has only one field is handled as an ordinary field
+    let (decoder_inner, encoder_inner, from_inner, into_inner) = if variants.len() == 1 {
+        let name = names[0];
+        (
+            decoders[0].clone(),
+            encoders[0].clone(),
+            quote! { #item::#name(v) },
+            quote! { match item { #item::#name(v) => v } },
+        )
+    } else {
+        let oneof_type = |t| quote! { #rt::field::Oneof<#t> };
+        let branches = (0..variants.len())
+            .map(|i| branch_pattern(rt, i, variants.len()))
+            .collect::<Vec<_>>();
+        (
+            nest(&decoders, &oneof_type),
+            nest(&encoders, &oneof_type),
+            quote! {
+                match v {
+                    #(#branches => #item::#names(v),)*
+                }
+            },
+            quote! {
+                match item {
+                    #(#item::#names(v) => #branches,)*
+                }
+            },
+        )
+    };
+
+    let decoder_doc = format!("Decoder for `{}`.", item);
+    let encoder_doc = format!("Encoder for `{}`.", item);
+    let decoder_new_doc = format!("Makes a new `{}` instance.", decoder);
+    let encoder_new_doc = format!("Makes a new `{}` instance.", encoder);
+    Ok(quote! {
+        #[doc = #decoder_doc]
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        #vis struct #decoder {
+            inner: #decoder_inner,
+        }
+        impl #decoder {
+            #[doc = #decoder_new_doc]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Decode for #decoder {
+            type Item = #item;
+
+            fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+            }
+
+            fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+                let v = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+                Ok(#from_inner)
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Decode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Decode::is_idle(&self.inner)
+            }
+        }
+        impl #rt::field::FieldDecode for #decoder {
+            fn start_decoding(&mut self, tag: #rt::wire::Tag) -> ::bytecodec::Result<bool> {
+                #rt::field::FieldDecode::start_decoding(&mut self.inner, tag)
+            }
+        }
+        impl #rt::field::RequiredFieldDecode for #decoder {
+            fn is_present(&self) -> bool {
+                #rt::field::RequiredFieldDecode::is_present(&self.inner)
+            }
+        }
+
+        #[doc = #encoder_doc]
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        #vis struct #encoder {
+            inner: #encoder_inner,
+        }
+        impl #encoder {
+            #[doc = #encoder_new_doc]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Encode for #encoder {
+            type Item = #item;
+
+            fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+            }
+
+            fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+                let v = #into_inner;
+                ::bytecodec::Encode::start_encoding(&mut self.inner, v)
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Encode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Encode::is_idle(&self.inner)
+            }
+        }
+        impl ::bytecodec::SizedEncode for #encoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+            }
+        }
+        impl #rt::field::FieldEncode for #encoder {}
+        impl #rt::field::RequiredFieldEncode for #encoder {}
+    })
+}
+
+// Oneofs that have more than `MAX_TUPLE_LEN` fields are nested in the last branch
+// (e.g., the 33rd field of a 34-fields oneof is `Branch32::AF(Branch3::B(v))`).
+fn branch_pattern(rt: &Path, i: usize, n: usize) -> TokenStream {
+    let branch = format_ident!("Branch{}", n.min(MAX_TUPLE_LEN));
+    if n <= MAX_TUPLE_LEN || i < MAX_TUPLE_LEN - 1 {
+        let variant = branch_variant(i);
+        quote! { #rt::field::branch::#branch::#variant(v) }
+    } else {
+        let variant = branch_variant(MAX_TUPLE_LEN - 1);
+        let inner = branch_pattern(rt, i - (MAX_TUPLE_LEN - 1), n - (MAX_TUPLE_LEN - 1));
+        quote! { #rt::field::branch::#branch::#variant(#inner) }
+    }
+}
+
+// 0 => `A`, 1 => `B`, ..., 25 => `Z`, 26 => `AA`, ...
+fn branch_variant(i: usize) -> Ident {
+    let letter = |i: usize| (b'A' + i as u8) as char;
+    if i < 26 {
+        format_ident!("{}", letter(i))
+    } else {
+        format_ident!("A{}", letter(i - 26))
+    }
+}
@@ -0,0 +1,546 @@
+extern crate bytecodec;
+extern crate protobuf_codec;
+#[macro_use]
+extern crate protobuf_codec_derive;
+#[macro_use]
+extern crate trackable;
+
+use bytecodec::io::{IoDecodeExt, IoEncodeExt};
+use bytecodec::{Encode, EncodeExt, SizedEncode};
+use protobuf_codec::scalar::Enum;
+use std::collections::{BTreeMap, HashMap};
+
+macro_rules! assert_roundtrip {
+    ($decoder:ty, $encoder:ty, $value:expr, $bytes:expr) => {
+        let value = $value;
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(<$encoder>::with_item(value.clone()));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, $bytes);
+
+        let mut decoder = <$decoder>::new();
+        let item = track_try_unwrap!(decoder.decode_exact(&buf[..]));
+        assert_eq!(item, value);
+    };
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct SearchRequest {
+    #[protobuf(num = 1, ty = "string")]
+    query: String,
+
+    #[protobuf(num = 2, ty = "int32")]
+    page_number: i32,
+
+    #[protobuf(num = 3, ty = "int32")]
+    result_per_page: i32,
+}
+
+#[test]
+fn scalar_fields_work() {
+    assert_roundtrip!(
+        SearchRequestDecoder,
+        SearchRequestEncoder,
+        SearchRequest {
+            query: "foo".to_owned(),
+            page_number: 3,
+            result_per_page: 10,
+        },
+        [10, 3, 102, 111, 111, 16, 3, 24, 10]
+    );
+
+    // Default values are omitted
+    assert_roundtrip!(
+        SearchRequestDecoder,
+        SearchRequestEncoder,
+        SearchRequest::default(),
+        []
+    );
+
+    let encoder = track_try_unwrap!(SearchRequestEncoder::with_item(SearchRequest::default()));
+    assert_eq!(encoder.exact_requiring_bytes(), 0);
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Corpus {
+    #[default]
+    Universal = 0,
+    Web = 1,
+}
+impl Enum for Corpus {
+    fn from_i32(n: i32) -> Option<Self> {
+        match n {
+            0 => Some(Corpus::Universal),
+            1 => Some(Corpus::Web),
+            _ => None,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        *self as i32
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct Labels {
+    #[protobuf(num = 1, ty = "sint32", optional)]
+    optional: Option<i32>,
+
+    #[protobuf(num = 2, ty = "fixed32", required)]
+    required: u32,
+
+    #[protobuf(num = 3, ty = "bytes", repeated)]
+    repeated: Vec<Vec<u8>>,
+
+    #[protobuf(num = 4, ty = "int32", packed)]
+    packed: Vec<i32>,
+
+    #[protobuf(num = 5, ty = "enum")]
+    corpus: Corpus,
+
+    #[protobuf(num = 6, ty = "enum", packed)]
+    corpora: Vec<Corpus>,
+}
+
+#[test]
+fn labels_work() {
+    assert_roundtrip!(
+        LabelsDecoder,
+        LabelsEncoder,
+        Labels {
+            optional: Some(-1),
+            required: 0,
+            repeated: vec![vec![1], vec![]],
+            packed: vec![3, 270, 86942],
+            corpus: Corpus::Web,
+            corpora: vec![Corpus::Web, Corpus::Universal],
+        },
+        [
+            8, 1, // optional=-1
+            21, 0, 0, 0, 0, // required=0
+            26, 1, 1, 26, 0, // repeated=[[1], []]
+            34, 6, 3, 142, 2, 158, 167, 5, // packed=[3, 270, 86942]
+            40, 1, // corpus=WEB
+            50, 2, 1, 0 // corpora=[WEB, UNIVERSAL]
+        ]
+    );
+
+    // Required fields cannot be omitted
+    let mut decoder = LabelsDecoder::new();
+    assert!(decoder.decode_exact(&[][..]).is_err());
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct Nested {
+    #[protobuf(num = 1, ty = "message")]
+    request: Option<SearchRequest>,
+
+    #[protobuf(num = 2, ty = "message", required)]
+    labels: Labels,
+
+    #[protobuf(num = 3, ty = "message", repeated)]
+    history: Vec<SearchRequest>,
+
+    #[protobuf(num = 4, key = "string", value = "uint64")]
+    counts: HashMap<String, u64>,
+
+    #[protobuf(num = 5, key = "int32", value = "message")]
+    requests: BTreeMap<i32, SearchRequest>,
+}
+
+#[test]
+fn message_and_map_fields_work() {
+    let mut counts = HashMap::new();
+    counts.insert("a".to_owned(), 1);
+    let mut requests = BTreeMap::new();
+    requests.insert(
+        -1,
+        SearchRequest {
+            page_number: 2,
+            ..SearchRequest::default()
+        },
+    );
+    assert_roundtrip!(
+        NestedDecoder,
+        NestedEncoder,
+        Nested {
+            request: Some(SearchRequest::default()),
+            labels: Labels {
+                required: 1,
+                ..Labels::default()
+            },
+            history: vec![SearchRequest {
+                result_per_page: 1,
+                ..SearchRequest::default()
+            }],
+            counts,
+            requests,
+        },
+        [
+            10, 0, // request={}
+            18, 5, 21, 1, 0, 0, 0, // labels={required=1}
+            26, 2, 24, 1, // history=[{result_per_page=1}]
+            34, 5, 10, 1, 97, 16, 1, // counts={"a": 1}
+            42, 15, 8, 255, 255, 255, 255, 255, 255, 255, 255, 255, 1, 18, 2, 16,
+            2 // requests={-1: {page_number=2}}
+        ]
+    );
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct Outer {
+    #[protobuf(num = 1, ty = "message")]
+    nested: Option<Nested>,
+
+    #[protobuf(num = 2, ty = "message", repeated)]
+    all: Vec<Nested>,
+}
+
+#[test]
+fn messages_that_have_repeated_and_map_fields_can_be_embedded() {
+    let mut counts = HashMap::new();
+    counts.insert("a".to_owned(), 1);
+    let nested = Nested {
+        history: vec![SearchRequest::default(); 2],
+        counts,
+        ..Nested::default()
+    };
+    assert_roundtrip!(
+        OuterDecoder,
+        OuterEncoder,
+        Outer {
+            nested: Some(nested.clone()),
+            all: vec![nested, Nested::default()],
+        },
+        [
+            10, 18, 18, 5, 21, 0, 0, 0, 0, 26, 0, 26, 0, 34, 5, 10, 1, 97, 16, 1, // nested
+            18, 18, 18, 5, 21, 0, 0, 0, 0, 26, 0, 26, 0, 34, 5, 10, 1, 97, 16, 1, // all[0]
+            18, 7, 18, 5, 21, 0, 0, 0, 0 // all[1]
+        ]
+    );
+
+    // The sizes of the nested messages are computed when starting encoding
+    let encoder = track_try_unwrap!(OuterEncoder::with_item(Outer {
+        all: vec![Nested::default(); 3],
+        ..Outer::default()
+    }));
+    assert_eq!(encoder.exact_requiring_bytes(), 27);
+}
+
+#[derive(Debug, Clone, PartialEq, ProtobufOneof)]
+enum Source {
+    #[protobuf(num = 1, ty = "string")]
+    Url(String),
+
+    #[protobuf(num = 2, ty = "message")]
+    Request(SearchRequest),
+
+    #[protobuf(num = 300, ty = "enum")]
+    Corpus(Corpus),
+}
+
+#[derive(Debug, Clone, PartialEq, ProtobufOneof)]
+enum Single {
+    #[protobuf(num = 3, ty = "bool")]
+    Flag(bool),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct OneofTest {
+    #[protobuf(oneof)]
+    source: Option<Source>,
+
+    #[protobuf(oneof)]
+    single: Option<Single>,
+}
+
+#[derive(Debug, Clone, PartialEq, ProtobufMessage)]
+struct RequiredOneofTest {
+    #[protobuf(oneof)]
+    source: Source,
+}
+
+#[test]
+fn oneof_fields_work() {
+    assert_roundtrip!(
+        OneofTestDecoder,
+        OneofTestEncoder,
+        OneofTest {
+            source: Some(Source::Url("foo".to_owned())),
+            single: Some(Single::Flag(false)),
+        },
+        [10, 3, 102, 111, 111, 24, 0]
+    );
+    assert_roundtrip!(
+        OneofTestDecoder,
+        OneofTestEncoder,
+        OneofTest {
+            source: Some(Source::Corpus(Corpus::Web)),
+            single: None,
+        },
+        [224, 18, 1]
+    );
+    assert_roundtrip!(OneofTestDecoder, OneofTestEncoder, OneofTest::default(), []);
+    assert_roundtrip!(
+        RequiredOneofTestDecoder,
+        RequiredOneofTestEncoder,
+        RequiredOneofTest {
+            source: Source::Request(SearchRequest::default()),
+        },
+        [18, 0]
+    );
+
+    // The last field wins
+    let mut decoder = OneofTestDecoder::new();
+    let item = track_try_unwrap!(decoder.decode_exact(&[10, 1, 97, 18, 0][..]));
+    assert_eq!(item.source, Some(Source::Request(SearchRequest::default())));
+
+    // Required oneof fields cannot be omitted
+    let mut decoder = RequiredOneofTestDecoder::new();
+    assert!(decoder.decode_exact(&[][..]).is_err());
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct Many {
+    #[protobuf(num = 1, ty = "uint32")]
+    f1: u32,
+    #[protobuf(num = 2, ty = "uint32")]
+    f2: u32,
+    #[protobuf(num = 3, ty = "uint32")]
+    f3: u32,
+    #[protobuf(num = 4, ty = "uint32")]
+    f4: u32,
+    #[protobuf(num = 5, ty = "uint32")]
+    f5: u32,
+    #[protobuf(num = 6, ty = "uint32")]
+    f6: u32,
+    #[protobuf(num = 7, ty = "uint32")]
+    f7: u32,
+    #[protobuf(num = 8, ty = "uint32")]
+    f8: u32,
+    #[protobuf(num = 9, ty = "uint32")]
+    f9: u32,
+    #[protobuf(num = 100, ty = "double")]
+    f100: f64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+#[protobuf(crate = "::protobuf_codec")]
+struct Empty;
+
+#[test]
+fn many_fields_work() {
+    assert_roundtrip!(
+        ManyDecoder,
+        ManyEncoder,
+        Many {
+            f1: 1,
+            f8: 8,
+            f9: 9,
+            f100: 0.5,
+            ..Many::default()
+        },
+        [8, 1, 64, 8, 72, 9, 161, 6, 0, 0, 0, 0, 0, 0, 224, 63]
+    );
+    assert_roundtrip!(EmptyDecoder, EmptyEncoder, Empty, []);
+
+    let encoder = track_try_unwrap!(ManyEncoder::with_item(Many::default()));
+    assert!(encoder.is_idle());
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct Wide {
+    #[protobuf(num = 1, ty = "uint32")]
+    f1: u32,
+    #[protobuf(num = 2, ty = "uint32")]
+    f2: u32,
+    #[protobuf(num = 3, ty = "uint32")]
+    f3: u32,
+    #[protobuf(num = 4, ty = "uint32")]
+    f4: u32,
+    #[protobuf(num = 5, ty = "uint32")]
+    f5: u32,
+    #[protobuf(num = 6, ty = "uint32")]
+    f6: u32,
+    #[protobuf(num = 7, ty = "uint32")]
+    f7: u32,
+    #[protobuf(num = 8, ty = "uint32")]
+    f8: u32,
+    #[protobuf(num = 9, ty = "uint32")]
+    f9: u32,
+    #[protobuf(num = 10, ty = "uint32")]
+    f10: u32,
+    #[protobuf(num = 11, ty = "uint32")]
+    f11: u32,
+    #[protobuf(num = 12, ty = "uint32")]
+    f12: u32,
+    #[protobuf(num = 13, ty = "uint32")]
+    f13: u32,
+    #[protobuf(num = 14, ty = "uint32")]
+    f14: u32,
+    #[protobuf(num = 15, ty = "uint32")]
+    f15: u32,
+    #[protobuf(num = 16, ty = "uint32")]
+    f16: u32,
+    #[protobuf(num = 17, ty = "uint32")]
+    f17: u32,
+    #[protobuf(num = 18, ty = "uint32")]
+    f18: u32,
+    #[protobuf(num = 19, ty = "uint32")]
+    f19: u32,
+    #[protobuf(num = 20, ty = "uint32")]
+    f20: u32,
+    #[protobuf(num = 21, ty = "uint32")]
+    f21: u32,
+    #[protobuf(num = 22, ty = "uint32")]
+    f22: u32,
+    #[protobuf(num = 23, ty = "uint32")]
+    f23: u32,
+    #[protobuf(num = 24, ty = "uint32")]
+    f24: u32,
+    #[protobuf(num = 25, ty = "uint32")]
+    f25: u32,
+    #[protobuf(num = 26, ty = "uint32")]
+    f26: u32,
+    #[protobuf(num = 27, ty = "uint32")]
+    f27: u32,
+    #[protobuf(num = 28, ty = "uint32")]
+    f28: u32,
+    #[protobuf(num = 29, ty = "uint32")]
+    f29: u32,
+    #[protobuf(num = 30, ty = "uint32")]
+    f30: u32,
+    #[protobuf(num = 31, ty = "uint32")]
+    f31: u32,
+    #[protobuf(num = 32, ty = "uint32")]
+    f32: u32,
+    #[protobuf(num = 33, ty = "uint32")]
+    f33: u32,
+    #[protobuf(oneof)]
+    choice: Option<WideChoice>,
+}
+
+#[derive(Debug, Clone, PartialEq, ProtobufOneof)]
+enum WideChoice {
+    #[protobuf(num = 101, ty = "uint32")]
+    C101(u32),
+    #[protobuf(num = 102, ty = "uint32")]
+    C102(u32),
+    #[protobuf(num = 103, ty = "uint32")]
+    C103(u32),
+    #[protobuf(num = 104, ty = "uint32")]
+    C104(u32),
+    #[protobuf(num = 105, ty = "uint32")]
+    C105(u32),
+    #[protobuf(num = 106, ty = "uint32")]
+    C106(u32),
+    #[protobuf(num = 107, ty = "uint32")]
+    C107(u32),
+    #[protobuf(num = 108, ty = "uint32")]
+    C108(u32),
+    #[protobuf(num = 109, ty = "uint32")]
+    C109(u32),
+    #[protobuf(num = 110, ty = "uint32")]
+    C110(u32),
+    #[protobuf(num = 111, ty = "uint32")]
+    C111(u32),
+    #[protobuf(num = 112, ty = "uint32")]
+    C112(u32),
+    #[protobuf(num = 113, ty = "uint32")]
+    C113(u32),
+    #[protobuf(num = 114, ty = "uint32")]
+    C114(u32),
+    #[protobuf(num = 115, ty = "uint32")]
+    C115(u32),
+    #[protobuf(num = 116, ty = "uint32")]
+    C116(u32),
+    #[protobuf(num = 117, ty = "uint32")]
+    C117(u32),
+    #[protobuf(num = 118, ty = "uint32")]
+    C118(u32),
+    #[protobuf(num = 119, ty = "uint32")]
+    C119(u32),
+    #[protobuf(num = 120, ty = "uint32")]
+    C120(u32),
+    #[protobuf(num = 121, ty = "uint32")]
+    C121(u32),
+    #[protobuf(num = 122, ty = "uint32")]
+    C122(u32),
+    #[protobuf(num = 123, ty = "uint32")]
+    C123(u32),
+    #[protobuf(num = 124, ty = "uint32")]
+    C124(u32),
+    #[protobuf(num = 125, ty = "uint32")]
+    C125(u32),
+    #[protobuf(num = 126, ty = "uint32")]
+    C126(u32),
+    #[protobuf(num = 127, ty = "uint32")]
+    C127(u32),
+    #[protobuf(num = 128, ty = "uint32")]
+    C128(u32),
+    #[protobuf(num = 129, ty = "uint32")]
+    C129(u32),
+    #[protobuf(num = 130, ty = "uint32")]
+    C130(u32),
+    #[protobuf(num = 131, ty = "uint32")]
+    C131(u32),
+    #[protobuf(num = 132, ty = "uint32")]
+    C132(u32),
+    #[protobuf(num = 133, ty = "uint32")]
+    C133(u32),
+    #[protobuf(num = 134, ty = "uint32")]
+    C134(u32),
+}
+
+#[test]
+fn wide_messages_work() {
+    assert_roundtrip!(
+        WideDecoder,
+        WideEncoder,
+        Wide {
+            f1: 1,
+            f32: 32,
+            f33: 33,
+            choice: Some(WideChoice::C134(4)),
+            ..Wide::default()
+        },
+        [8, 1, 128, 2, 32, 136, 2, 33, 176, 8, 4]
+    );
+    assert_roundtrip!(
+        WideDecoder,
+        WideEncoder,
+        Wide {
+            choice: Some(WideChoice::C101(1)),
+            ..Wide::default()
+        },
+        [168, 6, 1]
+    );
+}
+
+#[derive(Debug, Default, Clone, PartialEq, ProtobufMessage)]
+struct OldSearchRequest {
+    #[protobuf(num = 1, ty = "string")]
+    query: String,
+
+    #[protobuf(unknown_fields)]
+    unknown_fields: protobuf_codec::field::UnknownFields,
+}
+
+#[test]
+fn unknown_fields_work() {
+    let request = SearchRequest {
+        query: "foo".to_owned(),
+        page_number: 3,
+        result_per_page: 10,
+    };
+    let mut bytes = Vec::new();
+    let mut encoder = track_try_unwrap!(SearchRequestEncoder::with_item(request));
+    track_try_unwrap!(encoder.encode_all(&mut bytes));
+
+    let mut decoder = OldSearchRequestDecoder::new();
+    let old = track_try_unwrap!(decoder.decode_exact(&bytes[..]));
+    assert_eq!(old.query, "foo");
+    assert_eq!(old.unknown_fields.len(), 2);
+
+    assert_roundtrip!(OldSearchRequestDecoder, OldSearchRequestEncoder, old, bytes);
+}
@@ -0,0 +1,1366 @@
+use super::types::{package_module, package_scope, TypeKind, TypeTable};
+use crate::dynamic::json_name;
+use crate::schema::{Enum, EnumValue, Field, FieldType, Label, Message, Oneof, ProtoFile, Syntax};
+use bytecodec::{ErrorKind, Result};
+use std::collections::BTreeMap;
+
+const MAX_TUPLE_LEN: usize = 32;
+const MAX_LINE_LEN: usize = 80;
+const UNRECOGNIZED_VARIANT: &str = "Unrecognized";
+
+#[derive(Debug)]
+pub struct Emitter<'a> {
+    rt: &'a str,
+    table: &'a TypeTable,
+    unknown_fields: bool,
+    json_mapping: bool,
+    root: Module,
+}
+impl<'a> Emitter<'a> {
+    pub fn new(
+        crate_path: &'a str,
+        table: &'a TypeTable,
+        unknown_fields: bool,
+        json_mapping: bool,
+    ) -> Self {
+        Emitter {
+            rt: crate_path,
+            table,
+            unknown_fields,
+            json_mapping,
+            root: Module::default(),
+        }
+    }
+
+    pub fn emit_file(&mut self, file: &ProtoFile) -> Result<()> {
+        let scope = package_scope(file);
+        let module = package_module(file);
+        if let Some(ref package) = file.package {
+            let components = package.split('.').collect::<Vec<_>>();
+            for i in 0..module.len() {
+                let doc = format!("`{}` package.", components[..=i].join("."));
+                self.root.get_mut(&module[..=i]).doc = doc;
+            }
+        }
+
+        for m in &file.messages {
+            track!(self.emit_message(&module, &scope, file.syntax, m))?;
+        }
+        for e in &file.enums {
+            track!(self.emit_enum(&module, &scope, file.syntax, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> String {
+        let mut out = String::new();
+        out.push_str("// This file is generated by `protobuf_codec::codegen`. Do not edit.\n");
+        self.root.render(&mut out, 0);
+        out
+    }
+
+    fn emit_message(
+        &mut self,
+        module: &[String],
+        scope: &str,
+        syntax: Syntax,
+        m: &Message,
+    ) -> Result<()> {
+        let full_name = format!("{}.{}", scope, m.name);
+        let mut nested_module = module.to_owned();
+        nested_module.push(module_name(&m.name));
+
+        let mut members = Vec::new();
+        for f in &m.fields {
+            let member = track!(self.field_member(module, &full_name, syntax, f); f.name)?;
+            members.push(member);
+        }
+        for o in &m.oneofs {
+            let member = track!(self.oneof_member(module, &full_name, &m.name, o); o.name)?;
+            members.push(member);
+        }
+        members.sort_by_key(|m| m.order);
+        if self.unknown_fields {
+            track_assert!(
+                members.iter().all(|m| m.name != "unknown_fields"),
+                ErrorKind::InvalidInput,
+                "{}: Message `{}` has a member that conflicts with `unknown_fields`",
+                m.position,
+                m.name
+            );
+
+            // `UnknownFieldsDecoder` accepts any tags, so it must be the last field
+            members.push(Member {
+                name: "unknown_fields".to_owned(),
+                ty: format!("{}::field::UnknownFields", self.rt),
+                doc: "Fields which are not defined in the schema.".to_owned(),
+                decoder: format!("{}::field::UnknownFieldsDecoder", self.rt),
+                encoder: format!("{}::field::UnknownFieldsEncoder", self.rt),
+                from_decoded: "{}".to_owned(),
+                to_encoded: "{}".to_owned(),
+                order: u32::MAX,
+                json: None,
+            });
+        }
+
+        for nested in &m.messages {
+            track!(self.emit_message(&nested_module, &full_name, syntax, nested))?;
+        }
+        for e in &m.enums {
+            track!(self.emit_enum(&nested_module, &full_name, syntax, e))?;
+        }
+        if let Some(nested) = self.root.get(&nested_module) {
+            if nested.doc.is_empty() {
+                let doc = format!("Nested types of `{}` message.", m.name);
+                self.root.get_mut(&nested_module).doc = doc;
+            }
+        }
+
+        let mut item = self.message_items(&m.name, &members);
+        if self.json_mapping {
+            item.push('\n');
+            item.push_str(&self.message_json_items(&full_name, &m.name, &members));
+        }
+        self.root.get_mut(module).items.push(item);
+        Ok(())
+    }
+
+    fn message_items(&self, name: &str, members: &[Member]) -> String {
+        let rt = self.rt;
+        let ident = type_ident(name);
+
+        let mut out = String::new();
+        out.push_str(&format!(
+            "/// `{}` message.\n#[derive(Debug, Default, Clone, PartialEq)]\npub struct {} {{\n",
+            name, ident
+        ));
+        for m in members {
+            out.push_str(&format!(
+                "    /// {}\n    pub {}: {},\n",
+                m.doc, m.name, m.ty
+            ));
+        }
+        out.push_str("}\n\n");
+
+        let vars = (0..members.len())
+            .map(|i| format!("f{}", i))
+            .collect::<Vec<_>>();
+        let fields = format!("{}::field::Fields<(", rt);
+        let decoder_fields = layout(
+            &members
+                .iter()
+                .map(|m| m.decoder.clone())
+                .collect::<Vec<_>>(),
+            &fields,
+            ")>",
+        );
+        let encoder_fields = layout(
+            &members
+                .iter()
+                .map(|m| m.encoder.clone())
+                .collect::<Vec<_>>(),
+            &fields,
+            ")>",
+        );
+
+        // Decoder
+        let finish_decoding = if members.is_empty() {
+            format!(
+                "::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+        Ok({} {{}})",
+                ident
+            )
+        } else {
+            let mut s = format!(
+                "let {} = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+        Ok({} {{\n",
+                indent(&layout(&vars, "(", ")"), 8),
+                ident
+            );
+            for (m, v) in members.iter().zip(&vars) {
+                s.push_str(&format!(
+                    "            {}: {},\n",
+                    m.name,
+                    indent(&m.from_decoded.replace("{}", v), 12)
+                ));
+            }
+            s.push_str("        })");
+            s
+        };
+        out.push_str(&format!(
+            "/// Decoder for `{name}` message.
+#[derive(Debug, Default)]
+#[allow(clippy::type_complexity)]
+pub struct {ident}Decoder {{
+    inner: {rt}::message::MessageDecoder<
+        {fields}
+    >,
+}}
+impl {ident}Decoder {{
+    /// Makes a new `{ident}Decoder` instance.
+    pub fn new() -> Self {{
+        Self::default()
+    }}
+}}
+impl ::bytecodec::Decode for {ident}Decoder {{
+    type Item = {ident};
+
+    fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {{
+        ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+    }}
+
+    fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {{
+        {finish_decoding}
+    }}
+
+    fn requiring_bytes(&self) -> ::bytecodec::ByteCount {{
+        ::bytecodec::Decode::requiring_bytes(&self.inner)
+    }}
+
+    fn is_idle(&self) -> bool {{
+        ::bytecodec::Decode::is_idle(&self.inner)
+    }}
+}}
+impl {rt}::message::MessageDecode for {ident}Decoder {{
+    fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {{
+        {rt}::message::MessageDecode::resume_decoding(&mut self.inner)
+    }}
+}}
+
+",
+            name = name,
+            ident = ident,
+            rt = rt,
+            fields = indent(&decoder_fields, 8),
+            finish_decoding = finish_decoding
+        ));
+
+        // Encoder
+        let (item_var, start_encoding) = if members.is_empty() {
+            (
+                "_item",
+                "::bytecodec::Encode::start_encoding(&mut self.inner, ())".to_owned(),
+            )
+        } else {
+            let mut s = String::new();
+            let mut values = Vec::new();
+            for m in members {
+                let value = format!("item.{}", m.name);
+                if m.to_encoded == "{}" {
+                    values.push(value);
+                } else {
+                    s.push_str(&format!(
+                        "let {} = {};\n        ",
+                        m.name,
+                        indent(&m.to_encoded.replace("{}", &value), 8)
+                    ));
+                    values.push(m.name.clone());
+                }
+            }
+            s.push_str(&format!(
+                "let item = {};
+        ::bytecodec::Encode::start_encoding(&mut self.inner, item)",
+                indent(&layout(&values, "(", ")"), 8)
+            ));
+            ("item", s)
+        };
+        out.push_str(&format!(
+            "/// Encoder for `{name}` message.
+#[derive(Debug, Default)]
+#[allow(clippy::type_complexity)]
+pub struct {ident}Encoder {{
+    inner: {rt}::message::MessageEncoder<
+        {fields}
+    >,
+}}
+impl {ident}Encoder {{
+    /// Makes a new `{ident}Encoder` instance.
+    pub fn new() -> Self {{
+        Self::default()
+    }}
+}}
+impl ::bytecodec::Encode for {ident}Encoder {{
+    type Item = {ident};
+
+    fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {{
+        ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+    }}
+
+    fn start_encoding(&mut self, {item_var}: Self::Item) -> ::bytecodec::Result<()> {{
+        {start_encoding}
+    }}
+
+    fn requiring_bytes(&self) -> ::bytecodec::ByteCount {{
+        ::bytecodec::Encode::requiring_bytes(&self.inner)
+    }}
+
+    fn is_idle(&self) -> bool {{
+        ::bytecodec::Encode::is_idle(&self.inner)
+    }}
+}}
+",
+            name = name,
+            ident = ident,
+            rt = rt,
+            fields = indent(&encoder_fields, 8),
+            item_var = item_var,
+            start_encoding = start_encoding
+        ));
+        out.push_str(&format!(
+            "impl ::bytecodec::SizedEncode for {ident}Encoder {{
+    fn exact_requiring_bytes(&self) -> u64 {{
+        ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+    }}
+}}
+impl {rt}::message::MessageEncode for {ident}Encoder {{}}",
+            rt = rt,
+            ident = ident
+        ));
+        out
+    }
+
+    fn message_json_items(&self, full_name: &str, name: &str, members: &[Member]) -> String {
+        let rt = self.rt;
+        let ident = type_ident(name);
+        let full_name = full_name.trim_start_matches('.');
+
+        let mut fields = Vec::new();
+        let mut writes = String::new();
+        let mut reads = String::new();
+        for m in members {
+            let json = match m.json {
+                Some(ref json) => json,
+                None => continue,
+            };
+            let i = fields.len();
+            let oneof = json
+                .oneof
+                .as_ref()
+                .map_or_else(|| "None".to_owned(), |o| format!("Some({:?})", o));
+            for f in &json.fields {
+                fields.push(format!(
+                    "{}::json::JsonField {{ name: {:?}, json_name: {:?}, oneof: {} }},",
+                    rt,
+                    f,
+                    json_name(f),
+                    oneof
+                ));
+            }
+
+            let field = format!("&Self::JSON_FIELDS[{}]", i);
+            let (write, read) = match json.kind {
+                JsonKind::Plain => (
+                    format!("object.field({}, &self.{})?;", field, m.name),
+                    "reader.read(value)?",
+                ),
+                JsonKind::Required(boxed) => (
+                    format!(
+                        "object.optional_field({}, Some(&{}self.{}))?;",
+                        field,
+                        if boxed { "*" } else { "" },
+                        m.name
+                    ),
+                    if boxed {
+                        "Box::new(reader.read(value)?)"
+                    } else {
+                        "reader.read(value)?"
+                    },
+                ),
+                JsonKind::Optional(boxed) => (
+                    format!(
+                        "object.optional_field({}, self.{}.{}())?;",
+                        field,
+                        m.name,
+                        if boxed { "as_deref" } else { "as_ref" }
+                    ),
+                    if boxed {
+                        "Some(Box::new(reader.read(value)?))"
+                    } else {
+                        "Some(reader.read(value)?)"
+                    },
+                ),
+                JsonKind::Repeated => (
+                    format!("object.repeated_field({}, &self.{})?;", field, m.name),
+                    "reader.read_repeated(value)?",
+                ),
+                JsonKind::Oneof(ref variants) => {
+                    let mut arms = Vec::new();
+                    for (j, (variant, boxed)) in variants.iter().enumerate() {
+                        let (value, read) = if *boxed {
+                            ("&**v", "Box::new(reader.read(value)?)")
+                        } else {
+                            ("v", "reader.read(value)?")
+                        };
+                        arms.push((
+                            format!("Some({}(ref v))", variant),
+                            format!(
+                                "object.optional_field(&Self::JSON_FIELDS[{}], Some({}))?",
+                                i + j,
+                                value
+                            ),
+                        ));
+                        reads.push_str(&format!(
+                            "\n{} => message.{} = Some({}({})),",
+                            i + j,
+                            m.name,
+                            variant,
+                            read
+                        ));
+                    }
+                    if arms.len() == 1 {
+                        writes.push_str(&format!(
+                            "\nif let {} = self.{} {{\n    {};\n}}",
+                            arms[0].0, m.name, arms[0].1
+                        ));
+                    } else {
+                        writes.push_str(&format!("\nmatch self.{} {{", m.name));
+                        for (pattern, write) in arms {
+                            writes.push_str(&format!("\n    {} => {},", pattern, write));
+                        }
+                        writes.push_str("\n    None => {}\n}");
+                    }
+                    continue;
+                }
+            };
+            writes.push_str(&format!("\n{}", write));
+            reads.push_str(&format!("\n{} => message.{} = {},", i, m.name, read));
+        }
+
+        let signatures = format!(
+            "fn to_json(
+        &self,
+        options: &{rt}::json::JsonOptions,
+    ) -> ::bytecodec::Result<{rt}::json::JsonValue> {{",
+            rt = rt
+        );
+        let from_json = format!(
+            "fn from_json(
+        json: &{rt}::json::JsonValue,
+        options: &{rt}::json::JsonOptions,
+    ) -> ::bytecodec::Result<Self> {{",
+            rt = rt
+        );
+        if fields.is_empty() {
+            return format!(
+                "impl {rt}::json::JsonMapping for {ident} {{
+    {to_json}
+        Ok({rt}::json::JsonObjectWriter::new(options).finish())
+    }}
+
+    {from_json}
+        let mut reader =
+            {rt}::json::JsonObjectReader::new(json, {full_name:?}, &[], options)?;
+        while reader.next_field()?.is_some() {{}}
+        Ok(Self::default())
+    }}
+}}",
+                rt = rt,
+                ident = ident,
+                to_json = signatures,
+                from_json = from_json,
+                full_name = full_name
+            );
+        }
+        format!(
+            "impl {ident} {{
+    const JSON_FIELDS: &'static [{rt}::json::JsonField] = &[{fields}
+    ];
+}}
+impl {rt}::json::JsonMapping for {ident} {{
+    {to_json}
+        let mut object = {rt}::json::JsonObjectWriter::new(options);{writes}
+        Ok(object.finish())
+    }}
+
+    {from_json}
+        let mut reader =
+            {rt}::json::JsonObjectReader::new(json, {full_name:?}, Self::JSON_FIELDS, options)?;
+        let mut message = Self::default();
+        while let Some((i, value)) = reader.next_field()? {{
+            match i {{{reads}
+                _ => unreachable!(),
+            }}
+        }}
+        Ok(message)
+    }}
+}}",
+            rt = rt,
+            ident = ident,
+            fields = indent(&format!("\n{}", fields.join("\n")), 8),
+            to_json = signatures,
+            from_json = from_json,
+            writes = indent(&writes, 8),
+            reads = indent(&reads, 16),
+            full_name = full_name
+        )
+    }
+
+    // Values are represented by their names, and unknown values of open enums are represented by numbers.
+    fn enum_json_items(
+        &self,
+        full_name: &str,
+        ident: &str,
+        open: bool,
+        variants: &[(&EnumValue, String)],
+        aliases: &[(&EnumValue, String)],
+    ) -> String {
+        let rt = self.rt;
+        let mut to_arms = String::new();
+        let mut from_arms = String::new();
+        for (v, variant) in variants {
+            to_arms.push_str(&format!(
+                "\n            {}::{} => {:?},",
+                ident, variant, v.name
+            ));
+            from_arms.push_str(&format!(
+                "\n            {:?} => Some({}::{}),",
+                v.name, ident, variant
+            ));
+        }
+        if open {
+            to_arms.push_str(&format!(
+                "\n            {}::{}(n) => return Ok({}::json::JsonValue::Number(n.to_string())),",
+                ident, UNRECOGNIZED_VARIANT, rt
+            ));
+        }
+        for (v, target) in aliases {
+            from_arms.push_str(&format!("\n            {:?} => Some({}),", v.name, target));
+        }
+        format!(
+            "impl {rt}::json::JsonMapping for {ident} {{
+    fn to_json(
+        &self,
+        _options: &{rt}::json::JsonOptions,
+    ) -> ::bytecodec::Result<{rt}::json::JsonValue> {{
+        let name = match *self {{{to_arms}
+        }};
+        Ok({rt}::json::JsonValue::String(name.to_owned()))
+    }}
+
+    fn from_json(
+        json: &{rt}::json::JsonValue,
+        _options: &{rt}::json::JsonOptions,
+    ) -> ::bytecodec::Result<Self> {{
+        {rt}::json::enum_from_json(json, {full_name:?}, |name| match name {{{from_arms}
+            _ => None,
+        }})
+    }}
+}}",
+            rt = rt,
+            ident = ident,
+            to_arms = to_arms,
+            from_arms = from_arms,
+            full_name = full_name.trim_start_matches('.')
+        )
+    }
+
+    // Proto3 enums are open (i.e., they keep unknown values in `Unrecognized` variant),
+    // whereas proto2 enums are closed.
+    fn emit_enum(
+        &mut self,
+        module: &[String],
+        scope: &str,
+        syntax: Syntax,
+        e: &Enum,
+    ) -> Result<()> {
+        track_assert!(
+            !e.values.is_empty(),
+            ErrorKind::InvalidInput,
+            "{}: Enum `{}` has no values",
+            e.position,
+            e.name
+        );
+
+        let ident = type_ident(&e.name);
+        let open = syntax == Syntax::Proto3;
+        let mut variants = Vec::new();
+        let mut aliases = Vec::new();
+        let mut numbers = BTreeMap::new();
+        for v in &e.values {
+            if let Some(variant) = numbers.get(&v.number) {
+                aliases.push((v, format!("{}::{}", ident, variant)));
+            } else {
+                let variant = variant_ident(&v.name);
+                track_assert!(
+                    !open || variant != UNRECOGNIZED_VARIANT,
+                    ErrorKind::InvalidInput,
+                    "{}: Enum value `{}` conflicts with `{}` variant",
+                    e.position,
+                    v.name,
+                    UNRECOGNIZED_VARIANT
+                );
+                numbers.insert(v.number, variant.clone());
+                variants.push((v, variant));
+            }
+        }
+
+        let mut out = format!(
+            "/// `{}` enum.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+",
+            e.name
+        );
+        if !open {
+            out.push_str("#[repr(i32)]\n");
+        }
+        out.push_str(&format!("pub enum {} {{\n", ident));
+        for (i, &(v, ref variant)) in variants.iter().enumerate() {
+            out.push_str(&format!("    /// `{} = {};`\n", v.name, v.number));
+            if i == 0 {
+                out.push_str("    #[default]\n");
+            }
+            if open {
+                out.push_str(&format!("    {},\n", variant));
+            } else {
+                out.push_str(&format!("    {} = {},\n", variant, v.number));
+            }
+        }
+        if open {
+            out.push_str(&format!(
+                "    /// Value which is not defined in the schema.\n    {}(i32),\n",
+                UNRECOGNIZED_VARIANT
+            ));
+        }
+        out.push_str("}\n");
+        if !aliases.is_empty() {
+            out.push_str(&format!("impl {} {{\n", ident));
+            for (i, &(v, ref target)) in aliases.iter().enumerate() {
+                if i != 0 {
+                    out.push('\n');
+                }
+                out.push_str(&format!("    /// `{} = {};`\n", v.name, v.number));
+                if v.name != v.name.to_uppercase() {
+                    out.push_str("    #[allow(non_upper_case_globals)]\n");
+                }
+                out.push_str(&format!("    pub const {}: Self = {};\n", v.name, target));
+            }
+            out.push_str("}\n");
+        }
+        out.push_str(&format!(
+            "impl {rt}::scalar::Enum for {ident} {{
+    fn from_i32(n: i32) -> Option<Self> {{
+        match n {{
+",
+            rt = self.rt,
+            ident = ident
+        ));
+        for (v, variant) in &variants {
+            out.push_str(&format!(
+                "            {} => Some({}::{}),\n",
+                v.number, ident, variant
+            ));
+        }
+        if open {
+            out.push_str(&format!(
+                "            n => Some({}::{}(n)),
+        }}
+    }}
+
+    fn to_i32(&self) -> i32 {{
+        match *self {{
+",
+                ident, UNRECOGNIZED_VARIANT
+            ));
+            for (v, variant) in &variants {
+                out.push_str(&format!(
+                    "            {}::{} => {},\n",
+                    ident, variant, v.number
+                ));
+            }
+            out.push_str(&format!(
+                "            {}::{}(n) => n,
+        }}
+    }}
+}}",
+                ident, UNRECOGNIZED_VARIANT
+            ));
+        } else {
+            out.push_str(
+                "            _ => None,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        *self as i32
+    }
+}",
+            );
+        }
+        if self.json_mapping {
+            out.push('\n');
+            let full_name = format!("{}.{}", scope, e.name);
+            out.push_str(&self.enum_json_items(&full_name, &ident, open, &variants, &aliases));
+        }
+        self.root.get_mut(module).items.push(out);
+        Ok(())
+    }
+
+    fn field_member(
+        &mut self,
+        module: &[String],
+        scope: &str,
+        syntax: Syntax,
+        f: &Field,
+    ) -> Result<Member> {
+        let rt = self.rt;
+        let num = self.field_num_type(f.number);
+        let mut from_decoded = "{}".to_owned();
+        let mut to_encoded = "{}".to_owned();
+        let mut json = JsonKind::Plain;
+        let (ty, decoder, encoder) = if let FieldType::Map(ref k, ref v) = f.ty {
+            let key = track!(self.value(module, scope, f, k))?;
+            let value = track!(self.value(module, scope, f, v))?;
+            let ty = format!("::std::collections::HashMap<{}, {}>", key.ty, value.ty);
+            let kind = if value.kind == ValueKind::Message {
+                "MapMessageField"
+            } else {
+                "MapField"
+            };
+            let decoder = format!(
+                "{}::field::{}Decoder<{}, {}, {}, {}>",
+                rt, kind, num, key.decoder, value.decoder, ty
+            );
+            let encoder = format!(
+                "{}::field::Sized{}Encoder<{}, {}, {}, {}>",
+                rt, kind, num, key.encoder, value.encoder, ty
+            );
+            (ty, decoder, encoder)
+        } else {
+            let value = track!(self.value(module, scope, f, &f.ty))?;
+            let (field_decoder, field_encoder) = value.field_codec(rt, &num);
+            if f.is_repeated() {
+                let ty = format!("Vec<{}>", value.ty);
+                let decoder = if value.kind == ValueKind::Numeric {
+                    format!(
+                        "{}::field::PackedFieldDecoder<{}, {}, {}>",
+                        rt, num, value.decoder, ty
+                    )
+                } else {
+                    format!("{}::field::Repeated<{}, {}>", rt, field_decoder, ty)
+                };
+                let encoder = if value.kind == ValueKind::Numeric && f.is_packed(syntax) {
+                    format!(
+                        "{}::field::PackedFieldEncoder<{}, {}, {}>",
+                        rt, num, value.encoder, ty
+                    )
+                } else {
+                    format!("{}::field::SizedRepeated<{}, {}>", rt, field_encoder, ty)
+                };
+                json = JsonKind::Repeated;
+                (ty, decoder, encoder)
+            } else if f.label == Some(Label::Required) {
+                if value.boxed {
+                    from_decoded = "Box::new({})".to_owned();
+                    to_encoded = "*{}".to_owned();
+                }
+                json = JsonKind::Required(value.boxed);
+                (value.member_ty(), field_decoder, field_encoder)
+            } else if f.label == Some(Label::Optional) || value.kind == ValueKind::Message {
+                if value.boxed {
+                    from_decoded = "{}.map(Box::new)".to_owned();
+                    to_encoded = "{}.map(|v| *v)".to_owned();
+                }
+                json = JsonKind::Optional(value.boxed);
+                let ty = format!("Option<{}>", value.member_ty());
+                let decoder = format!("{}::field::Optional<{}>", rt, field_decoder);
+                let encoder = format!("{}::field::Optional<{}>", rt, field_encoder);
+                (ty, decoder, encoder)
+            } else {
+                let decoder = format!("{}::field::MaybeDefault<{}>", rt, field_decoder);
+                let encoder = format!("{}::field::MaybeDefault<{}>", rt, field_encoder);
+                (value.ty, decoder, encoder)
+            }
+        };
+        Ok(Member {
+            name: field_ident(&f.name),
+            ty,
+            doc: format!("`{}`", field_decl(f)),
+            decoder,
+            encoder,
+            from_decoded,
+            to_encoded,
+            order: f.number,
+            json: Some(JsonMember {
+                fields: vec![f.name.clone()],
+                oneof: None,
+                kind: json,
+            }),
+        })
+    }
+
+    fn oneof_member(
+        &mut self,
+        module: &[String],
+        scope: &str,
+        message_name: &str,
+        o: &Oneof,
+    ) -> Result<Member> {
+        let rt = self.rt;
+        let mut nested_module = module.to_owned();
+        nested_module.push(module_name(message_name));
+        let enum_ident = type_ident(&variant_ident(&o.name));
+        let enum_path = relative_path(module, &nested_module, &enum_ident);
+
+        let mut decoders = Vec::new();
+        let mut encoders = Vec::new();
+        let mut variants = Vec::new();
+        for f in &o.fields {
+            let num = self.field_num_type(f.number);
+            let value = track!(self.value(module, scope, f, &f.ty))?;
+            let (decoder, encoder) = value.field_codec(rt, &num);
+            decoders.push(decoder);
+            encoders.push(encoder);
+
+            let variant_ty = track!(self.value(&nested_module, scope, f, &f.ty))?.member_ty();
+            variants.push((f, variant_ident(&f.name), variant_ty, value.boxed));
+        }
+
+        let mut item = format!(
+            "/// `{}` oneof of `{}` message.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum {} {{
+",
+            o.name, message_name, enum_ident
+        );
+        for (f, variant, ty, _) in &variants {
+            item.push_str(&format!(
+                "    /// `{}`\n    {}({}),\n",
+                field_decl(f),
+                variant,
+                ty
+            ));
+        }
+        item.push('}');
+        self.root.get_mut(&nested_module).items.push(item);
+
+        let (decoder, encoder, from_decoded, to_encoded) = if variants.len() == 1 {
+            let variant = format!("{}::{}", enum_path, variants[0].1);
+            let (from_decoded, to_encoded) = if variants[0].3 {
+                (
+                    format!("{{}}.map(|v| {}(Box::new(v)))", variant),
+                    format!("{{}}.map(|{}(v)| *v)", variant),
+                )
+            } else {
+                (
+                    format!("{{}}.map({})", variant),
+                    format!("{{}}.map(|{}(v)| v)", variant),
+                )
+            };
+            (
+                decoders.remove(0),
+                encoders.remove(0),
+                from_decoded,
+                to_encoded,
+            )
+        } else {
+            let mut from_arms = String::new();
+            let mut to_arms = String::new();
+            for (i, (_, variant, _, boxed)) in variants.iter().enumerate() {
+                let (wrapped, unwrapped) = if *boxed {
+                    ("Box::new(v)", "*v")
+                } else {
+                    ("v", "v")
+                };
+                from_arms.push_str(&format!(
+                    "    {} => {}::{}({}),\n",
+                    branch_pattern(rt, i, variants.len(), "v"),
+                    enum_path,
+                    variant,
+                    wrapped
+                ));
+                to_arms.push_str(&format!(
+                    "    {}::{}(v) => {},\n",
+                    enum_path,
+                    variant,
+                    branch_pattern(rt, i, variants.len(), unwrapped)
+                ));
+            }
+            let oneof = format!("{}::field::Oneof<(", rt);
+            (
+                layout(&decoders, &oneof, ")>"),
+                layout(&encoders, &oneof, ")>"),
+                format!("{{}}.map(|v| match v {{\n{}}})", from_arms),
+                format!("{{}}.map(|v| match v {{\n{}}})", to_arms),
+            )
+        };
+        Ok(Member {
+            name: field_ident(&o.name),
+            ty: format!("Option<{}>", enum_path),
+            doc: format!("`oneof {}`", o.name),
+            decoder: format!("{}::field::Optional<{}>", rt, decoder),
+            encoder: format!("{}::field::Optional<{}>", rt, encoder),
+            from_decoded,
+            to_encoded,
+            order: o.fields.iter().map(|f| f.number).min().unwrap_or(0),
+            json: Some(JsonMember {
+                fields: o.fields.iter().map(|f| f.name.clone()).collect(),
+                oneof: Some(o.name.clone()),
+                kind: JsonKind::Oneof(
+                    variants
+                        .iter()
+                        .map(|v| (format!("{}::{}", enum_path, v.1), v.3))
+                        .collect(),
+                ),
+            }),
+        })
+    }
+
+    fn value(&self, module: &[String], scope: &str, f: &Field, ty: &FieldType) -> Result<Value> {
+        let rt = self.rt;
+        let (ty, codec, kind) = match *ty {
+            FieldType::Double => ("f64", "Double", ValueKind::Numeric),
+            FieldType::Float => ("f32", "Float", ValueKind::Numeric),
+            FieldType::Int32 => ("i32", "Int32", ValueKind::Numeric),
+            FieldType::Int64 => ("i64", "Int64", ValueKind::Numeric),
+            FieldType::Uint32 => ("u32", "Uint32", ValueKind::Numeric),
+            FieldType::Uint64 => ("u64", "Uint64", ValueKind::Numeric),
+            FieldType::Sint32 => ("i32", "Sint32", ValueKind::Numeric),
+            FieldType::Sint64 => ("i64", "Sint64", ValueKind::Numeric),
+            FieldType::Fixed32 => ("u32", "Fixed32", ValueKind::Numeric),
+            FieldType::Fixed64 => ("u64", "Fixed64", ValueKind::Numeric),
+            FieldType::Sfixed32 => ("i32", "Sfixed32", ValueKind::Numeric),
+            FieldType::Sfixed64 => ("i64", "Sfixed64", ValueKind::Numeric),
+            FieldType::Bool => ("bool", "Bool", ValueKind::Numeric),
+            FieldType::String => ("String", "String", ValueKind::LengthDelimited),
+            FieldType::Bytes => ("Vec<u8>", "Bytes", ValueKind::LengthDelimited),
+            FieldType::Named(ref name) => return track!(self.named_value(module, scope, f, name)),
+            FieldType::Map(..) => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Map fields cannot be used here",
+                f.position
+            ),
+            FieldType::Group(_) => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Group fields are not supported",
+                f.position
+            ),
+        };
+        Ok(Value {
+            ty: ty.to_owned(),
+            decoder: format!("{}::scalar::{}Decoder", rt, codec),
+            encoder: format!("{}::scalar::{}Encoder", rt, codec),
+            kind,
+            boxed: false,
+        })
+    }
+
+    fn named_value(&self, module: &[String], scope: &str, f: &Field, name: &str) -> Result<Value> {
+        let rt = self.rt;
+        let (full_name, t) = track_assert_some!(
+            self.table.resolve(scope, name),
+            ErrorKind::InvalidInput,
+            "{}: Unknown type: {}",
+            f.position,
+            name
+        );
+        let path =
+            |suffix: &str| relative_path(module, &t.module, &format!("{}{}", t.ident, suffix));
+        let value = match t.kind {
+            TypeKind::Message => {
+                // `scope` is the full name of the message that has the field
+                let boxed = self.table.refers_to(&full_name, scope);
+                let (mut decoder, mut encoder) = (path("Decoder"), path("Encoder"));
+                if boxed {
+                    decoder = format!("{}::message::BoxedMessageDecoder<{}>", rt, decoder);
+                    encoder = format!("{}::message::BoxedMessageEncoder<{}>", rt, encoder);
+                }
+                Value {
+                    ty: path(""),
+                    decoder,
+                    encoder,
+                    kind: ValueKind::Message,
+                    boxed,
+                }
+            }
+            TypeKind::Enum => Value {
+                ty: path(""),
+                decoder: format!("{}::scalar::EnumDecoder<{}>", rt, path("")),
+                encoder: format!("{}::scalar::EnumEncoder<{}>", rt, path("")),
+                kind: ValueKind::Numeric,
+                boxed: false,
+            },
+            TypeKind::Builtin(b) => Value {
+                ty: b.item.to_owned(),
+                decoder: b.decoder.replace("{}", rt),
+                encoder: b.encoder.replace("{}", rt),
+                kind: ValueKind::Message,
+                boxed: false,
+            },
+        };
+        Ok(value)
+    }
+
+    fn field_num_type(&self, n: u32) -> String {
+        if n <= 16 {
+            format!("{}::field::num::F{}", self.rt, n)
+        } else {
+            format!("{}::field::num::Fnum<{}>", self.rt, n)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Module {
+    doc: String,
+    items: Vec<String>,
+    children: BTreeMap<String, Module>,
+}
+impl Module {
+    fn get(&self, path: &[String]) -> Option<&Module> {
+        if let Some((first, rest)) = path.split_first() {
+            self.children.get(first).and_then(|m| m.get(rest))
+        } else {
+            Some(self)
+        }
+    }
+
+    fn get_mut(&mut self, path: &[String]) -> &mut Module {
+        if let Some((first, rest)) = path.split_first() {
+            self.children
+                .entry(first.clone())
+                .or_default()
+                .get_mut(rest)
+        } else {
+            self
+        }
+    }
+
+    fn render(&self, out: &mut String, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let mut first = true;
+        for item in &self.items {
+            out.push('\n');
+            if !first {
+                out.push('\n');
+            }
+            first = false;
+            for line in item.lines() {
+                if !line.is_empty() {
+                    out.push_str(&indent);
+                    out.push_str(line);
+                }
+                out.push('\n');
+            }
+            out.pop();
+        }
+        for (name, child) in &self.children {
+            out.push('\n');
+            if !first {
+                out.push('\n');
+            }
+            first = false;
+            out.push_str(&format!("{}/// {}\n", indent, child.doc));
+            out.push_str(&format!("{}pub mod {} {{", indent, name));
+            child.render(out, depth + 1);
+            out.push_str(&format!("\n{}}}", indent));
+        }
+        if depth == 0 {
+            out.push('\n');
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Member {
+    name: String,
+    ty: String,
+    doc: String,
+    decoder: String,
+    encoder: String,
+
+    // Conversions between the member value and the item of the field codec (`{}` is the value)
+    from_decoded: String,
+    to_encoded: String,
+
+    // Members are sorted by this key
+    order: u32,
+
+    // `None` if the member is not mapped to JSON
+    json: Option<JsonMember>,
+}
+
+#[derive(Debug)]
+struct JsonMember {
+    // Names of the fields that the member represents
+    fields: Vec<String>,
+    oneof: Option<String>,
+    kind: JsonKind,
+}
+
+// The `bool` values indicate whether the values are boxed.
+#[derive(Debug)]
+enum JsonKind {
+    // Fields that do not track presence, and map fields
+    Plain,
+    Required(bool),
+    Optional(bool),
+    Repeated,
+    // Paths of the variants of the oneof enum
+    Oneof(Vec<(String, bool)>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ValueKind {
+    Numeric,
+    LengthDelimited,
+    Message,
+}
+
+#[derive(Debug)]
+struct Value {
+    ty: String,
+    decoder: String,
+    // This always implements `SizedEncode`
+    encoder: String,
+    kind: ValueKind,
+
+    // Whether this is a message that refers back to the message which has the field
+    boxed: bool,
+}
+impl Value {
+    // Returns the type of singular members (and oneof variants) that have this value.
+    fn member_ty(&self) -> String {
+        if self.boxed {
+            format!("Box<{}>", self.ty)
+        } else {
+            self.ty.clone()
+        }
+    }
+
+    fn field_codec(&self, rt: &str, num: &str) -> (String, String) {
+        let kind = if self.kind == ValueKind::Message {
+            "MessageField"
+        } else {
+            "Field"
+        };
+        (
+            format!("{}::field::{}Decoder<{}, {}>", rt, kind, num, self.decoder),
+            format!("{}::field::{}Encoder<{}, {}>", rt, kind, num, self.encoder),
+        )
+    }
+}
+
+fn field_decl(f: &Field) -> String {
+    let label = match f.label {
+        None => "",
+        Some(Label::Required) => "required ",
+        Some(Label::Optional) => "optional ",
+        Some(Label::Repeated) => "repeated ",
+    };
+    format!("{}{} {} = {};", label, type_name(&f.ty), f.name, f.number)
+}
+
+fn type_name(ty: &FieldType) -> String {
+    let name = match *ty {
+        FieldType::Double => "double",
+        FieldType::Float => "float",
+        FieldType::Int32 => "int32",
+        FieldType::Int64 => "int64",
+        FieldType::Uint32 => "uint32",
+        FieldType::Uint64 => "uint64",
+        FieldType::Sint32 => "sint32",
+        FieldType::Sint64 => "sint64",
+        FieldType::Fixed32 => "fixed32",
+        FieldType::Fixed64 => "fixed64",
+        FieldType::Sfixed32 => "sfixed32",
+        FieldType::Sfixed64 => "sfixed64",
+        FieldType::Bool => "bool",
+        FieldType::String => "string",
+        FieldType::Bytes => "bytes",
+        FieldType::Named(ref name) => return name.clone(),
+        FieldType::Map(ref k, ref v) => return format!("map<{}, {}>", type_name(k), type_name(v)),
+        FieldType::Group(ref name) => return format!("group {}", name),
+    };
+    name.to_owned()
+}
+
+// Lays out a (possibly nested) tuple which has at most `MAX_TUPLE_LEN` elements.
+//
+// `open` and `close` are the delimiters of each tuple (e.g., `(` and `)`).
+// The result is split into multiple lines if it is too long.
+fn layout(items: &[String], open: &str, close: &str) -> String {
+    let oneline = match items.len() {
+        0 => format!("{}{}", open, close),
+        1 => format!("{}{},{}", open, items[0], close),
+        _ => format!("{}{}{}", open, items.join(", "), close),
+    };
+    if items.len() <= MAX_TUPLE_LEN && oneline.len() <= MAX_LINE_LEN && !oneline.contains('\n') {
+        return oneline;
+    }
+
+    let mut s = format!("{}\n", open);
+    let (head, rest) = if items.len() <= MAX_TUPLE_LEN {
+        (items, None)
+    } else {
+        let rest = layout(&items[MAX_TUPLE_LEN - 1..], open, close);
+        (&items[..MAX_TUPLE_LEN - 1], Some(rest))
+    };
+    for item in head.iter().chain(rest.as_ref()) {
+        s.push_str(&format!("    {},\n", indent(item, 4)));
+    }
+    s.push_str(close);
+    s
+}
+
+// Indents the second and subsequent lines of `s`.
+fn indent(s: &str, n: usize) -> String {
+    s.replace('\n', &format!("\n{}", " ".repeat(n)))
+}
+
+// Makes the pattern (or expression) of the `i`-th branch of a (possibly nested) `Oneof` which has `n` fields.
+//
+// `v` is the value of the branch.
+fn branch_pattern(rt: &str, i: usize, n: usize, v: &str) -> String {
+    let branch = format!("{}::field::branch::Branch{}", rt, n.min(MAX_TUPLE_LEN));
+    if n <= MAX_TUPLE_LEN || i < MAX_TUPLE_LEN - 1 {
+        format!("{}::{}({})", branch, branch_variant(i), v)
+    } else {
+        let inner = branch_pattern(rt, i - (MAX_TUPLE_LEN - 1), n - (MAX_TUPLE_LEN - 1), v);
+        format!(
+            "{}::{}({})",
+            branch,
+            branch_variant(MAX_TUPLE_LEN - 1),
+            inner
+        )
+    }
+}
+
+// 0 => `A`, 1 => `B`, ..., 25 => `Z`, 26 => `AA`, ...
+fn branch_variant(i: usize) -> String {
+    let letter = |i: usize| (b'A' + i as u8) as char;
+    if i < 26 {
+        letter(i).to_string()
+    } else {
+        format!("A{}", letter(i - 26))
+    }
+}
+
+fn relative_path(from: &[String], to: &[String], ident: &str) -> String {
+    let common = from.iter().zip(to).take_while(|(a, b)| a == b).count();
+    let mut path = "super::".repeat(from.len() - common);
+    if path.is_empty() && common < to.len() {
+        path.push_str("self::");
+    }
+    for m in &to[common..] {
+        path.push_str(m);
+        path.push_str("::");
+    }
+    path.push_str(ident);
+    path
+}
+
+pub fn module_name(name: &str) -> String {
+    escape_keyword(snake_case(name))
+}
+
+pub fn type_ident(name: &str) -> String {
+    escape_keyword(name.to_owned())
+}
+
+fn field_ident(name: &str) -> String {
+    escape_keyword(snake_case(name))
+}
+
+fn variant_ident(name: &str) -> String {
+    escape_keyword(upper_camel_case(name))
+}
+
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut s = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = if i == 0 { None } else { Some(chars[i - 1]) };
+            let next = chars.get(i + 1);
+            let boundary = match prev {
+                None | Some('_') => false,
+                Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit() => true,
+                Some(p) => p.is_ascii_uppercase() && next.is_some_and(|n| n.is_ascii_lowercase()),
+            };
+            if boundary {
+                s.push('_');
+            }
+            s.push(c.to_ascii_lowercase());
+        } else {
+            s.push(c);
+        }
+    }
+    s
+}
+
+fn upper_camel_case(name: &str) -> String {
+    let mut s = String::new();
+    for part in name.split('_').filter(|p| !p.is_empty()) {
+        let all_upper = !part.chars().any(|c| c.is_ascii_lowercase());
+        let mut chars = part.chars();
+        if let Some(c) = chars.next() {
+            s.push(c.to_ascii_uppercase());
+        }
+        for c in chars {
+            s.push(if all_upper { c.to_ascii_lowercase() } else { c });
+        }
+    }
+    s
+}
+
+fn escape_keyword(ident: String) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let",
+        "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+        "virtual", "where", "while", "yield",
+    ];
+    match ident.as_str() {
+        "self" | "super" | "crate" | "Self" => format!("{}_", ident),
+        s if KEYWORDS.contains(&s) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naming_works() {
+        assert_eq!(snake_case("SearchRequest"), "search_request");
+        assert_eq!(snake_case("HTTPRequest"), "http_request");
+        assert_eq!(snake_case("pageNumber"), "page_number");
+        assert_eq!(snake_case("page_number"), "page_number");
+        assert_eq!(upper_camel_case("UNIVERSAL"), "Universal");
+        assert_eq!(upper_camel_case("FOO_BAR2"), "FooBar2");
+        assert_eq!(upper_camel_case("sub_project"), "SubProject");
+        assert_eq!(field_ident("type"), "r#type");
+        assert_eq!(module_name("Self"), "self_");
+    }
+
+    #[test]
+    fn unrecognized_enum_value_conflicts() {
+        let emit = |text: &str| {
+            let file = track_try_unwrap!(crate::schema::parse(text));
+            let files = vec![("foo.proto".to_owned(), file)];
+            let table = track_try_unwrap!(TypeTable::new(&files));
+            let mut emitter = Emitter::new("crate", &table, false, false);
+            emitter.emit_file(&files[0].1)
+        };
+        assert!(emit("syntax = \"proto3\"; enum Foo { UNRECOGNIZED = 0; }").is_err());
+        assert!(emit("syntax = \"proto2\"; enum Foo { UNRECOGNIZED = 0; }").is_ok());
+        assert!(emit("syntax = \"proto3\"; enum Foo { UNKNOWN = 0; }").is_ok());
+    }
+
+    #[test]
+    fn relative_path_works() {
+        let m = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(relative_path(&m(&["a"]), &m(&["a"]), "Foo"), "Foo");
+        assert_eq!(
+            relative_path(&m(&["a"]), &m(&["a", "b"]), "Foo"),
+            "self::b::Foo"
+        );
+        assert_eq!(
+            relative_path(&m(&["a", "b"]), &m(&["c"]), "Foo"),
+            "super::super::c::Foo"
+        );
+    }
+}
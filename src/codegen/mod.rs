@@ -0,0 +1,659 @@
+//! Rust code generator for `.proto` files.
+//!
+//! [`Generator`](./struct.Generator.html) reads `.proto` files, and generates Rust structs and
+//! enums that represent the messages and enums defined in the files,
+//! together with the encoders and decoders for them.
+//!
+//! The generated codecs are built from the components of this crate
+//! (e.g., `MessageDecoder`, `Fields`, `Oneof`, `MapFieldDecoder` and `PackedFieldDecoder`)
+//! in the same way as the hand-written ones in [`wellknown`](../wellknown/index.html).
+//! `protoc` is not required.
+//!
+//! # Examples
+//!
+//! `build.rs`:
+//!
+//! ```no_run
+//! # extern crate protobuf_codec;
+//! use protobuf_codec::codegen::Generator;
+//! use std::env;
+//! use std::path::Path;
+//!
+//! # fn main() {
+//! println!("cargo:rerun-if-changed=protos/search.proto");
+//!
+//! let out = Path::new(&env::var("OUT_DIR").unwrap()).join("protos.rs");
+//! Generator::new()
+//!     .include_dir("protos/")
+//!     .input("protos/search.proto")
+//!     .generate_to_file(out)
+//!     .unwrap();
+//! # }
+//! ```
+//!
+//! `src/lib.rs`:
+//!
+//! ```ignore
+//! pub mod protos {
+//!     include!(concat!(env!("OUT_DIR"), "/protos.rs"));
+//! }
+//! ```
+//!
+//! The generated code depends on `protobuf_codec` and `bytecodec` crates.
+//!
+//! # Generated items
+//!
+//! For each message `Foo`, the following items are generated:
+//!
+//! - `Foo`: A struct that has a public member for each field
+//! - `FooDecoder`: The decoder for `Foo` (implements `MessageDecode`)
+//! - `FooEncoder`: The encoder for `Foo` (implements `MessageEncode`)
+//!
+//! The nested types of `Foo` and the enums for its oneofs are placed in the `foo` module.
+//! Packages are mapped to nested modules (e.g., `package foo.bar;` is mapped to `foo::bar`).
+//!
+//! Each field is mapped to a member as follows:
+//!
+//! | Field                                      | Rust type                  |
+//! |--------------------------------------------|----------------------------|
+//! | singular scalar or enum (proto3)           | `T`                        |
+//! | `optional` scalar or enum                  | `Option<T>`                |
+//! | `required` field (proto2)                  | `T`                        |
+//! | singular message                           | `Option<T>`                |
+//! | `repeated`                                 | `Vec<T>`                   |
+//! | `map<K, V>`                                | `HashMap<K, V>`            |
+//! | `oneof foo`                                | `Option<self::msg::Foo>`   |
+//!
+//! Singular fields and oneof variants whose message types refer back to the containing message
+//! (e.g., `Node parent = 1;` in `message Node`) are boxed (e.g., `Option<Box<Node>>`),
+//! so recursive message types can be used.
+//!
+//! Enums are mapped to Rust enums that have a variant for each value.
+//! Proto3 enums are open: they also have `Unrecognized(i32)` variant which keeps
+//! the values that are not defined in the schema.
+//! Proto2 enums are closed, and their decoders reject unknown values.
+//!
+//! `google.protobuf.Duration` and `google.protobuf.Empty` are mapped to
+//! `std::time::Duration` and `()` respectively.
+//!
+//! If [`Generator::preserve_unknown_fields`](./struct.Generator.html#method.preserve_unknown_fields)
+//! is enabled, each message also has `unknown_fields: UnknownFields` member.
+//!
+//! If [`Generator::json_mapping`](./struct.Generator.html#method.json_mapping) is enabled,
+//! the messages and enums also implement `json::JsonMapping`,
+//! so they can be converted to and from JSON by `json::JsonEncoder` and `json::JsonDecoder`.
+//!
+//! # Limitations
+//!
+//! - Default values specified by the `default` option are ignored
+//! - Unknown values of proto2 enums are rejected by the decoders instead of being kept as unknown fields
+//! - Groups, extensions and services are not supported
+use self::emit::Emitter;
+use self::types::TypeTable;
+use crate::schema::{self, ProtoFile};
+use bytecodec::{Error, ErrorKind, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod emit;
+mod types;
+
+/// Rust code generator for `.proto` files.
+///
+/// See [the module documentation](./index.html) for details.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    include_dirs: Vec<PathBuf>,
+    inputs: Vec<PathBuf>,
+    crate_path: String,
+    unknown_fields: bool,
+    json_mapping: bool,
+}
+impl Generator {
+    /// Makes a new `Generator` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a directory where imported files are searched.
+    ///
+    /// If no directories are added, the parent directories of the input files are used.
+    pub fn include_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        self.include_dirs.push(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Adds a `.proto` file to be compiled.
+    ///
+    /// The files imported by the file are compiled too.
+    pub fn input<P: AsRef<Path>>(&mut self, file: P) -> &mut Self {
+        self.inputs.push(file.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the path of `protobuf_codec` crate used in the generated code.
+    ///
+    /// The default value is `::protobuf_codec`.
+    pub fn crate_path(&mut self, path: &str) -> &mut Self {
+        self.crate_path = path.to_owned();
+        self
+    }
+
+    /// Makes the generated messages preserve unknown fields.
+    ///
+    /// If `true`, each message has `unknown_fields: UnknownFields` member which holds
+    /// the fields that are not defined in the schema, and the encoder re-emits them.
+    ///
+    /// The default value is `false`.
+    pub fn preserve_unknown_fields(&mut self, enabled: bool) -> &mut Self {
+        self.unknown_fields = enabled;
+        self
+    }
+
+    /// Sets whether to implement `json::JsonMapping` for the generated messages and enums.
+    ///
+    /// If `true`, the messages can be converted to and from JSON directly
+    /// (e.g., by `json::JsonEncoder` and `json::JsonDecoder`) without descriptors.
+    /// Unknown fields are not mapped to JSON.
+    ///
+    /// The default value is `false`.
+    pub fn json_mapping(&mut self, enabled: bool) -> &mut Self {
+        self.json_mapping = enabled;
+        self
+    }
+
+    /// Generates Rust code from the input files.
+    pub fn generate(&self) -> Result<String> {
+        let files = track!(self.load_files())?;
+        let table = track!(TypeTable::new(&files))?;
+        let mut emitter = Emitter::new(
+            &self.crate_path,
+            &table,
+            self.unknown_fields,
+            self.json_mapping,
+        );
+        for (name, file) in &files {
+            track!(emitter.emit_file(file), "file={:?}", name)?;
+        }
+        Ok(emitter.finish())
+    }
+
+    /// Generates Rust code from the input files, and writes it to `path`.
+    pub fn generate_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let code = track!(self.generate())?;
+        track!(fs::write(path.as_ref(), code).map_err(Error::from); path.as_ref())?;
+        Ok(())
+    }
+
+    fn load_files(&self) -> Result<Vec<(String, ProtoFile)>> {
+        let mut loader = Loader {
+            include_dirs: Vec::new(),
+            files: Vec::new(),
+            loaded: HashSet::new(),
+        };
+        for input in &self.inputs {
+            loader.include_dirs = if self.include_dirs.is_empty() {
+                let dir = input.parent().unwrap_or_else(|| Path::new(""));
+                vec![dir.to_path_buf()]
+            } else {
+                self.include_dirs.clone()
+            };
+            track!(loader.load(&self.import_name(input), input))?;
+        }
+        Ok(loader.files)
+    }
+
+    fn import_name(&self, path: &Path) -> String {
+        let name = self
+            .include_dirs
+            .iter()
+            .filter_map(|dir| path.strip_prefix(dir).ok())
+            .next()
+            .or_else(|| path.file_name().map(Path::new))
+            .unwrap_or(path);
+        name.to_string_lossy().replace('\\', "/")
+    }
+}
+impl Default for Generator {
+    fn default() -> Self {
+        Generator {
+            include_dirs: Vec::new(),
+            inputs: Vec::new(),
+            crate_path: "::protobuf_codec".to_owned(),
+            unknown_fields: false,
+            json_mapping: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Loader {
+    include_dirs: Vec<PathBuf>,
+    files: Vec<(String, ProtoFile)>,
+    loaded: HashSet<String>,
+}
+impl Loader {
+    fn load(&mut self, name: &str, path: &Path) -> Result<()> {
+        if !self.loaded.insert(name.to_owned()) {
+            return Ok(());
+        }
+
+        let text = track!(fs::read_to_string(path).map_err(Error::from); path)?;
+        let file = track!(schema::parse(&text); path)?;
+        for import in &file.imports {
+            if types::is_builtin_file(&import.path) {
+                continue;
+            }
+            let path = self
+                .include_dirs
+                .iter()
+                .map(|dir| dir.join(&import.path))
+                .find(|path| path.is_file());
+            let path = track_assert_some!(
+                path,
+                ErrorKind::InvalidInput,
+                "{}: Imported file is not found: {:?}",
+                import.position,
+                import.path
+            );
+            track!(self.load(&import.path, &path))?;
+        }
+        self.files.push((name.to_owned(), file));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytecodec::io::{IoDecodeExt, IoEncodeExt};
+    use bytecodec::EncodeExt;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    #[allow(dead_code, missing_docs)]
+    mod generated {
+        include!("testdata/example.rs");
+    }
+    #[allow(dead_code, missing_docs)]
+    mod generated_proxy {
+        include!("testdata/proxy.rs");
+    }
+    use self::generated::example::*;
+
+    const TESTDATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/codegen/testdata");
+
+    fn generate(name: &str, unknown_fields: bool) -> String {
+        track_try_unwrap!(Generator::new()
+            .include_dir(TESTDATA_DIR)
+            .input(format!("{}/{}.proto", TESTDATA_DIR, name))
+            .crate_path("crate")
+            .preserve_unknown_fields(unknown_fields)
+            .json_mapping(true)
+            .generate())
+    }
+
+    #[test]
+    fn generated_code_is_up_to_date() {
+        // Run with `UPDATE_TESTDATA=1` to regenerate `testdata/*.rs`.
+        for &(name, unknown_fields) in &[("example", false), ("proxy", true)] {
+            let path = format!("{}/{}.rs", TESTDATA_DIR, name);
+            let code = generate(name, unknown_fields);
+            if std::env::var_os("UPDATE_TESTDATA").is_some() {
+                track_try_unwrap!(fs::write(&path, &code).map_err(Error::from));
+            }
+            let expected = track_try_unwrap!(fs::read_to_string(&path).map_err(Error::from));
+            assert!(code == expected, "{} is outdated", path);
+        }
+
+        // The benchmarks use the generated code as an external crate would.
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/testdata");
+        let path = format!("{}/nested.rs", dir);
+        let code = track_try_unwrap!(Generator::new()
+            .input(format!("{}/nested.proto", dir))
+            .generate());
+        if std::env::var_os("UPDATE_TESTDATA").is_some() {
+            track_try_unwrap!(fs::write(&path, &code).map_err(Error::from));
+        }
+        let expected = track_try_unwrap!(fs::read_to_string(&path).map_err(Error::from));
+        assert!(code == expected, "{} is outdated", path);
+    }
+
+    #[test]
+    fn generated_codec_is_wire_compatible() {
+        let request = SearchRequest {
+            query: "foo".to_owned(),
+            page_number: 3,
+            result_per_page: 10,
+            ..SearchRequest::default()
+        };
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(SearchRequestEncoder::with_item(request.clone()));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, [10, 3, 102, 111, 111, 16, 3, 24, 10]);
+
+        let mut decoder = SearchRequestDecoder::new();
+        let decoded = track_try_unwrap!(decoder.decode_exact(&buf[..]));
+        assert_eq!(decoded, request);
+    }
+
+    #[test]
+    fn generated_wide_codec_works() {
+        let wide = Wide {
+            f1: 1,
+            f33: 33,
+            choice: Some(wide::Choice::C133(1)),
+            ..Wide::default()
+        };
+
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(WideEncoder::with_item(wide.clone()));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, [8, 1, 136, 2, 33, 168, 8, 1]);
+
+        let mut decoder = WideDecoder::new();
+        let decoded = track_try_unwrap!(decoder.decode_exact(&buf[..]));
+        assert_eq!(decoded, wide);
+    }
+
+    #[test]
+    fn generated_codec_roundtrip_works() {
+        let mut projects = HashMap::new();
+        projects.insert(
+            "foo".to_owned(),
+            search_request::Project {
+                name: "bar".to_owned(),
+                elapsed: Some(Duration::new(3, 4)),
+            },
+        );
+        let mut counts = HashMap::new();
+        counts.insert(7, Corpus::Web);
+        let request = SearchRequest {
+            query: "foo".to_owned(),
+            page_number: 3,
+            result_per_page: 10,
+            corpus: Corpus::Images,
+            samples: vec![1, -2, 300],
+            tags: vec!["a".to_owned(), "b".to_owned()],
+            projects,
+            counts,
+            owner: Some(search_request::Project::default()),
+            score: Some(1.5),
+            source: Some(search_request::Source::SubProject(
+                search_request::Project {
+                    name: "baz".to_owned(),
+                    elapsed: None,
+                },
+            )),
+            many: Some(Many {
+                f1: 1,
+                f20: Some(-20),
+                f300: vec![Level::High, Level::Low],
+                f4000: vec![4000, 0],
+                ..Many::default()
+            }),
+            history: vec![Many::default(), Many::default()],
+            nothing: Some(()),
+        };
+
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(SearchRequestEncoder::with_item(request.clone()));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+
+        let mut decoder = SearchRequestDecoder::new();
+        let decoded = track_try_unwrap!(decoder.decode_exact(&buf[..]));
+        assert_eq!(decoded, request);
+    }
+
+    #[test]
+    fn generated_codec_preserves_unknown_fields() {
+        use self::generated_proxy::proxy;
+
+        let request = SearchRequest {
+            query: "foo".to_owned(),
+            page_number: 3,
+            corpus: Corpus::Images,
+            samples: vec![1, -2, 300],
+            score: Some(1.5),
+            ..SearchRequest::default()
+        };
+        let mut bytes = Vec::new();
+        let mut encoder = track_try_unwrap!(SearchRequestEncoder::with_item(request));
+        track_try_unwrap!(encoder.encode_all(&mut bytes));
+
+        // Decodes the message with the older schema which only knows `query` field
+        let mut decoder = proxy::SearchRequestDecoder::new();
+        let old = track_try_unwrap!(decoder.decode_exact(&bytes[..]));
+        assert_eq!(old.query, "foo");
+        assert_eq!(old.unknown_fields.len(), 4);
+
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(proxy::ProxyEncoder::with_item(proxy::Proxy {
+            request: Some(old),
+            ..proxy::Proxy::default()
+        }));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf[0], 10);
+        assert_eq!(&buf[2..], &bytes[..]);
+    }
+
+    #[test]
+    fn generated_enum_works() {
+        use crate::scalar::Enum;
+
+        assert_eq!(Corpus::default(), Corpus::Universal);
+        assert_eq!(Corpus::from_i32(2), Some(Corpus::Images));
+        assert_eq!(Corpus::from_i32(3), Some(Corpus::Unrecognized(3)));
+        assert_eq!(Corpus::PICTURES, Corpus::Images);
+        assert_eq!(Corpus::Web.to_i32(), 1);
+        assert_eq!(Corpus::Unrecognized(-1).to_i32(), -1);
+
+        // Proto2 enums are closed
+        assert_eq!(Level::from_i32(100), None);
+
+        // Unknown values of proto3 enums are preserved
+        let bytes = [32, 5, 66, 4, 8, 7, 16, 6];
+        let mut decoder = SearchRequestDecoder::new();
+        let request = track_try_unwrap!(decoder.decode_exact(&bytes[..]));
+        assert_eq!(request.corpus, Corpus::Unrecognized(5));
+        assert_eq!(request.counts.get(&7), Some(&Corpus::Unrecognized(6)));
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(SearchRequestEncoder::with_item(request));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, bytes);
+    }
+
+    #[test]
+    fn generated_recursive_codec_works() {
+        let leaf = node::Leaf {
+            owner: Some(Box::new(Node {
+                name: "owner".to_owned(),
+                ..Node::default()
+            })),
+            corpus: Corpus::Web,
+        };
+        let mut links = HashMap::new();
+        links.insert(
+            "self".to_owned(),
+            Node {
+                name: "link".to_owned(),
+                extra: Some(node::Extra::Leaf(Box::new(leaf.clone()))),
+                ..Node::default()
+            },
+        );
+        let node = Node {
+            name: "root".to_owned(),
+            parent: Some(Box::new(Node {
+                name: "parent".to_owned(),
+                parent: Some(Box::new(Node::default())),
+                ..Node::default()
+            })),
+            children: vec![
+                Node {
+                    name: "child".to_owned(),
+                    children: vec![Node::default()],
+                    ..Node::default()
+                },
+                Node::default(),
+            ],
+            links,
+            extra: Some(node::Extra::Alias(Box::new(Node {
+                name: "alias".to_owned(),
+                ..Node::default()
+            }))),
+            first_leaf: Some(Box::new(leaf)),
+        };
+
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(NodeEncoder::with_item(node.clone()));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+
+        let mut decoder = NodeDecoder::new();
+        let decoded = track_try_unwrap!(decoder.decode_exact(&buf[..]));
+        assert_eq!(decoded, node);
+
+        // `Node { parent: Node { name: "a" } }`
+        let mut decoder = NodeDecoder::new();
+        let decoded = track_try_unwrap!(decoder.decode_exact(&[18, 3, 10, 1, 97][..]));
+        assert_eq!(decoded.parent.map(|p| p.name), Some("a".to_owned()));
+    }
+
+    #[test]
+    fn generated_json_mapping_works() {
+        use crate::dynamic::DescriptorPool;
+        use crate::json::{JsonDecoder, JsonEncoder, JsonMessageEncoder, JsonOptions};
+        use bytecodec::DecodeExt;
+        use std::sync::Arc;
+
+        let mut projects = HashMap::new();
+        projects.insert(
+            "b".to_owned(),
+            search_request::Project {
+                name: "bar".to_owned(),
+                elapsed: Some(Duration::from_millis(1500)),
+            },
+        );
+        projects.insert("a".to_owned(), search_request::Project::default());
+        let mut counts = HashMap::new();
+        counts.insert(10, Corpus::Web);
+        counts.insert(-2, Corpus::Unrecognized(7));
+        let request = SearchRequest {
+            query: "foo".to_owned(),
+            page_number: 3,
+            corpus: Corpus::Images,
+            samples: vec![-1, 2],
+            projects,
+            counts,
+            score: Some(0.0),
+            source: Some(search_request::Source::Url("http://a".to_owned())),
+            many: Some(Many {
+                f1: 1,
+                f5: Some(u64::MAX),
+                f9: Some(b"abc".to_vec()),
+                f300: vec![Level::High],
+                ..Many::default()
+            }),
+            nothing: Some(()),
+            ..SearchRequest::default()
+        };
+        let json = concat!(
+            r#"{"query":"foo","pageNumber":3,"corpus":"IMAGES","samples":[-1,2],"#,
+            r#""projects":{"a":{},"b":{"name":"bar","elapsed":"1.500s"}},"#,
+            r#""counts":{"-2":7,"10":"WEB"},"score":0,"url":"http://a","#,
+            r#""many":{"f1":1,"f5":"18446744073709551615","f9":"YWJj","f300":["HIGH"]},"#,
+            r#""nothing":{}}"#
+        );
+        let mut encoder = JsonEncoder::new();
+        let encoded = track_try_unwrap!(encoder.encode_into_bytes(request.clone()));
+        assert_eq!(String::from_utf8(encoded).ok(), Some(json.to_owned()));
+
+        let mut decoder = JsonDecoder::<SearchRequest>::new();
+        let decoded = track_try_unwrap!(decoder.decode_from_bytes(json.as_bytes()));
+        assert_eq!(decoded, request);
+
+        // The typed mapping agrees with the descriptor-based mapping
+        let mut pool = DescriptorPool::new();
+        pool.add_wellknown_types();
+        let files = ["other", "example"]
+            .iter()
+            .map(|name| {
+                let path = format!("{}/{}.proto", TESTDATA_DIR, name);
+                let text = track_try_unwrap!(fs::read_to_string(path).map_err(Error::from));
+                track_try_unwrap!(schema::parse(&text))
+            })
+            .collect::<Vec<_>>();
+        track_try_unwrap!(pool.add_files(&files));
+        let pool = Arc::new(pool);
+        let options = [
+            JsonOptions::default(),
+            JsonOptions {
+                emit_defaults: true,
+                preserve_proto_field_names: true,
+                ..JsonOptions::default()
+            },
+        ];
+        for request in &[request, SearchRequest::default()] {
+            for options in &options {
+                let mut encoder = JsonEncoder::with_options(options.clone());
+                let typed = track_try_unwrap!(encoder.encode_into_bytes(request.clone()));
+
+                let mut encoder = track_try_unwrap!(JsonMessageEncoder::with_options(
+                    SearchRequestEncoder::new(),
+                    pool.clone(),
+                    "example.SearchRequest",
+                    options.clone()
+                ));
+                let dynamic = track_try_unwrap!(encoder.encode_into_bytes(request.clone()));
+                assert_eq!(typed, dynamic);
+            }
+        }
+
+        // Decoding
+        let decode = |json: &str, options: JsonOptions| {
+            JsonDecoder::<SearchRequest>::with_options(options).decode_from_bytes(json.as_bytes())
+        };
+        let decoded = track_try_unwrap!(decode(
+            r#"{"page_number":"5","corpus":"PICTURES","query":null,"sub_project":{}}"#,
+            JsonOptions::default()
+        ));
+        assert_eq!(decoded.page_number, 5);
+        assert_eq!(decoded.corpus, Corpus::Images);
+        assert_eq!(
+            decoded.source,
+            Some(search_request::Source::SubProject(
+                search_request::Project::default()
+            ))
+        );
+        let decoded = track_try_unwrap!(decode(r#"{"corpus":9}"#, JsonOptions::default()));
+        assert_eq!(decoded.corpus, Corpus::Unrecognized(9));
+
+        let options = JsonOptions {
+            ignore_unknown_fields: true,
+            ..JsonOptions::default()
+        };
+        assert!(decode(r#"{"unknown":1}"#, JsonOptions::default()).is_err());
+        assert!(decode(r#"{"unknown":1}"#, options).is_ok());
+        assert!(decode(r#"{"url":"a","subProject":{}}"#, JsonOptions::default()).is_err());
+        assert!(decode(r#"{"query":"a","query":"b"}"#, JsonOptions::default()).is_err());
+        assert!(decode(r#"{"corpus":"MUSIC"}"#, JsonOptions::default()).is_err());
+        assert!(decode(r#"{"many":{"f300":[3]}}"#, JsonOptions::default()).is_err());
+        assert!(decode(r#"{"pageNumber":"x"}"#, JsonOptions::default()).is_err());
+
+        // Recursive messages
+        let node = Node {
+            name: "root".to_owned(),
+            parent: Some(Box::new(Node::default())),
+            extra: Some(node::Extra::Alias(Box::new(Node {
+                name: "alias".to_owned(),
+                ..Node::default()
+            }))),
+            ..Node::default()
+        };
+        let json = r#"{"name":"root","parent":{},"alias":{"name":"alias"}}"#;
+        let encoded = track_try_unwrap!(JsonEncoder::new().encode_into_bytes(node.clone()));
+        assert_eq!(encoded, json.as_bytes());
+        let decoded = track_try_unwrap!(JsonDecoder::<Node>::new().decode_from_bytes(&encoded));
+        assert_eq!(decoded, node);
+    }
+}
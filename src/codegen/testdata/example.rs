@@ -0,0 +1,1857 @@
+// This file is generated by `protobuf_codec::codegen`. Do not edit.
+
+/// `example` package.
+pub mod example {
+    /// `Many` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Many {
+        /// `required int32 f1 = 1;`
+        pub f1: i32,
+        /// `optional int32 f2 = 2;`
+        pub f2: Option<i32>,
+        /// `optional string f3 = 3;`
+        pub f3: Option<String>,
+        /// `optional bool f4 = 4;`
+        pub f4: Option<bool>,
+        /// `optional uint64 f5 = 5;`
+        pub f5: Option<u64>,
+        /// `optional fixed32 f6 = 6;`
+        pub f6: Option<u32>,
+        /// `optional sfixed64 f7 = 7;`
+        pub f7: Option<i64>,
+        /// `optional float f8 = 8;`
+        pub f8: Option<f32>,
+        /// `optional bytes f9 = 9;`
+        pub f9: Option<Vec<u8>>,
+        /// `optional sint64 f20 = 20;`
+        pub f20: Option<i64>,
+        /// `repeated Level f300 = 300;`
+        pub f300: Vec<Level>,
+        /// `repeated int64 f4000 = 4000;`
+        pub f4000: Vec<i64>,
+    }
+
+    /// Decoder for `Many` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct ManyDecoder {
+        inner: crate::message::MessageDecoder<
+            crate::field::Fields<(
+                crate::field::FieldDecoder<crate::field::num::F1, crate::scalar::Int32Decoder>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F2, crate::scalar::Int32Decoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F3, crate::scalar::StringDecoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F4, crate::scalar::BoolDecoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F5, crate::scalar::Uint64Decoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F6, crate::scalar::Fixed32Decoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F7, crate::scalar::Sfixed64Decoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F8, crate::scalar::FloatDecoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F9, crate::scalar::BytesDecoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::Fnum<20>, crate::scalar::Sint64Decoder>>,
+                crate::field::PackedFieldDecoder<crate::field::num::Fnum<300>, crate::scalar::EnumDecoder<Level>, Vec<Level>>,
+                crate::field::PackedFieldDecoder<crate::field::num::Fnum<4000>, crate::scalar::Int64Decoder, Vec<i64>>,
+            )>
+        >,
+    }
+    impl ManyDecoder {
+        /// Makes a new `ManyDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for ManyDecoder {
+        type Item = Many;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (f0, f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(Many {
+                f1: f0,
+                f2: f1,
+                f3: f2,
+                f4: f3,
+                f5: f4,
+                f6: f5,
+                f7: f6,
+                f8: f7,
+                f9: f8,
+                f20: f9,
+                f300: f10,
+                f4000: f11,
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl crate::message::MessageDecode for ManyDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            crate::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `Many` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct ManyEncoder {
+        inner: crate::message::MessageEncoder<
+            crate::field::Fields<(
+                crate::field::FieldEncoder<crate::field::num::F1, crate::scalar::Int32Encoder>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F2, crate::scalar::Int32Encoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F3, crate::scalar::StringEncoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F4, crate::scalar::BoolEncoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F5, crate::scalar::Uint64Encoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F6, crate::scalar::Fixed32Encoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F7, crate::scalar::Sfixed64Encoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F8, crate::scalar::FloatEncoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F9, crate::scalar::BytesEncoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::Fnum<20>, crate::scalar::Sint64Encoder>>,
+                crate::field::PackedFieldEncoder<crate::field::num::Fnum<300>, crate::scalar::EnumEncoder<Level>, Vec<Level>>,
+                crate::field::SizedRepeated<crate::field::FieldEncoder<crate::field::num::Fnum<4000>, crate::scalar::Int64Encoder>, Vec<i64>>,
+            )>
+        >,
+    }
+    impl ManyEncoder {
+        /// Makes a new `ManyEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for ManyEncoder {
+        type Item = Many;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let item = (
+                item.f1,
+                item.f2,
+                item.f3,
+                item.f4,
+                item.f5,
+                item.f6,
+                item.f7,
+                item.f8,
+                item.f9,
+                item.f20,
+                item.f300,
+                item.f4000,
+            );
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for ManyEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl crate::message::MessageEncode for ManyEncoder {}
+    impl Many {
+        const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+            crate::json::JsonField { name: "f1", json_name: "f1", oneof: None },
+            crate::json::JsonField { name: "f2", json_name: "f2", oneof: None },
+            crate::json::JsonField { name: "f3", json_name: "f3", oneof: None },
+            crate::json::JsonField { name: "f4", json_name: "f4", oneof: None },
+            crate::json::JsonField { name: "f5", json_name: "f5", oneof: None },
+            crate::json::JsonField { name: "f6", json_name: "f6", oneof: None },
+            crate::json::JsonField { name: "f7", json_name: "f7", oneof: None },
+            crate::json::JsonField { name: "f8", json_name: "f8", oneof: None },
+            crate::json::JsonField { name: "f9", json_name: "f9", oneof: None },
+            crate::json::JsonField { name: "f20", json_name: "f20", oneof: None },
+            crate::json::JsonField { name: "f300", json_name: "f300", oneof: None },
+            crate::json::JsonField { name: "f4000", json_name: "f4000", oneof: None },
+        ];
+    }
+    impl crate::json::JsonMapping for Many {
+        fn to_json(
+            &self,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let mut object = crate::json::JsonObjectWriter::new(options);
+            object.optional_field(&Self::JSON_FIELDS[0], Some(&self.f1))?;
+            object.optional_field(&Self::JSON_FIELDS[1], self.f2.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[2], self.f3.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[3], self.f4.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[4], self.f5.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[5], self.f6.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[6], self.f7.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[7], self.f8.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[8], self.f9.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[9], self.f20.as_ref())?;
+            object.repeated_field(&Self::JSON_FIELDS[10], &self.f300)?;
+            object.repeated_field(&Self::JSON_FIELDS[11], &self.f4000)?;
+            Ok(object.finish())
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            let mut reader =
+                crate::json::JsonObjectReader::new(json, "example.Many", Self::JSON_FIELDS, options)?;
+            let mut message = Self::default();
+            while let Some((i, value)) = reader.next_field()? {
+                match i {
+                    0 => message.f1 = reader.read(value)?,
+                    1 => message.f2 = Some(reader.read(value)?),
+                    2 => message.f3 = Some(reader.read(value)?),
+                    3 => message.f4 = Some(reader.read(value)?),
+                    4 => message.f5 = Some(reader.read(value)?),
+                    5 => message.f6 = Some(reader.read(value)?),
+                    6 => message.f7 = Some(reader.read(value)?),
+                    7 => message.f8 = Some(reader.read(value)?),
+                    8 => message.f9 = Some(reader.read(value)?),
+                    9 => message.f20 = Some(reader.read(value)?),
+                    10 => message.f300 = reader.read_repeated(value)?,
+                    11 => message.f4000 = reader.read_repeated(value)?,
+                    _ => unreachable!(),
+                }
+            }
+            Ok(message)
+        }
+    }
+
+    /// `Level` enum.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    #[repr(i32)]
+    pub enum Level {
+        /// `LOW = 1;`
+        #[default]
+        Low = 1,
+        /// `HIGH = 2;`
+        High = 2,
+    }
+    impl crate::scalar::Enum for Level {
+        fn from_i32(n: i32) -> Option<Self> {
+            match n {
+                1 => Some(Level::Low),
+                2 => Some(Level::High),
+                _ => None,
+            }
+        }
+
+        fn to_i32(&self) -> i32 {
+            *self as i32
+        }
+    }
+    impl crate::json::JsonMapping for Level {
+        fn to_json(
+            &self,
+            _options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let name = match *self {
+                Level::Low => "LOW",
+                Level::High => "HIGH",
+            };
+            Ok(crate::json::JsonValue::String(name.to_owned()))
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            _options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            crate::json::enum_from_json(json, "example.Level", |name| match name {
+                "LOW" => Some(Level::Low),
+                "HIGH" => Some(Level::High),
+                _ => None,
+            })
+        }
+    }
+
+    /// `SearchRequest` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct SearchRequest {
+        /// `string query = 1;`
+        pub query: String,
+        /// `int32 page_number = 2;`
+        pub page_number: i32,
+        /// `int32 result_per_page = 3;`
+        pub result_per_page: i32,
+        /// `Corpus corpus = 4;`
+        pub corpus: Corpus,
+        /// `repeated sint32 samples = 5;`
+        pub samples: Vec<i32>,
+        /// `repeated string tags = 6;`
+        pub tags: Vec<String>,
+        /// `map<string, Project> projects = 7;`
+        pub projects: ::std::collections::HashMap<String, self::search_request::Project>,
+        /// `map<int32, Corpus> counts = 8;`
+        pub counts: ::std::collections::HashMap<i32, Corpus>,
+        /// `Project owner = 9;`
+        pub owner: Option<self::search_request::Project>,
+        /// `optional double score = 10;`
+        pub score: Option<f64>,
+        /// `oneof source`
+        pub source: Option<self::search_request::Source>,
+        /// `Many many = 13;`
+        pub many: Option<Many>,
+        /// `repeated Many history = 14;`
+        pub history: Vec<Many>,
+        /// `google.protobuf.Empty nothing = 15;`
+        pub nothing: Option<()>,
+    }
+
+    /// Decoder for `SearchRequest` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct SearchRequestDecoder {
+        inner: crate::message::MessageDecoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F1, crate::scalar::StringDecoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F2, crate::scalar::Int32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F3, crate::scalar::Int32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F4, crate::scalar::EnumDecoder<Corpus>>>,
+                crate::field::PackedFieldDecoder<crate::field::num::F5, crate::scalar::Sint32Decoder, Vec<i32>>,
+                crate::field::Repeated<crate::field::FieldDecoder<crate::field::num::F6, crate::scalar::StringDecoder>, Vec<String>>,
+                crate::field::MapMessageFieldDecoder<crate::field::num::F7, crate::scalar::StringDecoder, self::search_request::ProjectDecoder, ::std::collections::HashMap<String, self::search_request::Project>>,
+                crate::field::MapFieldDecoder<crate::field::num::F8, crate::scalar::Int32Decoder, crate::scalar::EnumDecoder<Corpus>, ::std::collections::HashMap<i32, Corpus>>,
+                crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F9, self::search_request::ProjectDecoder>>,
+                crate::field::Optional<crate::field::FieldDecoder<crate::field::num::F10, crate::scalar::DoubleDecoder>>,
+                crate::field::Optional<crate::field::Oneof<(
+                    crate::field::FieldDecoder<crate::field::num::F11, crate::scalar::StringDecoder>,
+                    crate::field::MessageFieldDecoder<crate::field::num::F12, self::search_request::ProjectDecoder>,
+                )>>,
+                crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F13, ManyDecoder>>,
+                crate::field::Repeated<crate::field::MessageFieldDecoder<crate::field::num::F14, ManyDecoder>, Vec<Many>>,
+                crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F15, crate::wellknown::google::protobuf::EmptyMessageDecoder>>,
+            )>
+        >,
+    }
+    impl SearchRequestDecoder {
+        /// Makes a new `SearchRequestDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for SearchRequestDecoder {
+        type Item = SearchRequest;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (f0, f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11, f12, f13) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(SearchRequest {
+                query: f0,
+                page_number: f1,
+                result_per_page: f2,
+                corpus: f3,
+                samples: f4,
+                tags: f5,
+                projects: f6,
+                counts: f7,
+                owner: f8,
+                score: f9,
+                source: f10.map(|v| match v {
+                    crate::field::branch::Branch2::A(v) => self::search_request::Source::Url(v),
+                    crate::field::branch::Branch2::B(v) => self::search_request::Source::SubProject(v),
+                }),
+                many: f11,
+                history: f12,
+                nothing: f13,
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl crate::message::MessageDecode for SearchRequestDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            crate::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `SearchRequest` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct SearchRequestEncoder {
+        inner: crate::message::MessageEncoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F1, crate::scalar::StringEncoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F2, crate::scalar::Int32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F3, crate::scalar::Int32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F4, crate::scalar::EnumEncoder<Corpus>>>,
+                crate::field::PackedFieldEncoder<crate::field::num::F5, crate::scalar::Sint32Encoder, Vec<i32>>,
+                crate::field::SizedRepeated<crate::field::FieldEncoder<crate::field::num::F6, crate::scalar::StringEncoder>, Vec<String>>,
+                crate::field::SizedMapMessageFieldEncoder<crate::field::num::F7, crate::scalar::StringEncoder, self::search_request::ProjectEncoder, ::std::collections::HashMap<String, self::search_request::Project>>,
+                crate::field::SizedMapFieldEncoder<crate::field::num::F8, crate::scalar::Int32Encoder, crate::scalar::EnumEncoder<Corpus>, ::std::collections::HashMap<i32, Corpus>>,
+                crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F9, self::search_request::ProjectEncoder>>,
+                crate::field::Optional<crate::field::FieldEncoder<crate::field::num::F10, crate::scalar::DoubleEncoder>>,
+                crate::field::Optional<crate::field::Oneof<(
+                    crate::field::FieldEncoder<crate::field::num::F11, crate::scalar::StringEncoder>,
+                    crate::field::MessageFieldEncoder<crate::field::num::F12, self::search_request::ProjectEncoder>,
+                )>>,
+                crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F13, ManyEncoder>>,
+                crate::field::SizedRepeated<crate::field::MessageFieldEncoder<crate::field::num::F14, ManyEncoder>, Vec<Many>>,
+                crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F15, crate::wellknown::google::protobuf::EmptyMessageEncoder>>,
+            )>
+        >,
+    }
+    impl SearchRequestEncoder {
+        /// Makes a new `SearchRequestEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for SearchRequestEncoder {
+        type Item = SearchRequest;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let source = item.source.map(|v| match v {
+                self::search_request::Source::Url(v) => crate::field::branch::Branch2::A(v),
+                self::search_request::Source::SubProject(v) => crate::field::branch::Branch2::B(v),
+            });
+            let item = (
+                item.query,
+                item.page_number,
+                item.result_per_page,
+                item.corpus,
+                item.samples,
+                item.tags,
+                item.projects,
+                item.counts,
+                item.owner,
+                item.score,
+                source,
+                item.many,
+                item.history,
+                item.nothing,
+            );
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for SearchRequestEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl crate::message::MessageEncode for SearchRequestEncoder {}
+    impl SearchRequest {
+        const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+            crate::json::JsonField { name: "query", json_name: "query", oneof: None },
+            crate::json::JsonField { name: "page_number", json_name: "pageNumber", oneof: None },
+            crate::json::JsonField { name: "result_per_page", json_name: "resultPerPage", oneof: None },
+            crate::json::JsonField { name: "corpus", json_name: "corpus", oneof: None },
+            crate::json::JsonField { name: "samples", json_name: "samples", oneof: None },
+            crate::json::JsonField { name: "tags", json_name: "tags", oneof: None },
+            crate::json::JsonField { name: "projects", json_name: "projects", oneof: None },
+            crate::json::JsonField { name: "counts", json_name: "counts", oneof: None },
+            crate::json::JsonField { name: "owner", json_name: "owner", oneof: None },
+            crate::json::JsonField { name: "score", json_name: "score", oneof: None },
+            crate::json::JsonField { name: "url", json_name: "url", oneof: Some("source") },
+            crate::json::JsonField { name: "sub_project", json_name: "subProject", oneof: Some("source") },
+            crate::json::JsonField { name: "many", json_name: "many", oneof: None },
+            crate::json::JsonField { name: "history", json_name: "history", oneof: None },
+            crate::json::JsonField { name: "nothing", json_name: "nothing", oneof: None },
+        ];
+    }
+    impl crate::json::JsonMapping for SearchRequest {
+        fn to_json(
+            &self,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let mut object = crate::json::JsonObjectWriter::new(options);
+            object.field(&Self::JSON_FIELDS[0], &self.query)?;
+            object.field(&Self::JSON_FIELDS[1], &self.page_number)?;
+            object.field(&Self::JSON_FIELDS[2], &self.result_per_page)?;
+            object.field(&Self::JSON_FIELDS[3], &self.corpus)?;
+            object.repeated_field(&Self::JSON_FIELDS[4], &self.samples)?;
+            object.repeated_field(&Self::JSON_FIELDS[5], &self.tags)?;
+            object.field(&Self::JSON_FIELDS[6], &self.projects)?;
+            object.field(&Self::JSON_FIELDS[7], &self.counts)?;
+            object.optional_field(&Self::JSON_FIELDS[8], self.owner.as_ref())?;
+            object.optional_field(&Self::JSON_FIELDS[9], self.score.as_ref())?;
+            match self.source {
+                Some(self::search_request::Source::Url(ref v)) => object.optional_field(&Self::JSON_FIELDS[10], Some(v))?,
+                Some(self::search_request::Source::SubProject(ref v)) => object.optional_field(&Self::JSON_FIELDS[11], Some(v))?,
+                None => {}
+            }
+            object.optional_field(&Self::JSON_FIELDS[12], self.many.as_ref())?;
+            object.repeated_field(&Self::JSON_FIELDS[13], &self.history)?;
+            object.optional_field(&Self::JSON_FIELDS[14], self.nothing.as_ref())?;
+            Ok(object.finish())
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            let mut reader =
+                crate::json::JsonObjectReader::new(json, "example.SearchRequest", Self::JSON_FIELDS, options)?;
+            let mut message = Self::default();
+            while let Some((i, value)) = reader.next_field()? {
+                match i {
+                    0 => message.query = reader.read(value)?,
+                    1 => message.page_number = reader.read(value)?,
+                    2 => message.result_per_page = reader.read(value)?,
+                    3 => message.corpus = reader.read(value)?,
+                    4 => message.samples = reader.read_repeated(value)?,
+                    5 => message.tags = reader.read_repeated(value)?,
+                    6 => message.projects = reader.read(value)?,
+                    7 => message.counts = reader.read(value)?,
+                    8 => message.owner = Some(reader.read(value)?),
+                    9 => message.score = Some(reader.read(value)?),
+                    10 => message.source = Some(self::search_request::Source::Url(reader.read(value)?)),
+                    11 => message.source = Some(self::search_request::Source::SubProject(reader.read(value)?)),
+                    12 => message.many = Some(reader.read(value)?),
+                    13 => message.history = reader.read_repeated(value)?,
+                    14 => message.nothing = Some(reader.read(value)?),
+                    _ => unreachable!(),
+                }
+            }
+            Ok(message)
+        }
+    }
+
+    /// `Wide` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Wide {
+        /// `uint32 f1 = 1;`
+        pub f1: u32,
+        /// `uint32 f2 = 2;`
+        pub f2: u32,
+        /// `uint32 f3 = 3;`
+        pub f3: u32,
+        /// `uint32 f4 = 4;`
+        pub f4: u32,
+        /// `uint32 f5 = 5;`
+        pub f5: u32,
+        /// `uint32 f6 = 6;`
+        pub f6: u32,
+        /// `uint32 f7 = 7;`
+        pub f7: u32,
+        /// `uint32 f8 = 8;`
+        pub f8: u32,
+        /// `uint32 f9 = 9;`
+        pub f9: u32,
+        /// `uint32 f10 = 10;`
+        pub f10: u32,
+        /// `uint32 f11 = 11;`
+        pub f11: u32,
+        /// `uint32 f12 = 12;`
+        pub f12: u32,
+        /// `uint32 f13 = 13;`
+        pub f13: u32,
+        /// `uint32 f14 = 14;`
+        pub f14: u32,
+        /// `uint32 f15 = 15;`
+        pub f15: u32,
+        /// `uint32 f16 = 16;`
+        pub f16: u32,
+        /// `uint32 f17 = 17;`
+        pub f17: u32,
+        /// `uint32 f18 = 18;`
+        pub f18: u32,
+        /// `uint32 f19 = 19;`
+        pub f19: u32,
+        /// `uint32 f20 = 20;`
+        pub f20: u32,
+        /// `uint32 f21 = 21;`
+        pub f21: u32,
+        /// `uint32 f22 = 22;`
+        pub f22: u32,
+        /// `uint32 f23 = 23;`
+        pub f23: u32,
+        /// `uint32 f24 = 24;`
+        pub f24: u32,
+        /// `uint32 f25 = 25;`
+        pub f25: u32,
+        /// `uint32 f26 = 26;`
+        pub f26: u32,
+        /// `uint32 f27 = 27;`
+        pub f27: u32,
+        /// `uint32 f28 = 28;`
+        pub f28: u32,
+        /// `uint32 f29 = 29;`
+        pub f29: u32,
+        /// `uint32 f30 = 30;`
+        pub f30: u32,
+        /// `uint32 f31 = 31;`
+        pub f31: u32,
+        /// `uint32 f32 = 32;`
+        pub f32: u32,
+        /// `uint32 f33 = 33;`
+        pub f33: u32,
+        /// `oneof choice`
+        pub choice: Option<self::wide::Choice>,
+    }
+
+    /// Decoder for `Wide` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct WideDecoder {
+        inner: crate::message::MessageDecoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F1, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F2, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F3, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F4, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F5, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F6, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F7, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F8, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F9, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F10, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F11, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F12, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F13, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F14, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F15, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F16, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<17>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<18>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<19>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<20>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<21>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<22>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<23>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<24>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<25>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<26>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<27>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<28>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<29>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<30>, crate::scalar::Uint32Decoder>>,
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<31>, crate::scalar::Uint32Decoder>>,
+                crate::field::Fields<(
+                    crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<32>, crate::scalar::Uint32Decoder>>,
+                    crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::Fnum<33>, crate::scalar::Uint32Decoder>>,
+                    crate::field::Optional<crate::field::Oneof<(
+                        crate::field::FieldDecoder<crate::field::num::Fnum<101>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<102>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<103>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<104>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<105>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<106>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<107>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<108>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<109>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<110>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<111>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<112>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<113>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<114>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<115>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<116>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<117>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<118>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<119>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<120>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<121>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<122>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<123>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<124>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<125>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<126>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<127>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<128>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<129>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<130>, crate::scalar::Uint32Decoder>,
+                        crate::field::FieldDecoder<crate::field::num::Fnum<131>, crate::scalar::Uint32Decoder>,
+                        crate::field::Oneof<(
+                            crate::field::FieldDecoder<crate::field::num::Fnum<132>, crate::scalar::Uint32Decoder>,
+                            crate::field::FieldDecoder<crate::field::num::Fnum<133>, crate::scalar::Uint32Decoder>,
+                        )>,
+                    )>>,
+                )>,
+            )>
+        >,
+    }
+    impl WideDecoder {
+        /// Makes a new `WideDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for WideDecoder {
+        type Item = Wide;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (
+                f0,
+                f1,
+                f2,
+                f3,
+                f4,
+                f5,
+                f6,
+                f7,
+                f8,
+                f9,
+                f10,
+                f11,
+                f12,
+                f13,
+                f14,
+                f15,
+                f16,
+                f17,
+                f18,
+                f19,
+                f20,
+                f21,
+                f22,
+                f23,
+                f24,
+                f25,
+                f26,
+                f27,
+                f28,
+                f29,
+                f30,
+                (f31, f32, f33),
+            ) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(Wide {
+                f1: f0,
+                f2: f1,
+                f3: f2,
+                f4: f3,
+                f5: f4,
+                f6: f5,
+                f7: f6,
+                f8: f7,
+                f9: f8,
+                f10: f9,
+                f11: f10,
+                f12: f11,
+                f13: f12,
+                f14: f13,
+                f15: f14,
+                f16: f15,
+                f17: f16,
+                f18: f17,
+                f19: f18,
+                f20: f19,
+                f21: f20,
+                f22: f21,
+                f23: f22,
+                f24: f23,
+                f25: f24,
+                f26: f25,
+                f27: f26,
+                f28: f27,
+                f29: f28,
+                f30: f29,
+                f31: f30,
+                f32: f31,
+                f33: f32,
+                choice: f33.map(|v| match v {
+                    crate::field::branch::Branch32::A(v) => self::wide::Choice::C101(v),
+                    crate::field::branch::Branch32::B(v) => self::wide::Choice::C102(v),
+                    crate::field::branch::Branch32::C(v) => self::wide::Choice::C103(v),
+                    crate::field::branch::Branch32::D(v) => self::wide::Choice::C104(v),
+                    crate::field::branch::Branch32::E(v) => self::wide::Choice::C105(v),
+                    crate::field::branch::Branch32::F(v) => self::wide::Choice::C106(v),
+                    crate::field::branch::Branch32::G(v) => self::wide::Choice::C107(v),
+                    crate::field::branch::Branch32::H(v) => self::wide::Choice::C108(v),
+                    crate::field::branch::Branch32::I(v) => self::wide::Choice::C109(v),
+                    crate::field::branch::Branch32::J(v) => self::wide::Choice::C110(v),
+                    crate::field::branch::Branch32::K(v) => self::wide::Choice::C111(v),
+                    crate::field::branch::Branch32::L(v) => self::wide::Choice::C112(v),
+                    crate::field::branch::Branch32::M(v) => self::wide::Choice::C113(v),
+                    crate::field::branch::Branch32::N(v) => self::wide::Choice::C114(v),
+                    crate::field::branch::Branch32::O(v) => self::wide::Choice::C115(v),
+                    crate::field::branch::Branch32::P(v) => self::wide::Choice::C116(v),
+                    crate::field::branch::Branch32::Q(v) => self::wide::Choice::C117(v),
+                    crate::field::branch::Branch32::R(v) => self::wide::Choice::C118(v),
+                    crate::field::branch::Branch32::S(v) => self::wide::Choice::C119(v),
+                    crate::field::branch::Branch32::T(v) => self::wide::Choice::C120(v),
+                    crate::field::branch::Branch32::U(v) => self::wide::Choice::C121(v),
+                    crate::field::branch::Branch32::V(v) => self::wide::Choice::C122(v),
+                    crate::field::branch::Branch32::W(v) => self::wide::Choice::C123(v),
+                    crate::field::branch::Branch32::X(v) => self::wide::Choice::C124(v),
+                    crate::field::branch::Branch32::Y(v) => self::wide::Choice::C125(v),
+                    crate::field::branch::Branch32::Z(v) => self::wide::Choice::C126(v),
+                    crate::field::branch::Branch32::AA(v) => self::wide::Choice::C127(v),
+                    crate::field::branch::Branch32::AB(v) => self::wide::Choice::C128(v),
+                    crate::field::branch::Branch32::AC(v) => self::wide::Choice::C129(v),
+                    crate::field::branch::Branch32::AD(v) => self::wide::Choice::C130(v),
+                    crate::field::branch::Branch32::AE(v) => self::wide::Choice::C131(v),
+                    crate::field::branch::Branch32::AF(crate::field::branch::Branch2::A(v)) => self::wide::Choice::C132(v),
+                    crate::field::branch::Branch32::AF(crate::field::branch::Branch2::B(v)) => self::wide::Choice::C133(v),
+                }),
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl crate::message::MessageDecode for WideDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            crate::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `Wide` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct WideEncoder {
+        inner: crate::message::MessageEncoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F1, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F2, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F3, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F4, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F5, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F6, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F7, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F8, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F9, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F10, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F11, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F12, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F13, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F14, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F15, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F16, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<17>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<18>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<19>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<20>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<21>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<22>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<23>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<24>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<25>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<26>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<27>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<28>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<29>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<30>, crate::scalar::Uint32Encoder>>,
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<31>, crate::scalar::Uint32Encoder>>,
+                crate::field::Fields<(
+                    crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<32>, crate::scalar::Uint32Encoder>>,
+                    crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::Fnum<33>, crate::scalar::Uint32Encoder>>,
+                    crate::field::Optional<crate::field::Oneof<(
+                        crate::field::FieldEncoder<crate::field::num::Fnum<101>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<102>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<103>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<104>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<105>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<106>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<107>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<108>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<109>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<110>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<111>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<112>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<113>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<114>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<115>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<116>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<117>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<118>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<119>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<120>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<121>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<122>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<123>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<124>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<125>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<126>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<127>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<128>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<129>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<130>, crate::scalar::Uint32Encoder>,
+                        crate::field::FieldEncoder<crate::field::num::Fnum<131>, crate::scalar::Uint32Encoder>,
+                        crate::field::Oneof<(
+                            crate::field::FieldEncoder<crate::field::num::Fnum<132>, crate::scalar::Uint32Encoder>,
+                            crate::field::FieldEncoder<crate::field::num::Fnum<133>, crate::scalar::Uint32Encoder>,
+                        )>,
+                    )>>,
+                )>,
+            )>
+        >,
+    }
+    impl WideEncoder {
+        /// Makes a new `WideEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for WideEncoder {
+        type Item = Wide;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let choice = item.choice.map(|v| match v {
+                self::wide::Choice::C101(v) => crate::field::branch::Branch32::A(v),
+                self::wide::Choice::C102(v) => crate::field::branch::Branch32::B(v),
+                self::wide::Choice::C103(v) => crate::field::branch::Branch32::C(v),
+                self::wide::Choice::C104(v) => crate::field::branch::Branch32::D(v),
+                self::wide::Choice::C105(v) => crate::field::branch::Branch32::E(v),
+                self::wide::Choice::C106(v) => crate::field::branch::Branch32::F(v),
+                self::wide::Choice::C107(v) => crate::field::branch::Branch32::G(v),
+                self::wide::Choice::C108(v) => crate::field::branch::Branch32::H(v),
+                self::wide::Choice::C109(v) => crate::field::branch::Branch32::I(v),
+                self::wide::Choice::C110(v) => crate::field::branch::Branch32::J(v),
+                self::wide::Choice::C111(v) => crate::field::branch::Branch32::K(v),
+                self::wide::Choice::C112(v) => crate::field::branch::Branch32::L(v),
+                self::wide::Choice::C113(v) => crate::field::branch::Branch32::M(v),
+                self::wide::Choice::C114(v) => crate::field::branch::Branch32::N(v),
+                self::wide::Choice::C115(v) => crate::field::branch::Branch32::O(v),
+                self::wide::Choice::C116(v) => crate::field::branch::Branch32::P(v),
+                self::wide::Choice::C117(v) => crate::field::branch::Branch32::Q(v),
+                self::wide::Choice::C118(v) => crate::field::branch::Branch32::R(v),
+                self::wide::Choice::C119(v) => crate::field::branch::Branch32::S(v),
+                self::wide::Choice::C120(v) => crate::field::branch::Branch32::T(v),
+                self::wide::Choice::C121(v) => crate::field::branch::Branch32::U(v),
+                self::wide::Choice::C122(v) => crate::field::branch::Branch32::V(v),
+                self::wide::Choice::C123(v) => crate::field::branch::Branch32::W(v),
+                self::wide::Choice::C124(v) => crate::field::branch::Branch32::X(v),
+                self::wide::Choice::C125(v) => crate::field::branch::Branch32::Y(v),
+                self::wide::Choice::C126(v) => crate::field::branch::Branch32::Z(v),
+                self::wide::Choice::C127(v) => crate::field::branch::Branch32::AA(v),
+                self::wide::Choice::C128(v) => crate::field::branch::Branch32::AB(v),
+                self::wide::Choice::C129(v) => crate::field::branch::Branch32::AC(v),
+                self::wide::Choice::C130(v) => crate::field::branch::Branch32::AD(v),
+                self::wide::Choice::C131(v) => crate::field::branch::Branch32::AE(v),
+                self::wide::Choice::C132(v) => crate::field::branch::Branch32::AF(crate::field::branch::Branch2::A(v)),
+                self::wide::Choice::C133(v) => crate::field::branch::Branch32::AF(crate::field::branch::Branch2::B(v)),
+            });
+            let item = (
+                item.f1,
+                item.f2,
+                item.f3,
+                item.f4,
+                item.f5,
+                item.f6,
+                item.f7,
+                item.f8,
+                item.f9,
+                item.f10,
+                item.f11,
+                item.f12,
+                item.f13,
+                item.f14,
+                item.f15,
+                item.f16,
+                item.f17,
+                item.f18,
+                item.f19,
+                item.f20,
+                item.f21,
+                item.f22,
+                item.f23,
+                item.f24,
+                item.f25,
+                item.f26,
+                item.f27,
+                item.f28,
+                item.f29,
+                item.f30,
+                item.f31,
+                (item.f32, item.f33, choice),
+            );
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for WideEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl crate::message::MessageEncode for WideEncoder {}
+    impl Wide {
+        const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+            crate::json::JsonField { name: "f1", json_name: "f1", oneof: None },
+            crate::json::JsonField { name: "f2", json_name: "f2", oneof: None },
+            crate::json::JsonField { name: "f3", json_name: "f3", oneof: None },
+            crate::json::JsonField { name: "f4", json_name: "f4", oneof: None },
+            crate::json::JsonField { name: "f5", json_name: "f5", oneof: None },
+            crate::json::JsonField { name: "f6", json_name: "f6", oneof: None },
+            crate::json::JsonField { name: "f7", json_name: "f7", oneof: None },
+            crate::json::JsonField { name: "f8", json_name: "f8", oneof: None },
+            crate::json::JsonField { name: "f9", json_name: "f9", oneof: None },
+            crate::json::JsonField { name: "f10", json_name: "f10", oneof: None },
+            crate::json::JsonField { name: "f11", json_name: "f11", oneof: None },
+            crate::json::JsonField { name: "f12", json_name: "f12", oneof: None },
+            crate::json::JsonField { name: "f13", json_name: "f13", oneof: None },
+            crate::json::JsonField { name: "f14", json_name: "f14", oneof: None },
+            crate::json::JsonField { name: "f15", json_name: "f15", oneof: None },
+            crate::json::JsonField { name: "f16", json_name: "f16", oneof: None },
+            crate::json::JsonField { name: "f17", json_name: "f17", oneof: None },
+            crate::json::JsonField { name: "f18", json_name: "f18", oneof: None },
+            crate::json::JsonField { name: "f19", json_name: "f19", oneof: None },
+            crate::json::JsonField { name: "f20", json_name: "f20", oneof: None },
+            crate::json::JsonField { name: "f21", json_name: "f21", oneof: None },
+            crate::json::JsonField { name: "f22", json_name: "f22", oneof: None },
+            crate::json::JsonField { name: "f23", json_name: "f23", oneof: None },
+            crate::json::JsonField { name: "f24", json_name: "f24", oneof: None },
+            crate::json::JsonField { name: "f25", json_name: "f25", oneof: None },
+            crate::json::JsonField { name: "f26", json_name: "f26", oneof: None },
+            crate::json::JsonField { name: "f27", json_name: "f27", oneof: None },
+            crate::json::JsonField { name: "f28", json_name: "f28", oneof: None },
+            crate::json::JsonField { name: "f29", json_name: "f29", oneof: None },
+            crate::json::JsonField { name: "f30", json_name: "f30", oneof: None },
+            crate::json::JsonField { name: "f31", json_name: "f31", oneof: None },
+            crate::json::JsonField { name: "f32", json_name: "f32", oneof: None },
+            crate::json::JsonField { name: "f33", json_name: "f33", oneof: None },
+            crate::json::JsonField { name: "c101", json_name: "c101", oneof: Some("choice") },
+            crate::json::JsonField { name: "c102", json_name: "c102", oneof: Some("choice") },
+            crate::json::JsonField { name: "c103", json_name: "c103", oneof: Some("choice") },
+            crate::json::JsonField { name: "c104", json_name: "c104", oneof: Some("choice") },
+            crate::json::JsonField { name: "c105", json_name: "c105", oneof: Some("choice") },
+            crate::json::JsonField { name: "c106", json_name: "c106", oneof: Some("choice") },
+            crate::json::JsonField { name: "c107", json_name: "c107", oneof: Some("choice") },
+            crate::json::JsonField { name: "c108", json_name: "c108", oneof: Some("choice") },
+            crate::json::JsonField { name: "c109", json_name: "c109", oneof: Some("choice") },
+            crate::json::JsonField { name: "c110", json_name: "c110", oneof: Some("choice") },
+            crate::json::JsonField { name: "c111", json_name: "c111", oneof: Some("choice") },
+            crate::json::JsonField { name: "c112", json_name: "c112", oneof: Some("choice") },
+            crate::json::JsonField { name: "c113", json_name: "c113", oneof: Some("choice") },
+            crate::json::JsonField { name: "c114", json_name: "c114", oneof: Some("choice") },
+            crate::json::JsonField { name: "c115", json_name: "c115", oneof: Some("choice") },
+            crate::json::JsonField { name: "c116", json_name: "c116", oneof: Some("choice") },
+            crate::json::JsonField { name: "c117", json_name: "c117", oneof: Some("choice") },
+            crate::json::JsonField { name: "c118", json_name: "c118", oneof: Some("choice") },
+            crate::json::JsonField { name: "c119", json_name: "c119", oneof: Some("choice") },
+            crate::json::JsonField { name: "c120", json_name: "c120", oneof: Some("choice") },
+            crate::json::JsonField { name: "c121", json_name: "c121", oneof: Some("choice") },
+            crate::json::JsonField { name: "c122", json_name: "c122", oneof: Some("choice") },
+            crate::json::JsonField { name: "c123", json_name: "c123", oneof: Some("choice") },
+            crate::json::JsonField { name: "c124", json_name: "c124", oneof: Some("choice") },
+            crate::json::JsonField { name: "c125", json_name: "c125", oneof: Some("choice") },
+            crate::json::JsonField { name: "c126", json_name: "c126", oneof: Some("choice") },
+            crate::json::JsonField { name: "c127", json_name: "c127", oneof: Some("choice") },
+            crate::json::JsonField { name: "c128", json_name: "c128", oneof: Some("choice") },
+            crate::json::JsonField { name: "c129", json_name: "c129", oneof: Some("choice") },
+            crate::json::JsonField { name: "c130", json_name: "c130", oneof: Some("choice") },
+            crate::json::JsonField { name: "c131", json_name: "c131", oneof: Some("choice") },
+            crate::json::JsonField { name: "c132", json_name: "c132", oneof: Some("choice") },
+            crate::json::JsonField { name: "c133", json_name: "c133", oneof: Some("choice") },
+        ];
+    }
+    impl crate::json::JsonMapping for Wide {
+        fn to_json(
+            &self,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let mut object = crate::json::JsonObjectWriter::new(options);
+            object.field(&Self::JSON_FIELDS[0], &self.f1)?;
+            object.field(&Self::JSON_FIELDS[1], &self.f2)?;
+            object.field(&Self::JSON_FIELDS[2], &self.f3)?;
+            object.field(&Self::JSON_FIELDS[3], &self.f4)?;
+            object.field(&Self::JSON_FIELDS[4], &self.f5)?;
+            object.field(&Self::JSON_FIELDS[5], &self.f6)?;
+            object.field(&Self::JSON_FIELDS[6], &self.f7)?;
+            object.field(&Self::JSON_FIELDS[7], &self.f8)?;
+            object.field(&Self::JSON_FIELDS[8], &self.f9)?;
+            object.field(&Self::JSON_FIELDS[9], &self.f10)?;
+            object.field(&Self::JSON_FIELDS[10], &self.f11)?;
+            object.field(&Self::JSON_FIELDS[11], &self.f12)?;
+            object.field(&Self::JSON_FIELDS[12], &self.f13)?;
+            object.field(&Self::JSON_FIELDS[13], &self.f14)?;
+            object.field(&Self::JSON_FIELDS[14], &self.f15)?;
+            object.field(&Self::JSON_FIELDS[15], &self.f16)?;
+            object.field(&Self::JSON_FIELDS[16], &self.f17)?;
+            object.field(&Self::JSON_FIELDS[17], &self.f18)?;
+            object.field(&Self::JSON_FIELDS[18], &self.f19)?;
+            object.field(&Self::JSON_FIELDS[19], &self.f20)?;
+            object.field(&Self::JSON_FIELDS[20], &self.f21)?;
+            object.field(&Self::JSON_FIELDS[21], &self.f22)?;
+            object.field(&Self::JSON_FIELDS[22], &self.f23)?;
+            object.field(&Self::JSON_FIELDS[23], &self.f24)?;
+            object.field(&Self::JSON_FIELDS[24], &self.f25)?;
+            object.field(&Self::JSON_FIELDS[25], &self.f26)?;
+            object.field(&Self::JSON_FIELDS[26], &self.f27)?;
+            object.field(&Self::JSON_FIELDS[27], &self.f28)?;
+            object.field(&Self::JSON_FIELDS[28], &self.f29)?;
+            object.field(&Self::JSON_FIELDS[29], &self.f30)?;
+            object.field(&Self::JSON_FIELDS[30], &self.f31)?;
+            object.field(&Self::JSON_FIELDS[31], &self.f32)?;
+            object.field(&Self::JSON_FIELDS[32], &self.f33)?;
+            match self.choice {
+                Some(self::wide::Choice::C101(ref v)) => object.optional_field(&Self::JSON_FIELDS[33], Some(v))?,
+                Some(self::wide::Choice::C102(ref v)) => object.optional_field(&Self::JSON_FIELDS[34], Some(v))?,
+                Some(self::wide::Choice::C103(ref v)) => object.optional_field(&Self::JSON_FIELDS[35], Some(v))?,
+                Some(self::wide::Choice::C104(ref v)) => object.optional_field(&Self::JSON_FIELDS[36], Some(v))?,
+                Some(self::wide::Choice::C105(ref v)) => object.optional_field(&Self::JSON_FIELDS[37], Some(v))?,
+                Some(self::wide::Choice::C106(ref v)) => object.optional_field(&Self::JSON_FIELDS[38], Some(v))?,
+                Some(self::wide::Choice::C107(ref v)) => object.optional_field(&Self::JSON_FIELDS[39], Some(v))?,
+                Some(self::wide::Choice::C108(ref v)) => object.optional_field(&Self::JSON_FIELDS[40], Some(v))?,
+                Some(self::wide::Choice::C109(ref v)) => object.optional_field(&Self::JSON_FIELDS[41], Some(v))?,
+                Some(self::wide::Choice::C110(ref v)) => object.optional_field(&Self::JSON_FIELDS[42], Some(v))?,
+                Some(self::wide::Choice::C111(ref v)) => object.optional_field(&Self::JSON_FIELDS[43], Some(v))?,
+                Some(self::wide::Choice::C112(ref v)) => object.optional_field(&Self::JSON_FIELDS[44], Some(v))?,
+                Some(self::wide::Choice::C113(ref v)) => object.optional_field(&Self::JSON_FIELDS[45], Some(v))?,
+                Some(self::wide::Choice::C114(ref v)) => object.optional_field(&Self::JSON_FIELDS[46], Some(v))?,
+                Some(self::wide::Choice::C115(ref v)) => object.optional_field(&Self::JSON_FIELDS[47], Some(v))?,
+                Some(self::wide::Choice::C116(ref v)) => object.optional_field(&Self::JSON_FIELDS[48], Some(v))?,
+                Some(self::wide::Choice::C117(ref v)) => object.optional_field(&Self::JSON_FIELDS[49], Some(v))?,
+                Some(self::wide::Choice::C118(ref v)) => object.optional_field(&Self::JSON_FIELDS[50], Some(v))?,
+                Some(self::wide::Choice::C119(ref v)) => object.optional_field(&Self::JSON_FIELDS[51], Some(v))?,
+                Some(self::wide::Choice::C120(ref v)) => object.optional_field(&Self::JSON_FIELDS[52], Some(v))?,
+                Some(self::wide::Choice::C121(ref v)) => object.optional_field(&Self::JSON_FIELDS[53], Some(v))?,
+                Some(self::wide::Choice::C122(ref v)) => object.optional_field(&Self::JSON_FIELDS[54], Some(v))?,
+                Some(self::wide::Choice::C123(ref v)) => object.optional_field(&Self::JSON_FIELDS[55], Some(v))?,
+                Some(self::wide::Choice::C124(ref v)) => object.optional_field(&Self::JSON_FIELDS[56], Some(v))?,
+                Some(self::wide::Choice::C125(ref v)) => object.optional_field(&Self::JSON_FIELDS[57], Some(v))?,
+                Some(self::wide::Choice::C126(ref v)) => object.optional_field(&Self::JSON_FIELDS[58], Some(v))?,
+                Some(self::wide::Choice::C127(ref v)) => object.optional_field(&Self::JSON_FIELDS[59], Some(v))?,
+                Some(self::wide::Choice::C128(ref v)) => object.optional_field(&Self::JSON_FIELDS[60], Some(v))?,
+                Some(self::wide::Choice::C129(ref v)) => object.optional_field(&Self::JSON_FIELDS[61], Some(v))?,
+                Some(self::wide::Choice::C130(ref v)) => object.optional_field(&Self::JSON_FIELDS[62], Some(v))?,
+                Some(self::wide::Choice::C131(ref v)) => object.optional_field(&Self::JSON_FIELDS[63], Some(v))?,
+                Some(self::wide::Choice::C132(ref v)) => object.optional_field(&Self::JSON_FIELDS[64], Some(v))?,
+                Some(self::wide::Choice::C133(ref v)) => object.optional_field(&Self::JSON_FIELDS[65], Some(v))?,
+                None => {}
+            }
+            Ok(object.finish())
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            let mut reader =
+                crate::json::JsonObjectReader::new(json, "example.Wide", Self::JSON_FIELDS, options)?;
+            let mut message = Self::default();
+            while let Some((i, value)) = reader.next_field()? {
+                match i {
+                    0 => message.f1 = reader.read(value)?,
+                    1 => message.f2 = reader.read(value)?,
+                    2 => message.f3 = reader.read(value)?,
+                    3 => message.f4 = reader.read(value)?,
+                    4 => message.f5 = reader.read(value)?,
+                    5 => message.f6 = reader.read(value)?,
+                    6 => message.f7 = reader.read(value)?,
+                    7 => message.f8 = reader.read(value)?,
+                    8 => message.f9 = reader.read(value)?,
+                    9 => message.f10 = reader.read(value)?,
+                    10 => message.f11 = reader.read(value)?,
+                    11 => message.f12 = reader.read(value)?,
+                    12 => message.f13 = reader.read(value)?,
+                    13 => message.f14 = reader.read(value)?,
+                    14 => message.f15 = reader.read(value)?,
+                    15 => message.f16 = reader.read(value)?,
+                    16 => message.f17 = reader.read(value)?,
+                    17 => message.f18 = reader.read(value)?,
+                    18 => message.f19 = reader.read(value)?,
+                    19 => message.f20 = reader.read(value)?,
+                    20 => message.f21 = reader.read(value)?,
+                    21 => message.f22 = reader.read(value)?,
+                    22 => message.f23 = reader.read(value)?,
+                    23 => message.f24 = reader.read(value)?,
+                    24 => message.f25 = reader.read(value)?,
+                    25 => message.f26 = reader.read(value)?,
+                    26 => message.f27 = reader.read(value)?,
+                    27 => message.f28 = reader.read(value)?,
+                    28 => message.f29 = reader.read(value)?,
+                    29 => message.f30 = reader.read(value)?,
+                    30 => message.f31 = reader.read(value)?,
+                    31 => message.f32 = reader.read(value)?,
+                    32 => message.f33 = reader.read(value)?,
+                    33 => message.choice = Some(self::wide::Choice::C101(reader.read(value)?)),
+                    34 => message.choice = Some(self::wide::Choice::C102(reader.read(value)?)),
+                    35 => message.choice = Some(self::wide::Choice::C103(reader.read(value)?)),
+                    36 => message.choice = Some(self::wide::Choice::C104(reader.read(value)?)),
+                    37 => message.choice = Some(self::wide::Choice::C105(reader.read(value)?)),
+                    38 => message.choice = Some(self::wide::Choice::C106(reader.read(value)?)),
+                    39 => message.choice = Some(self::wide::Choice::C107(reader.read(value)?)),
+                    40 => message.choice = Some(self::wide::Choice::C108(reader.read(value)?)),
+                    41 => message.choice = Some(self::wide::Choice::C109(reader.read(value)?)),
+                    42 => message.choice = Some(self::wide::Choice::C110(reader.read(value)?)),
+                    43 => message.choice = Some(self::wide::Choice::C111(reader.read(value)?)),
+                    44 => message.choice = Some(self::wide::Choice::C112(reader.read(value)?)),
+                    45 => message.choice = Some(self::wide::Choice::C113(reader.read(value)?)),
+                    46 => message.choice = Some(self::wide::Choice::C114(reader.read(value)?)),
+                    47 => message.choice = Some(self::wide::Choice::C115(reader.read(value)?)),
+                    48 => message.choice = Some(self::wide::Choice::C116(reader.read(value)?)),
+                    49 => message.choice = Some(self::wide::Choice::C117(reader.read(value)?)),
+                    50 => message.choice = Some(self::wide::Choice::C118(reader.read(value)?)),
+                    51 => message.choice = Some(self::wide::Choice::C119(reader.read(value)?)),
+                    52 => message.choice = Some(self::wide::Choice::C120(reader.read(value)?)),
+                    53 => message.choice = Some(self::wide::Choice::C121(reader.read(value)?)),
+                    54 => message.choice = Some(self::wide::Choice::C122(reader.read(value)?)),
+                    55 => message.choice = Some(self::wide::Choice::C123(reader.read(value)?)),
+                    56 => message.choice = Some(self::wide::Choice::C124(reader.read(value)?)),
+                    57 => message.choice = Some(self::wide::Choice::C125(reader.read(value)?)),
+                    58 => message.choice = Some(self::wide::Choice::C126(reader.read(value)?)),
+                    59 => message.choice = Some(self::wide::Choice::C127(reader.read(value)?)),
+                    60 => message.choice = Some(self::wide::Choice::C128(reader.read(value)?)),
+                    61 => message.choice = Some(self::wide::Choice::C129(reader.read(value)?)),
+                    62 => message.choice = Some(self::wide::Choice::C130(reader.read(value)?)),
+                    63 => message.choice = Some(self::wide::Choice::C131(reader.read(value)?)),
+                    64 => message.choice = Some(self::wide::Choice::C132(reader.read(value)?)),
+                    65 => message.choice = Some(self::wide::Choice::C133(reader.read(value)?)),
+                    _ => unreachable!(),
+                }
+            }
+            Ok(message)
+        }
+    }
+
+    /// `Node` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Node {
+        /// `string name = 1;`
+        pub name: String,
+        /// `Node parent = 2;`
+        pub parent: Option<Box<Node>>,
+        /// `repeated Node children = 3;`
+        pub children: Vec<Node>,
+        /// `map<string, Node> links = 4;`
+        pub links: ::std::collections::HashMap<String, Node>,
+        /// `oneof extra`
+        pub extra: Option<self::node::Extra>,
+        /// `Leaf first_leaf = 7;`
+        pub first_leaf: Option<Box<self::node::Leaf>>,
+    }
+
+    /// Decoder for `Node` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct NodeDecoder {
+        inner: crate::message::MessageDecoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F1, crate::scalar::StringDecoder>>,
+                crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F2, crate::message::BoxedMessageDecoder<NodeDecoder>>>,
+                crate::field::Repeated<crate::field::MessageFieldDecoder<crate::field::num::F3, crate::message::BoxedMessageDecoder<NodeDecoder>>, Vec<Node>>,
+                crate::field::MapMessageFieldDecoder<crate::field::num::F4, crate::scalar::StringDecoder, crate::message::BoxedMessageDecoder<NodeDecoder>, ::std::collections::HashMap<String, Node>>,
+                crate::field::Optional<crate::field::Oneof<(
+                    crate::field::MessageFieldDecoder<crate::field::num::F5, crate::message::BoxedMessageDecoder<NodeDecoder>>,
+                    crate::field::MessageFieldDecoder<crate::field::num::F6, crate::message::BoxedMessageDecoder<self::node::LeafDecoder>>,
+                )>>,
+                crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F7, crate::message::BoxedMessageDecoder<self::node::LeafDecoder>>>,
+            )>
+        >,
+    }
+    impl NodeDecoder {
+        /// Makes a new `NodeDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for NodeDecoder {
+        type Item = Node;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (f0, f1, f2, f3, f4, f5) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(Node {
+                name: f0,
+                parent: f1.map(Box::new),
+                children: f2,
+                links: f3,
+                extra: f4.map(|v| match v {
+                    crate::field::branch::Branch2::A(v) => self::node::Extra::Alias(Box::new(v)),
+                    crate::field::branch::Branch2::B(v) => self::node::Extra::Leaf(Box::new(v)),
+                }),
+                first_leaf: f5.map(Box::new),
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl crate::message::MessageDecode for NodeDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            crate::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `Node` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct NodeEncoder {
+        inner: crate::message::MessageEncoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F1, crate::scalar::StringEncoder>>,
+                crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F2, crate::message::BoxedMessageEncoder<NodeEncoder>>>,
+                crate::field::SizedRepeated<crate::field::MessageFieldEncoder<crate::field::num::F3, crate::message::BoxedMessageEncoder<NodeEncoder>>, Vec<Node>>,
+                crate::field::SizedMapMessageFieldEncoder<crate::field::num::F4, crate::scalar::StringEncoder, crate::message::BoxedMessageEncoder<NodeEncoder>, ::std::collections::HashMap<String, Node>>,
+                crate::field::Optional<crate::field::Oneof<(
+                    crate::field::MessageFieldEncoder<crate::field::num::F5, crate::message::BoxedMessageEncoder<NodeEncoder>>,
+                    crate::field::MessageFieldEncoder<crate::field::num::F6, crate::message::BoxedMessageEncoder<self::node::LeafEncoder>>,
+                )>>,
+                crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F7, crate::message::BoxedMessageEncoder<self::node::LeafEncoder>>>,
+            )>
+        >,
+    }
+    impl NodeEncoder {
+        /// Makes a new `NodeEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for NodeEncoder {
+        type Item = Node;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let parent = item.parent.map(|v| *v);
+            let extra = item.extra.map(|v| match v {
+                self::node::Extra::Alias(v) => crate::field::branch::Branch2::A(*v),
+                self::node::Extra::Leaf(v) => crate::field::branch::Branch2::B(*v),
+            });
+            let first_leaf = item.first_leaf.map(|v| *v);
+            let item = (item.name, parent, item.children, item.links, extra, first_leaf);
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for NodeEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl crate::message::MessageEncode for NodeEncoder {}
+    impl Node {
+        const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+            crate::json::JsonField { name: "name", json_name: "name", oneof: None },
+            crate::json::JsonField { name: "parent", json_name: "parent", oneof: None },
+            crate::json::JsonField { name: "children", json_name: "children", oneof: None },
+            crate::json::JsonField { name: "links", json_name: "links", oneof: None },
+            crate::json::JsonField { name: "alias", json_name: "alias", oneof: Some("extra") },
+            crate::json::JsonField { name: "leaf", json_name: "leaf", oneof: Some("extra") },
+            crate::json::JsonField { name: "first_leaf", json_name: "firstLeaf", oneof: None },
+        ];
+    }
+    impl crate::json::JsonMapping for Node {
+        fn to_json(
+            &self,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let mut object = crate::json::JsonObjectWriter::new(options);
+            object.field(&Self::JSON_FIELDS[0], &self.name)?;
+            object.optional_field(&Self::JSON_FIELDS[1], self.parent.as_deref())?;
+            object.repeated_field(&Self::JSON_FIELDS[2], &self.children)?;
+            object.field(&Self::JSON_FIELDS[3], &self.links)?;
+            match self.extra {
+                Some(self::node::Extra::Alias(ref v)) => object.optional_field(&Self::JSON_FIELDS[4], Some(&**v))?,
+                Some(self::node::Extra::Leaf(ref v)) => object.optional_field(&Self::JSON_FIELDS[5], Some(&**v))?,
+                None => {}
+            }
+            object.optional_field(&Self::JSON_FIELDS[6], self.first_leaf.as_deref())?;
+            Ok(object.finish())
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            let mut reader =
+                crate::json::JsonObjectReader::new(json, "example.Node", Self::JSON_FIELDS, options)?;
+            let mut message = Self::default();
+            while let Some((i, value)) = reader.next_field()? {
+                match i {
+                    0 => message.name = reader.read(value)?,
+                    1 => message.parent = Some(Box::new(reader.read(value)?)),
+                    2 => message.children = reader.read_repeated(value)?,
+                    3 => message.links = reader.read(value)?,
+                    4 => message.extra = Some(self::node::Extra::Alias(Box::new(reader.read(value)?))),
+                    5 => message.extra = Some(self::node::Extra::Leaf(Box::new(reader.read(value)?))),
+                    6 => message.first_leaf = Some(Box::new(reader.read(value)?)),
+                    _ => unreachable!(),
+                }
+            }
+            Ok(message)
+        }
+    }
+
+    /// `Corpus` enum.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub enum Corpus {
+        /// `UNIVERSAL = 0;`
+        #[default]
+        Universal,
+        /// `WEB = 1;`
+        Web,
+        /// `IMAGES = 2;`
+        Images,
+        /// Value which is not defined in the schema.
+        Unrecognized(i32),
+    }
+    impl Corpus {
+        /// `PICTURES = 2;`
+        pub const PICTURES: Self = Corpus::Images;
+    }
+    impl crate::scalar::Enum for Corpus {
+        fn from_i32(n: i32) -> Option<Self> {
+            match n {
+                0 => Some(Corpus::Universal),
+                1 => Some(Corpus::Web),
+                2 => Some(Corpus::Images),
+                n => Some(Corpus::Unrecognized(n)),
+            }
+        }
+
+        fn to_i32(&self) -> i32 {
+            match *self {
+                Corpus::Universal => 0,
+                Corpus::Web => 1,
+                Corpus::Images => 2,
+                Corpus::Unrecognized(n) => n,
+            }
+        }
+    }
+    impl crate::json::JsonMapping for Corpus {
+        fn to_json(
+            &self,
+            _options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let name = match *self {
+                Corpus::Universal => "UNIVERSAL",
+                Corpus::Web => "WEB",
+                Corpus::Images => "IMAGES",
+                Corpus::Unrecognized(n) => return Ok(crate::json::JsonValue::Number(n.to_string())),
+            };
+            Ok(crate::json::JsonValue::String(name.to_owned()))
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            _options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            crate::json::enum_from_json(json, "example.Corpus", |name| match name {
+                "UNIVERSAL" => Some(Corpus::Universal),
+                "WEB" => Some(Corpus::Web),
+                "IMAGES" => Some(Corpus::Images),
+                "PICTURES" => Some(Corpus::Images),
+                _ => None,
+            })
+        }
+    }
+
+    /// Nested types of `Node` message.
+    pub mod node {
+        /// `extra` oneof of `Node` message.
+        #[derive(Debug, Clone, PartialEq)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum Extra {
+            /// `Node alias = 5;`
+            Alias(Box<super::Node>),
+            /// `Leaf leaf = 6;`
+            Leaf(Box<Leaf>),
+        }
+
+        /// `Leaf` message.
+        #[derive(Debug, Default, Clone, PartialEq)]
+        pub struct Leaf {
+            /// `Node owner = 1;`
+            pub owner: Option<Box<super::Node>>,
+            /// `Corpus corpus = 2;`
+            pub corpus: super::Corpus,
+        }
+
+        /// Decoder for `Leaf` message.
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        pub struct LeafDecoder {
+            inner: crate::message::MessageDecoder<
+                crate::field::Fields<(
+                    crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F1, crate::message::BoxedMessageDecoder<super::NodeDecoder>>>,
+                    crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F2, crate::scalar::EnumDecoder<super::Corpus>>>,
+                )>
+            >,
+        }
+        impl LeafDecoder {
+            /// Makes a new `LeafDecoder` instance.
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Decode for LeafDecoder {
+            type Item = Leaf;
+
+            fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+            }
+
+            fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+                let (f0, f1) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+                Ok(Leaf {
+                    owner: f0.map(Box::new),
+                    corpus: f1,
+                })
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Decode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Decode::is_idle(&self.inner)
+            }
+        }
+        impl crate::message::MessageDecode for LeafDecoder {
+            fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+                crate::message::MessageDecode::resume_decoding(&mut self.inner)
+            }
+        }
+
+        /// Encoder for `Leaf` message.
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        pub struct LeafEncoder {
+            inner: crate::message::MessageEncoder<
+                crate::field::Fields<(
+                    crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F1, crate::message::BoxedMessageEncoder<super::NodeEncoder>>>,
+                    crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F2, crate::scalar::EnumEncoder<super::Corpus>>>,
+                )>
+            >,
+        }
+        impl LeafEncoder {
+            /// Makes a new `LeafEncoder` instance.
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Encode for LeafEncoder {
+            type Item = Leaf;
+
+            fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+            }
+
+            fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+                let owner = item.owner.map(|v| *v);
+                let item = (owner, item.corpus);
+                ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Encode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Encode::is_idle(&self.inner)
+            }
+        }
+        impl ::bytecodec::SizedEncode for LeafEncoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+            }
+        }
+        impl crate::message::MessageEncode for LeafEncoder {}
+        impl Leaf {
+            const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+                crate::json::JsonField { name: "owner", json_name: "owner", oneof: None },
+                crate::json::JsonField { name: "corpus", json_name: "corpus", oneof: None },
+            ];
+        }
+        impl crate::json::JsonMapping for Leaf {
+            fn to_json(
+                &self,
+                options: &crate::json::JsonOptions,
+            ) -> ::bytecodec::Result<crate::json::JsonValue> {
+                let mut object = crate::json::JsonObjectWriter::new(options);
+                object.optional_field(&Self::JSON_FIELDS[0], self.owner.as_deref())?;
+                object.field(&Self::JSON_FIELDS[1], &self.corpus)?;
+                Ok(object.finish())
+            }
+
+            fn from_json(
+                json: &crate::json::JsonValue,
+                options: &crate::json::JsonOptions,
+            ) -> ::bytecodec::Result<Self> {
+                let mut reader =
+                    crate::json::JsonObjectReader::new(json, "example.Node.Leaf", Self::JSON_FIELDS, options)?;
+                let mut message = Self::default();
+                while let Some((i, value)) = reader.next_field()? {
+                    match i {
+                        0 => message.owner = Some(Box::new(reader.read(value)?)),
+                        1 => message.corpus = reader.read(value)?,
+                        _ => unreachable!(),
+                    }
+                }
+                Ok(message)
+            }
+        }
+    }
+
+    /// Nested types of `SearchRequest` message.
+    pub mod search_request {
+        /// `source` oneof of `SearchRequest` message.
+        #[derive(Debug, Clone, PartialEq)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum Source {
+            /// `string url = 11;`
+            Url(String),
+            /// `Project sub_project = 12;`
+            SubProject(Project),
+        }
+
+        /// `Project` message.
+        #[derive(Debug, Default, Clone, PartialEq)]
+        pub struct Project {
+            /// `string name = 1;`
+            pub name: String,
+            /// `google.protobuf.Duration elapsed = 2;`
+            pub elapsed: Option<::std::time::Duration>,
+        }
+
+        /// Decoder for `Project` message.
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        pub struct ProjectDecoder {
+            inner: crate::message::MessageDecoder<
+                crate::field::Fields<(
+                    crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F1, crate::scalar::StringDecoder>>,
+                    crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F2, crate::wellknown::google::protobuf::StdDurationDecoder>>,
+                )>
+            >,
+        }
+        impl ProjectDecoder {
+            /// Makes a new `ProjectDecoder` instance.
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Decode for ProjectDecoder {
+            type Item = Project;
+
+            fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+            }
+
+            fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+                let (f0, f1) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+                Ok(Project {
+                    name: f0,
+                    elapsed: f1,
+                })
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Decode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Decode::is_idle(&self.inner)
+            }
+        }
+        impl crate::message::MessageDecode for ProjectDecoder {
+            fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+                crate::message::MessageDecode::resume_decoding(&mut self.inner)
+            }
+        }
+
+        /// Encoder for `Project` message.
+        #[derive(Debug, Default)]
+        #[allow(clippy::type_complexity)]
+        pub struct ProjectEncoder {
+            inner: crate::message::MessageEncoder<
+                crate::field::Fields<(
+                    crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F1, crate::scalar::StringEncoder>>,
+                    crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F2, crate::wellknown::google::protobuf::StdDurationEncoder>>,
+                )>
+            >,
+        }
+        impl ProjectEncoder {
+            /// Makes a new `ProjectEncoder` instance.
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl ::bytecodec::Encode for ProjectEncoder {
+            type Item = Project;
+
+            fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+                ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+            }
+
+            fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+                let item = (item.name, item.elapsed);
+                ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+            }
+
+            fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+                ::bytecodec::Encode::requiring_bytes(&self.inner)
+            }
+
+            fn is_idle(&self) -> bool {
+                ::bytecodec::Encode::is_idle(&self.inner)
+            }
+        }
+        impl ::bytecodec::SizedEncode for ProjectEncoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+            }
+        }
+        impl crate::message::MessageEncode for ProjectEncoder {}
+        impl Project {
+            const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+                crate::json::JsonField { name: "name", json_name: "name", oneof: None },
+                crate::json::JsonField { name: "elapsed", json_name: "elapsed", oneof: None },
+            ];
+        }
+        impl crate::json::JsonMapping for Project {
+            fn to_json(
+                &self,
+                options: &crate::json::JsonOptions,
+            ) -> ::bytecodec::Result<crate::json::JsonValue> {
+                let mut object = crate::json::JsonObjectWriter::new(options);
+                object.field(&Self::JSON_FIELDS[0], &self.name)?;
+                object.optional_field(&Self::JSON_FIELDS[1], self.elapsed.as_ref())?;
+                Ok(object.finish())
+            }
+
+            fn from_json(
+                json: &crate::json::JsonValue,
+                options: &crate::json::JsonOptions,
+            ) -> ::bytecodec::Result<Self> {
+                let mut reader =
+                    crate::json::JsonObjectReader::new(json, "example.SearchRequest.Project", Self::JSON_FIELDS, options)?;
+                let mut message = Self::default();
+                while let Some((i, value)) = reader.next_field()? {
+                    match i {
+                        0 => message.name = reader.read(value)?,
+                        1 => message.elapsed = Some(reader.read(value)?),
+                        _ => unreachable!(),
+                    }
+                }
+                Ok(message)
+            }
+        }
+    }
+
+    /// Nested types of `Wide` message.
+    pub mod wide {
+        /// `choice` oneof of `Wide` message.
+        #[derive(Debug, Clone, PartialEq)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum Choice {
+            /// `uint32 c101 = 101;`
+            C101(u32),
+            /// `uint32 c102 = 102;`
+            C102(u32),
+            /// `uint32 c103 = 103;`
+            C103(u32),
+            /// `uint32 c104 = 104;`
+            C104(u32),
+            /// `uint32 c105 = 105;`
+            C105(u32),
+            /// `uint32 c106 = 106;`
+            C106(u32),
+            /// `uint32 c107 = 107;`
+            C107(u32),
+            /// `uint32 c108 = 108;`
+            C108(u32),
+            /// `uint32 c109 = 109;`
+            C109(u32),
+            /// `uint32 c110 = 110;`
+            C110(u32),
+            /// `uint32 c111 = 111;`
+            C111(u32),
+            /// `uint32 c112 = 112;`
+            C112(u32),
+            /// `uint32 c113 = 113;`
+            C113(u32),
+            /// `uint32 c114 = 114;`
+            C114(u32),
+            /// `uint32 c115 = 115;`
+            C115(u32),
+            /// `uint32 c116 = 116;`
+            C116(u32),
+            /// `uint32 c117 = 117;`
+            C117(u32),
+            /// `uint32 c118 = 118;`
+            C118(u32),
+            /// `uint32 c119 = 119;`
+            C119(u32),
+            /// `uint32 c120 = 120;`
+            C120(u32),
+            /// `uint32 c121 = 121;`
+            C121(u32),
+            /// `uint32 c122 = 122;`
+            C122(u32),
+            /// `uint32 c123 = 123;`
+            C123(u32),
+            /// `uint32 c124 = 124;`
+            C124(u32),
+            /// `uint32 c125 = 125;`
+            C125(u32),
+            /// `uint32 c126 = 126;`
+            C126(u32),
+            /// `uint32 c127 = 127;`
+            C127(u32),
+            /// `uint32 c128 = 128;`
+            C128(u32),
+            /// `uint32 c129 = 129;`
+            C129(u32),
+            /// `uint32 c130 = 130;`
+            C130(u32),
+            /// `uint32 c131 = 131;`
+            C131(u32),
+            /// `uint32 c132 = 132;`
+            C132(u32),
+            /// `uint32 c133 = 133;`
+            C133(u32),
+        }
+    }
+}
@@ -0,0 +1,264 @@
+// This file is generated by `protobuf_codec::codegen`. Do not edit.
+
+/// `proxy` package.
+pub mod proxy {
+    /// `SearchRequest` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct SearchRequest {
+        /// `string query = 1;`
+        pub query: String,
+        /// Fields which are not defined in the schema.
+        pub unknown_fields: crate::field::UnknownFields,
+    }
+
+    /// Decoder for `SearchRequest` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct SearchRequestDecoder {
+        inner: crate::message::MessageDecoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldDecoder<crate::field::num::F1, crate::scalar::StringDecoder>>,
+                crate::field::UnknownFieldsDecoder,
+            )>
+        >,
+    }
+    impl SearchRequestDecoder {
+        /// Makes a new `SearchRequestDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for SearchRequestDecoder {
+        type Item = SearchRequest;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (f0, f1) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(SearchRequest {
+                query: f0,
+                unknown_fields: f1,
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl crate::message::MessageDecode for SearchRequestDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            crate::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `SearchRequest` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct SearchRequestEncoder {
+        inner: crate::message::MessageEncoder<
+            crate::field::Fields<(
+                crate::field::MaybeDefault<crate::field::FieldEncoder<crate::field::num::F1, crate::scalar::StringEncoder>>,
+                crate::field::UnknownFieldsEncoder,
+            )>
+        >,
+    }
+    impl SearchRequestEncoder {
+        /// Makes a new `SearchRequestEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for SearchRequestEncoder {
+        type Item = SearchRequest;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let item = (item.query, item.unknown_fields);
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for SearchRequestEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl crate::message::MessageEncode for SearchRequestEncoder {}
+    impl SearchRequest {
+        const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+            crate::json::JsonField { name: "query", json_name: "query", oneof: None },
+        ];
+    }
+    impl crate::json::JsonMapping for SearchRequest {
+        fn to_json(
+            &self,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let mut object = crate::json::JsonObjectWriter::new(options);
+            object.field(&Self::JSON_FIELDS[0], &self.query)?;
+            Ok(object.finish())
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            let mut reader =
+                crate::json::JsonObjectReader::new(json, "proxy.SearchRequest", Self::JSON_FIELDS, options)?;
+            let mut message = Self::default();
+            while let Some((i, value)) = reader.next_field()? {
+                match i {
+                    0 => message.query = reader.read(value)?,
+                    _ => unreachable!(),
+                }
+            }
+            Ok(message)
+        }
+    }
+
+    /// `Proxy` message.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Proxy {
+        /// `SearchRequest request = 1;`
+        pub request: Option<SearchRequest>,
+        /// Fields which are not defined in the schema.
+        pub unknown_fields: crate::field::UnknownFields,
+    }
+
+    /// Decoder for `Proxy` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct ProxyDecoder {
+        inner: crate::message::MessageDecoder<
+            crate::field::Fields<(
+                crate::field::Optional<crate::field::MessageFieldDecoder<crate::field::num::F1, SearchRequestDecoder>>,
+                crate::field::UnknownFieldsDecoder,
+            )>
+        >,
+    }
+    impl ProxyDecoder {
+        /// Makes a new `ProxyDecoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Decode for ProxyDecoder {
+        type Item = Proxy;
+
+        fn decode(&mut self, buf: &[u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Decode::decode(&mut self.inner, buf, eos)
+        }
+
+        fn finish_decoding(&mut self) -> ::bytecodec::Result<Self::Item> {
+            let (f0, f1) = ::bytecodec::Decode::finish_decoding(&mut self.inner)?;
+            Ok(Proxy {
+                request: f0,
+                unknown_fields: f1,
+            })
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Decode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Decode::is_idle(&self.inner)
+        }
+    }
+    impl crate::message::MessageDecode for ProxyDecoder {
+        fn resume_decoding(&mut self) -> ::bytecodec::Result<()> {
+            crate::message::MessageDecode::resume_decoding(&mut self.inner)
+        }
+    }
+
+    /// Encoder for `Proxy` message.
+    #[derive(Debug, Default)]
+    #[allow(clippy::type_complexity)]
+    pub struct ProxyEncoder {
+        inner: crate::message::MessageEncoder<
+            crate::field::Fields<(
+                crate::field::Optional<crate::field::MessageFieldEncoder<crate::field::num::F1, SearchRequestEncoder>>,
+                crate::field::UnknownFieldsEncoder,
+            )>
+        >,
+    }
+    impl ProxyEncoder {
+        /// Makes a new `ProxyEncoder` instance.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+    impl ::bytecodec::Encode for ProxyEncoder {
+        type Item = Proxy;
+
+        fn encode(&mut self, buf: &mut [u8], eos: ::bytecodec::Eos) -> ::bytecodec::Result<usize> {
+            ::bytecodec::Encode::encode(&mut self.inner, buf, eos)
+        }
+
+        fn start_encoding(&mut self, item: Self::Item) -> ::bytecodec::Result<()> {
+            let item = (item.request, item.unknown_fields);
+            ::bytecodec::Encode::start_encoding(&mut self.inner, item)
+        }
+
+        fn requiring_bytes(&self) -> ::bytecodec::ByteCount {
+            ::bytecodec::Encode::requiring_bytes(&self.inner)
+        }
+
+        fn is_idle(&self) -> bool {
+            ::bytecodec::Encode::is_idle(&self.inner)
+        }
+    }
+    impl ::bytecodec::SizedEncode for ProxyEncoder {
+        fn exact_requiring_bytes(&self) -> u64 {
+            ::bytecodec::SizedEncode::exact_requiring_bytes(&self.inner)
+        }
+    }
+    impl crate::message::MessageEncode for ProxyEncoder {}
+    impl Proxy {
+        const JSON_FIELDS: &'static [crate::json::JsonField] = &[
+            crate::json::JsonField { name: "request", json_name: "request", oneof: None },
+        ];
+    }
+    impl crate::json::JsonMapping for Proxy {
+        fn to_json(
+            &self,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<crate::json::JsonValue> {
+            let mut object = crate::json::JsonObjectWriter::new(options);
+            object.optional_field(&Self::JSON_FIELDS[0], self.request.as_ref())?;
+            Ok(object.finish())
+        }
+
+        fn from_json(
+            json: &crate::json::JsonValue,
+            options: &crate::json::JsonOptions,
+        ) -> ::bytecodec::Result<Self> {
+            let mut reader =
+                crate::json::JsonObjectReader::new(json, "proxy.Proxy", Self::JSON_FIELDS, options)?;
+            let mut message = Self::default();
+            while let Some((i, value)) = reader.next_field()? {
+                match i {
+                    0 => message.request = Some(reader.read(value)?),
+                    _ => unreachable!(),
+                }
+            }
+            Ok(message)
+        }
+    }
+}
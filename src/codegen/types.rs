@@ -0,0 +1,221 @@
+use super::emit::{module_name, type_ident};
+use crate::schema::{FieldType, Message, ProtoFile};
+use bytecodec::{ErrorKind, Result};
+use std::collections::{HashMap, HashSet};
+
+/// Well-known type which is mapped to a codec provided by this crate.
+#[derive(Debug)]
+pub struct BuiltinType {
+    pub file: &'static str,
+    pub name: &'static str,
+    pub item: &'static str,
+    pub decoder: &'static str,
+    pub encoder: &'static str,
+}
+
+// `{}` in `decoder` and `encoder` is replaced with the path of `protobuf_codec` crate.
+const BUILTIN_TYPES: &[BuiltinType] = &[
+    BuiltinType {
+        file: "google/protobuf/duration.proto",
+        name: ".google.protobuf.Duration",
+        item: "::std::time::Duration",
+        decoder: "{}::wellknown::google::protobuf::StdDurationDecoder",
+        encoder: "{}::wellknown::google::protobuf::StdDurationEncoder",
+    },
+    BuiltinType {
+        file: "google/protobuf/empty.proto",
+        name: ".google.protobuf.Empty",
+        item: "()",
+        decoder: "{}::wellknown::google::protobuf::EmptyMessageDecoder",
+        encoder: "{}::wellknown::google::protobuf::EmptyMessageEncoder",
+    },
+];
+
+pub fn is_builtin_file(path: &str) -> bool {
+    BUILTIN_TYPES.iter().any(|t| t.file == path)
+}
+
+#[derive(Debug)]
+pub enum TypeKind {
+    Message,
+    Enum,
+    Builtin(&'static BuiltinType),
+}
+
+#[derive(Debug)]
+pub struct TypeInfo {
+    pub kind: TypeKind,
+
+    // Path of the module where the type is defined (relative to the root of the generated code)
+    pub module: Vec<String>,
+    pub ident: String,
+}
+
+#[derive(Debug)]
+pub struct TypeTable {
+    types: HashMap<String, TypeInfo>,
+
+    // Full names of the messages referred from the fields of each message
+    references: HashMap<String, Vec<String>>,
+}
+impl TypeTable {
+    pub fn new(files: &[(String, ProtoFile)]) -> Result<Self> {
+        let mut table = TypeTable {
+            types: HashMap::new(),
+            references: HashMap::new(),
+        };
+        for t in BUILTIN_TYPES {
+            table.types.insert(
+                t.name.to_owned(),
+                TypeInfo {
+                    kind: TypeKind::Builtin(t),
+                    module: Vec::new(),
+                    ident: String::new(),
+                },
+            );
+        }
+
+        for (_, file) in files {
+            let scope = package_scope(file);
+            let module = package_module(file);
+            for m in &file.messages {
+                track!(table.register_message(&scope, &module, m))?;
+            }
+            for e in &file.enums {
+                track!(table.register(&scope, &module, &e.name, TypeKind::Enum))?;
+            }
+        }
+
+        for (_, file) in files {
+            let scope = package_scope(file);
+            for m in &file.messages {
+                track!(table.collect_references(&scope, m))?;
+            }
+        }
+        Ok(table)
+    }
+
+    /// Resolves `name` referred from `scope` in accordance with the scoping rules of protobuf.
+    pub fn resolve(&self, scope: &str, name: &str) -> Option<(String, &TypeInfo)> {
+        if name.starts_with('.') {
+            return self.types.get(name).map(|t| (name.to_owned(), t));
+        }
+
+        let mut scope = scope;
+        loop {
+            let full_name = format!("{}.{}", scope, name);
+            if let Some(t) = self.types.get(&full_name) {
+                return Some((full_name, t));
+            }
+            if scope.is_empty() {
+                return None;
+            }
+            scope = &scope[..scope.rfind('.').unwrap_or(0)];
+        }
+    }
+
+    /// Returns `true` if the message `from` refers to the message `to` directly or indirectly.
+    ///
+    /// Fields of a message that refers back to the message itself are boxed.
+    pub fn refers_to(&self, from: &str, to: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(name) = stack.pop() {
+            if !visited.insert(name) {
+                continue;
+            }
+            for r in self.references.get(name).into_iter().flatten() {
+                if r == to {
+                    return true;
+                }
+                stack.push(r);
+            }
+        }
+        false
+    }
+
+    fn register(
+        &mut self,
+        scope: &str,
+        module: &[String],
+        name: &str,
+        kind: TypeKind,
+    ) -> Result<()> {
+        let full_name = format!("{}.{}", scope, name);
+        let info = TypeInfo {
+            kind,
+            module: module.to_owned(),
+            ident: type_ident(name),
+        };
+        track_assert!(
+            self.types.insert(full_name.clone(), info).is_none(),
+            ErrorKind::InvalidInput,
+            "Duplicate type: {}",
+            full_name
+        );
+        Ok(())
+    }
+
+    fn register_message(&mut self, scope: &str, module: &[String], m: &Message) -> Result<()> {
+        track!(self.register(scope, module, &m.name, TypeKind::Message))?;
+
+        let scope = format!("{}.{}", scope, m.name);
+        let mut module = module.to_owned();
+        module.push(module_name(&m.name));
+        for nested in &m.messages {
+            track!(self.register_message(&scope, &module, nested))?;
+        }
+        for e in &m.enums {
+            track!(self.register(&scope, &module, &e.name, TypeKind::Enum))?;
+        }
+        Ok(())
+    }
+
+    // Messages referred from map fields and repeated fields are also collected
+    // since their decoders and encoders contain the codecs of the referred messages.
+    fn collect_references(&mut self, scope: &str, m: &Message) -> Result<()> {
+        let full_name = format!("{}.{}", scope, m.name);
+        let mut references = Vec::new();
+        let fields = m
+            .fields
+            .iter()
+            .chain(m.oneofs.iter().flat_map(|o| &o.fields));
+        for f in fields {
+            let ty = match f.ty {
+                FieldType::Map(_, ref v) => v,
+                ref ty => ty,
+            };
+            if let FieldType::Named(ref name) = *ty {
+                let (name, t) = track_assert_some!(
+                    self.resolve(&full_name, name),
+                    ErrorKind::InvalidInput,
+                    "{}: Unknown type: {}",
+                    f.position,
+                    name
+                );
+                if let TypeKind::Message = t.kind {
+                    references.push(name);
+                }
+            }
+        }
+        self.references.insert(full_name.clone(), references);
+        for nested in &m.messages {
+            track!(self.collect_references(&full_name, nested))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn package_scope(file: &ProtoFile) -> String {
+    file.package
+        .as_ref()
+        .map(|p| format!(".{}", p))
+        .unwrap_or_default()
+}
+
+pub fn package_module(file: &ProtoFile) -> Vec<String> {
+    file.package
+        .as_ref()
+        .map(|p| p.split('.').map(module_name).collect())
+        .unwrap_or_default()
+}
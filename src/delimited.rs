@@ -0,0 +1,564 @@
+//! Streams of length-delimited messages.
+//!
+//! Each message in a stream is prefixed by its length encoded as a varint.
+//! This is the format written by `writeDelimitedTo` and read by `parseDelimitedFrom` of the Java implementation.
+//!
+//! `Fixed32DelimitedMessageDecoder` and `Fixed32DelimitedMessageEncoder` handle streams
+//! in which the lengths are encoded as 4-byte little-endian integers instead.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! use bytecodec::io::IoEncodeExt;
+//! use bytecodec::Encode;
+//! use protobuf_codec::delimited::{DelimitedMessageEncoder, DelimitedMessages};
+//! use protobuf_codec::field::{Fields, FieldDecoder, FieldEncoder, MaybeDefault};
+//! use protobuf_codec::field::num::F1;
+//! use protobuf_codec::message::{MessageDecoder, MessageEncoder};
+//! use protobuf_codec::scalar::{StringDecoder, StringEncoder};
+//!
+//! # fn main() {
+//! type LogEncoder = MessageEncoder<Fields<(MaybeDefault<FieldEncoder<F1, StringEncoder>>,)>>;
+//! type LogDecoder = MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, StringDecoder>>,)>>;
+//!
+//! let mut buf = Vec::new();
+//! let mut encoder = DelimitedMessageEncoder::new(LogEncoder::default());
+//! for line in &["foo", "bar"] {
+//!     encoder.start_encoding((line.to_string(),)).unwrap();
+//!     encoder.encode_all(&mut buf).unwrap();
+//! }
+//! assert_eq!(buf, [5, 10, 3, 102, 111, 111, 5, 10, 3, 98, 97, 114]);
+//!
+//! let lines = DelimitedMessages::new(&buf[..], LogDecoder::default())
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! assert_eq!(lines, [("foo".to_owned(),), ("bar".to_owned(),)]);
+//! # }
+//! ```
+use crate::limit;
+use crate::message::{MessageDecode, MessageEncode};
+use crate::wire::{LengthDelimitedDecoder, LengthDelimitedEncoder};
+use bytecodec::bytes::{BytesEncoder, CopyableBytesDecoder};
+use bytecodec::{ByteCount, Decode, Encode, Eos, Error, ErrorKind, Result, SizedEncode};
+use std::cmp;
+use std::io::{self, Read};
+
+/// Decoder for length-delimited messages.
+///
+/// This decodes one message at a time, so an unbounded stream of messages can be decoded
+/// by repeating `decode` and `finish_decoding`.
+///
+/// If the end of the stream is reached at a boundary between messages,
+/// `decode` returns an `ErrorKind::DecoderTerminated` error.
+/// If it is reached in the middle of a message, an `ErrorKind::UnexpectedEos` error is returned instead.
+#[derive(Debug, Default)]
+pub struct DelimitedMessageDecoder<M> {
+    inner: LengthDelimitedDecoder<M>,
+    started: bool,
+}
+impl<M: MessageDecode> DelimitedMessageDecoder<M> {
+    /// Makes a new `DelimitedMessageDecoder` instance.
+    pub fn new(message_decoder: M) -> Self {
+        DelimitedMessageDecoder {
+            inner: LengthDelimitedDecoder::new(message_decoder),
+            started: false,
+        }
+    }
+
+    /// Returns a reference to the inner message decoder.
+    pub fn inner_ref(&self) -> &M {
+        self.inner.inner_ref()
+    }
+
+    /// Returns a mutable reference to the inner message decoder.
+    pub fn inner_mut(&mut self) -> &mut M {
+        self.inner.inner_mut()
+    }
+
+    /// Takes ownership of the instance and returns the inner message decoder.
+    pub fn into_inner(self) -> M {
+        self.inner.into_inner()
+    }
+}
+impl<M: MessageDecode> Decode for DelimitedMessageDecoder<M> {
+    type Item = M::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        if !self.started {
+            if buf.is_empty() {
+                track_assert!(!eos.is_reached(), ErrorKind::DecoderTerminated);
+                return Ok(0);
+            }
+            self.started = true;
+        }
+
+        let size = track!(self.inner.decode(buf, eos))?;
+        if size == buf.len() && eos.is_reached() {
+            track_assert!(self.inner.is_idle(), ErrorKind::UnexpectedEos);
+        }
+        Ok(size)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track!(self.inner.finish_decoding())?;
+        self.started = false;
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+
+/// Encoder for length-delimited messages.
+///
+/// Non-sized message encoders can be used by wrapping them with `PreEncode`.
+#[derive(Debug, Default)]
+pub struct DelimitedMessageEncoder<M> {
+    inner: LengthDelimitedEncoder<M>,
+}
+impl<M: MessageEncode + SizedEncode> DelimitedMessageEncoder<M> {
+    /// Makes a new `DelimitedMessageEncoder` instance.
+    pub fn new(message_encoder: M) -> Self {
+        DelimitedMessageEncoder {
+            inner: LengthDelimitedEncoder::new(message_encoder),
+        }
+    }
+
+    /// Returns a reference to the inner message encoder.
+    pub fn inner_ref(&self) -> &M {
+        self.inner.inner_ref()
+    }
+
+    /// Returns a mutable reference to the inner message encoder.
+    pub fn inner_mut(&mut self) -> &mut M {
+        self.inner.inner_mut()
+    }
+
+    /// Takes ownership of the instance and returns the inner message encoder.
+    pub fn into_inner(self) -> M {
+        self.inner.into_inner()
+    }
+}
+impl<M: MessageEncode + SizedEncode> Encode for DelimitedMessageEncoder<M> {
+    type Item = M::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl<M: MessageEncode + SizedEncode> SizedEncode for DelimitedMessageEncoder<M> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+/// Decoder for messages prefixed by their lengths encoded as fixed32 (i.e., 4-byte little-endian integers).
+///
+/// Like `DelimitedMessageDecoder`, this decodes one message at a time and
+/// returns an `ErrorKind::DecoderTerminated` error if the end of the stream is reached at a boundary between messages.
+#[derive(Debug, Default)]
+pub struct Fixed32DelimitedMessageDecoder<M> {
+    len: CopyableBytesDecoder<[u8; 4]>,
+    message: M,
+    remaining: Option<u64>,
+    started: bool,
+}
+impl<M: MessageDecode> Fixed32DelimitedMessageDecoder<M> {
+    /// Makes a new `Fixed32DelimitedMessageDecoder` instance.
+    pub fn new(message_decoder: M) -> Self {
+        Fixed32DelimitedMessageDecoder {
+            len: Default::default(),
+            message: message_decoder,
+            remaining: None,
+            started: false,
+        }
+    }
+
+    /// Returns a reference to the inner message decoder.
+    pub fn inner_ref(&self) -> &M {
+        &self.message
+    }
+
+    /// Returns a mutable reference to the inner message decoder.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.message
+    }
+
+    /// Takes ownership of the instance and returns the inner message decoder.
+    pub fn into_inner(self) -> M {
+        self.message
+    }
+}
+impl<M: MessageDecode> Decode for Fixed32DelimitedMessageDecoder<M> {
+    type Item = M::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        if !self.started {
+            if buf.is_empty() {
+                track_assert!(!eos.is_reached(), ErrorKind::DecoderTerminated);
+                return Ok(0);
+            }
+            self.started = true;
+        }
+
+        let mut offset = 0;
+        if self.remaining.is_none() {
+            bytecodec_try_decode!(self.len, offset, buf, eos);
+            let len = u32::from_le_bytes(track!(self.len.finish_decoding())?);
+            track!(limit::check_length(u64::from(len)))?;
+            self.remaining = Some(u64::from(len));
+        }
+        if let Some(remaining) = self.remaining {
+            let buf = &buf[offset..];
+            let limit = cmp::min(buf.len() as u64, remaining) as usize;
+            let required = remaining - limit as u64;
+            if let Some(n) = eos.remaining_bytes().to_u64() {
+                track_assert!(n >= required, ErrorKind::UnexpectedEos; n, required);
+            }
+
+            let inner_eos = Eos::with_remaining_bytes(ByteCount::Finite(required));
+            let size = track!(self.message.decode(&buf[..limit], inner_eos))?;
+            self.remaining = Some(remaining - size as u64);
+            offset += size;
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(self.is_idle(), ErrorKind::IncompleteDecoding);
+        track_assert_eq!(self.remaining, Some(0), ErrorKind::InvalidInput);
+        let item = track!(self.message.finish_decoding())?;
+        self.remaining = None;
+        self.started = false;
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        match self.remaining {
+            None => self.len.requiring_bytes(),
+            Some(n) => ByteCount::Finite(n),
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.remaining.is_some() && self.message.is_idle()
+    }
+}
+
+/// Encoder for messages prefixed by their lengths encoded as fixed32 (i.e., 4-byte little-endian integers).
+///
+/// Non-sized message encoders can be used by wrapping them with `PreEncode`.
+#[derive(Debug, Default)]
+pub struct Fixed32DelimitedMessageEncoder<M> {
+    len: BytesEncoder<[u8; 4]>,
+    message: M,
+}
+impl<M: MessageEncode + SizedEncode> Fixed32DelimitedMessageEncoder<M> {
+    /// Makes a new `Fixed32DelimitedMessageEncoder` instance.
+    pub fn new(message_encoder: M) -> Self {
+        Fixed32DelimitedMessageEncoder {
+            len: Default::default(),
+            message: message_encoder,
+        }
+    }
+
+    /// Returns a reference to the inner message encoder.
+    pub fn inner_ref(&self) -> &M {
+        &self.message
+    }
+
+    /// Returns a mutable reference to the inner message encoder.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.message
+    }
+
+    /// Takes ownership of the instance and returns the inner message encoder.
+    pub fn into_inner(self) -> M {
+        self.message
+    }
+}
+impl<M: MessageEncode + SizedEncode> Encode for Fixed32DelimitedMessageEncoder<M> {
+    type Item = M::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = track!(self.len.encode(buf, eos))?;
+        offset += track!(self.message.encode(&mut buf[offset..], eos))?;
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        track!(self.message.start_encoding(item))?;
+        let len = self.message.exact_requiring_bytes();
+        track_assert!(
+            len <= u64::from(u32::MAX),
+            ErrorKind::InvalidInput,
+            "Too large message: {} bytes",
+            len
+        );
+        track!(self.len.start_encoding((len as u32).to_le_bytes()))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        ByteCount::Finite(self.exact_requiring_bytes())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.len.is_idle() && self.message.is_idle()
+    }
+}
+impl<M: MessageEncode + SizedEncode> SizedEncode for Fixed32DelimitedMessageEncoder<M> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.len.exact_requiring_bytes() + self.message.exact_requiring_bytes()
+    }
+}
+
+/// Iterator over the length-delimited messages read from a `std::io::Read`.
+///
+/// The iteration finishes when the reader reaches its end at a boundary between messages.
+/// After an error is returned, the iterator yields no more items.
+///
+/// The reader is expected to be blocking.
+/// Bytes are read only when the buffered ones are not enough to decode the next message,
+/// so messages are yielded as soon as they arrive.
+#[derive(Debug)]
+pub struct DelimitedMessages<R, M> {
+    reader: R,
+    decoder: DelimitedMessageDecoder<M>,
+    buf: Vec<u8>,
+    head: usize,
+    tail: usize,
+    eos: bool,
+    done: bool,
+}
+impl<R: Read, M: MessageDecode> DelimitedMessages<R, M> {
+    /// Makes a new `DelimitedMessages` instance that decodes messages with `message_decoder`.
+    pub fn new(reader: R, message_decoder: M) -> Self {
+        DelimitedMessages {
+            reader,
+            decoder: DelimitedMessageDecoder::new(message_decoder),
+            buf: vec![0; 8 * 1024],
+            head: 0,
+            tail: 0,
+            eos: false,
+            done: false,
+        }
+    }
+
+    /// Takes ownership of the instance and returns the reader.
+    ///
+    /// Note that the bytes buffered by the iterator are discarded.
+    pub fn into_reader(self) -> R {
+        self.reader
+    }
+
+    fn next_message(&mut self) -> Result<Option<M::Item>> {
+        loop {
+            if self.head == self.tail && !self.eos {
+                self.head = 0;
+                self.tail = loop {
+                    match self.reader.read(&mut self.buf) {
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                        Err(e) => return Err(track!(Error::from(e))),
+                        Ok(size) => break size,
+                    }
+                };
+                self.eos = self.tail == 0;
+            }
+
+            let eos = Eos::new(self.eos);
+            match self.decoder.decode(&self.buf[self.head..self.tail], eos) {
+                Err(ref e) if *e.kind() == ErrorKind::DecoderTerminated => return Ok(None),
+                Err(e) => return Err(track!(e)),
+                Ok(size) => self.head += size,
+            }
+            if self.decoder.is_idle() {
+                let item = track!(self.decoder.finish_decoding())?;
+                return Ok(Some(item));
+            }
+        }
+    }
+}
+impl<R: Read, M: MessageDecode> Iterator for DelimitedMessages<R, M> {
+    type Item = Result<M::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_message() {
+            Ok(Some(item)) => Some(Ok(item)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::num::{F1, F2};
+    use crate::field::{FieldDecoder, FieldEncoder, Fields, MaybeDefault};
+    use crate::message::{MessageDecoder, MessageEncoder};
+    use crate::scalar::{StringDecoder, StringEncoder, Uint32Decoder, Uint32Encoder};
+    use bytecodec::io::{IoDecodeExt, IoEncodeExt};
+
+    type LogEncoder = MessageEncoder<
+        Fields<(
+            MaybeDefault<FieldEncoder<F1, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+        )>,
+    >;
+    type LogDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+        )>,
+    >;
+
+    fn logs() -> Vec<(u32, String)> {
+        vec![
+            (1, "foo".to_owned()),
+            (0, String::new()), // empty message
+            (3, "x".repeat(300)),
+        ]
+    }
+
+    fn encode(logs: Vec<(u32, String)>) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let mut encoder = DelimitedMessageEncoder::new(LogEncoder::default());
+        for log in logs {
+            track_try_unwrap!(encoder.start_encoding(log));
+            track_try_unwrap!(encoder.encode_all(&mut buf));
+        }
+        buf
+    }
+
+    // Reader that returns at most one byte per `read` call
+    struct OneByteReader<'a>(&'a [u8]);
+    impl<'a> Read for OneByteReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let size = std::cmp::min(1, std::cmp::min(buf.len(), self.0.len()));
+            buf[..size].copy_from_slice(&self.0[..size]);
+            self.0 = &self.0[size..];
+            Ok(size)
+        }
+    }
+
+    #[test]
+    fn delimited_messages_work() {
+        let bytes = encode(logs());
+        assert_eq!(&bytes[..8], [7, 8, 1, 18, 3, 102, 111, 111]);
+        assert_eq!(bytes[8], 0);
+
+        let decoded =
+            DelimitedMessages::new(&bytes[..], LogDecoder::default()).collect::<Result<Vec<_>>>();
+        assert_eq!(track_try_unwrap!(decoded), logs());
+
+        let decoded = DelimitedMessages::new(OneByteReader(&bytes), LogDecoder::default())
+            .collect::<Result<Vec<_>>>();
+        assert_eq!(track_try_unwrap!(decoded), logs());
+
+        // Empty stream
+        let mut messages = DelimitedMessages::new(&[][..], LogDecoder::default());
+        assert!(messages.next().is_none());
+    }
+
+    #[test]
+    fn truncated_streams_are_detected() {
+        let bytes = encode(logs());
+        for end in 1..bytes.len() {
+            if end == 8 || end == 9 {
+                // Boundaries between messages
+                continue;
+            }
+            let results =
+                DelimitedMessages::new(&bytes[..end], LogDecoder::default()).collect::<Vec<_>>();
+            let e = results.last().and_then(|r| r.as_ref().err()).cloned();
+            assert_eq!(
+                e.map(|e| *e.kind()),
+                Some(ErrorKind::UnexpectedEos),
+                "end={}",
+                end
+            );
+        }
+    }
+
+    #[test]
+    fn delimited_message_decoder_works() {
+        let bytes = encode(logs());
+        let mut input = &bytes[..];
+        let mut decoder = DelimitedMessageDecoder::new(LogDecoder::default());
+        for log in logs() {
+            assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), log);
+        }
+        assert_eq!(
+            decoder.decode_exact(&mut input).err().map(|e| *e.kind()),
+            Some(ErrorKind::DecoderTerminated)
+        );
+    }
+
+    #[test]
+    fn fixed32_delimited_codecs_work() {
+        let mut bytes = Vec::new();
+        let mut encoder = Fixed32DelimitedMessageEncoder::new(LogEncoder::default());
+        for log in logs() {
+            track_try_unwrap!(encoder.start_encoding(log));
+            track_try_unwrap!(encoder.encode_all(&mut bytes));
+        }
+        assert_eq!(&bytes[..11], [7, 0, 0, 0, 8, 1, 18, 3, 102, 111, 111]);
+        assert_eq!(&bytes[11..15], [0, 0, 0, 0]);
+        assert_eq!(&bytes[15..19], [0x31, 1, 0, 0]);
+
+        let mut input = &bytes[..];
+        let mut decoder = Fixed32DelimitedMessageDecoder::new(LogDecoder::default());
+        for log in logs() {
+            assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), log);
+        }
+        assert_eq!(
+            decoder.decode_exact(&mut input).err().map(|e| *e.kind()),
+            Some(ErrorKind::DecoderTerminated)
+        );
+
+        for end in 1..bytes.len() {
+            if end == 11 || end == 15 {
+                // Boundaries between messages
+                continue;
+            }
+            let mut input = &bytes[..end];
+            let mut decoder = Fixed32DelimitedMessageDecoder::new(LogDecoder::default());
+            let e = (0..3).find_map(|_| decoder.decode_exact(&mut input).err());
+            assert_eq!(
+                e.map(|e| *e.kind()),
+                Some(ErrorKind::UnexpectedEos),
+                "end={}",
+                end
+            );
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use super::descriptor::{DescriptorPool, FieldDescriptor, FieldType, MessageDescriptor};
+use super::value::{DynamicMessage, MapKey, Value};
+use crate::field::num::FieldNum;
+use crate::field::UnknownField;
+use crate::limit::{self, LimitExceeded};
+use crate::message::MessageDecode;
+use crate::schema::Label;
+use crate::wire::{
+    LengthDelimitedDecoder, RawValueDecoder, Tag, TagDecoder, VarintDecoder, WireType,
+};
+use bytecodec::bytes::{CopyableBytesDecoder, RemainingBytesDecoder};
+use bytecodec::{ByteCount, Decode, Eos, ErrorKind, Result};
+use std::collections::BTreeMap;
+use std::mem;
+use std::sync::Arc;
+
+/// Decoder for messages described by a `MessageDescriptor` at runtime.
+///
+/// The types of embedded messages and enums are looked up from the `DescriptorPool` given to the decoder.
+///
+/// Fields that are not defined in the descriptor are preserved as the unknown fields of the resulting message.
+/// Enum values that are not defined in the enum descriptor are accepted as they are.
+///
+/// The nesting depth of embedded messages and groups is limited to `limit::DEFAULT_MAX_DEPTH` by default,
+/// even if the decoder is not driven by a `LimitedDecoder`.
+/// If the limit is exceeded, an `ErrorKind::InvalidInput` error caused by `LimitExceeded::Depth` is returned.
+#[derive(Debug)]
+pub struct DynamicMessageDecoder {
+    pool: Arc<DescriptorPool>,
+    message: DynamicMessage,
+    tag: TagDecoder,
+    reading_tag: bool,
+    field: FieldState,
+
+    // The field number of the group if this decodes the body of a group
+    group: Option<FieldNum>,
+
+    depth: usize,
+    max_depth: Option<usize>,
+
+    started: bool,
+    eos: bool, // end-of-stream
+}
+impl DynamicMessageDecoder {
+    /// Makes a new `DynamicMessageDecoder` instance that decodes the message named `message_name`.
+    ///
+    /// If `pool` does not have such message, this returns an `ErrorKind::InvalidInput` error.
+    pub fn new(pool: Arc<DescriptorPool>, message_name: &str) -> Result<Self> {
+        let descriptor = track_assert_some!(
+            pool.message(message_name).cloned(),
+            ErrorKind::InvalidInput,
+            "Unknown message: {}",
+            message_name
+        );
+        Ok(Self::with_descriptor(pool, descriptor))
+    }
+
+    /// Makes a new `DynamicMessageDecoder` instance that decodes the message described by `descriptor`.
+    pub fn with_descriptor(pool: Arc<DescriptorPool>, descriptor: Arc<MessageDescriptor>) -> Self {
+        DynamicMessageDecoder {
+            pool,
+            message: DynamicMessage::new(descriptor),
+            tag: TagDecoder::new(),
+            reading_tag: false,
+            field: FieldState::None,
+            group: None,
+            depth: 0,
+            max_depth: Some(limit::DEFAULT_MAX_DEPTH),
+            started: false,
+            eos: false,
+        }
+    }
+
+    /// Returns the maximum nesting depth of embedded messages and groups.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Sets the maximum nesting depth of embedded messages and groups.
+    ///
+    /// The fields of the outermost message are at depth `0`.
+    /// `None` means that the depth is not limited by the decoder itself
+    /// (the limit of an enclosing `LimitedDecoder` is still enforced).
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+        self.max_depth = max_depth;
+    }
+
+    fn nested_decoder(&self, descriptor: Arc<MessageDescriptor>) -> Result<Self> {
+        if self.max_depth.is_some_and(|max| self.depth >= max) {
+            return Err(limit::limit_error(LimitExceeded::Depth));
+        }
+        let mut decoder = Self::with_descriptor(self.pool.clone(), descriptor);
+        decoder.depth = self.depth + 1;
+        decoder.max_depth = self.max_depth;
+        Ok(decoder)
+    }
+
+    fn start_field(&mut self, tag: Tag) -> Result<()> {
+        let descriptor = self.message.descriptor().clone();
+        let field = if let Some(field) = descriptor.field(tag.field_num.as_u32()) {
+            field
+        } else {
+            let mut raw = RawValueDecoder::new(false);
+            track!(raw.start(tag))?;
+            self.field = FieldState::Unknown(tag, raw);
+            return Ok(());
+        };
+
+        let number = field.number;
+        let packed = field.is_repeated()
+            && field.ty.is_numeric()
+            && tag.wire_type == WireType::LengthDelimited;
+        if !packed {
+            track_assert_eq!(
+                tag.wire_type,
+                field.ty.wire_type(),
+                ErrorKind::InvalidInput,
+                "Unexpected wire type: message={}, field={}",
+                descriptor.name,
+                field.name
+            );
+        }
+
+        self.field = match field.ty {
+            _ if packed => FieldState::Bytes(number, Default::default()),
+            FieldType::Message(ref name) => {
+                let descriptor = track!(self.message_descriptor(name))?;
+                let decoder = track!(self.nested_decoder(descriptor))?;
+                FieldState::Message(number, Box::new(LengthDelimitedDecoder::new(decoder)))
+            }
+            FieldType::Map(..) => {
+                let descriptor = MessageDescriptor::map_entry(field).expect("Never fails");
+                let decoder = track!(self.nested_decoder(Arc::new(descriptor)))?;
+                FieldState::Message(number, Box::new(LengthDelimitedDecoder::new(decoder)))
+            }
+            FieldType::Group(ref name) => {
+                let descriptor = track!(self.message_descriptor(name))?;
+                let mut decoder = track!(self.nested_decoder(descriptor))?;
+                decoder.group = Some(tag.field_num);
+                FieldState::Group(number, Box::new(decoder))
+            }
+            ref ty => match ty.wire_type() {
+                WireType::Varint => FieldState::Varint(number, VarintDecoder::new()),
+                WireType::Bit32 => FieldState::Bit32(number, Default::default()),
+                WireType::Bit64 => FieldState::Bit64(number, Default::default()),
+                _ => FieldState::Bytes(number, Default::default()),
+            },
+        };
+        Ok(())
+    }
+
+    fn finish_field(&mut self) -> Result<()> {
+        let descriptor = self.message.descriptor().clone();
+        let (number, value) = match mem::replace(&mut self.field, FieldState::None) {
+            FieldState::None => unreachable!(),
+            FieldState::Unknown(tag, mut raw) => {
+                let value = track!(raw.finish_decoding())?;
+                self.message
+                    .unknown_fields_mut()
+                    .push(UnknownField { tag, value });
+                return Ok(());
+            }
+            FieldState::Varint(number, mut d) => {
+                let n = track!(d.finish_decoding())?;
+                (number, varint_value(field_type(&descriptor, number), n))
+            }
+            FieldState::Bit32(number, mut d) => {
+                let bytes = track!(d.finish_decoding())?;
+                (number, bit32_value(field_type(&descriptor, number), bytes))
+            }
+            FieldState::Bit64(number, mut d) => {
+                let bytes = track!(d.finish_decoding())?;
+                (number, bit64_value(field_type(&descriptor, number), bytes))
+            }
+            FieldState::Bytes(number, mut d) => {
+                let bytes = track!(d.finish_decoding())?;
+                let field = descriptor.field(number).expect("Never fails");
+                match field.ty {
+                    FieldType::String => match String::from_utf8(bytes) {
+                        Ok(s) => (number, Value::String(s)),
+                        Err(e) => {
+                            track_panic!(ErrorKind::InvalidInput, "{}: field={}", e, field.name)
+                        }
+                    },
+                    FieldType::Bytes => (number, Value::Bytes(bytes)),
+                    ref ty => {
+                        let values = track!(packed_values(ty, &bytes); field.name)?;
+                        self.message.merge_field(number, Value::List(values));
+                        return track!(self.check_count(number); field.name);
+                    }
+                }
+            }
+            FieldState::Message(number, mut d) => {
+                let m = track!(d.finish_decoding())?;
+                let field = descriptor.field(number).expect("Never fails");
+                if field.is_map() {
+                    (number, track!(self.map_entry(field, m))?)
+                } else {
+                    (number, Value::Message(m))
+                }
+            }
+            FieldState::Group(number, mut d) => {
+                let m = track!(d.finish_decoding())?;
+                (number, Value::Message(m))
+            }
+        };
+
+        let field = descriptor.field(number).expect("Never fails");
+        if field.is_repeated() {
+            self.message.merge_field(number, Value::List(vec![value]));
+        } else {
+            self.message.merge_field(number, value);
+        }
+        track!(self.check_count(number); field.name)
+    }
+
+    fn check_count(&self, number: u32) -> Result<()> {
+        match self.message.get(number) {
+            Some(Value::List(values)) => track!(limit::check_repeated(values.len())),
+            Some(Value::Map(entries)) => track!(limit::check_map_entries(entries.len())),
+            _ => Ok(()),
+        }
+    }
+
+    fn map_entry(&self, field: &FieldDescriptor, mut entry: DynamicMessage) -> Result<Value> {
+        let (key_type, value_type) = match field.ty {
+            FieldType::Map(ref k, ref v) => (k, v),
+            _ => unreachable!(),
+        };
+        let key = match entry.remove(1) {
+            Some(key) => key,
+            None => track!(Value::default_of(key_type, &self.pool))?,
+        };
+        let key = track_assert_some!(
+            MapKey::from_value(key),
+            ErrorKind::InvalidInput,
+            "Invalid map key type: field={}",
+            field.name
+        );
+        let value = match entry.remove(2) {
+            Some(value) => value,
+            None => track!(Value::default_of(value_type, &self.pool))?,
+        };
+
+        let mut entries = BTreeMap::new();
+        entries.insert(key, value);
+        Ok(Value::Map(entries))
+    }
+
+    fn message_descriptor(&self, name: &str) -> Result<Arc<MessageDescriptor>> {
+        let descriptor = track_assert_some!(
+            self.pool.message(name),
+            ErrorKind::InvalidInput,
+            "Unknown message: {}",
+            name
+        );
+        Ok(descriptor.clone())
+    }
+}
+impl Decode for DynamicMessageDecoder {
+    type Item = DynamicMessage;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        self.started = true;
+        if self.eos {
+            return Ok(0);
+        }
+
+        let mut offset = 0;
+        while offset < buf.len() {
+            if let FieldState::None = self.field {
+                offset += track!(self.tag.decode(&buf[offset..], eos))?;
+                self.reading_tag = true;
+                if self.tag.is_idle() {
+                    self.reading_tag = false;
+                    let tag = track!(self.tag.finish_decoding())?;
+                    if tag.wire_type == WireType::EndGroup {
+                        track_assert_eq!(
+                            self.group,
+                            Some(tag.field_num),
+                            ErrorKind::InvalidInput,
+                            "Unmatched end group tag"
+                        );
+                        self.eos = true;
+                        return Ok(offset);
+                    }
+                    track!(self.start_field(tag))?;
+                }
+            } else {
+                offset += track!(self.field.decode(&buf[offset..], eos))?;
+                if !self.field.is_idle() {
+                    return Ok(offset);
+                }
+                track!(self.finish_field())?;
+            }
+        }
+        if self.group.is_none() {
+            self.eos = eos.is_reached();
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(!self.started | self.eos, ErrorKind::IncompleteDecoding; self.started, self.eos);
+        track_assert!(
+            !self.reading_tag && matches!(self.field, FieldState::None),
+            ErrorKind::IncompleteDecoding
+        );
+        let descriptor = self.message.descriptor().clone();
+        for f in &descriptor.fields {
+            track_assert!(
+                f.label != Some(Label::Required) || self.message.get(f.number).is_some(),
+                ErrorKind::InvalidInput,
+                "Missing required field: message={}, field={}",
+                descriptor.name,
+                f.name
+            );
+        }
+        self.started = false;
+        self.eos = false;
+        Ok(mem::replace(
+            &mut self.message,
+            DynamicMessage::new(descriptor),
+        ))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.eos {
+            ByteCount::Finite(0)
+        } else {
+            match self.field {
+                FieldState::None => ByteCount::Unknown,
+                ref field => field.requiring_bytes(),
+            }
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.eos
+    }
+}
+impl MessageDecode for DynamicMessageDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::IncompleteDecoding; self.started);
+        self.eos = false;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum FieldState {
+    None,
+    Varint(u32, VarintDecoder),
+    Bit32(u32, CopyableBytesDecoder<[u8; 4]>),
+    Bit64(u32, CopyableBytesDecoder<[u8; 8]>),
+    Bytes(u32, LengthDelimitedDecoder<RemainingBytesDecoder>),
+    Message(u32, Box<LengthDelimitedDecoder<DynamicMessageDecoder>>),
+    Group(u32, Box<DynamicMessageDecoder>),
+    Unknown(Tag, RawValueDecoder),
+}
+impl FieldState {
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        match *self {
+            FieldState::None => Ok(0),
+            FieldState::Varint(_, ref mut d) => track!(d.decode(buf, eos)),
+            FieldState::Bit32(_, ref mut d) => track!(d.decode(buf, eos)),
+            FieldState::Bit64(_, ref mut d) => track!(d.decode(buf, eos)),
+            FieldState::Bytes(_, ref mut d) => track!(d.decode(buf, eos)),
+            FieldState::Message(_, ref mut d) => {
+                let _nested = track!(limit::enter_nested())?;
+                track!(d.decode(buf, eos))
+            }
+            FieldState::Group(_, ref mut d) => {
+                let _nested = track!(limit::enter_nested())?;
+                track!(d.decode(buf, eos))
+            }
+            FieldState::Unknown(_, ref mut d) => track!(d.decode(buf, eos)),
+        }
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        match *self {
+            FieldState::None => ByteCount::Finite(0),
+            FieldState::Varint(_, ref d) => d.requiring_bytes(),
+            FieldState::Bit32(_, ref d) => d.requiring_bytes(),
+            FieldState::Bit64(_, ref d) => d.requiring_bytes(),
+            FieldState::Bytes(_, ref d) => d.requiring_bytes(),
+            FieldState::Message(_, ref d) => d.requiring_bytes(),
+            FieldState::Group(_, ref d) => d.requiring_bytes(),
+            FieldState::Unknown(_, ref d) => d.requiring_bytes(),
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        match *self {
+            FieldState::None => true,
+            FieldState::Varint(_, ref d) => d.is_idle(),
+            FieldState::Bit32(_, ref d) => d.is_idle(),
+            FieldState::Bit64(_, ref d) => d.is_idle(),
+            FieldState::Bytes(_, ref d) => d.is_idle(),
+            FieldState::Message(_, ref d) => d.is_idle(),
+            FieldState::Group(_, ref d) => d.is_idle(),
+            FieldState::Unknown(_, ref d) => d.is_idle(),
+        }
+    }
+}
+
+fn field_type(descriptor: &MessageDescriptor, number: u32) -> &FieldType {
+    &descriptor.field(number).expect("Never fails").ty
+}
+
+fn varint_value(ty: &FieldType, n: u64) -> Value {
+    match *ty {
+        FieldType::Int32 => Value::I32(n as i32),
+        FieldType::Int64 => Value::I64(n as i64),
+        FieldType::Uint32 => Value::U32(n as u32),
+        FieldType::Uint64 => Value::U64(n),
+        FieldType::Sint32 => {
+            let n = n as u32;
+            Value::I32((n >> 1) as i32 ^ -((n & 1) as i32))
+        }
+        FieldType::Sint64 => Value::I64((n >> 1) as i64 ^ -((n & 1) as i64)),
+        FieldType::Bool => Value::Bool(n != 0),
+        FieldType::Enum(_) => Value::Enum(n as i32),
+        _ => unreachable!(),
+    }
+}
+
+fn bit32_value(ty: &FieldType, bytes: [u8; 4]) -> Value {
+    match *ty {
+        FieldType::Fixed32 => Value::U32(u32::from_le_bytes(bytes)),
+        FieldType::Sfixed32 => Value::I32(i32::from_le_bytes(bytes)),
+        FieldType::Float => Value::F32(f32::from_le_bytes(bytes)),
+        _ => unreachable!(),
+    }
+}
+
+fn bit64_value(ty: &FieldType, bytes: [u8; 8]) -> Value {
+    match *ty {
+        FieldType::Fixed64 => Value::U64(u64::from_le_bytes(bytes)),
+        FieldType::Sfixed64 => Value::I64(i64::from_le_bytes(bytes)),
+        FieldType::Double => Value::F64(f64::from_le_bytes(bytes)),
+        _ => unreachable!(),
+    }
+}
+
+fn packed_values(ty: &FieldType, bytes: &[u8]) -> Result<Vec<Value>> {
+    let mut values = Vec::new();
+    match ty.wire_type() {
+        WireType::Varint => {
+            let mut decoder = VarintDecoder::new();
+            let mut offset = 0;
+            while offset < bytes.len() {
+                offset += track!(decoder.decode(&bytes[offset..], Eos::new(true)))?;
+                let n = track!(decoder.finish_decoding())?;
+                values.push(varint_value(ty, n));
+            }
+        }
+        WireType::Bit32 => {
+            track_assert_eq!(bytes.len() % 4, 0, ErrorKind::InvalidInput);
+            for chunk in bytes.chunks(4) {
+                let mut buf = [0; 4];
+                buf.copy_from_slice(chunk);
+                values.push(bit32_value(ty, buf));
+            }
+        }
+        WireType::Bit64 => {
+            track_assert_eq!(bytes.len() % 8, 0, ErrorKind::InvalidInput);
+            for chunk in bytes.chunks(8) {
+                let mut buf = [0; 8];
+                buf.copy_from_slice(chunk);
+                values.push(bit64_value(ty, buf));
+            }
+        }
+        _ => unreachable!(),
+    }
+    Ok(values)
+}
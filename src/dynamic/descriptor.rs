@@ -0,0 +1,943 @@
+use crate::schema::{self, Label, Message, ProtoFile, Syntax};
+use crate::wellknown::google::protobuf::{
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FieldDescriptorProtoLabel,
+    FieldDescriptorProtoType, FileDescriptorProto, FileDescriptorSet,
+};
+use crate::wire::{self, WireType};
+use bytecodec::{ErrorKind, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Set of message and enum descriptors.
+///
+/// Types are registered by their full names (e.g., `foo.bar.SearchRequest`),
+/// and the message and enum types of fields refer to other types by those names.
+/// So recursive and mutually recursive messages can be described.
+#[derive(Debug, Default, Clone)]
+pub struct DescriptorPool {
+    messages: HashMap<String, Arc<MessageDescriptor>>,
+    enums: HashMap<String, Arc<EnumDescriptor>>,
+}
+impl DescriptorPool {
+    /// Makes a new empty `DescriptorPool` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes a new `DescriptorPool` instance that has the types defined in the given files.
+    pub fn from_files(files: &[ProtoFile]) -> Result<Self> {
+        let mut pool = Self::new();
+        track!(pool.add_files(files))?;
+        Ok(pool)
+    }
+
+    /// Adds the types defined in the given files.
+    ///
+    /// Type names in the files are resolved in accordance with the scoping rules of protobuf.
+    /// The files can refer to each other and to the types that have been added to this pool.
+    pub fn add_files(&mut self, files: &[ProtoFile]) -> Result<()> {
+        let mut names = HashMap::new();
+        for name in self.messages.keys() {
+            names.insert(format!(".{}", name), NameKind::Message);
+        }
+        for name in self.enums.keys() {
+            names.insert(format!(".{}", name), NameKind::Enum);
+        }
+        for file in files {
+            let scope = package_scope(file);
+            for m in &file.messages {
+                track!(collect_message_names(&mut names, &scope, m))?;
+            }
+            for e in &file.enums {
+                track!(insert_name(&mut names, &scope, &e.name, NameKind::Enum))?;
+            }
+        }
+
+        let map_entries = HashMap::new();
+        let mut converter = Converter {
+            names: &names,
+            map_entries: &map_entries,
+            messages: Vec::new(),
+            enums: Vec::new(),
+        };
+        for file in files {
+            let scope = package_scope(file);
+            for m in &file.messages {
+                track!(converter.message(&scope, file.syntax, m))?;
+            }
+            for e in &file.enums {
+                converter.enumeration(&scope, e);
+            }
+        }
+        let Converter {
+            messages, enums, ..
+        } = converter;
+        for m in messages {
+            track!(self.add_message(m))?;
+        }
+        for e in enums {
+            track!(self.add_enum(e))?;
+        }
+        Ok(())
+    }
+
+    /// Makes a new `DescriptorPool` instance that has the types defined in the given descriptor set.
+    ///
+    /// This can be used for loading the output of `protoc --descriptor_set_out`.
+    pub fn from_file_descriptor_set(set: &FileDescriptorSet) -> Result<Self> {
+        let mut pool = Self::new();
+        track!(pool.add_file_descriptors(&set.file))?;
+        Ok(pool)
+    }
+
+    /// Adds the types defined in the given file descriptors.
+    ///
+    /// Like `add_files`, type names are resolved in accordance with the scoping rules of protobuf,
+    /// so the files can refer to each other and to the types that have been added to this pool.
+    ///
+    /// The map entry messages (i.e., the messages that have the `map_entry` option) are
+    /// converted to `FieldType::Map` types of the fields that refer to them,
+    /// and are not added to this pool.
+    pub fn add_file_descriptors(&mut self, files: &[FileDescriptorProto]) -> Result<()> {
+        let mut names = HashMap::new();
+        for name in self.messages.keys() {
+            names.insert(format!(".{}", name), NameKind::Message);
+        }
+        for name in self.enums.keys() {
+            names.insert(format!(".{}", name), NameKind::Enum);
+        }
+        let mut map_entries = HashMap::new();
+        for file in files {
+            let scope = file_scope(file);
+            for m in &file.message_type {
+                track!(collect_descriptor_names(
+                    &mut names,
+                    &mut map_entries,
+                    &scope,
+                    m
+                ))?;
+            }
+            for e in &file.enum_type {
+                let name = track_assert_some!(e.name.as_ref(), ErrorKind::InvalidInput);
+                track!(insert_name(&mut names, &scope, name, NameKind::Enum))?;
+            }
+        }
+
+        let mut converter = Converter {
+            names: &names,
+            map_entries: &map_entries,
+            messages: Vec::new(),
+            enums: Vec::new(),
+        };
+        for file in files {
+            let syntax = match file.syntax.as_deref() {
+                None | Some("proto2") => Syntax::Proto2,
+                Some("proto3") => Syntax::Proto3,
+                Some(other) => track_panic!(ErrorKind::InvalidInput, "Unknown syntax: {}", other),
+            };
+            let scope = file_scope(file);
+            for m in &file.message_type {
+                track!(converter.descriptor(&scope, syntax, m); file.name)?;
+            }
+            for e in &file.enum_type {
+                converter.enum_descriptor(&scope, e);
+            }
+        }
+        let Converter {
+            messages, enums, ..
+        } = converter;
+        for m in messages {
+            track!(self.add_message(m))?;
+        }
+        for e in enums {
+            track!(self.add_enum(e))?;
+        }
+        Ok(())
+    }
+
+    /// Adds a message descriptor.
+    ///
+    /// If a type that has the same name already exists, this returns an `ErrorKind::InvalidInput` error.
+    pub fn add_message(&mut self, message: MessageDescriptor) -> Result<()> {
+        track!(self.check_name(&message.name))?;
+        self.messages
+            .insert(message.name.clone(), Arc::new(message));
+        Ok(())
+    }
+
+    /// Adds an enum descriptor.
+    ///
+    /// If a type that has the same name already exists, this returns an `ErrorKind::InvalidInput` error.
+    pub fn add_enum(&mut self, enumeration: EnumDescriptor) -> Result<()> {
+        track!(self.check_name(&enumeration.name))?;
+        self.enums
+            .insert(enumeration.name.clone(), Arc::new(enumeration));
+        Ok(())
+    }
+
+    /// Adds the descriptors of the well-known types supported by this crate.
+    ///
+    /// The following types are added (the types that already exist in this pool are skipped):
+    ///
+    /// - `google.protobuf.Any`
+    /// - `google.protobuf.Duration`
+    /// - `google.protobuf.Empty`
+    /// - `google.protobuf.FieldMask`
+    /// - `google.protobuf.Timestamp`
+    /// - `google.protobuf.Struct`, `google.protobuf.Value`, `google.protobuf.ListValue`
+    ///   and `google.protobuf.NullValue`
+    /// - The wrapper types (e.g., `google.protobuf.Int32Value`)
+    ///
+    /// This should be called before adding files that import the well-known types.
+    pub fn add_wellknown_types(&mut self) {
+        for m in wellknown_messages() {
+            if !self.messages.contains_key(&m.name) {
+                self.messages.insert(m.name.clone(), Arc::new(m));
+            }
+        }
+        let null_value = EnumDescriptor::new(
+            "google.protobuf.NullValue",
+            vec![EnumValueDescriptor::new("NULL_VALUE", 0)],
+        );
+        if !self.enums.contains_key(&null_value.name) {
+            self.enums
+                .insert(null_value.name.clone(), Arc::new(null_value));
+        }
+    }
+
+    /// Returns the message descriptor that has the given full name.
+    ///
+    /// The leading dot of the name can be omitted (e.g., both `.foo.Bar` and `foo.Bar` are accepted).
+    pub fn message(&self, name: &str) -> Option<&Arc<MessageDescriptor>> {
+        self.messages.get(name.trim_start_matches('.'))
+    }
+
+    /// Returns the enum descriptor that has the given full name.
+    ///
+    /// The leading dot of the name can be omitted.
+    pub fn enum_type(&self, name: &str) -> Option<&Arc<EnumDescriptor>> {
+        self.enums.get(name.trim_start_matches('.'))
+    }
+
+    /// Returns an iterator over the message descriptors in this pool.
+    pub fn messages(&self) -> impl Iterator<Item = &Arc<MessageDescriptor>> {
+        self.messages.values()
+    }
+
+    /// Returns an iterator over the enum descriptors in this pool.
+    pub fn enums(&self) -> impl Iterator<Item = &Arc<EnumDescriptor>> {
+        self.enums.values()
+    }
+
+    fn check_name(&self, name: &str) -> Result<()> {
+        track_assert!(
+            !self.messages.contains_key(name) && !self.enums.contains_key(name),
+            ErrorKind::InvalidInput,
+            "Duplicate type: {}",
+            name
+        );
+        Ok(())
+    }
+}
+
+/// Message descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageDescriptor {
+    /// Full name of the message (e.g., `foo.bar.SearchRequest`).
+    pub name: String,
+
+    /// Fields of the message (including the fields of the oneofs).
+    pub fields: Vec<FieldDescriptor>,
+}
+impl MessageDescriptor {
+    /// Makes a new `MessageDescriptor` instance.
+    pub fn new(name: &str, fields: Vec<FieldDescriptor>) -> Self {
+        MessageDescriptor {
+            name: name.trim_start_matches('.').to_owned(),
+            fields,
+        }
+    }
+
+    /// Returns the field that has the given number.
+    pub fn field(&self, number: u32) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|f| f.number == number)
+    }
+
+    /// Returns the field that has the given name.
+    pub fn field_by_name(&self, name: &str) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Returns the descriptor of the entries of the given map field.
+    ///
+    /// Map entries are encoded as messages that have a key (field `1`) and a value (field `2`).
+    pub(crate) fn map_entry(field: &FieldDescriptor) -> Option<Self> {
+        if let FieldType::Map(ref key, ref value) = field.ty {
+            let fields = vec![
+                FieldDescriptor::new("key", 1, (**key).clone()),
+                FieldDescriptor::new("value", 2, (**value).clone()),
+            ];
+            Some(MessageDescriptor::new(
+                &format!("{}Entry", field.name),
+                fields,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Field descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDescriptor {
+    /// Field name.
+    pub name: String,
+
+    /// Field number.
+    pub number: u32,
+
+    /// Field type.
+    pub ty: FieldType,
+
+    /// Field label.
+    ///
+    /// This is `None` for proto3 singular fields, map fields and oneof fields.
+    pub label: Option<Label>,
+
+    /// Whether repeated numeric values of the field are encoded in the packed format.
+    pub packed: bool,
+
+    /// Name of the oneof to which the field belongs.
+    pub oneof: Option<String>,
+}
+impl FieldDescriptor {
+    /// Makes a new `FieldDescriptor` instance.
+    ///
+    /// The field does not have a label, is not packed and does not belong to any oneof.
+    pub fn new(name: &str, number: u32, ty: FieldType) -> Self {
+        FieldDescriptor {
+            name: name.to_owned(),
+            number,
+            ty,
+            label: None,
+            packed: false,
+            oneof: None,
+        }
+    }
+
+    /// Returns the name of the field used in the JSON mapping.
+    ///
+    /// This is the lowerCamelCase form of the field name (e.g., `page_number` is converted to `pageNumber`).
+    pub fn json_name(&self) -> String {
+        json_name(&self.name)
+    }
+
+    /// Returns `true` if the field is a repeated field, otherwise `false`.
+    ///
+    /// Note that map fields are not regarded as repeated fields.
+    pub fn is_repeated(&self) -> bool {
+        self.label == Some(Label::Repeated)
+    }
+
+    /// Returns `true` if the field is a map field, otherwise `false`.
+    pub fn is_map(&self) -> bool {
+        matches!(self.ty, FieldType::Map(..))
+    }
+
+    /// Returns `true` if the field tracks the presence of its value, otherwise `false`.
+    ///
+    /// The fields that do not track presence (i.e., proto3 singular scalar fields) are
+    /// omitted by encoders when they have the default values.
+    pub fn has_presence(&self) -> bool {
+        match self.label {
+            Some(Label::Repeated) => false,
+            Some(_) => true,
+            None => {
+                self.oneof.is_some()
+                    || matches!(self.ty, FieldType::Message(_) | FieldType::Group(_))
+            }
+        }
+    }
+}
+
+/// Field type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum FieldType {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+
+    /// Enum type that has the given full name.
+    Enum(String),
+
+    /// Message type that has the given full name.
+    Message(String),
+
+    /// Group type whose body is the message that has the given full name.
+    Group(String),
+
+    /// Map type.
+    Map(Box<FieldType>, Box<FieldType>),
+}
+impl FieldType {
+    /// Returns the wire type used for encoding a (non-packed) value of this type.
+    pub fn wire_type(&self) -> WireType {
+        match *self {
+            FieldType::Int32
+            | FieldType::Int64
+            | FieldType::Uint32
+            | FieldType::Uint64
+            | FieldType::Sint32
+            | FieldType::Sint64
+            | FieldType::Bool
+            | FieldType::Enum(_) => WireType::Varint,
+            FieldType::Fixed32 | FieldType::Sfixed32 | FieldType::Float => WireType::Bit32,
+            FieldType::Fixed64 | FieldType::Sfixed64 | FieldType::Double => WireType::Bit64,
+            FieldType::String | FieldType::Bytes | FieldType::Message(_) | FieldType::Map(..) => {
+                WireType::LengthDelimited
+            }
+            FieldType::Group(_) => WireType::StartGroup,
+        }
+    }
+
+    /// Returns `true` if this is a numeric type (i.e., a type that can be packed), otherwise `false`.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.wire_type(),
+            WireType::Varint | WireType::Bit32 | WireType::Bit64
+        )
+    }
+}
+
+/// Enum descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumDescriptor {
+    /// Full name of the enum.
+    pub name: String,
+
+    /// Values of the enum.
+    pub values: Vec<EnumValueDescriptor>,
+}
+impl EnumDescriptor {
+    /// Makes a new `EnumDescriptor` instance.
+    pub fn new(name: &str, values: Vec<EnumValueDescriptor>) -> Self {
+        EnumDescriptor {
+            name: name.trim_start_matches('.').to_owned(),
+            values,
+        }
+    }
+
+    /// Returns the first value that has the given number.
+    pub fn value(&self, number: i32) -> Option<&EnumValueDescriptor> {
+        self.values.iter().find(|v| v.number == number)
+    }
+
+    /// Returns the value that has the given name.
+    pub fn value_by_name(&self, name: &str) -> Option<&EnumValueDescriptor> {
+        self.values.iter().find(|v| v.name == name)
+    }
+
+    /// Returns the default value of the enum (i.e., the number of the first value).
+    pub fn default_value(&self) -> i32 {
+        self.values.first().map_or(0, |v| v.number)
+    }
+}
+
+/// Enum value descriptor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumValueDescriptor {
+    /// Name of the value.
+    pub name: String,
+
+    /// Number of the value.
+    pub number: i32,
+}
+impl EnumValueDescriptor {
+    /// Makes a new `EnumValueDescriptor` instance.
+    pub fn new(name: &str, number: i32) -> Self {
+        EnumValueDescriptor {
+            name: name.to_owned(),
+            number,
+        }
+    }
+}
+
+fn wellknown_messages() -> Vec<MessageDescriptor> {
+    let message = |name: &str| FieldType::Message(format!("google.protobuf.{}", name));
+    let kind = |name: &str, number: u32, ty: FieldType| FieldDescriptor {
+        oneof: Some("kind".to_owned()),
+        ..FieldDescriptor::new(name, number, ty)
+    };
+    let mut messages = vec![
+        MessageDescriptor::new(
+            "google.protobuf.Any",
+            vec![
+                FieldDescriptor::new("type_url", 1, FieldType::String),
+                FieldDescriptor::new("value", 2, FieldType::Bytes),
+            ],
+        ),
+        MessageDescriptor::new(
+            "google.protobuf.Duration",
+            vec![
+                FieldDescriptor::new("seconds", 1, FieldType::Int64),
+                FieldDescriptor::new("nanos", 2, FieldType::Int32),
+            ],
+        ),
+        MessageDescriptor::new("google.protobuf.Empty", Vec::new()),
+        MessageDescriptor::new(
+            "google.protobuf.FieldMask",
+            vec![FieldDescriptor {
+                label: Some(Label::Repeated),
+                ..FieldDescriptor::new("paths", 1, FieldType::String)
+            }],
+        ),
+        MessageDescriptor::new(
+            "google.protobuf.Timestamp",
+            vec![
+                FieldDescriptor::new("seconds", 1, FieldType::Int64),
+                FieldDescriptor::new("nanos", 2, FieldType::Int32),
+            ],
+        ),
+        MessageDescriptor::new(
+            "google.protobuf.Struct",
+            vec![FieldDescriptor::new(
+                "fields",
+                1,
+                FieldType::Map(Box::new(FieldType::String), Box::new(message("Value"))),
+            )],
+        ),
+        MessageDescriptor::new(
+            "google.protobuf.Value",
+            vec![
+                kind(
+                    "null_value",
+                    1,
+                    FieldType::Enum("google.protobuf.NullValue".to_owned()),
+                ),
+                kind("number_value", 2, FieldType::Double),
+                kind("string_value", 3, FieldType::String),
+                kind("bool_value", 4, FieldType::Bool),
+                kind("struct_value", 5, message("Struct")),
+                kind("list_value", 6, message("ListValue")),
+            ],
+        ),
+        MessageDescriptor::new(
+            "google.protobuf.ListValue",
+            vec![FieldDescriptor {
+                label: Some(Label::Repeated),
+                ..FieldDescriptor::new("values", 1, message("Value"))
+            }],
+        ),
+    ];
+    let wrappers = vec![
+        ("DoubleValue", FieldType::Double),
+        ("FloatValue", FieldType::Float),
+        ("Int64Value", FieldType::Int64),
+        ("UInt64Value", FieldType::Uint64),
+        ("Int32Value", FieldType::Int32),
+        ("UInt32Value", FieldType::Uint32),
+        ("BoolValue", FieldType::Bool),
+        ("StringValue", FieldType::String),
+        ("BytesValue", FieldType::Bytes),
+    ];
+    for (name, ty) in wrappers {
+        messages.push(MessageDescriptor::new(
+            &format!("google.protobuf.{}", name),
+            vec![FieldDescriptor::new("value", 1, ty)],
+        ));
+    }
+    messages
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NameKind {
+    Message,
+    Enum,
+}
+
+fn package_scope(file: &ProtoFile) -> String {
+    file.package
+        .as_ref()
+        .map(|p| format!(".{}", p))
+        .unwrap_or_default()
+}
+
+fn insert_name(
+    names: &mut HashMap<String, NameKind>,
+    scope: &str,
+    name: &str,
+    kind: NameKind,
+) -> Result<()> {
+    let full_name = format!("{}.{}", scope, name);
+    track_assert!(
+        names.insert(full_name.clone(), kind).is_none(),
+        ErrorKind::InvalidInput,
+        "Duplicate type: {}",
+        full_name
+    );
+    Ok(())
+}
+
+fn collect_message_names(
+    names: &mut HashMap<String, NameKind>,
+    scope: &str,
+    m: &Message,
+) -> Result<()> {
+    track!(insert_name(names, scope, &m.name, NameKind::Message))?;
+    let scope = format!("{}.{}", scope, m.name);
+    for nested in &m.messages {
+        track!(collect_message_names(names, &scope, nested))?;
+    }
+    for e in &m.enums {
+        track!(insert_name(names, &scope, &e.name, NameKind::Enum))?;
+    }
+    Ok(())
+}
+
+fn file_scope(file: &FileDescriptorProto) -> String {
+    file.package
+        .as_ref()
+        .map(|p| format!(".{}", p))
+        .unwrap_or_default()
+}
+
+fn collect_descriptor_names<'a>(
+    names: &mut HashMap<String, NameKind>,
+    map_entries: &mut HashMap<String, &'a DescriptorProto>,
+    scope: &str,
+    m: &'a DescriptorProto,
+) -> Result<()> {
+    let name = track_assert_some!(m.name.as_ref(), ErrorKind::InvalidInput);
+    track!(insert_name(names, scope, name, NameKind::Message))?;
+    let scope = format!("{}.{}", scope, name);
+    if track!(option_bool(&m.options, 7))? == Some(true) {
+        map_entries.insert(scope.clone(), m);
+    }
+    for nested in &m.nested_type {
+        track!(collect_descriptor_names(names, map_entries, &scope, nested))?;
+    }
+    for e in &m.enum_type {
+        let name = track_assert_some!(e.name.as_ref(), ErrorKind::InvalidInput);
+        track!(insert_name(names, &scope, name, NameKind::Enum))?;
+    }
+    Ok(())
+}
+
+/// Returns the value of the `bool` field that has the given number in the encoded options message.
+fn option_bool(options: &Option<Vec<u8>>, number: u32) -> Result<Option<bool>> {
+    let mut buf = options.as_ref().map_or(&[][..], |b| &b[..]);
+    let mut value = None;
+    while !buf.is_empty() {
+        let (tag, mut bytes) = track!(wire::read_field(&mut buf))?;
+        if tag.field_num.as_u32() == number && tag.wire_type == WireType::Varint {
+            value = Some(track!(wire::read_varint(&mut bytes))? != 0);
+        }
+    }
+    Ok(value)
+}
+
+#[derive(Debug)]
+struct Converter<'a> {
+    names: &'a HashMap<String, NameKind>,
+    map_entries: &'a HashMap<String, &'a DescriptorProto>,
+    messages: Vec<MessageDescriptor>,
+    enums: Vec<EnumDescriptor>,
+}
+impl<'a> Converter<'a> {
+    fn message(&mut self, scope: &str, syntax: Syntax, m: &Message) -> Result<()> {
+        let full_name = format!("{}.{}", scope, m.name);
+        let mut fields = Vec::new();
+        for f in &m.fields {
+            fields.push(track!(self.field(&full_name, syntax, f, None))?);
+        }
+        for o in &m.oneofs {
+            for f in &o.fields {
+                fields.push(track!(self.field(&full_name, syntax, f, Some(&o.name)))?);
+            }
+        }
+        fields.sort_by_key(|f| f.number);
+        self.messages
+            .push(MessageDescriptor::new(&full_name, fields));
+
+        for nested in &m.messages {
+            track!(self.message(&full_name, syntax, nested))?;
+        }
+        for e in &m.enums {
+            self.enumeration(&full_name, e);
+        }
+        Ok(())
+    }
+
+    fn enumeration(&mut self, scope: &str, e: &schema::Enum) {
+        let values = e
+            .values
+            .iter()
+            .map(|v| EnumValueDescriptor::new(&v.name, v.number))
+            .collect();
+        self.enums.push(EnumDescriptor::new(
+            &format!("{}.{}", scope, e.name),
+            values,
+        ));
+    }
+
+    fn field(
+        &self,
+        scope: &str,
+        syntax: Syntax,
+        f: &schema::Field,
+        oneof: Option<&str>,
+    ) -> Result<FieldDescriptor> {
+        let ty = track!(self.field_type(scope, &f.ty); f.position, f.name)?;
+        Ok(FieldDescriptor {
+            name: f.name.clone(),
+            number: f.number,
+            packed: f.is_repeated() && ty.is_numeric() && f.is_packed(syntax),
+            ty,
+            label: f.label,
+            oneof: oneof.map(ToOwned::to_owned),
+        })
+    }
+
+    fn field_type(&self, scope: &str, ty: &schema::FieldType) -> Result<FieldType> {
+        Ok(match *ty {
+            schema::FieldType::Double => FieldType::Double,
+            schema::FieldType::Float => FieldType::Float,
+            schema::FieldType::Int32 => FieldType::Int32,
+            schema::FieldType::Int64 => FieldType::Int64,
+            schema::FieldType::Uint32 => FieldType::Uint32,
+            schema::FieldType::Uint64 => FieldType::Uint64,
+            schema::FieldType::Sint32 => FieldType::Sint32,
+            schema::FieldType::Sint64 => FieldType::Sint64,
+            schema::FieldType::Fixed32 => FieldType::Fixed32,
+            schema::FieldType::Fixed64 => FieldType::Fixed64,
+            schema::FieldType::Sfixed32 => FieldType::Sfixed32,
+            schema::FieldType::Sfixed64 => FieldType::Sfixed64,
+            schema::FieldType::Bool => FieldType::Bool,
+            schema::FieldType::String => FieldType::String,
+            schema::FieldType::Bytes => FieldType::Bytes,
+            schema::FieldType::Named(ref name) => {
+                let (full_name, kind) = track_assert_some!(
+                    self.resolve(scope, name),
+                    ErrorKind::InvalidInput,
+                    "Unknown type: {}",
+                    name
+                );
+                let full_name = full_name[1..].to_owned();
+                match kind {
+                    NameKind::Message => FieldType::Message(full_name),
+                    NameKind::Enum => FieldType::Enum(full_name),
+                }
+            }
+            schema::FieldType::Map(ref key, ref value) => {
+                let key = track!(self.field_type(scope, key))?;
+                let value = track!(self.field_type(scope, value))?;
+                FieldType::Map(Box::new(key), Box::new(value))
+            }
+            schema::FieldType::Group(ref name) => {
+                FieldType::Group(format!("{}.{}", &scope[1..], name))
+            }
+        })
+    }
+
+    fn descriptor(&mut self, scope: &str, syntax: Syntax, m: &DescriptorProto) -> Result<()> {
+        let name = track_assert_some!(m.name.as_ref(), ErrorKind::InvalidInput);
+        let full_name = format!("{}.{}", scope, name);
+        if self.map_entries.contains_key(&full_name) {
+            return Ok(());
+        }
+
+        let mut fields = Vec::new();
+        for f in &m.field {
+            let oneof = match f.oneof_index {
+                Some(i) if f.proto3_optional != Some(true) => {
+                    let oneof = track_assert_some!(
+                        m.oneof_decl.get(i as usize).and_then(|o| o.name.as_ref()),
+                        ErrorKind::InvalidInput,
+                        "Unknown oneof: index={}",
+                        i
+                    );
+                    Some(oneof.as_str())
+                }
+                _ => None,
+            };
+            fields.push(track!(self.field_descriptor(&full_name, syntax, f, oneof); full_name)?);
+        }
+        fields.sort_by_key(|f| f.number);
+        self.messages
+            .push(MessageDescriptor::new(&full_name, fields));
+
+        for nested in &m.nested_type {
+            track!(self.descriptor(&full_name, syntax, nested))?;
+        }
+        for e in &m.enum_type {
+            self.enum_descriptor(&full_name, e);
+        }
+        Ok(())
+    }
+
+    fn enum_descriptor(&mut self, scope: &str, e: &EnumDescriptorProto) {
+        let values = e
+            .value
+            .iter()
+            .map(|v| {
+                EnumValueDescriptor::new(v.name.as_ref().map_or("", |n| n), v.number.unwrap_or(0))
+            })
+            .collect();
+        let name = e.name.as_ref().map_or("", |n| n);
+        self.enums
+            .push(EnumDescriptor::new(&format!("{}.{}", scope, name), values));
+    }
+
+    fn field_descriptor(
+        &self,
+        scope: &str,
+        syntax: Syntax,
+        f: &FieldDescriptorProto,
+        oneof: Option<&str>,
+    ) -> Result<FieldDescriptor> {
+        let name = track_assert_some!(f.name.as_ref(), ErrorKind::InvalidInput);
+        let number = track_assert_some!(f.number, ErrorKind::InvalidInput; name);
+        track_assert!(number > 0, ErrorKind::InvalidInput; name, number);
+
+        let ty = track!(self.descriptor_field_type(scope, f); name)?;
+        let label = if matches!(ty, FieldType::Map(..)) || oneof.is_some() {
+            None
+        } else {
+            match (syntax, f.label) {
+                (_, Some(FieldDescriptorProtoLabel::Repeated)) => Some(Label::Repeated),
+                (Syntax::Proto3, _) if f.proto3_optional == Some(true) => Some(Label::Optional),
+                (Syntax::Proto3, _) => None,
+                (Syntax::Proto2, Some(FieldDescriptorProtoLabel::Required)) => {
+                    Some(Label::Required)
+                }
+                (Syntax::Proto2, _) => Some(Label::Optional),
+            }
+        };
+        let packed = label == Some(Label::Repeated)
+            && ty.is_numeric()
+            && track!(option_bool(&f.options, 2))?.unwrap_or(syntax == Syntax::Proto3);
+        Ok(FieldDescriptor {
+            name: name.clone(),
+            number: number as u32,
+            ty,
+            label,
+            packed,
+            oneof: oneof.map(ToOwned::to_owned),
+        })
+    }
+
+    fn descriptor_field_type(&self, scope: &str, f: &FieldDescriptorProto) -> Result<FieldType> {
+        let named = |expected: Option<NameKind>| -> Result<(String, NameKind)> {
+            let type_name = track_assert_some!(f.type_name.as_ref(), ErrorKind::InvalidInput);
+            let (full_name, kind) = track_assert_some!(
+                self.resolve(scope, type_name),
+                ErrorKind::InvalidInput,
+                "Unknown type: {}",
+                type_name
+            );
+            if let Some(expected) = expected {
+                track_assert_eq!(kind, expected, ErrorKind::InvalidInput; type_name);
+            }
+            Ok((full_name, kind))
+        };
+        Ok(match f.ty {
+            Some(FieldDescriptorProtoType::Double) => FieldType::Double,
+            Some(FieldDescriptorProtoType::Float) => FieldType::Float,
+            Some(FieldDescriptorProtoType::Int64) => FieldType::Int64,
+            Some(FieldDescriptorProtoType::Uint64) => FieldType::Uint64,
+            Some(FieldDescriptorProtoType::Int32) => FieldType::Int32,
+            Some(FieldDescriptorProtoType::Fixed64) => FieldType::Fixed64,
+            Some(FieldDescriptorProtoType::Fixed32) => FieldType::Fixed32,
+            Some(FieldDescriptorProtoType::Bool) => FieldType::Bool,
+            Some(FieldDescriptorProtoType::String) => FieldType::String,
+            Some(FieldDescriptorProtoType::Bytes) => FieldType::Bytes,
+            Some(FieldDescriptorProtoType::Uint32) => FieldType::Uint32,
+            Some(FieldDescriptorProtoType::Sfixed32) => FieldType::Sfixed32,
+            Some(FieldDescriptorProtoType::Sfixed64) => FieldType::Sfixed64,
+            Some(FieldDescriptorProtoType::Sint32) => FieldType::Sint32,
+            Some(FieldDescriptorProtoType::Sint64) => FieldType::Sint64,
+            Some(FieldDescriptorProtoType::Group) => {
+                let (full_name, _) = track!(named(Some(NameKind::Message)))?;
+                FieldType::Group(full_name[1..].to_owned())
+            }
+            Some(FieldDescriptorProtoType::Enum) => {
+                let (full_name, _) = track!(named(Some(NameKind::Enum)))?;
+                FieldType::Enum(full_name[1..].to_owned())
+            }
+            Some(FieldDescriptorProtoType::Message) | None => {
+                // The type is omitted if the type name has not been resolved by the producer
+                let (full_name, kind) = track!(named(f.ty.map(|_| NameKind::Message)))?;
+                if kind == NameKind::Enum {
+                    FieldType::Enum(full_name[1..].to_owned())
+                } else if let Some(entry) = self.map_entries.get(&full_name) {
+                    let key = track_assert_some!(
+                        entry.field.iter().find(|f| f.number == Some(1)),
+                        ErrorKind::InvalidInput,
+                        "Map entry without key: {}",
+                        full_name
+                    );
+                    let value = track_assert_some!(
+                        entry.field.iter().find(|f| f.number == Some(2)),
+                        ErrorKind::InvalidInput,
+                        "Map entry without value: {}",
+                        full_name
+                    );
+                    let key = track!(self.descriptor_field_type(&full_name, key))?;
+                    let value = track!(self.descriptor_field_type(&full_name, value))?;
+                    FieldType::Map(Box::new(key), Box::new(value))
+                } else {
+                    FieldType::Message(full_name[1..].to_owned())
+                }
+            }
+        })
+    }
+
+    /// Resolves `name` referred from `scope` in accordance with the scoping rules of protobuf.
+    fn resolve(&self, scope: &str, name: &str) -> Option<(String, NameKind)> {
+        if name.starts_with('.') {
+            return self.names.get(name).map(|&k| (name.to_owned(), k));
+        }
+
+        let mut scope = scope;
+        loop {
+            let full_name = format!("{}.{}", scope, name);
+            if let Some(&k) = self.names.get(&full_name) {
+                return Some((full_name, k));
+            }
+            if scope.is_empty() {
+                return None;
+            }
+            scope = &scope[..scope.rfind('.').unwrap_or(0)];
+        }
+    }
+}
+
+// Converts a field name to the lowerCamelCase form used in the JSON mapping.
+pub(crate) fn json_name(field_name: &str) -> String {
+    let mut name = String::with_capacity(field_name.len());
+    let mut upper = false;
+    for c in field_name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            name.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
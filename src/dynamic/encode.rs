@@ -0,0 +1,180 @@
+use super::descriptor::{FieldDescriptor, FieldType};
+use super::value::{type_accepts, DynamicMessage, Value};
+use crate::message::MessageEncode;
+use crate::schema::Label;
+use crate::wire::{write_tag, write_varint, WireType};
+use bytecodec::bytes::BytesEncoder;
+use bytecodec::{ByteCount, Encode, Eos, ErrorKind, Result, SizedEncode};
+
+/// Encoder for messages described by a `MessageDescriptor` at runtime.
+///
+/// Fields are emitted in ascending order of their numbers, followed by the unknown fields of the message.
+/// Fields that do not track presence are omitted if they have the default values.
+///
+/// If a required field is missing or a value does not match the type of its field,
+/// `start_encoding` returns an `ErrorKind::InvalidInput` error.
+#[derive(Debug, Default)]
+pub struct DynamicMessageEncoder(BytesEncoder<Vec<u8>>);
+impl DynamicMessageEncoder {
+    /// Makes a new `DynamicMessageEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for DynamicMessageEncoder {
+    type Item = DynamicMessage;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let mut bytes = Vec::new();
+        track!(write_message(&item, &mut bytes))?;
+        track!(self.0.start_encoding(bytes))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+}
+impl SizedEncode for DynamicMessageEncoder {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl MessageEncode for DynamicMessageEncoder {}
+
+fn write_message(message: &DynamicMessage, buf: &mut Vec<u8>) -> Result<()> {
+    let descriptor = message.descriptor();
+    for f in &descriptor.fields {
+        track_assert!(
+            f.label != Some(Label::Required) || message.get(f.number).is_some(),
+            ErrorKind::InvalidInput,
+            "Missing required field: message={}, field={}",
+            descriptor.name,
+            f.name
+        );
+    }
+    for (&number, value) in message.fields() {
+        let field = track_assert_some!(
+            descriptor.field(number),
+            ErrorKind::InvalidInput,
+            "Unknown field: message={}, number={}",
+            descriptor.name,
+            number
+        );
+        track!(write_field(field, value, buf); descriptor.name, field.name)?;
+    }
+    for f in message.unknown_fields() {
+        write_tag(buf, f.tag.field_num.as_u32(), f.tag.wire_type);
+        buf.extend_from_slice(&f.value);
+    }
+    Ok(())
+}
+
+fn write_field(field: &FieldDescriptor, value: &Value, buf: &mut Vec<u8>) -> Result<()> {
+    match (&field.ty, value) {
+        (FieldType::Map(key_type, value_type), Value::Map(entries)) => {
+            for (k, v) in entries {
+                let mut entry = Vec::new();
+                track!(write_value(1, key_type, &k.to_value(), &mut entry))?;
+                track!(write_value(2, value_type, v, &mut entry))?;
+                write_tag(buf, field.number, WireType::LengthDelimited);
+                write_varint(buf, entry.len() as u64);
+                buf.extend_from_slice(&entry);
+            }
+        }
+        (ty, Value::List(values)) if field.is_repeated() => {
+            if field.packed && ty.is_numeric() {
+                if values.is_empty() {
+                    return Ok(());
+                }
+                let mut payload = Vec::new();
+                for v in values {
+                    track_assert!(type_accepts(ty, v), ErrorKind::InvalidInput; v);
+                    write_scalar(ty, v, &mut payload);
+                }
+                write_tag(buf, field.number, WireType::LengthDelimited);
+                write_varint(buf, payload.len() as u64);
+                buf.extend_from_slice(&payload);
+            } else {
+                for v in values {
+                    track!(write_value(field.number, ty, v, buf))?;
+                }
+            }
+        }
+        (ty, value) => {
+            track_assert!(
+                !field.is_repeated() && !field.is_map(),
+                ErrorKind::InvalidInput,
+                "Type mismatch: {:?}",
+                value
+            );
+            if !field.has_presence() && value.is_default() {
+                return Ok(());
+            }
+            track!(write_value(field.number, ty, value, buf))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_value(number: u32, ty: &FieldType, value: &Value, buf: &mut Vec<u8>) -> Result<()> {
+    track_assert!(
+        type_accepts(ty, value),
+        ErrorKind::InvalidInput,
+        "Type mismatch: {:?}",
+        value
+    );
+    let wire_type = ty.wire_type();
+    write_tag(buf, number, wire_type);
+    match *value {
+        Value::String(ref v) => {
+            write_varint(buf, v.len() as u64);
+            buf.extend_from_slice(v.as_bytes());
+        }
+        Value::Bytes(ref v) => {
+            write_varint(buf, v.len() as u64);
+            buf.extend_from_slice(v);
+        }
+        Value::Message(ref m) if wire_type == WireType::StartGroup => {
+            track!(write_message(m, buf))?;
+            write_tag(buf, number, WireType::EndGroup);
+        }
+        Value::Message(ref m) => {
+            let mut payload = Vec::new();
+            track!(write_message(m, &mut payload))?;
+            write_varint(buf, payload.len() as u64);
+            buf.extend_from_slice(&payload);
+        }
+        ref v => write_scalar(ty, v, buf),
+    }
+    Ok(())
+}
+
+// `value` must be a numeric value accepted by `ty`.
+fn write_scalar(ty: &FieldType, value: &Value, buf: &mut Vec<u8>) {
+    match (ty, value) {
+        (FieldType::Sint32, &Value::I32(v)) => {
+            write_varint(buf, u64::from(((v << 1) ^ (v >> 31)) as u32))
+        }
+        (FieldType::Sfixed32, &Value::I32(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+        (_, &Value::I32(v)) | (_, &Value::Enum(v)) => write_varint(buf, i64::from(v) as u64),
+        (FieldType::Sint64, &Value::I64(v)) => write_varint(buf, ((v << 1) ^ (v >> 63)) as u64),
+        (FieldType::Sfixed64, &Value::I64(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+        (_, &Value::I64(v)) => write_varint(buf, v as u64),
+        (FieldType::Fixed32, &Value::U32(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+        (_, &Value::U32(v)) => write_varint(buf, u64::from(v)),
+        (FieldType::Fixed64, &Value::U64(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+        (_, &Value::U64(v)) => write_varint(buf, v),
+        (_, &Value::Bool(v)) => write_varint(buf, u64::from(v)),
+        (_, &Value::F32(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+        (_, &Value::F64(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,647 @@
+//! Encoders and decoders for messages whose schemas are given at runtime.
+//!
+//! [`DynamicMessageDecoder`](./struct.DynamicMessageDecoder.html) and
+//! [`DynamicMessageEncoder`](./struct.DynamicMessageEncoder.html) are driven by descriptors
+//! registered in a [`DescriptorPool`](./struct.DescriptorPool.html) instead of Rust types.
+//! The decoded messages are represented as [`DynamicMessage`](./struct.DynamicMessage.html) trees
+//! that consist of typed field values, nested messages, maps and enums.
+//!
+//! Descriptors can be built programmatically, from `.proto` files parsed by [`schema::parse`],
+//! or from [`FileDescriptorSet`] messages (e.g., the output of `protoc --descriptor_set_out`).
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! use bytecodec::EncodeExt;
+//! use bytecodec::io::IoDecodeExt;
+//! use protobuf_codec::dynamic::{DescriptorPool, DynamicMessageDecoder, DynamicMessageEncoder, Value};
+//! use protobuf_codec::schema;
+//! use std::sync::Arc;
+//!
+//! # fn main() {
+//! let file = schema::parse(r#"
+//!     syntax = "proto3";
+//!     package example;
+//!     message SearchRequest {
+//!       string query = 1;
+//!       int32 page_number = 2;
+//!       int32 result_per_page = 3;
+//!     }
+//! "#).unwrap();
+//! let pool = Arc::new(DescriptorPool::from_files(&[file]).unwrap());
+//!
+//! let mut decoder = DynamicMessageDecoder::new(pool.clone(), "example.SearchRequest").unwrap();
+//! let mut message = decoder.decode_exact(&[10, 3, 102, 111, 111, 16, 3, 24, 10][..]).unwrap();
+//! assert_eq!(message.get_by_name("query"), Some(&Value::String("foo".to_owned())));
+//! assert_eq!(message.get(2), Some(&Value::I32(3)));
+//!
+//! message.set_by_name("page_number", Value::I32(0)).unwrap();
+//! let bytes = DynamicMessageEncoder::new().encode_into_bytes(message).unwrap();
+//! assert_eq!(bytes, [10, 3, 102, 111, 111, 24, 10]);
+//! # }
+//! ```
+//!
+//! [`schema::parse`]: ../schema/fn.parse.html
+//! [`FileDescriptorSet`]: ../wellknown/google/protobuf/struct.FileDescriptorSet.html
+pub use self::decode::DynamicMessageDecoder;
+pub(crate) use self::descriptor::json_name;
+pub use self::descriptor::{
+    DescriptorPool, EnumDescriptor, EnumValueDescriptor, FieldDescriptor, FieldType,
+    MessageDescriptor,
+};
+pub use self::encode::DynamicMessageEncoder;
+pub use self::value::{DynamicMessage, MapKey, Value};
+
+mod decode;
+mod descriptor;
+mod encode;
+mod value;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema;
+    use bytecodec::io::IoDecodeExt;
+    use bytecodec::{Decode, EncodeExt, Eos};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    const PROTO3: &str = r#"
+        syntax = "proto3";
+        package example;
+
+        enum Corpus {
+          UNIVERSAL = 0;
+          WEB = 1;
+        }
+
+        message SearchRequest {
+          message Project {
+            string name = 1;
+            sint64 delta = 2;
+          }
+          string query = 1;
+          int32 page_number = 2;
+          Corpus corpus = 3;
+          repeated int32 samples = 4;
+          map<string, Project> projects = 5;
+          oneof source {
+            string url = 6;
+            Project project = 7;
+          }
+          SearchRequest parent = 8;
+          repeated fixed32 hashes = 9 [packed = false];
+        }
+    "#;
+
+    const PROTO2: &str = r#"
+        syntax = "proto2";
+        package legacy;
+
+        message SearchResponse {
+          repeated group Result = 1 {
+            required string url = 2;
+            optional int32 rank = 3;
+          }
+        }
+    "#;
+
+    fn pool() -> Arc<DescriptorPool> {
+        let files = [
+            track_try_unwrap!(schema::parse(PROTO3)),
+            track_try_unwrap!(schema::parse(PROTO2)),
+        ];
+        Arc::new(track_try_unwrap!(DescriptorPool::from_files(&files)))
+    }
+
+    fn message(pool: &DescriptorPool, name: &str) -> DynamicMessage {
+        DynamicMessage::new(pool.message(name).cloned().expect("Unknown message"))
+    }
+
+    fn s(s: &str) -> Value {
+        Value::String(s.to_owned())
+    }
+
+    #[test]
+    fn dynamic_message_roundtrip_works() {
+        let pool = pool();
+        let mut project = message(&pool, "example.SearchRequest.Project");
+        track_try_unwrap!(project.set(1, s("bar")));
+        track_try_unwrap!(project.set(2, Value::I64(-1)));
+        let mut projects = BTreeMap::new();
+        projects.insert(
+            MapKey::String("foo".to_owned()),
+            Value::Message(project.clone()),
+        );
+
+        let mut parent = message(&pool, "example.SearchRequest");
+        track_try_unwrap!(parent.set_by_name("query", s("parent")));
+
+        let mut request = message(&pool, "example.SearchRequest");
+        track_try_unwrap!(request.set_by_name("query", s("foo")));
+        track_try_unwrap!(request.set_by_name("page_number", Value::I32(0)));
+        track_try_unwrap!(request.set_by_name("corpus", Value::Enum(1)));
+        let samples = vec![Value::I32(1), Value::I32(-2), Value::I32(300)];
+        track_try_unwrap!(request.set_by_name("samples", Value::List(samples)));
+        track_try_unwrap!(request.set_by_name("projects", Value::Map(projects)));
+        track_try_unwrap!(request.set_by_name("url", s("http://example.com")));
+        track_try_unwrap!(request.set_by_name("project", Value::Message(project)));
+        track_try_unwrap!(request.set_by_name("parent", Value::Message(parent)));
+        let hashes = vec![Value::U32(1), Value::U32(2)];
+        track_try_unwrap!(request.set_by_name("hashes", Value::List(hashes)));
+        assert_eq!(request.get_by_name("url"), None);
+
+        let bytes =
+            track_try_unwrap!(DynamicMessageEncoder::new().encode_into_bytes(request.clone()));
+        assert_eq!(&bytes[..5], [10, 3, 102, 111, 111]); // query="foo" (page_number=0 is omitted)
+        assert_eq!(&bytes[5..7], [24, 1]); // corpus=WEB
+        assert_eq!(
+            &bytes[7..22],
+            [34, 13, 1, 254, 255, 255, 255, 255, 255, 255, 255, 255, 1, 172, 2] // samples (packed)
+        );
+
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            ".example.SearchRequest"
+        ));
+        let decoded = track_try_unwrap!(decoder.decode_exact(&bytes[..]));
+        assert_eq!(decoded.get(2), None);
+        request.remove(2);
+        assert_eq!(decoded, request);
+
+        // Type mismatch
+        assert!(request.set_by_name("query", Value::I32(1)).is_err());
+        assert!(request.set_by_name("samples", Value::I32(1)).is_err());
+        assert!(request.set(100, Value::I32(1)).is_err());
+    }
+
+    #[test]
+    fn dynamic_message_decoder_merges_fields() {
+        let pool = pool();
+        let input = [
+            8, 1, // f1: unexpected wire type
+        ];
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            "example.SearchRequest"
+        ));
+        assert!(decoder.decode_exact(&input[..]).is_err());
+
+        let input = [
+            10, 1, 97, // query="a"
+            16, 3, // page_number=3
+            66, 5, 10, 3, 102, 111, 111, // parent.query="foo"
+            34, 2, 1, 2, // samples=[1, 2]
+            32, 3, // samples=[3]
+            10, 1, 98, // query="b"
+            66, 2, 16, 7, // parent.page_number=7
+            50, 1, 120, // url="x"
+            58, 0, // project={}
+            80, 5, // f10=5 (unknown)
+        ];
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            "example.SearchRequest"
+        ));
+        let m = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(m.get(1), Some(&s("b")));
+        assert_eq!(m.get(2), Some(&Value::I32(3)));
+        assert_eq!(
+            m.get(4),
+            Some(&Value::List(vec![
+                Value::I32(1),
+                Value::I32(2),
+                Value::I32(3)
+            ]))
+        );
+        assert_eq!(m.get(6), None);
+        assert!(m.get(7).is_some());
+        assert_eq!(m.unknown_fields().len(), 1);
+        match m.get(8) {
+            Some(Value::Message(parent)) => {
+                assert_eq!(parent.get(1), Some(&s("foo")));
+                assert_eq!(parent.get(2), Some(&Value::I32(7)));
+            }
+            v => panic!("{:?}", v),
+        }
+
+        // Truncated input
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            "example.SearchRequest"
+        ));
+        assert!(decoder.decode(&input[..9], Eos::new(true)).is_err());
+
+        // Truncated tag
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            "example.SearchRequest"
+        ));
+        assert!(decoder.decode_exact(&[10, 1, 97, 128][..]).is_err());
+    }
+
+    #[test]
+    fn dynamic_group_works() {
+        let pool = pool();
+        let input = [
+            11, // start group 1
+            18, 1, 97, // url="a"
+            24, 1,  // rank=1
+            12, // end group 1
+            11, // start group 1
+            18, 1, 98, // url="b"
+            12, // end group 1
+        ];
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            "legacy.SearchResponse"
+        ));
+        let m = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        let results = match m.get(1) {
+            Some(Value::List(results)) => results.clone(),
+            v => panic!("{:?}", v),
+        };
+        assert_eq!(results.len(), 2);
+        match results[0] {
+            Value::Message(ref r) => {
+                assert_eq!(r.descriptor().name, "legacy.SearchResponse.Result");
+                assert_eq!(r.get(3), Some(&Value::I32(1)));
+            }
+            ref v => panic!("{:?}", v),
+        }
+
+        let bytes = track_try_unwrap!(DynamicMessageEncoder::new().encode_into_bytes(m));
+        assert_eq!(bytes, input);
+
+        // Missing required field
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            "legacy.SearchResponse"
+        ));
+        assert!(decoder.decode_exact(&[11, 24, 1, 12][..]).is_err());
+
+        let mut result = message(&pool, "legacy.SearchResponse.Result");
+        track_try_unwrap!(result.set(3, Value::I32(1)));
+        let mut response = message(&pool, "legacy.SearchResponse");
+        track_try_unwrap!(response.set(1, Value::List(vec![Value::Message(result)])));
+        assert!(DynamicMessageEncoder::new()
+            .encode_into_bytes(response)
+            .is_err());
+    }
+
+    #[test]
+    fn descriptor_pool_from_file_descriptor_set_works() {
+        use crate::wellknown::google::protobuf::{
+            DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+            FieldDescriptorProtoLabel as L, FieldDescriptorProtoType as T, FileDescriptorProto,
+            FileDescriptorSet, FileDescriptorSetDecoder, FileDescriptorSetEncoder,
+            OneofDescriptorProto,
+        };
+        use bytecodec::DecodeExt;
+
+        fn field(name: &str, number: i32, label: L, ty: T) -> FieldDescriptorProto {
+            FieldDescriptorProto {
+                name: Some(name.to_owned()),
+                number: Some(number),
+                label: Some(label),
+                ty: Some(ty),
+                ..FieldDescriptorProto::default()
+            }
+        }
+        fn named(mut f: FieldDescriptorProto, type_name: &str) -> FieldDescriptorProto {
+            f.type_name = Some(type_name.to_owned());
+            f
+        }
+
+        let common = FileDescriptorProto {
+            name: Some("common.proto".to_owned()),
+            package: Some("common".to_owned()),
+            syntax: Some("proto3".to_owned()),
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Corpus".to_owned()),
+                value: vec![
+                    EnumValueDescriptorProto {
+                        name: Some("UNIVERSAL".to_owned()),
+                        number: Some(0),
+                        options: None,
+                    },
+                    EnumValueDescriptorProto {
+                        name: Some("WEB".to_owned()),
+                        number: Some(1),
+                        options: None,
+                    },
+                ],
+                ..EnumDescriptorProto::default()
+            }],
+            message_type: vec![DescriptorProto {
+                name: Some("Project".to_owned()),
+                field: vec![field("name", 1, L::Optional, T::String)],
+                ..DescriptorProto::default()
+            }],
+            ..FileDescriptorProto::default()
+        };
+        let search = FileDescriptorProto {
+            name: Some("search.proto".to_owned()),
+            package: Some("example".to_owned()),
+            dependency: vec!["common.proto".to_owned()],
+            syntax: Some("proto3".to_owned()),
+            message_type: vec![DescriptorProto {
+                name: Some("SearchRequest".to_owned()),
+                field: vec![
+                    field("query", 1, L::Optional, T::String),
+                    named(field("corpus", 2, L::Optional, T::Enum), ".common.Corpus"),
+                    named(
+                        field("projects", 3, L::Repeated, T::Message),
+                        ".example.SearchRequest.ProjectsEntry",
+                    ),
+                    FieldDescriptorProto {
+                        oneof_index: Some(0),
+                        ..field("url", 4, L::Optional, T::String)
+                    },
+                    FieldDescriptorProto {
+                        oneof_index: Some(0),
+                        ..named(field("inner", 5, L::Optional, T::Message), "Inner")
+                    },
+                    FieldDescriptorProto {
+                        oneof_index: Some(1),
+                        proto3_optional: Some(true),
+                        ..field("page", 6, L::Optional, T::Int32)
+                    },
+                    field("samples", 7, L::Repeated, T::Int32),
+                ],
+                nested_type: vec![
+                    DescriptorProto {
+                        name: Some("Inner".to_owned()),
+                        field: vec![FieldDescriptorProto {
+                            options: Some(vec![16, 0]), // packed = false
+                            ..field("hashes", 1, L::Repeated, T::Fixed32)
+                        }],
+                        ..DescriptorProto::default()
+                    },
+                    DescriptorProto {
+                        name: Some("ProjectsEntry".to_owned()),
+                        field: vec![
+                            field("key", 1, L::Optional, T::String),
+                            named(field("value", 2, L::Optional, T::Message), "common.Project"),
+                        ],
+                        options: Some(vec![56, 1]), // map_entry = true
+                        ..DescriptorProto::default()
+                    },
+                ],
+                oneof_decl: vec![
+                    OneofDescriptorProto {
+                        name: Some("source".to_owned()),
+                        options: None,
+                    },
+                    OneofDescriptorProto {
+                        name: Some("_page".to_owned()),
+                        options: None,
+                    },
+                ],
+                ..DescriptorProto::default()
+            }],
+            ..FileDescriptorProto::default()
+        };
+        let legacy = FileDescriptorProto {
+            name: Some("legacy.proto".to_owned()),
+            package: Some("legacy".to_owned()),
+            message_type: vec![DescriptorProto {
+                name: Some("SearchResponse".to_owned()),
+                field: vec![named(
+                    field("result", 1, L::Repeated, T::Group),
+                    ".legacy.SearchResponse.Result",
+                )],
+                nested_type: vec![DescriptorProto {
+                    name: Some("Result".to_owned()),
+                    field: vec![
+                        field("url", 2, L::Required, T::String),
+                        field("rank", 3, L::Optional, T::Int32),
+                    ],
+                    ..DescriptorProto::default()
+                }],
+                ..DescriptorProto::default()
+            }],
+            ..FileDescriptorProto::default()
+        };
+        let set = FileDescriptorSet {
+            file: vec![search, common, legacy],
+        };
+
+        let bytes =
+            track_try_unwrap!(FileDescriptorSetEncoder::new().encode_into_bytes(set.clone()));
+        let decoded = track_try_unwrap!(FileDescriptorSetDecoder::new().decode_from_bytes(&bytes));
+        assert_eq!(decoded, set);
+
+        let files = [
+            track_try_unwrap!(schema::parse(
+                r#"
+                syntax = "proto3";
+                package common;
+                enum Corpus {
+                  UNIVERSAL = 0;
+                  WEB = 1;
+                }
+                message Project {
+                  string name = 1;
+                }
+            "#
+            )),
+            track_try_unwrap!(schema::parse(
+                r#"
+                syntax = "proto3";
+                package example;
+                import "common.proto";
+                message SearchRequest {
+                  message Inner {
+                    repeated fixed32 hashes = 1 [packed = false];
+                  }
+                  string query = 1;
+                  common.Corpus corpus = 2;
+                  map<string, common.Project> projects = 3;
+                  oneof source {
+                    string url = 4;
+                    Inner inner = 5;
+                  }
+                  optional int32 page = 6;
+                  repeated int32 samples = 7;
+                }
+            "#
+            )),
+            track_try_unwrap!(schema::parse(PROTO2)),
+        ];
+        let expected = track_try_unwrap!(DescriptorPool::from_files(&files));
+        let actual = track_try_unwrap!(DescriptorPool::from_file_descriptor_set(&decoded));
+        assert_eq!(actual.messages().count(), expected.messages().count());
+        for m in expected.messages() {
+            assert_eq!(actual.message(&m.name), Some(m));
+        }
+        assert_eq!(actual.enums().count(), expected.enums().count());
+        for e in expected.enums() {
+            assert_eq!(actual.enum_type(&e.name), Some(e));
+        }
+
+        // Unresolvable type
+        let foo = FileDescriptorProto {
+            name: Some("foo.proto".to_owned()),
+            syntax: Some("proto3".to_owned()),
+            message_type: vec![DescriptorProto {
+                name: Some("Foo".to_owned()),
+                field: vec![named(
+                    field("bar", 1, L::Optional, T::Message),
+                    ".common.Project",
+                )],
+                ..DescriptorProto::default()
+            }],
+            ..FileDescriptorProto::default()
+        };
+        let set = FileDescriptorSet { file: vec![foo] };
+        assert!(DescriptorPool::from_file_descriptor_set(&set).is_err());
+    }
+
+    #[test]
+    fn descriptor_pool_from_protoc_output_works() {
+        use crate::schema::Label;
+        use crate::wellknown::google::protobuf::{
+            FileDescriptorSetDecoder, FileDescriptorSetEncoder,
+        };
+        use bytecodec::DecodeExt;
+
+        // Generated by `protoc --include_imports --descriptor_set_out=search.pb search.proto`
+        // (libprotoc 36.2) in the `testdata` directory.
+        const SEARCH_PB: &[u8] = include_bytes!("testdata/search.pb");
+
+        let set = track_try_unwrap!(FileDescriptorSetDecoder::new().decode_from_bytes(SEARCH_PB));
+        let names = set
+            .file
+            .iter()
+            .map(|f| f.name.as_deref().unwrap_or(""))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "google/protobuf/duration.proto",
+                "legacy.proto",
+                "search.proto"
+            ]
+        );
+        let search = &set.file[2];
+        assert_eq!(
+            search.dependency,
+            ["google/protobuf/duration.proto", "legacy.proto"]
+        );
+        assert_eq!(search.service.len(), 1);
+        assert_eq!(search.service[0].method.len(), 2);
+        assert_eq!(set.file[1].extension.len(), 1);
+
+        // The decoded set is encoded to the same bytes
+        let bytes =
+            track_try_unwrap!(FileDescriptorSetEncoder::new().encode_into_bytes(set.clone()));
+        assert_eq!(bytes, SEARCH_PB);
+
+        let pool = track_try_unwrap!(DescriptorPool::from_file_descriptor_set(&set));
+        let mut names = pool.messages().map(|m| m.name.as_str()).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "example.SearchRequest",
+                "example.SearchRequest.Project",
+                "google.protobuf.Duration",
+                "legacy.SearchResponse",
+                "legacy.SearchResponse.Result",
+            ]
+        );
+        let mut names = pool.enums().map(|e| e.name.as_str()).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["example.Corpus", "example.SearchRequest.Order"]);
+
+        let request = pool
+            .message(".example.SearchRequest")
+            .expect("Unknown message");
+        let field = |name: &str| request.field_by_name(name).expect("Unknown field");
+        assert_eq!(field("query").number, 1);
+        assert_eq!(field("query").ty, FieldType::String);
+        assert_eq!(field("query").label, None);
+        assert_eq!(field("page_number").json_name(), "pageNumber");
+        assert_eq!(
+            field("corpus").ty,
+            FieldType::Enum("example.Corpus".to_owned())
+        );
+        assert!(field("samples").is_repeated());
+        assert!(field("samples").packed);
+        assert!(field("hashes").is_repeated());
+        assert!(!field("hashes").packed);
+        assert_eq!(
+            field("projects").ty,
+            FieldType::Map(
+                Box::new(FieldType::String),
+                Box::new(FieldType::Message(
+                    "example.SearchRequest.Project".to_owned()
+                ))
+            )
+        );
+        assert_eq!(field("url").oneof.as_deref(), Some("source"));
+        assert_eq!(field("project").oneof.as_deref(), Some("source"));
+        assert!(field("order").has_presence());
+        assert_eq!(
+            field("order").ty,
+            FieldType::Enum("example.SearchRequest.Order".to_owned())
+        );
+        assert_eq!(
+            field("parent").ty,
+            FieldType::Message("example.SearchRequest".to_owned())
+        );
+        assert_eq!(
+            request.field(11).map(|f| f.name.as_str()),
+            Some("last_response")
+        );
+        assert!(request.field(12).is_none());
+        assert_eq!(request.fields.len(), 11);
+
+        let response = pool
+            .message("legacy.SearchResponse")
+            .expect("Unknown message");
+        let result = response.field(1).expect("Unknown field");
+        assert_eq!(result.name, "result");
+        assert_eq!(result.label, Some(Label::Repeated));
+        assert_eq!(
+            result.ty,
+            FieldType::Group("legacy.SearchResponse.Result".to_owned())
+        );
+        let result = pool
+            .message("legacy.SearchResponse.Result")
+            .expect("Unknown message");
+        assert_eq!(
+            result.field(2).map(|f| f.label),
+            Some(Some(Label::Required))
+        );
+
+        let corpus = pool.enum_type("example.Corpus").expect("Unknown enum");
+        assert_eq!(corpus.value(2).map(|v| v.name.as_str()), Some("IMAGES"));
+        assert_eq!(corpus.value_by_name("PICTURES").map(|v| v.number), Some(2));
+
+        // Decodes a message by using the loaded descriptors
+        let pool = Arc::new(pool);
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(
+            pool.clone(),
+            "example.SearchRequest"
+        ));
+        let input = [
+            10, 3, 102, 111, 111, // query
+            34, 2, 1, 2, // samples
+            50, 12, 10, 1, 97, 18, 7, 10, 1, 98, 18, 2, 8, 1, // projects
+            72, 1, // order
+        ];
+        let message = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(message.get_by_name("query"), Some(&s("foo")));
+        assert_eq!(
+            message.get_by_name("samples"),
+            Some(&Value::List(vec![Value::I32(1), Value::I32(2)]))
+        );
+        assert_eq!(message.get_by_name("order"), Some(&Value::Enum(1)));
+        let bytes = track_try_unwrap!(DynamicMessageEncoder::new().encode_into_bytes(message));
+        assert_eq!(bytes, &input[..]);
+    }
+}
@@ -0,0 +1,309 @@
+use super::descriptor::{DescriptorPool, FieldDescriptor, FieldType, MessageDescriptor};
+use crate::field::UnknownFields;
+use bytecodec::{ErrorKind, Result};
+use std::collections::{btree_map, BTreeMap};
+use std::sync::Arc;
+
+/// Message whose structure is described by a `MessageDescriptor` at runtime.
+///
+/// Field values are keyed by field numbers.
+/// The fields that are not set (e.g., the fields that did not appear in an input stream)
+/// have no values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicMessage {
+    descriptor: Arc<MessageDescriptor>,
+    fields: BTreeMap<u32, Value>,
+    unknown_fields: UnknownFields,
+}
+impl DynamicMessage {
+    /// Makes a new `DynamicMessage` instance that has no field values.
+    pub fn new(descriptor: Arc<MessageDescriptor>) -> Self {
+        DynamicMessage {
+            descriptor,
+            fields: BTreeMap::new(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+
+    /// Returns the descriptor of the message.
+    pub fn descriptor(&self) -> &Arc<MessageDescriptor> {
+        &self.descriptor
+    }
+
+    /// Returns the value of the field that has the given number.
+    pub fn get(&self, number: u32) -> Option<&Value> {
+        self.fields.get(&number)
+    }
+
+    /// Returns a mutable reference to the value of the field that has the given number.
+    pub fn get_mut(&mut self, number: u32) -> Option<&mut Value> {
+        self.fields.get_mut(&number)
+    }
+
+    /// Returns the value of the field that has the given name.
+    pub fn get_by_name(&self, name: &str) -> Option<&Value> {
+        self.descriptor
+            .field_by_name(name)
+            .and_then(|f| self.fields.get(&f.number))
+    }
+
+    /// Sets the value of the field that has the given number.
+    ///
+    /// If the field belongs to a oneof, the other fields of the oneof are cleared.
+    ///
+    /// If the message does not have such field or the value does not match the type of the field,
+    /// this returns an `ErrorKind::InvalidInput` error.
+    pub fn set(&mut self, number: u32, value: Value) -> Result<()> {
+        let field = track_assert_some!(
+            self.descriptor.field(number),
+            ErrorKind::InvalidInput,
+            "Unknown field: message={}, number={}",
+            self.descriptor.name,
+            number
+        );
+        track_assert!(
+            field_accepts(field, &value),
+            ErrorKind::InvalidInput,
+            "Type mismatch: message={}, field={}, value={:?}",
+            self.descriptor.name,
+            field.name,
+            value
+        );
+        self.put(number, value);
+        Ok(())
+    }
+
+    /// Sets the value of the field that has the given name.
+    ///
+    /// See the documentation of `set` method for details.
+    pub fn set_by_name(&mut self, name: &str, value: Value) -> Result<()> {
+        let number = track_assert_some!(
+            self.descriptor.field_by_name(name).map(|f| f.number),
+            ErrorKind::InvalidInput,
+            "Unknown field: message={}, name={}",
+            self.descriptor.name,
+            name
+        );
+        track!(self.set(number, value))
+    }
+
+    /// Removes the value of the field that has the given number, and returns it.
+    pub fn remove(&mut self, number: u32) -> Option<Value> {
+        self.fields.remove(&number)
+    }
+
+    /// Returns an iterator over the field numbers and values of the fields that are set.
+    ///
+    /// The fields are visited in ascending order of their numbers.
+    pub fn fields(&self) -> btree_map::Iter<'_, u32, Value> {
+        self.fields.iter()
+    }
+
+    /// Returns the unknown fields of the message.
+    pub fn unknown_fields(&self) -> &UnknownFields {
+        &self.unknown_fields
+    }
+
+    /// Returns a mutable reference to the unknown fields of the message.
+    pub fn unknown_fields_mut(&mut self) -> &mut UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    /// Merges `other` into this message.
+    ///
+    /// Singular scalar fields are overwritten, repeated and map fields are appended and
+    /// singular embedded message fields are merged recursively.
+    pub fn merge(&mut self, other: DynamicMessage) {
+        for (number, value) in other.fields {
+            self.merge_field(number, value);
+        }
+        self.unknown_fields.extend(other.unknown_fields);
+    }
+
+    pub(crate) fn merge_field(&mut self, number: u32, value: Value) {
+        match (self.fields.get_mut(&number), value) {
+            (Some(Value::List(a)), Value::List(b)) => a.extend(b),
+            (Some(Value::Map(a)), Value::Map(b)) => a.extend(b),
+            (Some(Value::Message(a)), Value::Message(b)) => a.merge(b),
+            (_, value) => self.put(number, value),
+        }
+    }
+
+    fn put(&mut self, number: u32, value: Value) {
+        let oneof = self.descriptor.field(number).and_then(|f| f.oneof.as_ref());
+        if let Some(oneof) = oneof {
+            for f in &self.descriptor.fields {
+                if f.number != number && f.oneof.as_ref() == Some(oneof) {
+                    self.fields.remove(&f.number);
+                }
+            }
+        }
+        self.fields.insert(number, value);
+    }
+}
+
+/// Value of a field of `DynamicMessage`.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum Value {
+    Bool(bool),
+
+    /// Value of `int32`, `sint32` and `sfixed32` fields.
+    I32(i32),
+
+    /// Value of `int64`, `sint64` and `sfixed64` fields.
+    I64(i64),
+
+    /// Value of `uint32` and `fixed32` fields.
+    U32(u32),
+
+    /// Value of `uint64` and `fixed64` fields.
+    U64(u64),
+
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+
+    /// Enum value.
+    ///
+    /// Numbers that are not defined in the enum are kept as they are.
+    Enum(i32),
+
+    /// Value of message and group fields.
+    Message(DynamicMessage),
+
+    /// Values of a repeated field.
+    List(Vec<Value>),
+
+    /// Entries of a map field.
+    Map(BTreeMap<MapKey, Value>),
+}
+impl Value {
+    /// Returns the default value of the given type.
+    ///
+    /// The default value of a message type is an empty message.
+    /// If `ty` refers to a message that is not found in `pool`, this returns an `ErrorKind::InvalidInput` error.
+    pub fn default_of(ty: &FieldType, pool: &DescriptorPool) -> Result<Self> {
+        Ok(match *ty {
+            FieldType::Bool => Value::Bool(false),
+            FieldType::Int32 | FieldType::Sint32 | FieldType::Sfixed32 => Value::I32(0),
+            FieldType::Int64 | FieldType::Sint64 | FieldType::Sfixed64 => Value::I64(0),
+            FieldType::Uint32 | FieldType::Fixed32 => Value::U32(0),
+            FieldType::Uint64 | FieldType::Fixed64 => Value::U64(0),
+            FieldType::Float => Value::F32(0.0),
+            FieldType::Double => Value::F64(0.0),
+            FieldType::String => Value::String(String::new()),
+            FieldType::Bytes => Value::Bytes(Vec::new()),
+            FieldType::Enum(ref name) => {
+                Value::Enum(pool.enum_type(name).map_or(0, |e| e.default_value()))
+            }
+            FieldType::Message(ref name) | FieldType::Group(ref name) => {
+                let descriptor = track_assert_some!(
+                    pool.message(name),
+                    ErrorKind::InvalidInput,
+                    "Unknown message: {}",
+                    name
+                );
+                Value::Message(DynamicMessage::new(descriptor.clone()))
+            }
+            FieldType::Map(..) => Value::Map(BTreeMap::new()),
+        })
+    }
+
+    /// Returns `true` if this is the default value of a scalar or enum type, otherwise `false`.
+    pub fn is_default(&self) -> bool {
+        match *self {
+            Value::Bool(v) => !v,
+            Value::I32(v) | Value::Enum(v) => v == 0,
+            Value::I64(v) => v == 0,
+            Value::U32(v) => v == 0,
+            Value::U64(v) => v == 0,
+            Value::F32(v) => v.to_bits() == 0,
+            Value::F64(v) => v.to_bits() == 0,
+            Value::String(ref v) => v.is_empty(),
+            Value::Bytes(ref v) => v.is_empty(),
+            Value::Message(_) => false,
+            Value::List(ref v) => v.is_empty(),
+            Value::Map(ref v) => v.is_empty(),
+        }
+    }
+}
+
+/// Key of a map field of `DynamicMessage`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(missing_docs)]
+pub enum MapKey {
+    Bool(bool),
+    I32(i32),
+    I64(i64),
+    U32(u32),
+    U64(u64),
+    String(String),
+}
+impl MapKey {
+    /// Converts the key to a `Value`.
+    pub fn to_value(&self) -> Value {
+        match *self {
+            MapKey::Bool(v) => Value::Bool(v),
+            MapKey::I32(v) => Value::I32(v),
+            MapKey::I64(v) => Value::I64(v),
+            MapKey::U32(v) => Value::U32(v),
+            MapKey::U64(v) => Value::U64(v),
+            MapKey::String(ref v) => Value::String(v.clone()),
+        }
+    }
+
+    /// Converts `value` to a key.
+    ///
+    /// If the value cannot be used as map keys, this returns `None`.
+    pub fn from_value(value: Value) -> Option<Self> {
+        Some(match value {
+            Value::Bool(v) => MapKey::Bool(v),
+            Value::I32(v) => MapKey::I32(v),
+            Value::I64(v) => MapKey::I64(v),
+            Value::U32(v) => MapKey::U32(v),
+            Value::U64(v) => MapKey::U64(v),
+            Value::String(v) => MapKey::String(v),
+            _ => return None,
+        })
+    }
+}
+
+fn field_accepts(field: &FieldDescriptor, value: &Value) -> bool {
+    match (&field.ty, value) {
+        (FieldType::Map(k, v), Value::Map(entries)) => entries
+            .iter()
+            .all(|(key, value)| type_accepts(k, &key.to_value()) && type_accepts(v, value)),
+        (_, Value::List(values)) if field.is_repeated() => {
+            values.iter().all(|v| type_accepts(&field.ty, v))
+        }
+        _ => !field.is_repeated() && !field.is_map() && type_accepts(&field.ty, value),
+    }
+}
+
+pub(crate) fn type_accepts(ty: &FieldType, value: &Value) -> bool {
+    match (ty, value) {
+        (FieldType::Bool, Value::Bool(_))
+        | (FieldType::Int32, Value::I32(_))
+        | (FieldType::Sint32, Value::I32(_))
+        | (FieldType::Sfixed32, Value::I32(_))
+        | (FieldType::Int64, Value::I64(_))
+        | (FieldType::Sint64, Value::I64(_))
+        | (FieldType::Sfixed64, Value::I64(_))
+        | (FieldType::Uint32, Value::U32(_))
+        | (FieldType::Fixed32, Value::U32(_))
+        | (FieldType::Uint64, Value::U64(_))
+        | (FieldType::Fixed64, Value::U64(_))
+        | (FieldType::Float, Value::F32(_))
+        | (FieldType::Double, Value::F64(_))
+        | (FieldType::String, Value::String(_))
+        | (FieldType::Bytes, Value::Bytes(_))
+        | (FieldType::Enum(_), Value::Enum(_)) => true,
+        (FieldType::Message(name), Value::Message(m))
+        | (FieldType::Group(name), Value::Message(m)) => {
+            m.descriptor.name == name.trim_start_matches('.')
+        }
+        _ => false,
+    }
+}
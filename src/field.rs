@@ -1,16 +1,23 @@
 //! Encoders, decoders and related components for message fields.
 use crate::field_num::FieldNum;
 pub use crate::fields::Fields;
+pub use crate::group_field::{GroupFieldDecoder, GroupFieldEncoder};
 use crate::message::{
     EmbeddedMessageDecoder, EmbeddedMessageEncoder, MessageDecode, MessageEncode,
 };
 pub use crate::oneof::Oneof;
 pub use crate::repeated_field::{
     MapFieldDecoder, MapFieldEncoder, MapMessageFieldDecoder, MapMessageFieldEncoder,
-    PackedFieldDecoder, PackedFieldEncoder, Repeated,
+    PackedFieldDecoder, PackedFieldEncoder, Repeated, SizedMapFieldEncoder,
+    SizedMapMessageFieldEncoder, SizedRepeated,
+};
+pub use crate::unknown_fields::{
+    UnknownField, UnknownFields, UnknownFieldsDecoder, UnknownFieldsEncoder,
 };
 use crate::value::{ValueDecode, ValueEncode};
-use crate::wire::{LengthDelimitedDecoder, Tag, TagEncoder, VarintDecoder, WireType};
+use crate::wire::{
+    LengthDelimitedDecoder, RawValueDecoder, Tag, TagEncoder, VarintDecoder, WireType,
+};
 use bytecodec::bytes::CopyableBytesDecoder;
 use bytecodec::padding::PaddingDecoder;
 use bytecodec::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
@@ -23,7 +30,12 @@ pub mod num {
 pub mod branch {
     //! Values for `Oneof` fields.
 
-    pub use crate::oneof::{Branch2, Branch3, Branch4, Branch5, Branch6, Branch7, Branch8};
+    pub use crate::oneof::{
+        Branch10, Branch11, Branch12, Branch13, Branch14, Branch15, Branch16, Branch17, Branch18,
+        Branch19, Branch2, Branch20, Branch21, Branch22, Branch23, Branch24, Branch25, Branch26,
+        Branch27, Branch28, Branch29, Branch3, Branch30, Branch31, Branch32, Branch4, Branch5,
+        Branch6, Branch7, Branch8, Branch9,
+    };
 }
 pub mod value {
     //! Traits for representing encoders and decoders of field values.
@@ -96,7 +108,13 @@ where
     D: MessageDecode,
 {
     fn start_decoding(&mut self, tag: Tag) -> Result<bool> {
-        track!(self.inner.start_decoding(tag))
+        if self.inner.present && self.inner.num.into() == tag.field_num {
+            track_assert_eq!(tag.wire_type, WireType::LengthDelimited, ErrorKind::InvalidInput; tag);
+            track!(self.inner.value.resume_decoding(); tag)?;
+            Ok(true)
+        } else {
+            track!(self.inner.start_decoding(tag))
+        }
     }
 }
 impl<F, D> RequiredFieldDecode for MessageFieldDecoder<F, D>
@@ -175,6 +193,10 @@ where
     fn start_decoding(&mut self, tag: Tag) -> Result<bool> {
         if self.num.into() == tag.field_num {
             track_assert_eq!(self.value.wire_type(), tag.wire_type, ErrorKind::InvalidInput; tag);
+            if self.present {
+                // The last value takes precedence
+                let _ = track!(self.value.finish_decoding(); tag)?;
+            }
             self.present = true;
             Ok(true)
         } else {
@@ -384,6 +406,9 @@ where
 /// Decoder for unknown fields.
 ///
 /// This accepts any tags but the decoded values will be discarded.
+/// Use `UnknownFieldsDecoder` instead to preserve them.
+///
+/// Groups are skipped including their nested fields.
 #[derive(Debug)]
 pub struct UnknownFieldDecoder(UnknownFieldDecoderInner);
 impl UnknownFieldDecoder {
@@ -402,6 +427,7 @@ impl Decode for UnknownFieldDecoder {
             UnknownFieldDecoderInner::Bit32(ref mut d) => track!(d.decode(buf, eos)),
             UnknownFieldDecoderInner::Bit64(ref mut d) => track!(d.decode(buf, eos)),
             UnknownFieldDecoderInner::LengthDelimited(ref mut d) => track!(d.decode(buf, eos)),
+            UnknownFieldDecoderInner::Group(ref mut d) => track!(d.decode(buf, eos)),
         }
     }
 
@@ -420,6 +446,9 @@ impl Decode for UnknownFieldDecoder {
             UnknownFieldDecoderInner::LengthDelimited(ref mut d) => {
                 track!(d.finish_decoding())?;
             }
+            UnknownFieldDecoderInner::Group(ref mut d) => {
+                track!(d.finish_decoding())?;
+            }
         }
         self.0 = UnknownFieldDecoderInner::None;
         Ok(())
@@ -432,6 +461,7 @@ impl Decode for UnknownFieldDecoder {
             UnknownFieldDecoderInner::Bit32(ref d) => d.is_idle(),
             UnknownFieldDecoderInner::Bit64(ref d) => d.is_idle(),
             UnknownFieldDecoderInner::LengthDelimited(ref d) => d.is_idle(),
+            UnknownFieldDecoderInner::Group(ref d) => d.is_idle(),
         }
     }
 
@@ -442,6 +472,7 @@ impl Decode for UnknownFieldDecoder {
             UnknownFieldDecoderInner::Bit32(ref d) => d.requiring_bytes(),
             UnknownFieldDecoderInner::Bit64(ref d) => d.requiring_bytes(),
             UnknownFieldDecoderInner::LengthDelimited(ref d) => d.requiring_bytes(),
+            UnknownFieldDecoderInner::Group(ref d) => d.requiring_bytes(),
         }
     }
 }
@@ -454,6 +485,11 @@ impl FieldDecode for UnknownFieldDecoder {
             WireType::LengthDelimited => {
                 UnknownFieldDecoderInner::LengthDelimited(Default::default())
             }
+            WireType::StartGroup | WireType::EndGroup => {
+                let mut decoder = RawValueDecoder::new(true);
+                track!(decoder.start(tag))?;
+                UnknownFieldDecoderInner::Group(decoder)
+            }
         };
         Ok(true)
     }
@@ -471,6 +507,7 @@ enum UnknownFieldDecoderInner {
     Bit32(CopyableBytesDecoder<[u8; 4]>),
     Bit64(CopyableBytesDecoder<[u8; 8]>),
     LengthDelimited(LengthDelimitedDecoder<PaddingDecoder>),
+    Group(RawValueDecoder),
 }
 
 /// Encoder for required embedded message fields.
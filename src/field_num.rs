@@ -20,11 +20,15 @@ impl FieldNum {
     pub fn new(n: u32) -> Result<Self> {
         track_assert_ne!(n, 0, ErrorKind::InvalidInput);
         track_assert!(n < (1 << 29), ErrorKind::InvalidInput; n);
-        track_assert!(!(19_000 <= n && n < 20_000), ErrorKind::InvalidInput; n);
+        track_assert!(!(19_000..20_000).contains(&n), ErrorKind::InvalidInput; n);
         Ok(FieldNum(n))
     }
 
     /// Makes a new `FieldNum` instance without checking the value.
+    ///
+    /// # Safety
+    ///
+    /// `n` must satisfy the restriction described in the documentation of `FieldNum::new`.
     pub unsafe fn new_unchecked(n: u32) -> Self {
         FieldNum(n)
     }
@@ -35,6 +39,34 @@ impl FieldNum {
     }
 }
 
+/// Field number `N`.
+///
+/// This is a generic version of the field number types `F1`, `F2`, ..., `F16`
+/// which can be used for arbitrary field numbers (e.g., `Fnum<300>`).
+///
+/// `N` is validated at compile time in the same manner as `FieldNum::new`.
+/// If it is invalid, the conversion into `FieldNum` fails to compile.
+///
+/// ```compile_fail
+/// use protobuf_codec::field::num::{FieldNum, Fnum};
+///
+/// let _ = FieldNum::from(Fnum::<19_000>);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Fnum<const N: u32>;
+impl<const N: u32> Fnum<N> {
+    const VALID: () = assert!(
+        N != 0 && N < (1 << 29) && !(N >= 19_000 && N < 20_000),
+        "Invalid field number"
+    );
+}
+impl<const N: u32> From<Fnum<N>> for FieldNum {
+    fn from(_: Fnum<N>) -> Self {
+        let () = Fnum::<N>::VALID;
+        FieldNum(N)
+    }
+}
+
 macro_rules! impl_from {
     ($ty:ty, $n:expr) => {
         impl From<$ty> for FieldNum {
@@ -124,3 +156,16 @@ impl_from!(F15, 15);
 #[derive(Debug, Default, Clone, Copy)]
 pub struct F16;
 impl_from!(F16, 16);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnum_works() {
+        assert_eq!(FieldNum::from(Fnum::<1>).as_u32(), 1);
+        assert_eq!(FieldNum::from(Fnum::<18_999>).as_u32(), 18_999);
+        assert_eq!(FieldNum::from(Fnum::<20_000>).as_u32(), 20_000);
+        assert_eq!(FieldNum::from(Fnum::<536_870_911>).as_u32(), (1 << 29) - 1);
+    }
+}
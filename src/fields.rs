@@ -1,10 +1,13 @@
-#![allow(clippy::block_in_if_condition_stmt)]
+#![allow(clippy::blocks_in_conditions)]
 use crate::field::{FieldDecode, FieldEncode};
 use crate::wire::Tag;
 use bytecodec::{ByteCount, Decode, Encode, Eos, Result, SizedEncode};
+use std::fmt;
 
 /// Decoder and encoder for multiple fields.
-#[derive(Debug, Default)]
+///
+/// `F` is a tuple of field decoders (or encoders) which has at most 32 elements.
+/// Tuples can be nested for handling more fields.
 pub struct Fields<F> {
     fields: F,
     index: usize,
@@ -15,6 +18,19 @@ impl<F> Fields<F> {
         Fields { fields, index: 0 }
     }
 }
+impl fmt::Debug for Fields<()> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Fields")
+            .field("fields", &())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+impl Default for Fields<()> {
+    fn default() -> Self {
+        Fields::new(())
+    }
+}
 impl Decode for Fields<()> {
     type Item = ();
 
@@ -39,6 +55,39 @@ impl FieldDecode for Fields<()> {
         Ok(false)
     }
 }
+// Formats the elements of a tuple in the same way as `Debug` for tuples in the standard library.
+pub(crate) struct DebugTuple<'a>(pub &'a [&'a dyn fmt::Debug]);
+impl fmt::Debug for DebugTuple<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut t = f.debug_tuple("");
+        for x in self.0 {
+            t.field(x);
+        }
+        t.finish()
+    }
+}
+
+// `Debug` and `Default` are implemented manually,
+// because the standard library only implements them for tuples which have at most 12 elements.
+macro_rules! impl_debug_and_default {
+    ($name:ident, [$($f:ident),*], [$($i:tt),*]) => {
+        impl<$($f: fmt::Debug),*> fmt::Debug for $name<($($f),*,)> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let fields: &[&dyn fmt::Debug] = &[$(&self.fields.$i),*];
+                f.debug_struct(stringify!($name))
+                    .field("fields", &DebugTuple(fields))
+                    .field("index", &self.index)
+                    .finish()
+            }
+        }
+        impl<$($f: Default),*> Default for $name<($($f),*,)> {
+            fn default() -> Self {
+                $name::new(($($f::default()),*,))
+            }
+        }
+    };
+}
+
 macro_rules! impl_field_decode {
     ([$($f:ident),*],[$($i:tt),*]) => {
         impl<$($f),*> Decode for Fields<($($f),*,)>
@@ -97,6 +146,165 @@ macro_rules! impl_field_decode {
     };
 }
 
+impl_debug_and_default!(Fields, [A], [0]);
+impl_debug_and_default!(Fields, [A, B], [0, 1]);
+impl_debug_and_default!(Fields, [A, B, C], [0, 1, 2]);
+impl_debug_and_default!(Fields, [A, B, C, D], [0, 1, 2, 3]);
+impl_debug_and_default!(Fields, [A, B, C, D, E], [0, 1, 2, 3, 4]);
+impl_debug_and_default!(Fields, [A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
+impl_debug_and_default!(Fields, [A, B, C, D, E, F, G], [0, 1, 2, 3, 4, 5, 6]);
+impl_debug_and_default!(Fields, [A, B, C, D, E, F, G, H], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25
+    ]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26
+    ]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27
+    ]
+);
+impl_debug_and_default!(
+    Fields,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28
+    ]
+);
+impl_debug_and_default!(
+    Fields,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29
+    ]
+);
+impl_debug_and_default!(
+    Fields,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30
+    ]
+);
+impl_debug_and_default!(
+    Fields,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE, AF
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31
+    ]
+);
+
 impl_field_decode!([A], [0]);
 impl_field_decode!([A, B], [0, 1]);
 impl_field_decode!([A, B, C], [0, 1, 2]);
@@ -105,6 +313,129 @@ impl_field_decode!([A, B, C, D, E], [0, 1, 2, 3, 4]);
 impl_field_decode!([A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
 impl_field_decode!([A, B, C, D, E, F, G], [0, 1, 2, 3, 4, 5, 6]);
 impl_field_decode!([A, B, C, D, E, F, G, H], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_field_decode!([A, B, C, D, E, F, G, H, I], [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25
+    ]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26
+    ]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27
+    ]
+);
+impl_field_decode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28
+    ]
+);
+impl_field_decode!(
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29
+    ]
+);
+impl_field_decode!(
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30
+    ]
+);
+impl_field_decode!(
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE, AF
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31
+    ]
+);
 
 impl Encode for Fields<()> {
     type Item = ();
@@ -187,3 +518,126 @@ impl_field_encode!([A, B, C, D, E], [0, 1, 2, 3, 4]);
 impl_field_encode!([A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
 impl_field_encode!([A, B, C, D, E, F, G], [0, 1, 2, 3, 4, 5, 6]);
 impl_field_encode!([A, B, C, D, E, F, G, H], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_field_encode!([A, B, C, D, E, F, G, H, I], [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25
+    ]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26
+    ]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27
+    ]
+);
+impl_field_encode!(
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28
+    ]
+);
+impl_field_encode!(
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29
+    ]
+);
+impl_field_encode!(
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30
+    ]
+);
+impl_field_encode!(
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE, AF
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31
+    ]
+);
@@ -0,0 +1,417 @@
+//! Asynchronous streams and sinks of framed messages.
+//!
+//! This module is available only if the `futures` feature is enabled.
+//!
+//! `MessageStream` and `MessageSink` adapt a framing decoder/encoder to
+//! `AsyncRead`/`AsyncWrite` of the [futures] crate.
+//! The framing is chosen by the codec given to them:
+//!
+//! - `delimited::DelimitedMessageDecoder` / `delimited::DelimitedMessageEncoder` (varint lengths)
+//! - `delimited::Fixed32DelimitedMessageDecoder` / `delimited::Fixed32DelimitedMessageEncoder` (fixed32 lengths)
+//! - `grpc::GrpcFrameDecoder` / `grpc::GrpcFrameEncoder` (gRPC frames)
+//!
+//! Readers and writers of [tokio] can be used through the compatibility layer of the [tokio-util] crate.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate futures;
+//! # extern crate protobuf_codec;
+//! use futures::executor::block_on;
+//! use futures::{SinkExt, TryStreamExt};
+//! use protobuf_codec::delimited::{DelimitedMessageDecoder, DelimitedMessageEncoder};
+//! use protobuf_codec::field::{Fields, FieldDecoder, FieldEncoder, MaybeDefault};
+//! use protobuf_codec::field::num::F1;
+//! use protobuf_codec::framed::{MessageSink, MessageStream};
+//! use protobuf_codec::message::{MessageDecoder, MessageEncoder};
+//! use protobuf_codec::scalar::{StringDecoder, StringEncoder};
+//!
+//! # fn main() {
+//! type LogEncoder = MessageEncoder<Fields<(MaybeDefault<FieldEncoder<F1, StringEncoder>>,)>>;
+//! type LogDecoder = MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, StringDecoder>>,)>>;
+//!
+//! let mut sink = MessageSink::new(Vec::new(), DelimitedMessageEncoder::new(LogEncoder::default()));
+//! block_on(sink.send(("foo".to_owned(),))).unwrap();
+//! block_on(sink.send(("bar".to_owned(),))).unwrap();
+//! let bytes = sink.into_writer();
+//! assert_eq!(bytes, [5, 10, 3, 102, 111, 111, 5, 10, 3, 98, 97, 114]);
+//!
+//! let stream = MessageStream::new(&bytes[..], DelimitedMessageDecoder::new(LogDecoder::default()));
+//! let lines = block_on(stream.try_collect::<Vec<_>>()).unwrap();
+//! assert_eq!(lines, [("foo".to_owned(),), ("bar".to_owned(),)]);
+//! # }
+//! ```
+//!
+//! [futures]: https://crates.io/crates/futures
+//! [tokio]: https://crates.io/crates/tokio
+//! [tokio-util]: https://docs.rs/tokio-util/latest/tokio_util/compat/index.html
+use bytecodec::{ByteCount, Decode, Encode, Eos, Error, ErrorKind, Result};
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+use std::cmp;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use trackable::error::ErrorKindExt;
+
+const BUF_SIZE: usize = 8 * 1024;
+
+macro_rules! try_ready {
+    ($poll:expr) => {
+        match $poll {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(track!(e))),
+            Poll::Ready(Ok(v)) => v,
+        }
+    };
+}
+
+/// Stream of the messages read from an `AsyncRead`.
+///
+/// `D` is a decoder that decodes one frame at a time and returns an `ErrorKind::DecoderTerminated` error
+/// if the end of the input is reached at a boundary between frames (e.g., `delimited::DelimitedMessageDecoder`).
+///
+/// The stream finishes when the reader reaches its end at a boundary between frames.
+/// After an error is returned, the stream yields no more items.
+///
+/// Bytes are read only when the buffered ones are not enough to decode the next message,
+/// and each read is limited to the number of bytes reported by `D::requiring_bytes`.
+#[derive(Debug)]
+pub struct MessageStream<R, D> {
+    reader: R,
+    decoder: D,
+    buf: Vec<u8>,
+    head: usize,
+    tail: usize,
+    eos: bool,
+    done: bool,
+}
+impl<R: AsyncRead + Unpin, D: Decode> MessageStream<R, D> {
+    /// Makes a new `MessageStream` instance.
+    pub fn new(reader: R, decoder: D) -> Self {
+        MessageStream {
+            reader,
+            decoder,
+            buf: vec![0; BUF_SIZE],
+            head: 0,
+            tail: 0,
+            eos: false,
+            done: false,
+        }
+    }
+
+    /// Returns a reference to the reader.
+    pub fn reader_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a reference to the decoder.
+    pub fn decoder_ref(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Takes ownership of the instance and returns the reader.
+    ///
+    /// Note that the bytes buffered by the stream are discarded.
+    pub fn into_reader(self) -> R {
+        self.reader
+    }
+
+    fn poll_next_message(&mut self, cx: &mut Context) -> Poll<Result<Option<D::Item>>> {
+        loop {
+            if self.head < self.tail || self.eos {
+                let eos = Eos::new(self.eos);
+                match self.decoder.decode(&self.buf[self.head..self.tail], eos) {
+                    Err(ref e) if *e.kind() == ErrorKind::DecoderTerminated => {
+                        return Poll::Ready(Ok(None));
+                    }
+                    Err(e) => return Poll::Ready(Err(track!(e))),
+                    Ok(size) => self.head += size,
+                }
+                if self.decoder.is_idle() {
+                    let item = track!(self.decoder.finish_decoding())?;
+                    return Poll::Ready(Ok(Some(item)));
+                }
+                if self.eos {
+                    let e = Error::from(ErrorKind::UnexpectedEos.error());
+                    return Poll::Ready(Err(track!(e)));
+                }
+                if self.head < self.tail {
+                    continue;
+                }
+            }
+
+            self.head = 0;
+            self.tail = 0;
+            let size = match self.decoder.requiring_bytes() {
+                ByteCount::Finite(n) if n > 0 => cmp::min(n, self.buf.len() as u64) as usize,
+                _ => self.buf.len(),
+            };
+            let result = Pin::new(&mut self.reader).poll_read(cx, &mut self.buf[..size]);
+            match result {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(track!(Error::from(e)))),
+                Poll::Ready(Ok(size)) => {
+                    self.tail = size;
+                    self.eos = size == 0;
+                }
+            }
+        }
+    }
+}
+impl<R: AsyncRead + Unpin, D: Decode + Unpin> Stream for MessageStream<R, D> {
+    type Item = Result<D::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match this.poll_next_message(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(Some(item))) => Poll::Ready(Some(Ok(item))),
+            Poll::Ready(Ok(None)) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Ready(Err(e)) => {
+                this.done = true;
+                Poll::Ready(Some(Err(e)))
+            }
+        }
+    }
+}
+
+/// Sink of the messages written to an `AsyncWrite`.
+///
+/// `E` is an encoder that encodes one frame at a time (e.g., `delimited::DelimitedMessageEncoder`).
+///
+/// The sink accepts the next message only after the previous one has been encoded into the internal buffer,
+/// and the buffer is written when it becomes full or the sink is flushed.
+#[derive(Debug)]
+pub struct MessageSink<W, E> {
+    writer: W,
+    encoder: E,
+    buf: Vec<u8>,
+    head: usize,
+    tail: usize,
+}
+impl<W: AsyncWrite + Unpin, E: Encode> MessageSink<W, E> {
+    /// Makes a new `MessageSink` instance.
+    pub fn new(writer: W, encoder: E) -> Self {
+        MessageSink {
+            writer,
+            encoder,
+            buf: vec![0; BUF_SIZE],
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    /// Returns a reference to the writer.
+    pub fn writer_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a reference to the encoder.
+    pub fn encoder_ref(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Takes ownership of the instance and returns the writer.
+    ///
+    /// Note that the bytes that have not been flushed yet are discarded.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    fn poll_encode(&mut self, cx: &mut Context) -> Poll<Result<()>> {
+        while !self.encoder.is_idle() {
+            if self.tail < self.buf.len() {
+                let size = match self.encoder.requiring_bytes() {
+                    ByteCount::Finite(n) => cmp::min(n, (self.buf.len() - self.tail) as u64),
+                    _ => (self.buf.len() - self.tail) as u64,
+                } as usize;
+                let buf = &mut self.buf[self.tail..][..size];
+                self.tail += track!(self.encoder.encode(buf, Eos::new(false)))?;
+            } else {
+                try_ready!(self.poll_write_buf(cx));
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write_buf(&mut self, cx: &mut Context) -> Poll<Result<()>> {
+        let result = Pin::new(&mut self.writer).poll_write(cx, &self.buf[self.head..self.tail]);
+        let size = match result {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => 0,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(track!(Error::from(e)))),
+            Poll::Ready(Ok(0)) => {
+                let e = Error::from(io::Error::from(io::ErrorKind::WriteZero));
+                return Poll::Ready(Err(track!(e)));
+            }
+            Poll::Ready(Ok(size)) => size,
+        };
+        self.head += size;
+        if self.head == self.tail {
+            self.head = 0;
+            self.tail = 0;
+        } else if self.tail == self.buf.len() {
+            self.buf.copy_within(self.head..self.tail, 0);
+            self.tail -= self.head;
+            self.head = 0;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_flush_buf(&mut self, cx: &mut Context) -> Poll<Result<()>> {
+        try_ready!(self.poll_encode(cx));
+        while self.head < self.tail {
+            try_ready!(self.poll_write_buf(cx));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+impl<W: AsyncWrite + Unpin, E: Encode + Unpin> Sink<E::Item> for MessageSink<W, E> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        track!(self.get_mut().poll_encode(cx))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: E::Item) -> Result<()> {
+        track!(self.get_mut().encoder.start_encoding(item))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        try_ready!(this.poll_flush_buf(cx));
+        Pin::new(&mut this.writer)
+            .poll_flush(cx)
+            .map_err(|e| track!(Error::from(e)))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        try_ready!(this.poll_flush_buf(cx));
+        Pin::new(&mut this.writer)
+            .poll_close(cx)
+            .map_err(|e| track!(Error::from(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delimited::{
+        DelimitedMessageDecoder, DelimitedMessageEncoder, Fixed32DelimitedMessageDecoder,
+        Fixed32DelimitedMessageEncoder,
+    };
+    use crate::field::num::{F1, F2};
+    use crate::field::{FieldDecoder, FieldEncoder, Fields, MaybeDefault};
+    use crate::grpc::{GrpcFrameDecoder, GrpcFrameEncoder};
+    use crate::message::{MessageDecoder, MessageEncoder};
+    use crate::scalar::{StringDecoder, StringEncoder, Uint32Decoder, Uint32Encoder};
+    use futures::executor::block_on;
+    use futures::io::Cursor;
+    use futures::{stream, SinkExt, StreamExt, TryStreamExt};
+
+    type LogEncoder = MessageEncoder<
+        Fields<(
+            MaybeDefault<FieldEncoder<F1, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+        )>,
+    >;
+    type LogDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+        )>,
+    >;
+
+    fn logs() -> Vec<(u32, String)> {
+        vec![
+            (1, "foo".to_owned()),
+            (0, String::new()),
+            (3, "x".repeat(20_000)), // larger than the buffer
+        ]
+    }
+
+    // Reader that returns at most one byte per `poll_read` call and is pending every other call
+    struct SlowReader<'a> {
+        bytes: &'a [u8],
+        pending: bool,
+    }
+    impl<'a> AsyncRead for SlowReader<'a> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let size = cmp::min(1, cmp::min(buf.len(), self.bytes.len()));
+            buf[..size].copy_from_slice(&self.bytes[..size]);
+            self.bytes = &self.bytes[size..];
+            Poll::Ready(Ok(size))
+        }
+    }
+
+    fn roundtrip<D, E>(decoder: impl Fn() -> D, encoder: E)
+    where
+        D: Decode<Item = (u32, String)> + Unpin,
+        E: Encode<Item = (u32, String)> + Unpin,
+    {
+        let mut sink = MessageSink::new(Cursor::new(Vec::new()), encoder);
+        let mut items = stream::iter(logs()).map(Ok);
+        track_try_unwrap!(block_on(sink.send_all(&mut items)));
+        let bytes = sink.into_writer().into_inner();
+
+        let stream = MessageStream::new(&bytes[..], decoder());
+        assert_eq!(
+            track_try_unwrap!(block_on(stream.try_collect::<Vec<_>>())),
+            logs()
+        );
+
+        let reader = SlowReader {
+            bytes: &bytes,
+            pending: false,
+        };
+        let stream = MessageStream::new(reader, decoder());
+        assert_eq!(
+            track_try_unwrap!(block_on(stream.try_collect::<Vec<_>>())),
+            logs()
+        );
+
+        // Truncated
+        let stream = MessageStream::new(&bytes[..bytes.len() - 1], decoder());
+        let results = block_on(stream.collect::<Vec<_>>());
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[2].as_ref().err().map(|e| *e.kind()),
+            Some(ErrorKind::UnexpectedEos)
+        );
+    }
+
+    #[test]
+    fn framed_messages_work() {
+        roundtrip(
+            || DelimitedMessageDecoder::new(LogDecoder::default()),
+            DelimitedMessageEncoder::new(LogEncoder::default()),
+        );
+        roundtrip(
+            || Fixed32DelimitedMessageDecoder::new(LogDecoder::default()),
+            Fixed32DelimitedMessageEncoder::new(LogEncoder::default()),
+        );
+        roundtrip(
+            || GrpcFrameDecoder::new(LogDecoder::default()),
+            GrpcFrameEncoder::new(LogEncoder::default()),
+        );
+    }
+}
@@ -0,0 +1,401 @@
+use crate::field::num::FieldNum;
+use crate::field::{FieldDecode, FieldEncode, RequiredFieldDecode, RequiredFieldEncode};
+use crate::limit;
+use crate::message::{MessageDecode, MessageEncode};
+use crate::wire::{RawValueDecoder, Tag, TagDecoder, TagEncoder, WireType};
+use bytecodec::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
+
+/// Decoder for required group fields.
+///
+/// Groups are a deprecated way to embed messages in proto2.
+/// The fields of a group are enclosed by `StartGroup` and `EndGroup` tags instead of being length-delimited.
+///
+/// This decoder passes the fields of a group to the message decoder `D` as they arrive,
+/// and finishes the message when the matching `EndGroup` tag is found.
+/// If a group field appears more than once in an input stream, the instances are merged.
+#[derive(Debug)]
+pub struct GroupFieldDecoder<F, D> {
+    num: F,
+    tag: TagDecoder,
+    tag_bytes: Vec<u8>,
+    value: RawValueDecoder,
+    message: D,
+    state: GroupState,
+    present: bool,
+}
+impl<F, D: MessageDecode> GroupFieldDecoder<F, D> {
+    /// Makes a new `GroupFieldDecoder` instance.
+    pub fn new(field_num: F, message_decoder: D) -> Self {
+        GroupFieldDecoder {
+            num: field_num,
+            tag: TagDecoder::new(),
+            tag_bytes: Vec::new(),
+            value: RawValueDecoder::new(true),
+            message: message_decoder,
+            state: GroupState::Idle,
+            present: false,
+        }
+    }
+}
+impl<F: Default, D: MessageDecode + Default> Default for GroupFieldDecoder<F, D> {
+    fn default() -> Self {
+        Self::new(F::default(), D::default())
+    }
+}
+impl<F, D> GroupFieldDecoder<F, D>
+where
+    F: Copy + Into<FieldNum>,
+    D: MessageDecode,
+{
+    fn decode_message(&mut self, buf: &[u8], eos: Eos) -> Result<()> {
+        let _nested = track!(limit::enter_nested())?;
+        let size = track!(self.message.decode(buf, eos))?;
+        track_assert_eq!(size, buf.len(), ErrorKind::Other);
+        Ok(())
+    }
+}
+impl<F, D> Decode for GroupFieldDecoder<F, D>
+where
+    F: Copy + Into<FieldNum>,
+    D: MessageDecode,
+{
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while offset < buf.len() {
+            match self.state {
+                GroupState::Idle => break,
+                GroupState::Tag => {
+                    let size = track!(self.tag.decode(&buf[offset..], eos); self.num.into())?;
+                    self.tag_bytes.extend_from_slice(&buf[offset..][..size]);
+                    offset += size;
+                    if !self.tag.is_idle() {
+                        continue;
+                    }
+
+                    let tag = track!(self.tag.finish_decoding(); self.num.into())?;
+                    if tag.wire_type == WireType::EndGroup {
+                        track_assert_eq!(
+                            tag.field_num,
+                            self.num.into(),
+                            ErrorKind::InvalidInput,
+                            "Unmatched end group tag"
+                        );
+                        track!(self.decode_message(&[], Eos::new(true)); tag)?;
+                        self.state = GroupState::Idle;
+                    } else {
+                        // The tag belongs to a field of the group
+                        let tag_bytes = std::mem::take(&mut self.tag_bytes);
+                        track!(self.decode_message(&tag_bytes, Eos::new(false)); tag)?;
+                        self.tag_bytes = tag_bytes;
+                        track!(self.value.start(tag))?;
+                        self.state = GroupState::Value;
+                    }
+                    self.tag_bytes.clear();
+                }
+                GroupState::Value => {
+                    let size = track!(self.value.decode(&buf[offset..], eos); self.num.into())?;
+                    let bytes = &buf[offset..][..size];
+                    offset += size;
+                    track!(self.decode_message(bytes, Eos::new(false)); self.num.into())?;
+                    if self.value.is_idle() {
+                        track!(self.value.finish_decoding())?;
+                        self.state = GroupState::Tag;
+                    }
+                }
+            }
+        }
+        if self.state != GroupState::Idle {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos; self.num.into());
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(
+            self.present,
+            ErrorKind::InvalidInput,
+            "Missing required field: {:?}",
+            self.num.into()
+        );
+        track_assert_eq!(self.state, GroupState::Idle, ErrorKind::IncompleteDecoding; self.num.into());
+        let item = track!(self.message.finish_decoding(); self.num.into())?;
+        self.present = false;
+        Ok(item)
+    }
+
+    fn is_idle(&self) -> bool {
+        self.state == GroupState::Idle
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        match self.state {
+            GroupState::Idle => ByteCount::Finite(0),
+            GroupState::Tag => ByteCount::Unknown,
+            GroupState::Value => self.value.requiring_bytes(),
+        }
+    }
+}
+impl<F, D> FieldDecode for GroupFieldDecoder<F, D>
+where
+    F: Copy + Into<FieldNum>,
+    D: MessageDecode,
+{
+    fn start_decoding(&mut self, tag: Tag) -> Result<bool> {
+        if self.num.into() != tag.field_num {
+            return Ok(false);
+        }
+        track_assert_eq!(tag.wire_type, WireType::StartGroup, ErrorKind::InvalidInput; tag);
+        if self.present {
+            track!(self.message.resume_decoding(); tag)?;
+        }
+        self.state = GroupState::Tag;
+        self.present = true;
+        Ok(true)
+    }
+}
+impl<F, D> RequiredFieldDecode for GroupFieldDecoder<F, D>
+where
+    F: Copy + Into<FieldNum>,
+    D: MessageDecode,
+{
+    fn is_present(&self) -> bool {
+        self.present
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum GroupState {
+    #[default]
+    Idle,
+    Tag,
+    Value,
+}
+
+/// Encoder for required group fields.
+///
+/// See the documentation of `GroupFieldDecoder` for information about groups.
+#[derive(Debug, Default)]
+pub struct GroupFieldEncoder<F, E> {
+    num: F,
+    start_tag: TagEncoder,
+    message: E,
+    end_tag: TagEncoder,
+}
+impl<F, E: MessageEncode> GroupFieldEncoder<F, E> {
+    /// Makes a new `GroupFieldEncoder` instance.
+    pub fn new(field_num: F, message_encoder: E) -> Self {
+        GroupFieldEncoder {
+            num: field_num,
+            start_tag: TagEncoder::new(),
+            message: message_encoder,
+            end_tag: TagEncoder::new(),
+        }
+    }
+}
+impl<F, E> Encode for GroupFieldEncoder<F, E>
+where
+    F: Copy + Into<FieldNum>,
+    E: MessageEncode,
+{
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        bytecodec_try_encode!(self.start_tag, offset, buf, eos);
+        bytecodec_try_encode!(self.message, offset, buf, eos);
+        bytecodec_try_encode!(self.end_tag, offset, buf, eos);
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let num = self.num.into();
+        track!(self
+            .start_tag
+            .start_encoding(Tag::from((num, WireType::StartGroup))))?;
+        track!(self.message.start_encoding(item))?;
+        track!(self
+            .end_tag
+            .start_encoding(Tag::from((num, WireType::EndGroup))))?;
+        Ok(())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.start_tag.is_idle() && self.message.is_idle() && self.end_tag.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.start_tag
+            .requiring_bytes()
+            .add_for_encoding(self.message.requiring_bytes())
+            .add_for_encoding(self.end_tag.requiring_bytes())
+    }
+}
+impl<F, E> SizedEncode for GroupFieldEncoder<F, E>
+where
+    F: Copy + Into<FieldNum>,
+    E: MessageEncode + SizedEncode,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.start_tag.exact_requiring_bytes()
+            + self.message.exact_requiring_bytes()
+            + self.end_tag.exact_requiring_bytes()
+    }
+}
+impl<F, E> FieldEncode for GroupFieldEncoder<F, E>
+where
+    F: Copy + Into<FieldNum>,
+    E: MessageEncode,
+{
+}
+impl<F, E> RequiredFieldEncode for GroupFieldEncoder<F, E>
+where
+    F: Copy + Into<FieldNum>,
+    E: MessageEncode,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::num::{F1, F2, F3};
+    use crate::field::{
+        FieldDecoder, FieldEncoder, Fields, MaybeDefault, Optional, Repeated, UnknownFieldsDecoder,
+        UnknownFieldsEncoder,
+    };
+    use crate::message::{MessageDecoder, MessageEncoder};
+    use crate::scalar::{Int32Decoder, Int32Encoder, StringDecoder, StringEncoder};
+    use bytecodec::io::{IoDecodeExt, IoEncodeExt};
+    use bytecodec::EncodeExt;
+
+    // message SearchResponse {
+    //   repeated group Result = 1 {
+    //     required string url = 2;
+    //     optional int32 rank = 3;
+    //   }
+    // }
+    type ResultDecoder = MessageDecoder<
+        Fields<(
+            FieldDecoder<F2, StringDecoder>,
+            Optional<FieldDecoder<F3, Int32Decoder>>,
+        )>,
+    >;
+    type ResultEncoder = MessageEncoder<
+        Fields<(
+            FieldEncoder<F2, StringEncoder>,
+            Optional<FieldEncoder<F3, Int32Encoder>>,
+        )>,
+    >;
+    type SearchResponseDecoder = MessageDecoder<
+        Fields<(Repeated<GroupFieldDecoder<F1, ResultDecoder>, Vec<(String, Option<i32>)>>,)>,
+    >;
+    type SearchResponseEncoder = MessageEncoder<
+        Fields<(Repeated<GroupFieldEncoder<F1, ResultEncoder>, Vec<(String, Option<i32>)>>,)>,
+    >;
+
+    const SEARCH_RESPONSE: &[u8] = &[
+        11, // start group 1
+        18, 1, 97, // url="a"
+        24, 1,  // rank=1
+        12, // end group 1
+        11, // start group 1
+        18, 1, 98, // url="b"
+        12, // end group 1
+    ];
+
+    #[test]
+    fn group_field_decoder_works() {
+        let mut decoder = SearchResponseDecoder::default();
+        let (results,) = track_try_unwrap!(decoder.decode_exact(SEARCH_RESPONSE));
+        assert_eq!(results, [("a".to_owned(), Some(1)), ("b".to_owned(), None)]);
+
+        // Unmatched end group tag
+        let mut decoder = SearchResponseDecoder::default();
+        assert!(decoder.decode_exact(&[11, 18, 1, 97, 20][..]).is_err());
+
+        // Missing end group tag
+        let mut decoder = SearchResponseDecoder::default();
+        assert!(decoder.decode_exact(&[11, 18, 1, 97][..]).is_err());
+    }
+
+    #[test]
+    fn group_field_decoder_streams_fields() {
+        // Byte-by-byte
+        let mut decoder = SearchResponseDecoder::default();
+        for b in SEARCH_RESPONSE {
+            track_try_unwrap!(decoder.decode(&[*b], Eos::new(false)));
+        }
+        track_try_unwrap!(decoder.decode(&[], Eos::new(true)));
+        let (results,) = track_try_unwrap!(decoder.finish_decoding());
+        assert_eq!(results, [("a".to_owned(), Some(1)), ("b".to_owned(), None)]);
+
+        // The fields are passed to the message decoder before the end group tag arrives
+        let mut decoder = GroupFieldDecoder::new(F1, ResultDecoder::default());
+        assert!(track_try_unwrap!(
+            decoder.start_decoding(Tag::from((F1.into(), WireType::StartGroup)))
+        ));
+        track_try_unwrap!(decoder.decode(&[18, 3, 97], Eos::new(false)));
+        assert_eq!(decoder.requiring_bytes(), ByteCount::Finite(2));
+        track_try_unwrap!(decoder.decode(&[98, 99, 12], Eos::new(false)));
+        assert!(decoder.is_idle());
+        let result = track_try_unwrap!(decoder.finish_decoding());
+        assert_eq!(result, ("abc".to_owned(), None));
+
+        // Nested groups
+        let input = [
+            11, // start group 1
+            35, 19, 24, 1, 20, 36, // unknown group 4 {group 2 {rank=1}}
+            18, 1, 97, // url="a"
+            12, // end group 1
+        ];
+        let mut decoder = SearchResponseDecoder::default();
+        let (results,) = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(results, [("a".to_owned(), None)]);
+    }
+
+    #[test]
+    fn group_field_encoder_works() {
+        let mut encoder = track_try_unwrap!(SearchResponseEncoder::with_item((vec![
+            ("a".to_owned(), Some(1)),
+            ("b".to_owned(), None),
+        ],)));
+        let mut buf = Vec::new();
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, SEARCH_RESPONSE);
+    }
+
+    #[test]
+    fn unknown_groups_work() {
+        let input = [
+            11, // start group 1
+            19, 24, 1, 20, // nested group 2 {rank=1}
+            26, 1, 97, // f3="a"
+            12, // end group 1
+            16, 3, // f2=3
+        ];
+
+        // Skips
+        let mut decoder = MessageDecoder::new(Fields::new((MaybeDefault::new(FieldDecoder::new(
+            F2,
+            Int32Decoder::new(),
+        )),)));
+        let (f2,) = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(f2, 3);
+
+        // Preserves
+        let mut decoder = MessageDecoder::new(Fields::new((UnknownFieldsDecoder::new(),)));
+        let (unknown,) = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(unknown.len(), 2);
+
+        let mut encoder = MessageEncoder::new(Fields::new((UnknownFieldsEncoder::new(),)));
+        let mut buf = Vec::new();
+        track_try_unwrap!(encoder.start_encoding((unknown,)));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, input);
+
+        // Unexpected end group tag
+        let mut decoder = MessageDecoder::new(Fields::new((UnknownFieldsDecoder::new(),)));
+        assert!(decoder.decode_exact(&[12][..]).is_err());
+    }
+}
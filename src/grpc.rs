@@ -0,0 +1,643 @@
+//! Framing of messages used by [gRPC].
+//!
+//! Each message is prefixed by a 5-byte header that consists of
+//! a compressed-flag byte and the length of the message as a 4-byte big-endian integer.
+//!
+//! Compressed messages are supported by giving an implementation of `Compression` to the codecs.
+//! The length of a decoded message is limited by `GrpcFrameDecoder::set_max_message_len`
+//! regardless of whether it is compressed or not.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! use bytecodec::{DecodeExt, EncodeExt};
+//! use protobuf_codec::field::{Fields, FieldDecoder, FieldEncoder, MaybeDefault};
+//! use protobuf_codec::field::num::F1;
+//! use protobuf_codec::grpc::{GrpcFrameDecoder, GrpcFrameEncoder};
+//! use protobuf_codec::message::{MessageDecoder, MessageEncoder};
+//! use protobuf_codec::scalar::{StringDecoder, StringEncoder};
+//!
+//! # fn main() {
+//! // message HelloRequest { string name = 1; }
+//! type HelloRequestEncoder = MessageEncoder<Fields<(MaybeDefault<FieldEncoder<F1, StringEncoder>>,)>>;
+//! type HelloRequestDecoder = MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, StringDecoder>>,)>>;
+//!
+//! let mut encoder = GrpcFrameEncoder::new(HelloRequestEncoder::default());
+//! let bytes = encoder.encode_into_bytes(("foo".to_owned(),)).unwrap();
+//! assert_eq!(bytes, [0, 0, 0, 0, 5, 10, 3, 102, 111, 111]);
+//!
+//! let mut decoder = GrpcFrameDecoder::new(HelloRequestDecoder::default());
+//! let item = decoder.decode_from_bytes(&bytes).unwrap();
+//! assert_eq!(item, ("foo".to_owned(),));
+//! # }
+//! ```
+//!
+//! [gRPC]: https://github.com/grpc/grpc/blob/master/doc/PROTOCOL-HTTP2.md
+use crate::limit;
+use crate::message::{MessageDecode, MessageEncode};
+use bytecodec::bytes::{BytesEncoder, CopyableBytesDecoder};
+use bytecodec::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
+use std::cmp;
+
+const HEADER_SIZE: usize = 5;
+
+/// The default maximum length of messages accepted by `GrpcFrameDecoder` (4 MiB).
+///
+/// This is the same as the default limit of the official gRPC implementations.
+pub const DEFAULT_MAX_MESSAGE_LEN: u64 = 4 * 1024 * 1024;
+
+/// Compression algorithm of messages.
+pub trait Compression {
+    /// Returns the name of the algorithm used in the `grpc-encoding` header (e.g., `gzip`).
+    fn name(&self) -> &str;
+
+    /// Compresses the given bytes.
+    fn compress(&mut self, bytes: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decompresses the given bytes.
+    ///
+    /// If the decompressed bytes would be longer than `max_len`,
+    /// implementations should stop decompressing and return an `ErrorKind::InvalidInput` error,
+    /// so that a small frame cannot expand to a huge message.
+    fn decompress(&mut self, bytes: &[u8], max_len: u64) -> Result<Vec<u8>>;
+}
+
+/// The `identity` compression (i.e., no compression).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Identity;
+impl Compression for Identity {
+    fn name(&self) -> &str {
+        "identity"
+    }
+
+    fn compress(&mut self, bytes: &[u8]) -> Result<Vec<u8>> {
+        Ok(bytes.to_owned())
+    }
+
+    fn decompress(&mut self, bytes: &[u8], max_len: u64) -> Result<Vec<u8>> {
+        track_assert!(bytes.len() as u64 <= max_len, ErrorKind::InvalidInput; bytes.len(), max_len);
+        Ok(bytes.to_owned())
+    }
+}
+
+/// Decoder for gRPC frames.
+///
+/// This decodes one message at a time, so the messages of a streaming call can be decoded
+/// by repeating `decode` and `finish_decoding`.
+/// If the end of the stream is reached at a boundary between frames,
+/// `decode` returns an `ErrorKind::DecoderTerminated` error.
+///
+/// If a compressed frame is given to a decoder that has no `Compression`,
+/// an `ErrorKind::InvalidInput` error is returned.
+///
+/// If the length of a message (after decompression) exceeds the maximum length,
+/// an `ErrorKind::InvalidInput` error is returned.
+#[derive(Debug)]
+pub struct GrpcFrameDecoder<M, C = Identity> {
+    header: CopyableBytesDecoder<[u8; HEADER_SIZE]>,
+    message: M,
+    compression: Option<C>,
+    compressed: Vec<u8>,
+    state: DecodeState,
+    max_message_len: u64,
+}
+impl<M: MessageDecode> GrpcFrameDecoder<M> {
+    /// Makes a new `GrpcFrameDecoder` instance that does not accept compressed frames.
+    pub fn new(message_decoder: M) -> Self {
+        GrpcFrameDecoder {
+            header: Default::default(),
+            message: message_decoder,
+            compression: None,
+            compressed: Vec::new(),
+            state: DecodeState::Idle,
+            max_message_len: DEFAULT_MAX_MESSAGE_LEN,
+        }
+    }
+}
+impl<M: Default, C> Default for GrpcFrameDecoder<M, C> {
+    fn default() -> Self {
+        GrpcFrameDecoder {
+            header: Default::default(),
+            message: M::default(),
+            compression: None,
+            compressed: Vec::new(),
+            state: DecodeState::Idle,
+            max_message_len: DEFAULT_MAX_MESSAGE_LEN,
+        }
+    }
+}
+impl<M: MessageDecode, C: Compression> GrpcFrameDecoder<M, C> {
+    /// Makes a new `GrpcFrameDecoder` instance that decompresses compressed frames with `compression`.
+    pub fn with_compression(message_decoder: M, compression: C) -> Self {
+        GrpcFrameDecoder {
+            header: Default::default(),
+            message: message_decoder,
+            compression: Some(compression),
+            compressed: Vec::new(),
+            state: DecodeState::Idle,
+            max_message_len: DEFAULT_MAX_MESSAGE_LEN,
+        }
+    }
+
+    /// Returns the maximum length of a message.
+    pub fn max_message_len(&self) -> u64 {
+        self.max_message_len
+    }
+
+    /// Sets the maximum length of a message.
+    ///
+    /// The limit is applied to the length of a message after decompression.
+    /// The default value is `DEFAULT_MAX_MESSAGE_LEN`.
+    pub fn set_max_message_len(&mut self, max_len: u64) {
+        self.max_message_len = max_len;
+    }
+
+    /// Returns a reference to the inner message decoder.
+    pub fn inner_ref(&self) -> &M {
+        &self.message
+    }
+
+    /// Returns a mutable reference to the inner message decoder.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.message
+    }
+
+    /// Takes ownership of the instance and returns the inner message decoder.
+    pub fn into_inner(self) -> M {
+        self.message
+    }
+
+    fn start_payload(&mut self, header: [u8; HEADER_SIZE]) -> Result<DecodeState> {
+        let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+        track_assert!(
+            u64::from(len) <= self.max_message_len,
+            ErrorKind::InvalidInput,
+            "Too large message: {} bytes (max={})",
+            len,
+            self.max_message_len
+        );
+        track!(limit::check_length(u64::from(len)))?;
+        match header[0] {
+            0 => Ok(DecodeState::Payload(u64::from(len))),
+            1 => {
+                track_assert!(
+                    self.compression.is_some(),
+                    ErrorKind::InvalidInput,
+                    "Compressed frames are not accepted"
+                );
+                Ok(DecodeState::Compressed(u64::from(len)))
+            }
+            flag => track_panic!(ErrorKind::InvalidInput, "Unknown compressed flag: {}", flag),
+        }
+    }
+
+    fn decode_payload(&mut self, buf: &[u8], eos: Eos, remaining: u64) -> Result<usize> {
+        let limit = cmp::min(buf.len() as u64, remaining) as usize;
+        let required = remaining - limit as u64;
+        if let Some(n) = eos.remaining_bytes().to_u64() {
+            track_assert!(n >= required, ErrorKind::UnexpectedEos; n, required);
+        }
+
+        let inner_eos = Eos::with_remaining_bytes(ByteCount::Finite(required));
+        let size = track!(self.message.decode(&buf[..limit], inner_eos))?;
+        let remaining = remaining - size as u64;
+        self.state = if self.message.is_idle() {
+            track_assert_eq!(remaining, 0, ErrorKind::InvalidInput);
+            DecodeState::Done
+        } else {
+            DecodeState::Payload(remaining)
+        };
+        Ok(size)
+    }
+
+    fn decode_compressed(&mut self, buf: &[u8], eos: Eos, remaining: u64) -> Result<usize> {
+        let size = cmp::min(buf.len() as u64, remaining) as usize;
+        let remaining = remaining - size as u64;
+        if let Some(n) = eos.remaining_bytes().to_u64() {
+            track_assert!(n >= remaining, ErrorKind::UnexpectedEos; n, remaining);
+        }
+        self.compressed.extend_from_slice(&buf[..size]);
+        if remaining != 0 {
+            self.state = DecodeState::Compressed(remaining);
+            return Ok(size);
+        }
+
+        let max_len =
+            limit::max_length().map_or(self.max_message_len, |n| cmp::min(n, self.max_message_len));
+        let compression =
+            track_assert_some!(self.compression.as_mut(), ErrorKind::InconsistentState);
+        let bytes = track!(compression.decompress(&self.compressed, max_len); compression.name())?;
+        self.compressed.clear();
+        track_assert!(
+            bytes.len() as u64 <= self.max_message_len,
+            ErrorKind::InvalidInput,
+            "Too large decompressed message: {} bytes (max={})",
+            bytes.len(),
+            self.max_message_len
+        );
+        track!(limit::check_length(bytes.len() as u64))?;
+        let consumed = track!(self.message.decode(&bytes, Eos::new(true)))?;
+        track_assert_eq!(consumed, bytes.len(), ErrorKind::Other);
+        track_assert!(self.message.is_idle(), ErrorKind::Other);
+        self.state = DecodeState::Done;
+        Ok(size)
+    }
+}
+impl<M: MessageDecode, C: Compression> Decode for GrpcFrameDecoder<M, C> {
+    type Item = M::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        if let DecodeState::Idle = self.state {
+            if buf.is_empty() {
+                track_assert!(!eos.is_reached(), ErrorKind::DecoderTerminated);
+                return Ok(0);
+            }
+            self.state = DecodeState::Header;
+        }
+        if let DecodeState::Header = self.state {
+            bytecodec_try_decode!(self.header, offset, buf, eos);
+            let header = track!(self.header.finish_decoding())?;
+            self.state = track!(self.start_payload(header))?;
+        }
+        match self.state {
+            DecodeState::Payload(remaining) => {
+                offset += track!(self.decode_payload(&buf[offset..], eos, remaining))?;
+            }
+            DecodeState::Compressed(remaining) => {
+                offset += track!(self.decode_compressed(&buf[offset..], eos, remaining))?;
+            }
+            _ => {}
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert_eq!(self.state, DecodeState::Done, ErrorKind::IncompleteDecoding);
+        let item = track!(self.message.finish_decoding())?;
+        self.state = DecodeState::Idle;
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        match self.state {
+            DecodeState::Idle => ByteCount::Finite(HEADER_SIZE as u64),
+            DecodeState::Header => self.header.requiring_bytes(),
+            DecodeState::Payload(n) | DecodeState::Compressed(n) => ByteCount::Finite(n),
+            DecodeState::Done => ByteCount::Finite(0),
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.state == DecodeState::Done
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum DecodeState {
+    #[default]
+    Idle,
+    Header,
+    Payload(u64),
+    Compressed(u64),
+    Done,
+}
+
+/// Encoder for gRPC frames.
+///
+/// Non-sized message encoders can be used by wrapping them with `PreEncode`.
+#[derive(Debug, Default)]
+pub struct GrpcFrameEncoder<M, C = Identity> {
+    header: BytesEncoder<[u8; HEADER_SIZE]>,
+    message: M,
+    compression: Option<C>,
+    compressed: BytesEncoder<Vec<u8>>,
+}
+impl<M: MessageEncode + SizedEncode> GrpcFrameEncoder<M> {
+    /// Makes a new `GrpcFrameEncoder` instance that does not compress messages.
+    pub fn new(message_encoder: M) -> Self {
+        GrpcFrameEncoder {
+            header: Default::default(),
+            message: message_encoder,
+            compression: None,
+            compressed: Default::default(),
+        }
+    }
+}
+impl<M: MessageEncode + SizedEncode, C: Compression> GrpcFrameEncoder<M, C> {
+    /// Makes a new `GrpcFrameEncoder` instance that compresses every message with `compression`.
+    pub fn with_compression(message_encoder: M, compression: C) -> Self {
+        GrpcFrameEncoder {
+            header: Default::default(),
+            message: message_encoder,
+            compression: Some(compression),
+            compressed: Default::default(),
+        }
+    }
+
+    /// Returns a reference to the inner message encoder.
+    pub fn inner_ref(&self) -> &M {
+        &self.message
+    }
+
+    /// Returns a mutable reference to the inner message encoder.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.message
+    }
+
+    /// Takes ownership of the instance and returns the inner message encoder.
+    pub fn into_inner(self) -> M {
+        self.message
+    }
+}
+impl<M: MessageEncode + SizedEncode, C: Compression> Encode for GrpcFrameEncoder<M, C> {
+    type Item = M::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = track!(self.header.encode(buf, eos))?;
+        offset += track!(self.compressed.encode(&mut buf[offset..], eos))?;
+        offset += track!(self.message.encode(&mut buf[offset..], eos))?;
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        track!(self.message.start_encoding(item))?;
+
+        let (flag, len) = if let Some(compression) = self.compression.as_mut() {
+            let mut bytes = vec![0; self.message.exact_requiring_bytes() as usize];
+            let size = track!(self.message.encode(&mut bytes, Eos::new(true)))?;
+            track_assert_eq!(size, bytes.len(), ErrorKind::InconsistentState);
+            track_assert!(self.message.is_idle(), ErrorKind::InconsistentState);
+
+            let bytes = track!(compression.compress(&bytes); compression.name())?;
+            let len = bytes.len() as u64;
+            track!(self.compressed.start_encoding(bytes))?;
+            (1, len)
+        } else {
+            (0, self.message.exact_requiring_bytes())
+        };
+        track_assert!(
+            len <= u64::from(u32::MAX),
+            ErrorKind::InvalidInput,
+            "Too large message: {} bytes",
+            len
+        );
+
+        let mut header = [flag; HEADER_SIZE];
+        header[1..].copy_from_slice(&(len as u32).to_be_bytes());
+        track!(self.header.start_encoding(header))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        ByteCount::Finite(self.exact_requiring_bytes())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.header.is_idle() && self.compressed.is_idle() && self.message.is_idle()
+    }
+}
+impl<M: MessageEncode + SizedEncode, C: Compression> SizedEncode for GrpcFrameEncoder<M, C> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.header.exact_requiring_bytes()
+            + self.compressed.exact_requiring_bytes()
+            + self.message.exact_requiring_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::num::{F1, F2};
+    use crate::field::{FieldDecoder, FieldEncoder, Fields, MaybeDefault};
+    use crate::limit::{DecodeLimits, LimitedDecoder};
+    use crate::message::{MessageDecoder, MessageEncoder};
+    use crate::scalar::{BytesDecoder, BytesEncoder, Int32Decoder, Int32Encoder};
+    use crate::scalar::{StringDecoder, StringEncoder};
+    use bytecodec::io::IoDecodeExt;
+    use bytecodec::{DecodeExt, EncodeExt, Error};
+    use flate2::read::GzDecoder;
+    use flate2::write::GzEncoder;
+    use std::io::{Read, Write};
+
+    // message HelloReply {
+    //   string message = 1;
+    //   int32 count = 2;
+    // }
+    type HelloReplyEncoder = MessageEncoder<
+        Fields<(
+            MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+            MaybeDefault<FieldEncoder<F2, Int32Encoder>>,
+        )>,
+    >;
+    type HelloReplyDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+            MaybeDefault<FieldDecoder<F2, Int32Decoder>>,
+        )>,
+    >;
+
+    // The response body of a server-streaming call that replies
+    // `{message: "hello", count: 1}`, `{}` and `{message: "bye", count: 300}`.
+    //
+    // The frames follow the length-prefixed message format of the gRPC over HTTP/2 protocol.
+    const STREAMING_REPLY: &[u8] = &[
+        0, 0, 0, 0, 9, 10, 5, 104, 101, 108, 108, 111, 16, 1, // 1st
+        0, 0, 0, 0, 0, // 2nd
+        0, 0, 0, 0, 8, 10, 3, 98, 121, 101, 16, 172, 2, // 3rd
+    ];
+
+    // The same replies as `STREAMING_REPLY` sent with `grpc-encoding: gzip`.
+    //
+    // The messages were compressed by flate2 with the default compression level,
+    // i.e., by the `Gzip` compression below.
+    const GZIP_STREAMING_REPLY: &[u8] = &[
+        1, 0, 0, 0, 29, 31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 227, 98, 205, 72, 205, 201, 201, 23, 96,
+        4, 0, 18, 112, 219, 136, 9, 0, 0, 0, // 1st
+        1, 0, 0, 0, 20, 31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 3, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, // 2nd
+        1, 0, 0, 0, 28, 31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 227, 98, 78, 170, 76, 21, 88, 195, 4, 0,
+        91, 195, 150, 136, 8, 0, 0, 0, // 3rd
+    ];
+
+    // The same replies as `GZIP_STREAMING_REPLY` compressed by an independent implementation.
+    //
+    // Each message was compressed by GNU gzip 1.12, e.g.,
+    // `printf '\x0a\x05hello\x10\x01' | gzip -9 -n` for the 1st one.
+    const GNU_GZIP_STREAMING_REPLY: &[u8] = &[
+        1, 0, 0, 0, 29, 31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 227, 98, 205, 72, 205, 201, 201, 23, 96,
+        4, 0, 18, 112, 219, 136, 9, 0, 0, 0, // 1st
+        1, 0, 0, 0, 20, 31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, // 2nd
+        1, 0, 0, 0, 28, 31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 227, 98, 78, 170, 76, 21, 88, 195, 4, 0,
+        91, 195, 150, 136, 8, 0, 0, 0, // 3rd
+    ];
+
+    struct Gzip;
+    impl Compression for Gzip {
+        fn name(&self) -> &str {
+            "gzip"
+        }
+
+        fn compress(&mut self, bytes: &[u8]) -> Result<Vec<u8>> {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            track!(encoder.write_all(bytes).map_err(Error::from))?;
+            track!(encoder.finish().map_err(Error::from))
+        }
+
+        fn decompress(&mut self, bytes: &[u8], max_len: u64) -> Result<Vec<u8>> {
+            let mut decompressed = Vec::new();
+            let mut decoder = GzDecoder::new(bytes).take(max_len + 1);
+            track!(decoder.read_to_end(&mut decompressed).map_err(Error::from))?;
+            track_assert!(
+                decompressed.len() as u64 <= max_len,
+                ErrorKind::InvalidInput,
+                "Too large decompressed message"
+            );
+            Ok(decompressed)
+        }
+    }
+
+    fn replies() -> Vec<(String, i32)> {
+        vec![
+            ("hello".to_owned(), 1),
+            (String::new(), 0),
+            ("bye".to_owned(), 300),
+        ]
+    }
+
+    #[test]
+    fn grpc_frame_encoder_works() {
+        let mut bytes = Vec::new();
+        let mut encoder = GrpcFrameEncoder::new(HelloReplyEncoder::default());
+        for reply in replies() {
+            bytes.extend(track_try_unwrap!(encoder.encode_into_bytes(reply)));
+        }
+        assert_eq!(bytes, STREAMING_REPLY);
+
+        let mut bytes = Vec::new();
+        let mut encoder = GrpcFrameEncoder::with_compression(HelloReplyEncoder::default(), Gzip);
+        for reply in replies() {
+            bytes.extend(track_try_unwrap!(encoder.encode_into_bytes(reply)));
+        }
+        assert_eq!(bytes, GZIP_STREAMING_REPLY);
+    }
+
+    #[test]
+    fn grpc_frame_decoder_works() {
+        let mut decoder = GrpcFrameDecoder::new(HelloReplyDecoder::default());
+        let mut input = STREAMING_REPLY;
+        for reply in replies() {
+            assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), reply);
+        }
+        assert_eq!(
+            decoder.decode_exact(&mut input).err().map(|e| *e.kind()),
+            Some(ErrorKind::DecoderTerminated)
+        );
+
+        // Byte-by-byte
+        let mut decoder = GrpcFrameDecoder::new(HelloReplyDecoder::default());
+        let mut expected = replies().into_iter();
+        for &b in STREAMING_REPLY {
+            assert_eq!(track_try_unwrap!(decoder.decode(&[b], Eos::new(false))), 1);
+            if decoder.is_idle() {
+                assert_eq!(
+                    track_try_unwrap!(decoder.finish_decoding()),
+                    expected.next().unwrap()
+                );
+            }
+        }
+        assert!(expected.next().is_none());
+
+        // Compressed
+        let mut decoder = GrpcFrameDecoder::with_compression(HelloReplyDecoder::default(), Gzip);
+        let mut input = GZIP_STREAMING_REPLY;
+        for reply in replies() {
+            assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), reply);
+        }
+        let mut decoder = GrpcFrameDecoder::new(HelloReplyDecoder::default());
+        assert!(decoder.decode_from_bytes(GZIP_STREAMING_REPLY).is_err());
+    }
+
+    #[test]
+    fn grpc_frame_decoder_decompresses_external_gzip() {
+        let mut decoder = GrpcFrameDecoder::with_compression(HelloReplyDecoder::default(), Gzip);
+        let mut input = GNU_GZIP_STREAMING_REPLY;
+        for reply in replies() {
+            assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), reply);
+        }
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn message_length_is_limited() {
+        type BytesMessageDecoder =
+            MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, BytesDecoder>>,)>>;
+        type BytesMessageEncoder =
+            MessageEncoder<Fields<(MaybeDefault<FieldEncoder<F1, BytesEncoder>>,)>>;
+
+        let mut encoder = GrpcFrameEncoder::new(BytesMessageEncoder::default());
+        let plain = track_try_unwrap!(encoder.encode_into_bytes((vec![0; 1024],)));
+        let mut encoder = GrpcFrameEncoder::with_compression(BytesMessageEncoder::default(), Gzip);
+        let compressed = track_try_unwrap!(encoder.encode_into_bytes((vec![0; 1024 * 1024],)));
+        assert!(compressed.len() < 2048);
+
+        let mut decoder = GrpcFrameDecoder::with_compression(BytesMessageDecoder::default(), Gzip);
+        assert_eq!(decoder.max_message_len(), DEFAULT_MAX_MESSAGE_LEN);
+        let (bytes,) = track_try_unwrap!(decoder.decode_from_bytes(&plain));
+        assert_eq!(bytes.len(), 1024);
+        let (bytes,) = track_try_unwrap!(decoder.decode_from_bytes(&compressed));
+        assert_eq!(bytes.len(), 1024 * 1024);
+
+        // The length announced by the header
+        let mut decoder = GrpcFrameDecoder::with_compression(BytesMessageDecoder::default(), Gzip);
+        decoder.set_max_message_len(1000);
+        assert_eq!(
+            decoder.decode_from_bytes(&plain).err().map(|e| *e.kind()),
+            Some(ErrorKind::InvalidInput)
+        );
+
+        // The decompressed length
+        let mut decoder = GrpcFrameDecoder::with_compression(BytesMessageDecoder::default(), Gzip);
+        decoder.set_max_message_len(64 * 1024);
+        assert_eq!(
+            decoder
+                .decode_from_bytes(&compressed)
+                .err()
+                .map(|e| *e.kind()),
+            Some(ErrorKind::InvalidInput)
+        );
+
+        // The limits of `LimitedDecoder` are also applied to the decompressed length
+        let limits = DecodeLimits {
+            max_length: Some(64 * 1024),
+            ..DecodeLimits::default()
+        };
+        let decoder = GrpcFrameDecoder::with_compression(BytesMessageDecoder::default(), Gzip);
+        let mut decoder = LimitedDecoder::new(decoder, limits);
+        let error = decoder.decode_from_bytes(&compressed).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn invalid_grpc_frames_are_rejected() {
+        // Truncated
+        for end in 1..14 {
+            let mut decoder = GrpcFrameDecoder::new(HelloReplyDecoder::default());
+            assert_eq!(
+                decoder
+                    .decode_from_bytes(&STREAMING_REPLY[..end])
+                    .err()
+                    .map(|e| *e.kind()),
+                Some(ErrorKind::UnexpectedEos),
+                "end={}",
+                end
+            );
+        }
+
+        // Unknown flag
+        let mut decoder = GrpcFrameDecoder::new(HelloReplyDecoder::default());
+        assert!(decoder.decode_from_bytes(&[2, 0, 0, 0, 0]).is_err());
+    }
+}
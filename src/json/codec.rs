@@ -0,0 +1,303 @@
+use super::{JsonMapping, JsonOptions, JsonValue};
+use crate::dynamic::{
+    DescriptorPool, DynamicMessageDecoder, DynamicMessageEncoder, MessageDescriptor,
+};
+use crate::message::{MessageDecode, MessageEncode};
+use bytecodec::bytes::{BytesEncoder, RemainingBytesDecoder};
+use bytecodec::io::IoEncodeExt;
+use bytecodec::{
+    ByteCount, Decode, DecodeExt, Encode, EncodeExt, Eos, ErrorKind, Result, SizedEncode,
+};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Encoder that emits the JSON representations of items which implement `JsonMapping`.
+///
+/// Unlike `JsonMessageEncoder`, this converts items to JSON directly
+/// and needs neither binary encoders nor descriptors.
+#[derive(Debug)]
+pub struct JsonEncoder<T> {
+    options: JsonOptions,
+    bytes: BytesEncoder<Vec<u8>>,
+    _item: PhantomData<T>,
+}
+impl<T: JsonMapping> JsonEncoder<T> {
+    /// Makes a new `JsonEncoder` instance with the default options.
+    pub fn new() -> Self {
+        Self::with_options(JsonOptions::default())
+    }
+
+    /// Makes a new `JsonEncoder` instance with the given options.
+    pub fn with_options(options: JsonOptions) -> Self {
+        JsonEncoder {
+            options,
+            bytes: BytesEncoder::new(),
+            _item: PhantomData,
+        }
+    }
+}
+impl<T: JsonMapping> Default for JsonEncoder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: JsonMapping> Encode for JsonEncoder<T> {
+    type Item = T;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        let json = track!(item.to_json(&self.options))?;
+        track!(self.bytes.start_encoding(json.to_string().into_bytes()))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+}
+impl<T: JsonMapping> SizedEncode for JsonEncoder<T> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.bytes.exact_requiring_bytes()
+    }
+}
+
+/// Decoder that parses the JSON representations of items which implement `JsonMapping`.
+///
+/// Like `MessageDecoder`, this regards the end of the input stream as the end of the JSON text.
+#[derive(Debug)]
+pub struct JsonDecoder<T> {
+    options: JsonOptions,
+    bytes: RemainingBytesDecoder,
+    _item: PhantomData<T>,
+}
+impl<T: JsonMapping> JsonDecoder<T> {
+    /// Makes a new `JsonDecoder` instance with the default options.
+    pub fn new() -> Self {
+        Self::with_options(JsonOptions::default())
+    }
+
+    /// Makes a new `JsonDecoder` instance with the given options.
+    pub fn with_options(options: JsonOptions) -> Self {
+        JsonDecoder {
+            options,
+            bytes: RemainingBytesDecoder::new(),
+            _item: PhantomData,
+        }
+    }
+}
+impl<T: JsonMapping> Default for JsonDecoder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: JsonMapping> Decode for JsonDecoder<T> {
+    type Item = T;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let bytes = track!(self.bytes.finish_decoding())?;
+        let text = track_assert_some!(
+            String::from_utf8(bytes).ok(),
+            ErrorKind::InvalidInput,
+            "Invalid UTF-8 text"
+        );
+        let json = track!(JsonValue::parse(&text))?;
+        track!(T::from_json(&json, &self.options))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+}
+
+/// Encoder that emits the JSON representations of messages encoded by `E`.
+///
+/// The messages are encoded by `E` into the binary format at first,
+/// and then converted to JSON in accordance with the descriptor of the message.
+/// If the items implement `JsonMapping`, `JsonEncoder` can convert them directly instead.
+#[derive(Debug)]
+pub struct JsonMessageEncoder<E> {
+    inner: E,
+    decoder: DynamicMessageDecoder,
+    pool: Arc<DescriptorPool>,
+    options: JsonOptions,
+    bytes: BytesEncoder<Vec<u8>>,
+}
+impl<E: MessageEncode> JsonMessageEncoder<E> {
+    /// Makes a new `JsonMessageEncoder` instance with the default options.
+    ///
+    /// `message_name` is the full name of the message encoded by `inner`.
+    /// If `pool` does not have such message, this returns an `ErrorKind::InvalidInput` error.
+    pub fn new(inner: E, pool: Arc<DescriptorPool>, message_name: &str) -> Result<Self> {
+        track!(Self::with_options(
+            inner,
+            pool,
+            message_name,
+            JsonOptions::default()
+        ))
+    }
+
+    /// Makes a new `JsonMessageEncoder` instance with the given options.
+    pub fn with_options(
+        inner: E,
+        pool: Arc<DescriptorPool>,
+        message_name: &str,
+        options: JsonOptions,
+    ) -> Result<Self> {
+        let decoder = track!(DynamicMessageDecoder::new(pool.clone(), message_name))?;
+        Ok(JsonMessageEncoder {
+            inner,
+            decoder,
+            pool,
+            options,
+            bytes: BytesEncoder::new(),
+        })
+    }
+
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+}
+impl<E: MessageEncode> Encode for JsonMessageEncoder<E> {
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        track!(self.inner.start_encoding(item))?;
+        let mut binary = Vec::new();
+        track!(self.inner.encode_all(&mut binary))?;
+        let message = track!(self.decoder.decode_from_bytes(&binary))?;
+        let text = track!(super::to_string(&message, &self.pool, &self.options))?;
+        track!(self.bytes.start_encoding(text.into_bytes()))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+}
+impl<E: MessageEncode> SizedEncode for JsonMessageEncoder<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.bytes.exact_requiring_bytes()
+    }
+}
+
+/// Decoder that parses the JSON representations of messages and decodes them by `D`.
+///
+/// Like `MessageDecoder`, this regards the end of the input stream as the end of the JSON text.
+/// The text is converted to the binary format in accordance with the descriptor of the message,
+/// and then decoded by `D`.
+/// If the items implement `JsonMapping`, `JsonDecoder` can convert them directly instead.
+#[derive(Debug)]
+pub struct JsonMessageDecoder<D> {
+    inner: D,
+    descriptor: Arc<MessageDescriptor>,
+    pool: Arc<DescriptorPool>,
+    options: JsonOptions,
+    bytes: RemainingBytesDecoder,
+}
+impl<D: MessageDecode> JsonMessageDecoder<D> {
+    /// Makes a new `JsonMessageDecoder` instance with the default options.
+    ///
+    /// `message_name` is the full name of the message decoded by `inner`.
+    /// If `pool` does not have such message, this returns an `ErrorKind::InvalidInput` error.
+    pub fn new(inner: D, pool: Arc<DescriptorPool>, message_name: &str) -> Result<Self> {
+        track!(Self::with_options(
+            inner,
+            pool,
+            message_name,
+            JsonOptions::default()
+        ))
+    }
+
+    /// Makes a new `JsonMessageDecoder` instance with the given options.
+    pub fn with_options(
+        inner: D,
+        pool: Arc<DescriptorPool>,
+        message_name: &str,
+        options: JsonOptions,
+    ) -> Result<Self> {
+        let descriptor = track_assert_some!(
+            pool.message(message_name).cloned(),
+            ErrorKind::InvalidInput,
+            "Unknown message: {}",
+            message_name
+        );
+        Ok(JsonMessageDecoder {
+            inner,
+            descriptor,
+            pool,
+            options,
+            bytes: RemainingBytesDecoder::new(),
+        })
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+}
+impl<D: MessageDecode> Decode for JsonMessageDecoder<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let bytes = track!(self.bytes.finish_decoding())?;
+        let text = track_assert_some!(
+            String::from_utf8(bytes).ok(),
+            ErrorKind::InvalidInput,
+            "Invalid UTF-8 text"
+        );
+        let message = track!(super::from_str(
+            &text,
+            &self.pool,
+            &self.descriptor.name,
+            &self.options
+        ))?;
+        let binary = track!(DynamicMessageEncoder::new().encode_into_bytes(message))?;
+        track!(self.inner.decode_from_bytes(&binary))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+}
@@ -0,0 +1,885 @@
+use super::value::JsonValue;
+use super::JsonOptions;
+use crate::dynamic::{
+    DescriptorPool, DynamicMessage, DynamicMessageDecoder, DynamicMessageEncoder, FieldDescriptor,
+    FieldType, MapKey, MessageDescriptor, Value,
+};
+use crate::wellknown::google::protobuf::{DurationMessage, TimestampMessage};
+use bytecodec::{DecodeExt, EncodeExt, ErrorKind, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::Arc;
+
+const DURATION: &str = "google.protobuf.Duration";
+const TIMESTAMP: &str = "google.protobuf.Timestamp";
+const FIELD_MASK: &str = "google.protobuf.FieldMask";
+const STRUCT: &str = "google.protobuf.Struct";
+const VALUE: &str = "google.protobuf.Value";
+const LIST_VALUE: &str = "google.protobuf.ListValue";
+const NULL_VALUE: &str = "google.protobuf.NullValue";
+const ANY: &str = "google.protobuf.Any";
+const ANY_TYPE: &str = "@type";
+const WRAPPERS: &[&str] = &[
+    "google.protobuf.DoubleValue",
+    "google.protobuf.FloatValue",
+    "google.protobuf.Int64Value",
+    "google.protobuf.UInt64Value",
+    "google.protobuf.Int32Value",
+    "google.protobuf.UInt32Value",
+    "google.protobuf.BoolValue",
+    "google.protobuf.StringValue",
+    "google.protobuf.BytesValue",
+];
+
+/// Converter between `DynamicMessage` and `JsonValue`.
+#[derive(Debug)]
+pub(crate) struct Mapper<'a> {
+    pub pool: &'a Arc<DescriptorPool>,
+    pub options: &'a JsonOptions,
+}
+impl<'a> Mapper<'a> {
+    pub fn message_to_json(&self, message: &DynamicMessage) -> Result<JsonValue> {
+        let descriptor = message.descriptor();
+        if descriptor.name == DURATION {
+            return track!(duration_to_json(message));
+        }
+        if descriptor.name == TIMESTAMP {
+            return track!(timestamp_to_json(message));
+        }
+        if descriptor.name == FIELD_MASK {
+            return track!(field_mask_to_json(message));
+        }
+        if WRAPPERS.contains(&descriptor.name.as_str()) {
+            return track!(self.wrapper_to_json(message));
+        }
+        if descriptor.name == STRUCT || descriptor.name == LIST_VALUE {
+            return track!(self.struct_to_json(message));
+        }
+        if descriptor.name == VALUE {
+            return track!(self.value_message_to_json(message));
+        }
+        if descriptor.name == ANY {
+            return track!(self.any_to_json(message));
+        }
+
+        let mut members = Vec::new();
+        for f in &descriptor.fields {
+            let value = match message.get(f.number) {
+                Some(v) if f.has_presence() || !v.is_default() || self.options.emit_defaults => {
+                    track!(self.field_to_json(f, v); descriptor.name, f.name)?
+                }
+                None if !f.has_presence() && self.options.emit_defaults => {
+                    if f.is_map() {
+                        JsonValue::Object(Vec::new())
+                    } else if f.is_repeated() {
+                        JsonValue::Array(Vec::new())
+                    } else {
+                        let v = track!(Value::default_of(&f.ty, self.pool))?;
+                        track!(self.value_to_json(&f.ty, &v); descriptor.name, f.name)?
+                    }
+                }
+                _ => continue,
+            };
+            let name = if self.options.preserve_proto_field_names {
+                f.name.clone()
+            } else {
+                f.json_name()
+            };
+            members.push((name, value));
+        }
+        Ok(JsonValue::Object(members))
+    }
+
+    // Wrapper messages are represented by the JSON values of their wrapped values
+    fn wrapper_to_json(&self, message: &DynamicMessage) -> Result<JsonValue> {
+        let field = &message.descriptor().fields[0];
+        match message.get(field.number) {
+            Some(v) => track!(self.value_to_json(&field.ty, v)),
+            None => {
+                let v = track!(Value::default_of(&field.ty, self.pool))?;
+                track!(self.value_to_json(&field.ty, &v))
+            }
+        }
+    }
+
+    fn wrapper_from_json(
+        &self,
+        descriptor: &Arc<MessageDescriptor>,
+        json: &JsonValue,
+    ) -> Result<DynamicMessage> {
+        let field = &descriptor.fields[0];
+        let value = track!(self.value_from_json(&field.ty, json); descriptor.name)?;
+        let mut message = DynamicMessage::new(descriptor.clone());
+        track!(message.set(field.number, value))?;
+        Ok(message)
+    }
+
+    // `Struct` and `ListValue` are represented by the JSON values of their only fields
+    // (i.e., an object and an array respectively)
+    fn struct_to_json(&self, message: &DynamicMessage) -> Result<JsonValue> {
+        let field = &message.descriptor().fields[0];
+        match message.get(field.number) {
+            Some(v) => track!(self.field_to_json(field, v)),
+            None if field.is_map() => Ok(JsonValue::Object(Vec::new())),
+            None => Ok(JsonValue::Array(Vec::new())),
+        }
+    }
+
+    fn struct_from_json(
+        &self,
+        descriptor: &Arc<MessageDescriptor>,
+        json: &JsonValue,
+    ) -> Result<DynamicMessage> {
+        let field = &descriptor.fields[0];
+        let value = track!(self.field_from_json(field, json); descriptor.name)?;
+        let mut message = DynamicMessage::new(descriptor.clone());
+        track!(message.set(field.number, value))?;
+        Ok(message)
+    }
+
+    // `Value` is represented by the JSON value of the field set in `kind` oneof
+    fn value_message_to_json(&self, message: &DynamicMessage) -> Result<JsonValue> {
+        let (&number, value) = track_assert_some!(
+            message.fields().next(),
+            ErrorKind::InvalidInput,
+            "No kind of {} is set",
+            VALUE
+        );
+        if let Value::F64(v) = *value {
+            track_assert!(
+                v.is_finite(),
+                ErrorKind::InvalidInput,
+                "{} cannot be a non-finite number: {}",
+                VALUE,
+                v
+            );
+        }
+        let field = track_assert_some!(message.descriptor().field(number), ErrorKind::Other);
+        track!(self.value_to_json(&field.ty, value))
+    }
+
+    fn value_message_from_json(
+        &self,
+        descriptor: &Arc<MessageDescriptor>,
+        json: &JsonValue,
+    ) -> Result<DynamicMessage> {
+        let number = match *json {
+            JsonValue::Null => 1,
+            JsonValue::Number(_) => 2,
+            JsonValue::String(_) => 3,
+            JsonValue::Bool(_) => 4,
+            JsonValue::Object(_) => 5,
+            JsonValue::Array(_) => 6,
+        };
+        let field = track_assert_some!(descriptor.field(number), ErrorKind::Other);
+        let value = track!(self.value_from_json(&field.ty, json); descriptor.name)?;
+        let mut message = DynamicMessage::new(descriptor.clone());
+        track!(message.set(number, value))?;
+        Ok(message)
+    }
+
+    // `Any` is represented by the JSON object of the packed message with an additional `@type` member.
+    // If the packed message has a special representation (e.g., `Duration`),
+    // the representation is held by `value` member.
+    fn any_to_json(&self, message: &DynamicMessage) -> Result<JsonValue> {
+        let type_url = match message.get(1) {
+            Some(Value::String(v)) => v.as_str(),
+            _ => "",
+        };
+        let bytes = match message.get(2) {
+            Some(Value::Bytes(v)) => &v[..],
+            _ => &[],
+        };
+        if type_url.is_empty() && bytes.is_empty() {
+            return Ok(JsonValue::Object(Vec::new()));
+        }
+
+        let descriptor = track!(self.any_type(type_url))?;
+        let mut decoder = DynamicMessageDecoder::with_descriptor(self.pool.clone(), descriptor);
+        let packed = track!(decoder.decode_from_bytes(bytes); type_url)?;
+        let json = track!(self.message_to_json(&packed); type_url)?;
+
+        let mut members = vec![(ANY_TYPE.to_owned(), JsonValue::String(type_url.to_owned()))];
+        match json {
+            JsonValue::Object(m) if !has_special_json(&packed.descriptor().name) => {
+                members.extend(m)
+            }
+            json => members.push(("value".to_owned(), json)),
+        }
+        Ok(JsonValue::Object(members))
+    }
+
+    fn any_from_json(
+        &self,
+        descriptor: &Arc<MessageDescriptor>,
+        json: &JsonValue,
+    ) -> Result<DynamicMessage> {
+        let members = match *json {
+            JsonValue::Object(ref members) => members,
+            ref v => track_panic!(
+                ErrorKind::InvalidInput,
+                "Expected an object, but got {} (message={})",
+                v.kind(),
+                ANY
+            ),
+        };
+        let mut message = DynamicMessage::new(descriptor.clone());
+        if members.is_empty() {
+            return Ok(message);
+        }
+
+        let type_url = members.iter().find(|(k, _)| k == ANY_TYPE).map(|(_, v)| v);
+        let type_url = match type_url {
+            Some(JsonValue::String(v)) => v,
+            _ => track_panic!(
+                ErrorKind::InvalidInput,
+                "{} must have a string `{}` member",
+                ANY,
+                ANY_TYPE
+            ),
+        };
+        let packed_descriptor = track!(self.any_type(type_url))?;
+        let rest = members
+            .iter()
+            .filter(|(k, _)| k != ANY_TYPE)
+            .cloned()
+            .collect::<Vec<_>>();
+        let packed_json = if has_special_json(&packed_descriptor.name) {
+            match &rest[..] {
+                [(k, v)] if k == "value" => v.clone(),
+                _ => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{} of {} must have only `value` member",
+                    ANY,
+                    type_url
+                ),
+            }
+        } else {
+            JsonValue::Object(rest)
+        };
+        let packed = track!(self.message_from_json(&packed_descriptor, &packed_json); type_url)?;
+        let bytes = track!(DynamicMessageEncoder::new().encode_into_bytes(packed))?;
+
+        track!(message.set(1, Value::String(type_url.clone())))?;
+        track!(message.set(2, Value::Bytes(bytes)))?;
+        Ok(message)
+    }
+
+    // The type name is the part of the type URL after the last `/`
+    fn any_type(&self, type_url: &str) -> Result<Arc<MessageDescriptor>> {
+        let name = type_url.rsplit('/').next().expect("Never fails");
+        let descriptor = track_assert_some!(
+            self.pool.message(name),
+            ErrorKind::InvalidInput,
+            "Unknown type of {}: {:?}",
+            ANY,
+            type_url
+        );
+        Ok(descriptor.clone())
+    }
+
+    fn field_to_json(&self, field: &FieldDescriptor, value: &Value) -> Result<JsonValue> {
+        match (&field.ty, value) {
+            (FieldType::Map(_, value_type), Value::Map(entries)) => {
+                let mut members = Vec::new();
+                for (k, v) in entries {
+                    let key = match *k {
+                        MapKey::Bool(k) => k.to_string(),
+                        MapKey::I32(k) => k.to_string(),
+                        MapKey::I64(k) => k.to_string(),
+                        MapKey::U32(k) => k.to_string(),
+                        MapKey::U64(k) => k.to_string(),
+                        MapKey::String(ref k) => k.clone(),
+                    };
+                    members.push((key, track!(self.value_to_json(value_type, v))?));
+                }
+                Ok(JsonValue::Object(members))
+            }
+            (ty, Value::List(values)) => {
+                let mut array = Vec::with_capacity(values.len());
+                for v in values {
+                    array.push(track!(self.value_to_json(ty, v))?);
+                }
+                Ok(JsonValue::Array(array))
+            }
+            (ty, v) => track!(self.value_to_json(ty, v)),
+        }
+    }
+
+    fn value_to_json(&self, ty: &FieldType, value: &Value) -> Result<JsonValue> {
+        Ok(match *value {
+            Value::Bool(v) => JsonValue::Bool(v),
+            Value::I32(v) => JsonValue::Number(v.to_string()),
+            Value::U32(v) => JsonValue::Number(v.to_string()),
+            Value::I64(v) => JsonValue::String(v.to_string()),
+            Value::U64(v) => JsonValue::String(v.to_string()),
+            Value::F32(v) => float_to_json(v, f64::from(v)),
+            Value::F64(v) => float_to_json(v, v),
+            Value::String(ref v) => JsonValue::String(v.clone()),
+            Value::Bytes(ref v) => JsonValue::String(base64_encode(v)),
+            Value::Enum(_) if *ty == FieldType::Enum(NULL_VALUE.to_owned()) => JsonValue::Null,
+            Value::Enum(v) => {
+                let name = match *ty {
+                    FieldType::Enum(ref name) => self
+                        .pool
+                        .enum_type(name)
+                        .and_then(|e| e.value(v))
+                        .map(|v| v.name.clone()),
+                    _ => None,
+                };
+                name.map_or_else(|| JsonValue::Number(v.to_string()), JsonValue::String)
+            }
+            Value::Message(ref m) => track!(self.message_to_json(m))?,
+            Value::List(_) | Value::Map(_) => {
+                track_panic!(ErrorKind::InvalidInput, "Unexpected nested list or map")
+            }
+        })
+    }
+
+    pub fn message_from_json(
+        &self,
+        descriptor: &Arc<MessageDescriptor>,
+        json: &JsonValue,
+    ) -> Result<DynamicMessage> {
+        if descriptor.name == DURATION {
+            return track!(duration_from_json(descriptor, json));
+        }
+        if descriptor.name == TIMESTAMP {
+            return track!(timestamp_from_json(descriptor, json));
+        }
+        if descriptor.name == FIELD_MASK {
+            return track!(field_mask_from_json(descriptor, json));
+        }
+        if WRAPPERS.contains(&descriptor.name.as_str()) {
+            return track!(self.wrapper_from_json(descriptor, json));
+        }
+        if descriptor.name == STRUCT || descriptor.name == LIST_VALUE {
+            return track!(self.struct_from_json(descriptor, json));
+        }
+        if descriptor.name == VALUE {
+            return track!(self.value_message_from_json(descriptor, json));
+        }
+        if descriptor.name == ANY {
+            return track!(self.any_from_json(descriptor, json));
+        }
+
+        let members = match *json {
+            JsonValue::Object(ref members) => members,
+            ref v => track_panic!(
+                ErrorKind::InvalidInput,
+                "Expected an object, but got {} (message={})",
+                v.kind(),
+                descriptor.name
+            ),
+        };
+        let mut message = DynamicMessage::new(descriptor.clone());
+        let mut seen = HashSet::new();
+        let mut seen_oneofs = HashSet::new();
+        for (name, value) in members {
+            let field = descriptor
+                .fields
+                .iter()
+                .find(|f| f.json_name() == *name || f.name == *name);
+            let field = match field {
+                Some(f) => f,
+                None if self.options.ignore_unknown_fields => continue,
+                None => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "Unknown field: message={}, name={}",
+                    descriptor.name,
+                    name
+                ),
+            };
+            track_assert!(
+                seen.insert(field.number),
+                ErrorKind::InvalidInput,
+                "Duplicate field: message={}, field={}",
+                descriptor.name,
+                field.name
+            );
+            if let JsonValue::Null = *value {
+                if !accepts_null(&field.ty) {
+                    continue;
+                }
+            }
+            if let Some(ref oneof) = field.oneof {
+                track_assert!(
+                    seen_oneofs.insert(oneof),
+                    ErrorKind::InvalidInput,
+                    "Multiple fields of a oneof are set: message={}, oneof={}",
+                    descriptor.name,
+                    oneof
+                );
+            }
+            let value = track!(self.field_from_json(field, value); descriptor.name, field.name)?;
+            track!(message.set(field.number, value))?;
+        }
+        Ok(message)
+    }
+
+    fn field_from_json(&self, field: &FieldDescriptor, json: &JsonValue) -> Result<Value> {
+        match (&field.ty, json) {
+            (FieldType::Map(key_type, value_type), JsonValue::Object(members)) => {
+                let mut entries = BTreeMap::new();
+                for (k, v) in members {
+                    let key = track!(map_key_from_str(key_type, k))?;
+                    let value = track!(self.value_from_json(value_type, v); k)?;
+                    entries.insert(key, value);
+                }
+                Ok(Value::Map(entries))
+            }
+            (_, JsonValue::Array(values)) if field.is_repeated() => {
+                let mut list = Vec::with_capacity(values.len());
+                for v in values {
+                    list.push(track!(self.value_from_json(&field.ty, v))?);
+                }
+                Ok(Value::List(list))
+            }
+            (ty, v) => {
+                track_assert!(
+                    !field.is_map() && !field.is_repeated(),
+                    ErrorKind::InvalidInput,
+                    "Expected an object or an array, but got {}",
+                    v.kind()
+                );
+                track!(self.value_from_json(ty, v))
+            }
+        }
+    }
+
+    fn value_from_json(&self, ty: &FieldType, json: &JsonValue) -> Result<Value> {
+        Ok(match (ty, json) {
+            (FieldType::Bool, JsonValue::Bool(v)) => Value::Bool(*v),
+            (FieldType::Int32, _) | (FieldType::Sint32, _) | (FieldType::Sfixed32, _) => {
+                Value::I32(track!(integer_from_json(json))?)
+            }
+            (FieldType::Int64, _) | (FieldType::Sint64, _) | (FieldType::Sfixed64, _) => {
+                Value::I64(track!(integer_from_json(json))?)
+            }
+            (FieldType::Uint32, _) | (FieldType::Fixed32, _) => {
+                Value::U32(track!(integer_from_json(json))?)
+            }
+            (FieldType::Uint64, _) | (FieldType::Fixed64, _) => {
+                Value::U64(track!(integer_from_json(json))?)
+            }
+            (FieldType::Float, _) => {
+                let v = track!(float_from_json(json))?;
+                track_assert!(
+                    !v.is_finite() || v.abs() <= f64::from(f32::MAX),
+                    ErrorKind::InvalidInput,
+                    "Out of range: {}",
+                    v
+                );
+                Value::F32(v as f32)
+            }
+            (FieldType::Double, _) => Value::F64(track!(float_from_json(json))?),
+            (FieldType::String, JsonValue::String(v)) => Value::String(v.clone()),
+            (FieldType::Bytes, JsonValue::String(v)) => Value::Bytes(track!(base64_decode(v))?),
+            (FieldType::Enum(name), JsonValue::Null) if name == NULL_VALUE => Value::Enum(0),
+            (FieldType::Enum(_), JsonValue::Number(_)) => {
+                Value::Enum(track!(integer_from_json(json))?)
+            }
+            (FieldType::Enum(name), JsonValue::String(v)) => {
+                let n = self
+                    .pool
+                    .enum_type(name)
+                    .and_then(|e| e.value_by_name(v))
+                    .map(|v| v.number);
+                let n = track_assert_some!(
+                    n,
+                    ErrorKind::InvalidInput,
+                    "Unknown enum value: enum={}, value={}",
+                    name,
+                    v
+                );
+                Value::Enum(n)
+            }
+            (FieldType::Message(name), _) | (FieldType::Group(name), _) => {
+                let descriptor = track_assert_some!(
+                    self.pool.message(name),
+                    ErrorKind::InvalidInput,
+                    "Unknown message: {}",
+                    name
+                );
+                Value::Message(track!(self.message_from_json(descriptor, json))?)
+            }
+            (ty, v) => track_panic!(
+                ErrorKind::InvalidInput,
+                "Unexpected JSON value: type={:?}, value={}",
+                ty,
+                v.kind()
+            ),
+        })
+    }
+}
+
+// The well-known types whose JSON representations are not objects of their fields
+fn has_special_json(name: &str) -> bool {
+    [
+        DURATION, TIMESTAMP, FIELD_MASK, STRUCT, VALUE, LIST_VALUE, ANY,
+    ]
+    .contains(&name)
+        || WRAPPERS.contains(&name)
+}
+
+// `null` is the JSON value of `Value` and `NullValue` rather than the absence of the fields
+fn accepts_null(ty: &FieldType) -> bool {
+    match *ty {
+        FieldType::Message(ref name) => name == VALUE,
+        FieldType::Enum(ref name) => name == NULL_VALUE,
+        _ => false,
+    }
+}
+
+pub(crate) fn float_to_json<T: fmt::Display + fmt::LowerExp>(v: T, f: f64) -> JsonValue {
+    if f.is_nan() {
+        JsonValue::String("NaN".to_owned())
+    } else if f.is_infinite() {
+        let s = if f > 0.0 { "Infinity" } else { "-Infinity" };
+        JsonValue::String(s.to_owned())
+    } else if f == 0.0 || (1e-5..1e16).contains(&f.abs()) {
+        JsonValue::Number(v.to_string())
+    } else {
+        JsonValue::Number(format!("{:e}", v))
+    }
+}
+
+pub(crate) fn float_from_json(json: &JsonValue) -> Result<f64> {
+    let text = match *json {
+        JsonValue::Number(ref n) => n.as_str(),
+        JsonValue::String(ref s) => match s.as_str() {
+            "NaN" => return Ok(f64::NAN),
+            "Infinity" => return Ok(f64::INFINITY),
+            "-Infinity" => return Ok(f64::NEG_INFINITY),
+            s => s,
+        },
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected a number, but got {}",
+            v.kind()
+        ),
+    };
+    let v = track_assert_some!(
+        text.parse::<f64>().ok().filter(|v| v.is_finite()),
+        ErrorKind::InvalidInput,
+        "Invalid number: {:?}",
+        text
+    );
+    Ok(v)
+}
+
+pub(crate) fn integer_from_json<T: TryFrom<i128>>(json: &JsonValue) -> Result<T> {
+    let text = match *json {
+        JsonValue::Number(ref n) => n.as_str(),
+        JsonValue::String(ref s) => s.as_str(),
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected an integer, but got {}",
+            v.kind()
+        ),
+    };
+    let n = text.parse::<i128>().ok().or_else(|| {
+        // Exponent notation (e.g., `1e3`)
+        text.parse::<f64>()
+            .ok()
+            .filter(|v| v.fract() == 0.0 && v.abs() < 2e38)
+            .map(|v| v as i128)
+    });
+    let n = track_assert_some!(
+        n.and_then(|n| T::try_from(n).ok()),
+        ErrorKind::InvalidInput,
+        "Invalid integer: {:?}",
+        text
+    );
+    Ok(n)
+}
+
+fn map_key_from_str(ty: &FieldType, s: &str) -> Result<MapKey> {
+    let json = JsonValue::String(s.to_owned());
+    Ok(match *ty {
+        FieldType::Bool => match s {
+            "true" => MapKey::Bool(true),
+            "false" => MapKey::Bool(false),
+            _ => track_panic!(ErrorKind::InvalidInput, "Invalid bool key: {:?}", s),
+        },
+        FieldType::Int32 | FieldType::Sint32 | FieldType::Sfixed32 => {
+            MapKey::I32(track!(integer_from_json(&json))?)
+        }
+        FieldType::Int64 | FieldType::Sint64 | FieldType::Sfixed64 => {
+            MapKey::I64(track!(integer_from_json(&json))?)
+        }
+        FieldType::Uint32 | FieldType::Fixed32 => MapKey::U32(track!(integer_from_json(&json))?),
+        FieldType::Uint64 | FieldType::Fixed64 => MapKey::U64(track!(integer_from_json(&json))?),
+        FieldType::String => MapKey::String(s.to_owned()),
+        ref ty => track_panic!(ErrorKind::InvalidInput, "Invalid map key type: {:?}", ty),
+    })
+}
+
+fn duration_to_json(message: &DynamicMessage) -> Result<JsonValue> {
+    let seconds = match message.get(1) {
+        Some(&Value::I64(v)) => v,
+        _ => 0,
+    };
+    let nanos = match message.get(2) {
+        Some(&Value::I32(v)) => v,
+        _ => 0,
+    };
+    let s = track!(format_duration(seconds, nanos))?;
+    Ok(JsonValue::String(s))
+}
+
+pub(crate) fn format_duration(seconds: i64, nanos: i32) -> Result<String> {
+    track!(DurationMessage::new(seconds, nanos))?;
+    track_assert!(
+        seconds == 0 || nanos == 0 || (seconds < 0) == (nanos < 0),
+        ErrorKind::InvalidInput,
+        "Inconsistent signs: seconds={}, nanos={}",
+        seconds,
+        nanos
+    );
+
+    let sign = if seconds < 0 || nanos < 0 { "-" } else { "" };
+    let (seconds, nanos) = (seconds.abs(), nanos.abs());
+    Ok(if nanos == 0 {
+        format!("{}{}s", sign, seconds)
+    } else if nanos % 1_000_000 == 0 {
+        format!("{}{}.{:03}s", sign, seconds, nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        format!("{}{}.{:06}s", sign, seconds, nanos / 1_000)
+    } else {
+        format!("{}{}.{:09}s", sign, seconds, nanos)
+    })
+}
+
+fn duration_from_json(
+    descriptor: &Arc<MessageDescriptor>,
+    json: &JsonValue,
+) -> Result<DynamicMessage> {
+    let (seconds, nanos) = track!(parse_duration(json))?;
+    let mut message = DynamicMessage::new(descriptor.clone());
+    track!(message.set(1, Value::I64(seconds)))?;
+    track!(message.set(2, Value::I32(nanos)))?;
+    Ok(message)
+}
+
+pub(crate) fn parse_duration(json: &JsonValue) -> Result<(i64, i32)> {
+    let s = match *json {
+        JsonValue::String(ref s) => s.as_str(),
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected a duration string, but got {}",
+            v.kind()
+        ),
+    };
+    let parsed = s.strip_suffix('s').and_then(|s| {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (int, frac) = match s.find('.') {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => (s, "0"),
+        };
+        let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(int) || !is_digits(frac) || frac.len() > 9 {
+            return None;
+        }
+        let seconds = int.parse::<i64>().ok()?;
+        let nanos = format!("{:0<9}", frac).parse::<i32>().ok()?;
+        Some(if negative {
+            (-seconds, -nanos)
+        } else {
+            (seconds, nanos)
+        })
+    });
+    let (seconds, nanos) =
+        track_assert_some!(parsed, ErrorKind::InvalidInput, "Invalid duration: {:?}", s);
+    track!(DurationMessage::new(seconds, nanos))?;
+    Ok((seconds, nanos))
+}
+
+fn timestamp_to_json(message: &DynamicMessage) -> Result<JsonValue> {
+    let seconds = match message.get(1) {
+        Some(&Value::I64(v)) => v,
+        _ => 0,
+    };
+    let nanos = match message.get(2) {
+        Some(&Value::I32(v)) => v,
+        _ => 0,
+    };
+    let timestamp = track!(TimestampMessage::new(seconds, nanos))?;
+    Ok(JsonValue::String(timestamp.to_rfc3339()))
+}
+
+fn timestamp_from_json(
+    descriptor: &Arc<MessageDescriptor>,
+    json: &JsonValue,
+) -> Result<DynamicMessage> {
+    let s = match *json {
+        JsonValue::String(ref s) => s.as_str(),
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected a timestamp string, but got {}",
+            v.kind()
+        ),
+    };
+    let timestamp = track!(TimestampMessage::parse_rfc3339(s))?;
+
+    let mut message = DynamicMessage::new(descriptor.clone());
+    track!(message.set(1, Value::I64(timestamp.seconds())))?;
+    track!(message.set(2, Value::I32(timestamp.nanos())))?;
+    Ok(message)
+}
+
+// The paths are joined by commas, and each field name is converted to lowerCamelCase
+fn field_mask_to_json(message: &DynamicMessage) -> Result<JsonValue> {
+    let mut paths = Vec::new();
+    if let Some(Value::List(values)) = message.get(1) {
+        for v in values {
+            let path = match *v {
+                Value::String(ref v) => v,
+                _ => track_panic!(ErrorKind::InvalidInput, "Unexpected value: {:?}", v),
+            };
+            let mut json_path = String::with_capacity(path.len());
+            let mut chars = path.chars();
+            while let Some(c) = chars.next() {
+                let c = if c == '_' {
+                    chars
+                        .next()
+                        .filter(char::is_ascii_lowercase)
+                        .map(|c| c.to_ascii_uppercase())
+                } else {
+                    Some(c).filter(|c| !c.is_ascii_uppercase())
+                };
+                let c = track_assert_some!(
+                    c,
+                    ErrorKind::InvalidInput,
+                    "Path cannot be converted to lowerCamelCase: {:?}",
+                    path
+                );
+                json_path.push(c);
+            }
+            paths.push(json_path);
+        }
+    }
+    Ok(JsonValue::String(paths.join(",")))
+}
+
+fn field_mask_from_json(
+    descriptor: &Arc<MessageDescriptor>,
+    json: &JsonValue,
+) -> Result<DynamicMessage> {
+    let s = match *json {
+        JsonValue::String(ref s) => s.as_str(),
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected a field mask string, but got {}",
+            v.kind()
+        ),
+    };
+    let mut paths = Vec::new();
+    for json_path in s.split(',').filter(|p| !p.is_empty()) {
+        let mut path = String::with_capacity(json_path.len());
+        for c in json_path.chars() {
+            track_assert!(
+                c != '_',
+                ErrorKind::InvalidInput,
+                "Invalid field mask path: {:?}",
+                json_path
+            );
+            if c.is_ascii_uppercase() {
+                path.push('_');
+                path.push(c.to_ascii_lowercase());
+            } else {
+                path.push(c);
+            }
+        }
+        paths.push(Value::String(path));
+    }
+
+    let mut message = DynamicMessage::new(descriptor.clone());
+    track!(message.set(1, Value::List(paths)))?;
+    Ok(message)
+}
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - i * 8)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(BASE64_CHARS[(n >> (18 - i * 6)) as usize & 0x3F] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+// Both the standard and the URL-safe alphabets are accepted, and the padding can be omitted.
+pub(crate) fn base64_decode(s: &str) -> Result<Vec<u8>> {
+    let s = s.trim_end_matches('=');
+    track_assert_ne!(
+        s.len() % 4,
+        1,
+        ErrorKind::InvalidInput,
+        "Invalid base64 length"
+    );
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    let mut n = 0u32;
+    for (i, c) in s.bytes().enumerate() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => track_panic!(
+                ErrorKind::InvalidInput,
+                "Invalid base64 character: {:?}",
+                c as char
+            ),
+        };
+        n = (n << 6) | u32::from(v);
+        if i % 4 == 3 {
+            bytes.extend_from_slice(&[(n >> 16) as u8, (n >> 8) as u8, n as u8]);
+            n = 0;
+        }
+    }
+    match s.len() % 4 {
+        2 => bytes.push((n >> 4) as u8),
+        3 => bytes.extend_from_slice(&[(n >> 10) as u8, (n >> 2) as u8]),
+        _ => {}
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_works() {
+        for (bytes, text) in &[
+            (&b""[..], ""),
+            (&b"f"[..], "Zg=="),
+            (&b"fo"[..], "Zm8="),
+            (&b"foo"[..], "Zm9v"),
+            (&b"foob"[..], "Zm9vYg=="),
+            (&[0xFB, 0xFF][..], "+/8="),
+        ] {
+            assert_eq!(base64_encode(bytes), *text);
+            assert_eq!(track_try_unwrap!(base64_decode(text)), *bytes);
+        }
+        assert_eq!(track_try_unwrap!(base64_decode("-_8")), [0xFB, 0xFF]);
+        assert!(base64_decode("Zm9vY").is_err());
+        assert!(base64_decode("Zm9v!").is_err());
+    }
+}
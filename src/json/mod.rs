@@ -0,0 +1,518 @@
+//! [Canonical JSON mapping] of protocol buffer messages.
+//!
+//! [`JsonMessageEncoder`](./struct.JsonMessageEncoder.html) and
+//! [`JsonMessageDecoder`](./struct.JsonMessageDecoder.html) can be attached to
+//! existing message encoders and decoders (e.g., `MessageEncoder` and `MessageDecoder`).
+//! The field names, enum value names and field types used in the mapping are taken from
+//! the descriptor of the message registered in a [`DescriptorPool`].
+//!
+//! Items that implement [`JsonMapping`](./trait.JsonMapping.html) can be converted directly by
+//! [`JsonEncoder`](./struct.JsonEncoder.html) and [`JsonDecoder`](./struct.JsonDecoder.html)
+//! instead, without going through the binary format and descriptors.
+//! The messages and enums generated by [`codegen`](../codegen/index.html) implement it
+//! if `Generator::json_mapping` is enabled.
+//!
+//! The mapping follows the proto3 JSON specification:
+//!
+//! - Field names are converted to lowerCamelCase (unless `preserve_proto_field_names` is enabled)
+//! - 64-bit integers are represented as strings
+//! - `bytes` values are represented as base64 strings
+//! - Enum values are represented by their names (unknown values are represented as numbers)
+//! - `google.protobuf.Duration` is represented as a string such as `"1.5s"`
+//! - `google.protobuf.Timestamp` is represented as an RFC 3339 string such as `"1972-01-01T10:00:20.021Z"`
+//! - `google.protobuf.FieldMask` is represented as a string such as `"user.displayName,photo"`
+//! - The wrapper types (e.g., `google.protobuf.Int32Value`) are represented by the wrapped values
+//! - `google.protobuf.Struct`, `google.protobuf.Value` and `google.protobuf.ListValue` are represented
+//!   by arbitrary JSON objects, values and arrays respectively
+//! - `google.protobuf.Any` is represented by the JSON object of the packed message with an additional
+//!   `@type` member (or by `{"@type": ..., "value": ...}` if the packed message is a well-known type
+//!   that has a special representation)
+//! - Fields that have the default values are omitted (unless `emit_defaults` is enabled)
+//!
+//! The decoders accept both lowerCamelCase and the original field names.
+//! Unknown fields of the binary messages cannot be represented in JSON, so they are dropped.
+//!
+//! # Examples
+//!
+//! Conversion based on descriptors:
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! use bytecodec::EncodeExt;
+//! use bytecodec::io::IoDecodeExt;
+//! use protobuf_codec::dynamic::DescriptorPool;
+//! use protobuf_codec::field::{Fields, FieldDecoder, FieldEncoder, MaybeDefault};
+//! use protobuf_codec::field::num::{F1, F2};
+//! use protobuf_codec::json::{JsonMessageDecoder, JsonMessageEncoder};
+//! use protobuf_codec::message::{MessageDecoder, MessageEncoder};
+//! use protobuf_codec::scalar::{Int64Decoder, Int64Encoder, StringDecoder, StringEncoder};
+//! use protobuf_codec::schema;
+//! use std::sync::Arc;
+//!
+//! # fn main() {
+//! let file = schema::parse(r#"
+//!     syntax = "proto3";
+//!     message Item {
+//!       string item_name = 1;
+//!       int64 price = 2;
+//!     }
+//! "#).unwrap();
+//! let pool = Arc::new(DescriptorPool::from_files(&[file]).unwrap());
+//!
+//! let encoder = MessageEncoder::new(Fields::new((
+//!     MaybeDefault::<FieldEncoder<F1, StringEncoder>>::default(),
+//!     MaybeDefault::<FieldEncoder<F2, Int64Encoder>>::default(),
+//! )));
+//! let mut encoder = JsonMessageEncoder::new(encoder, pool.clone(), "Item").unwrap();
+//! let json = encoder.encode_into_bytes(("foo".to_owned(), 100)).unwrap();
+//! assert_eq!(json, br#"{"itemName":"foo","price":"100"}"#);
+//!
+//! let decoder = MessageDecoder::new(Fields::new((
+//!     MaybeDefault::<FieldDecoder<F1, StringDecoder>>::default(),
+//!     MaybeDefault::<FieldDecoder<F2, Int64Decoder>>::default(),
+//! )));
+//! let mut decoder = JsonMessageDecoder::new(decoder, pool, "Item").unwrap();
+//! let item = decoder.decode_exact(&br#"{"item_name": "bar", "price": 3}"#[..]).unwrap();
+//! assert_eq!(item, ("bar".to_owned(), 3));
+//! # }
+//! ```
+//!
+//!
+//! Direct conversion:
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! use bytecodec::{DecodeExt, EncodeExt};
+//! use protobuf_codec::json::{JsonDecoder, JsonEncoder};
+//! use std::time::Duration;
+//!
+//! # fn main() {
+//! let mut encoder = JsonEncoder::new();
+//! let json = encoder.encode_into_bytes(Duration::from_millis(1500)).unwrap();
+//! assert_eq!(json, br#""1.500s""#);
+//!
+//! let mut decoder = JsonDecoder::<Duration>::new();
+//! let item = decoder.decode_from_bytes(b"\"3s\"").unwrap();
+//! assert_eq!(item, Duration::from_secs(3));
+//! # }
+//! ```
+//!
+//! [Canonical JSON mapping]: https://developers.google.com/protocol-buffers/docs/proto3#json
+//! [`DescriptorPool`]: ../dynamic/struct.DescriptorPool.html
+use self::mapping::Mapper;
+use crate::dynamic::{DescriptorPool, DynamicMessage};
+use bytecodec::{ErrorKind, Result};
+use std::sync::Arc;
+
+pub use self::codec::{JsonDecoder, JsonEncoder, JsonMessageDecoder, JsonMessageEncoder};
+pub use self::typed::{
+    enum_from_json, JsonField, JsonMapKey, JsonMapping, JsonObjectReader, JsonObjectWriter,
+};
+pub use self::value::JsonValue;
+
+mod codec;
+mod mapping;
+mod typed;
+mod value;
+
+/// Options of the JSON mapping.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct JsonOptions {
+    /// If `true`, the fields that have the default values are emitted too.
+    ///
+    /// Note that unset message fields and the fields that track presence are never emitted.
+    pub emit_defaults: bool,
+
+    /// If `true`, the original field names are used instead of the lowerCamelCase names.
+    pub preserve_proto_field_names: bool,
+
+    /// If `true`, the decoders skip unknown fields instead of returning errors.
+    pub ignore_unknown_fields: bool,
+}
+
+/// Converts `message` to a JSON text.
+///
+/// The enum types referred from the message are looked up from `pool`.
+pub fn to_string(
+    message: &DynamicMessage,
+    pool: &Arc<DescriptorPool>,
+    options: &JsonOptions,
+) -> Result<String> {
+    let mapper = Mapper { pool, options };
+    let json = track!(mapper.message_to_json(message))?;
+    let mut text = String::new();
+    json.write(&mut text);
+    Ok(text)
+}
+
+/// Parses a JSON text as the message named `message_name`.
+///
+/// If `pool` does not have such message or the text is malformed,
+/// this returns an `ErrorKind::InvalidInput` error.
+pub fn from_str(
+    text: &str,
+    pool: &Arc<DescriptorPool>,
+    message_name: &str,
+    options: &JsonOptions,
+) -> Result<DynamicMessage> {
+    let descriptor = track_assert_some!(
+        pool.message(message_name),
+        ErrorKind::InvalidInput,
+        "Unknown message: {}",
+        message_name
+    );
+    let json = track!(JsonValue::parse(text))?;
+    let mapper = Mapper { pool, options };
+    track!(mapper.message_from_json(descriptor, &json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::Value;
+    use crate::field::num::{F1, F2};
+    use crate::field::{FieldDecoder, FieldEncoder, Fields, MaybeDefault, Optional};
+    use crate::message::{MessageDecoder, MessageEncoder};
+    use crate::scalar::{BytesDecoder, BytesEncoder, Int32Decoder, Int32Encoder};
+    use crate::schema;
+    use crate::wellknown::google::protobuf::{StdDurationDecoder, StdDurationEncoder};
+    use bytecodec::io::IoDecodeExt;
+    use bytecodec::EncodeExt;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const PROTO: &str = r#"
+        syntax = "proto3";
+        package example;
+        import "google/protobuf/duration.proto";
+        import "google/protobuf/timestamp.proto";
+
+        enum Corpus {
+          UNIVERSAL = 0;
+          WEB = 1;
+        }
+
+        message SearchRequest {
+          message Project {
+            string name = 1;
+          }
+          string query_text = 1;
+          int32 page_number = 2;
+          Corpus corpus = 3;
+          repeated sint64 samples = 4;
+          map<int32, Project> projects = 5;
+          oneof source {
+            string url = 6;
+            Project project = 7;
+          }
+          bytes payload = 8;
+          double score = 9;
+          google.protobuf.Duration timeout = 10;
+          repeated float weights = 11;
+          google.protobuf.Timestamp created_at = 12;
+          google.protobuf.Int32Value max_results = 13;
+          google.protobuf.FieldMask read_mask = 14;
+        }
+    "#;
+
+    fn pool() -> Arc<DescriptorPool> {
+        let mut pool = DescriptorPool::new();
+        pool.add_wellknown_types();
+        track_try_unwrap!(pool.add_files(&[track_try_unwrap!(schema::parse(PROTO))]));
+        Arc::new(pool)
+    }
+
+    fn request(pool: &Arc<DescriptorPool>) -> DynamicMessage {
+        let text = r#"{
+          "queryText": "foo\n\"bar\"",
+          "corpus": "WEB",
+          "samples": ["-1", 2, "3e2"],
+          "projects": {"10": {"name": "a"}, "-3": {}},
+          "project": {"name": "b"},
+          "payload": "AAH/",
+          "score": "NaN",
+          "timeout": "-1.500s",
+          "weights": [0.5, 1e20, "-Infinity"],
+          "createdAt": "2018-05-01T09:00:00.5+09:00",
+          "maxResults": 0,
+          "readMask": "queryText,project.name"
+        }"#;
+        track_try_unwrap!(from_str(
+            text,
+            pool,
+            "example.SearchRequest",
+            &JsonOptions::default()
+        ))
+    }
+
+    #[test]
+    fn json_mapping_works() {
+        let pool = pool();
+        let m = request(&pool);
+        assert_eq!(m.get_by_name("corpus"), Some(&Value::Enum(1)));
+        assert_eq!(
+            m.get_by_name("samples"),
+            Some(&Value::List(vec![
+                Value::I64(-1),
+                Value::I64(2),
+                Value::I64(300)
+            ]))
+        );
+        assert_eq!(
+            m.get_by_name("payload"),
+            Some(&Value::Bytes(vec![0, 1, 255]))
+        );
+
+        let text = track_try_unwrap!(to_string(&m, &pool, &JsonOptions::default()));
+        assert_eq!(
+            text,
+            concat!(
+                r#"{"queryText":"foo\n\"bar\"","corpus":"WEB","samples":["-1","2","300"],"#,
+                r#""projects":{"-3":{},"10":{"name":"a"}},"project":{"name":"b"},"#,
+                r#""payload":"AAH/","score":"NaN","timeout":"-1.500s","#,
+                r#""weights":[0.5,1e20,"-Infinity"],"createdAt":"2018-05-01T00:00:00.500Z","#,
+                r#""maxResults":0,"readMask":"queryText,project.name"}"#
+            )
+        );
+
+        let options = JsonOptions {
+            emit_defaults: true,
+            preserve_proto_field_names: true,
+            ..JsonOptions::default()
+        };
+        let empty = track_try_unwrap!(from_str("{}", &pool, "example.SearchRequest", &options));
+        let text = track_try_unwrap!(to_string(&empty, &pool, &options));
+        assert_eq!(
+            text,
+            concat!(
+                r#"{"query_text":"","page_number":0,"corpus":"UNIVERSAL","samples":[],"#,
+                r#""projects":{},"payload":"","score":0,"weights":[]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn json_mapping_rejects_invalid_input() {
+        let pool = pool();
+        let options = JsonOptions::default();
+        let name = "example.SearchRequest";
+        for text in &[
+            r#"{"queryText": 1}"#,
+            r#"{"pageNumber": "3000000000"}"#,
+            r#"{"pageNumber": 1.5}"#,
+            r#"{"corpus": "UNKNOWN"}"#,
+            r#"{"samples": 1}"#,
+            r#"{"url": "a", "project": {}}"#,
+            r#"{"queryText": "a", "query_text": "b"}"#,
+            r#"{"timeout": "1.5"}"#,
+            r#"{"timeout": "1.0000000001s"}"#,
+            r#"{"payload": "A"}"#,
+            r#"{"createdAt": "10000-01-01T00:00:00Z"}"#,
+            r#"{"unknown": 1}"#,
+            r#"{"queryText": "a",}"#,
+            r#"{"queryText": "\ud800"}"#,
+            r#"{} {}"#,
+        ] {
+            assert!(from_str(text, &pool, name, &options).is_err(), "{}", text);
+        }
+
+        let options = JsonOptions {
+            ignore_unknown_fields: true,
+            ..JsonOptions::default()
+        };
+        let m = track_try_unwrap!(from_str(
+            r#"{"unknown": [1], "url": null}"#,
+            &pool,
+            name,
+            &options
+        ));
+        assert_eq!(m.fields().count(), 0);
+    }
+
+    #[test]
+    fn wellknown_json_mapping_works() {
+        use crate::wellknown::google::protobuf::{self, StructDecoder};
+        use bytecodec::{DecodeExt, EncodeExt};
+        use std::collections::BTreeMap;
+
+        let file = track_try_unwrap!(schema::parse(
+            r#"
+            syntax = "proto3";
+            package example;
+            import "google/protobuf/any.proto";
+            import "google/protobuf/struct.proto";
+            message Event {
+              google.protobuf.Struct attributes = 1;
+              google.protobuf.Value value = 2;
+              google.protobuf.ListValue tags = 3;
+              repeated google.protobuf.Any details = 4;
+              google.protobuf.NullValue nothing = 5;
+            }
+        "#
+        ));
+        let mut pool = (*pool()).clone();
+        track_try_unwrap!(pool.add_files(&[file]));
+        let pool = Arc::new(pool);
+        let options = JsonOptions::default();
+        let name = "example.Event";
+
+        let text = concat!(
+            r#"{"attributes":{"a":1,"b":[true,null,"x"],"c":{"d":{}}},"value":null,"#,
+            r#""tags":[1.5,"s"],"details":["#,
+            r#"{"@type":"type.googleapis.com/example.SearchRequest.Project","name":"p"},"#,
+            r#"{"@type":"type.googleapis.com/google.protobuf.Duration","value":"1.500s"},"#,
+            r#"{"@type":"type.googleapis.com/google.protobuf.Value","value":{"k":[1]}}]}"#
+        );
+        let m = track_try_unwrap!(from_str(text, &pool, name, &options));
+        assert_eq!(track_try_unwrap!(to_string(&m, &pool, &options)), text);
+
+        // The binary form is the same as the one of the typed codecs
+        let attributes = match m.get_by_name("attributes") {
+            Some(Value::Message(m)) => m.clone(),
+            v => panic!("{:?}", v),
+        };
+        let bytes = track_try_unwrap!(
+            crate::dynamic::DynamicMessageEncoder::new().encode_into_bytes(attributes)
+        );
+        let mut expected = BTreeMap::new();
+        expected.insert("a".to_owned(), protobuf::Value::Number(1.0));
+        expected.insert(
+            "b".to_owned(),
+            protobuf::Value::List(vec![
+                protobuf::Value::Bool(true),
+                protobuf::Value::Null,
+                protobuf::Value::String("x".to_owned()),
+            ]),
+        );
+        let mut d = BTreeMap::new();
+        d.insert("d".to_owned(), protobuf::Value::Struct(BTreeMap::new()));
+        expected.insert("c".to_owned(), protobuf::Value::Struct(d));
+        assert_eq!(
+            track_try_unwrap!(StructDecoder::new().decode_from_bytes(&bytes)),
+            expected
+        );
+
+        let options = JsonOptions {
+            emit_defaults: true,
+            ..JsonOptions::default()
+        };
+        let empty = track_try_unwrap!(from_str("{}", &pool, name, &options));
+        assert_eq!(
+            track_try_unwrap!(to_string(&empty, &pool, &options)),
+            r#"{"details":[],"nothing":null}"#
+        );
+
+        for text in &[
+            r#"{"attributes": []}"#,
+            r#"{"tags": {}}"#,
+            r#"{"details": [{"name": "p"}]}"#,
+            r#"{"details": [{"@type": "type.googleapis.com/example.Unknown"}]}"#,
+            r#"{"details": [{"@type": "type.googleapis.com/google.protobuf.Duration"}]}"#,
+            r#"{"details": [{"@type": "type.googleapis.com/example.SearchRequest.Project",
+                             "value": "p"}]}"#,
+            r#"{"nothing": 0.5}"#,
+        ] {
+            assert!(from_str(text, &pool, name, &options).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn json_message_codec_works() {
+        let pool = pool();
+        let file = track_try_unwrap!(schema::parse(
+            r#"
+            syntax = "proto2";
+            import "google/protobuf/duration.proto";
+            message Task {
+              optional int32 retry_count = 1;
+              optional google.protobuf.Duration timeout = 2;
+              optional bytes data = 3;
+            }
+        "#
+        ));
+        let mut pool = (*pool).clone();
+        track_try_unwrap!(pool.add_files(&[file]));
+        let pool = Arc::new(pool);
+
+        let encoder = MessageEncoder::new(Fields::new((
+            FieldEncoder::<F1, Int32Encoder>::default(),
+            Optional::<crate::field::MessageFieldEncoder<F2, StdDurationEncoder>>::default(),
+        )));
+        let mut encoder = track_try_unwrap!(JsonMessageEncoder::new(encoder, pool.clone(), "Task"));
+        let json = track_try_unwrap!(
+            encoder.encode_into_bytes((0, Some(Duration::from_millis(1_000_001))))
+        );
+        assert_eq!(json, br#"{"retryCount":0,"timeout":"1000.001s"}"#);
+
+        let decoder = MessageDecoder::new(Fields::new((
+            MaybeDefault::<FieldDecoder<F1, Int32Decoder>>::default(),
+            Optional::<crate::field::MessageFieldDecoder<F2, StdDurationDecoder>>::default(),
+        )));
+        let mut decoder = track_try_unwrap!(JsonMessageDecoder::new(decoder, pool.clone(), "Task"));
+        let item = track_try_unwrap!(decoder.decode_exact(&br#"{"timeout": "0.5s"}"#[..]));
+        assert_eq!(item, (0, Some(Duration::from_millis(500))));
+
+        let encoder = MessageEncoder::new(Fields::new((FieldEncoder::<
+            crate::field::num::F3,
+            BytesEncoder,
+        >::default(),)));
+        let mut encoder = track_try_unwrap!(JsonMessageEncoder::new(encoder, pool.clone(), "Task"));
+        let json = track_try_unwrap!(encoder.encode_into_bytes((b"foo".to_vec(),)));
+        assert_eq!(json, br#"{"data":"Zm9v"}"#);
+
+        let decoder = MessageDecoder::new(Fields::new((FieldDecoder::<
+            crate::field::num::F3,
+            BytesDecoder,
+        >::default(),)));
+        let mut decoder = track_try_unwrap!(JsonMessageDecoder::new(decoder, pool, "Task"));
+        assert!(decoder.decode_exact(&b"{}"[..]).is_err());
+    }
+
+    #[test]
+    fn typed_json_mapping_works() {
+        use crate::wellknown::google::protobuf::Value as StructValue;
+        use bytecodec::DecodeExt;
+        use std::collections::{BTreeMap, HashMap};
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        fn roundtrip<T: JsonMapping + Clone + PartialEq + std::fmt::Debug>(item: T, json: &str) {
+            let encoded = track_try_unwrap!(JsonEncoder::new().encode_into_bytes(item.clone()));
+            assert_eq!(String::from_utf8(encoded).ok(), Some(json.to_owned()));
+            let decoded =
+                track_try_unwrap!(JsonDecoder::<T>::new().decode_from_bytes(json.as_bytes()));
+            assert_eq!(decoded, item);
+        }
+        roundtrip(Duration::new(3, 1_000), r#""3.000001s""#);
+        roundtrip(
+            UNIX_EPOCH + Duration::from_millis(20),
+            r#""1970-01-01T00:00:00.020Z""#,
+        );
+        roundtrip((), "{}");
+        roundtrip(-7i64, r#""-7""#);
+        roundtrip(1.5f32, "1.5");
+        roundtrip(b"\xff".to_vec(), r#""/w==""#);
+
+        let mut fields = BTreeMap::new();
+        fields.insert("b".to_owned(), StructValue::List(vec![StructValue::Null]));
+        fields.insert("a".to_owned(), StructValue::Number(-0.5));
+        roundtrip(StructValue::Struct(fields), r#"{"a":-0.5,"b":[null]}"#);
+
+        let mut map = HashMap::new();
+        map.insert(true, "t".to_owned());
+        map.insert(false, "f".to_owned());
+        roundtrip(map, r#"{"false":"f","true":"t"}"#);
+
+        // Values that cannot be represented
+        let options = JsonOptions::default();
+        assert!(StructValue::Number(f64::NAN).to_json(&options).is_err());
+        let negative = track_try_unwrap!(JsonValue::parse(r#""-1s""#));
+        assert!(Duration::from_json(&negative, &options).is_err());
+        let large = track_try_unwrap!(JsonValue::parse("1e39"));
+        assert!(f32::from_json(&large, &options).is_err());
+        let empty = track_try_unwrap!(JsonValue::parse(r#"{"a":1}"#));
+        assert!(<()>::from_json(&empty, &options).is_err());
+        assert!(SystemTime::from_json(&JsonValue::Null, &options).is_err());
+    }
+}
@@ -0,0 +1,578 @@
+use super::mapping::{
+    base64_decode, base64_encode, float_from_json, float_to_json, format_duration,
+    integer_from_json, parse_duration,
+};
+use super::{JsonOptions, JsonValue};
+use crate::scalar::Enum;
+use crate::wellknown::google::protobuf::{DurationMessage, TimestampMessage, Value};
+use bytecodec::{ErrorKind, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::slice;
+use std::time::{Duration, SystemTime};
+
+/// This trait allows for converting values to and from their JSON representations.
+///
+/// Unlike `JsonMessageEncoder` and `JsonMessageDecoder`, the implementations of this trait
+/// map values directly without descriptors, since the types know their own field names and types.
+///
+/// This is implemented by the following types:
+///
+/// - The types of the scalar values (`Vec<u8>` is regarded as `bytes`)
+/// - `std::time::Duration` and `std::time::SystemTime` (`google.protobuf.Duration` and `Timestamp`)
+/// - `()` (`google.protobuf.Empty`)
+/// - `Value`, `BTreeMap<String, Value>` and `Vec<Value>` of the `wellknown` module
+///   (`google.protobuf.Value`, `Struct` and `ListValue`)
+/// - `HashMap<K, V>` (map fields)
+/// - The messages and enums generated by `codegen` with `Generator::json_mapping` enabled
+pub trait JsonMapping: Sized {
+    /// Converts the value to JSON.
+    fn to_json(&self, options: &JsonOptions) -> Result<JsonValue>;
+
+    /// Converts `json` to a value of this type.
+    ///
+    /// If `json` does not represent such value, this returns an `ErrorKind::InvalidInput` error.
+    fn from_json(json: &JsonValue, options: &JsonOptions) -> Result<Self>;
+}
+
+/// This trait allows for converting the keys of map fields to and from the names of JSON object members.
+pub trait JsonMapKey: Sized {
+    /// Converts the key to a member name.
+    fn to_json_key(&self) -> String;
+
+    /// Converts `key` to a key of this type.
+    fn from_json_key(key: &str) -> Result<Self>;
+}
+
+macro_rules! impl_integer_json_mapping {
+    ($ty:ty, $variant:ident) => {
+        impl JsonMapping for $ty {
+            fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+                Ok(JsonValue::$variant(self.to_string()))
+            }
+
+            fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+                track!(integer_from_json(json))
+            }
+        }
+        impl JsonMapKey for $ty {
+            fn to_json_key(&self) -> String {
+                self.to_string()
+            }
+
+            fn from_json_key(key: &str) -> Result<Self> {
+                track!(integer_from_json(&JsonValue::String(key.to_owned())))
+            }
+        }
+    };
+}
+impl_integer_json_mapping!(i32, Number);
+impl_integer_json_mapping!(u32, Number);
+
+// 64-bit integers are represented as strings
+impl_integer_json_mapping!(i64, String);
+impl_integer_json_mapping!(u64, String);
+
+impl JsonMapping for bool {
+    fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+        Ok(JsonValue::Bool(*self))
+    }
+
+    fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+        match *json {
+            JsonValue::Bool(v) => Ok(v),
+            ref v => track_panic!(
+                ErrorKind::InvalidInput,
+                "Expected a bool, but got {}",
+                v.kind()
+            ),
+        }
+    }
+}
+impl JsonMapKey for bool {
+    fn to_json_key(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_json_key(key: &str) -> Result<Self> {
+        match key {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => track_panic!(ErrorKind::InvalidInput, "Invalid bool key: {:?}", key),
+        }
+    }
+}
+
+impl JsonMapping for f32 {
+    fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+        Ok(float_to_json(*self, f64::from(*self)))
+    }
+
+    fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+        let v = track!(float_from_json(json))?;
+        track_assert!(
+            !v.is_finite() || v.abs() <= f64::from(f32::MAX),
+            ErrorKind::InvalidInput,
+            "Out of range: {}",
+            v
+        );
+        Ok(v as f32)
+    }
+}
+
+impl JsonMapping for f64 {
+    fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+        Ok(float_to_json(*self, *self))
+    }
+
+    fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+        track!(float_from_json(json))
+    }
+}
+
+impl JsonMapping for String {
+    fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+        Ok(JsonValue::String(self.clone()))
+    }
+
+    fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+        Ok(track!(expect_string(json, "a string"))?.to_owned())
+    }
+}
+impl JsonMapKey for String {
+    fn to_json_key(&self) -> String {
+        self.clone()
+    }
+
+    fn from_json_key(key: &str) -> Result<Self> {
+        Ok(key.to_owned())
+    }
+}
+
+impl JsonMapping for Vec<u8> {
+    fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+        Ok(JsonValue::String(base64_encode(self)))
+    }
+
+    fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+        let s = track!(expect_string(json, "a base64 string"))?;
+        track!(base64_decode(s))
+    }
+}
+
+impl JsonMapping for Duration {
+    fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+        let d = track!(DurationMessage::from_duration(*self))?;
+        let s = track!(format_duration(d.seconds(), d.nanos()))?;
+        Ok(JsonValue::String(s))
+    }
+
+    fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+        let (seconds, nanos) = track!(parse_duration(json))?;
+        let d = track!(DurationMessage::new(seconds, nanos))?;
+        let d = track_assert_some!(d.to_duration(), ErrorKind::InvalidInput; d);
+        Ok(d)
+    }
+}
+
+impl JsonMapping for SystemTime {
+    fn to_json(&self, _options: &JsonOptions) -> Result<JsonValue> {
+        let t = track!(TimestampMessage::from_system_time(*self))?;
+        Ok(JsonValue::String(t.to_rfc3339()))
+    }
+
+    fn from_json(json: &JsonValue, _options: &JsonOptions) -> Result<Self> {
+        let s = track!(expect_string(json, "a timestamp string"))?;
+        let t = track!(TimestampMessage::parse_rfc3339(s))?;
+        let t = track_assert_some!(t.to_system_time(), ErrorKind::InvalidInput; t);
+        Ok(t)
+    }
+}
+
+impl JsonMapping for () {
+    fn to_json(&self, options: &JsonOptions) -> Result<JsonValue> {
+        Ok(JsonObjectWriter::new(options).finish())
+    }
+
+    fn from_json(json: &JsonValue, options: &JsonOptions) -> Result<Self> {
+        let mut reader = track!(JsonObjectReader::new(
+            json,
+            "google.protobuf.Empty",
+            &[],
+            options
+        ))?;
+        while track!(reader.next_field())?.is_some() {}
+        Ok(())
+    }
+}
+
+impl JsonMapping for Value {
+    fn to_json(&self, options: &JsonOptions) -> Result<JsonValue> {
+        Ok(match *self {
+            Value::Null => JsonValue::Null,
+            Value::Number(v) => {
+                track_assert!(
+                    v.is_finite(),
+                    ErrorKind::InvalidInput,
+                    "Non-finite number cannot be represented: {}",
+                    v
+                );
+                float_to_json(v, v)
+            }
+            Value::String(ref v) => JsonValue::String(v.clone()),
+            Value::Bool(v) => JsonValue::Bool(v),
+            Value::Struct(ref v) => track!(v.to_json(options))?,
+            Value::List(ref v) => track!(v.to_json(options))?,
+        })
+    }
+
+    fn from_json(json: &JsonValue, options: &JsonOptions) -> Result<Self> {
+        Ok(match *json {
+            JsonValue::Null => Value::Null,
+            JsonValue::Bool(v) => Value::Bool(v),
+            JsonValue::Number(_) => Value::Number(track!(float_from_json(json))?),
+            JsonValue::String(ref v) => Value::String(v.clone()),
+            JsonValue::Array(_) => Value::List(track!(JsonMapping::from_json(json, options))?),
+            JsonValue::Object(_) => Value::Struct(track!(JsonMapping::from_json(json, options))?),
+        })
+    }
+}
+
+impl JsonMapping for BTreeMap<String, Value> {
+    fn to_json(&self, options: &JsonOptions) -> Result<JsonValue> {
+        let mut members = Vec::with_capacity(self.len());
+        for (k, v) in self {
+            members.push((k.clone(), track!(v.to_json(options); k)?));
+        }
+        Ok(JsonValue::Object(members))
+    }
+
+    fn from_json(json: &JsonValue, options: &JsonOptions) -> Result<Self> {
+        let mut map = BTreeMap::new();
+        for (k, v) in track!(expect_object(json, "google.protobuf.Struct"))? {
+            map.insert(k.clone(), track!(Value::from_json(v, options); k)?);
+        }
+        Ok(map)
+    }
+}
+
+impl JsonMapping for Vec<Value> {
+    fn to_json(&self, options: &JsonOptions) -> Result<JsonValue> {
+        let mut values = Vec::with_capacity(self.len());
+        for v in self {
+            values.push(track!(v.to_json(options))?);
+        }
+        Ok(JsonValue::Array(values))
+    }
+
+    fn from_json(json: &JsonValue, options: &JsonOptions) -> Result<Self> {
+        track!(repeated_from_json(json, options))
+    }
+}
+
+/// Map fields.
+///
+/// The members are emitted in the order of the keys.
+impl<K, V> JsonMapping for HashMap<K, V>
+where
+    K: JsonMapKey + Eq + Hash + Ord,
+    V: JsonMapping,
+{
+    fn to_json(&self, options: &JsonOptions) -> Result<JsonValue> {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut members = Vec::with_capacity(entries.len());
+        for (k, v) in entries {
+            let key = k.to_json_key();
+            let value = track!(v.to_json(options); key)?;
+            members.push((key, value));
+        }
+        Ok(JsonValue::Object(members))
+    }
+
+    fn from_json(json: &JsonValue, options: &JsonOptions) -> Result<Self> {
+        let members = track!(expect_object(json, "map"))?;
+        let mut map = HashMap::with_capacity(members.len());
+        for (k, v) in members {
+            let key = track!(K::from_json_key(k))?;
+            let value = track!(V::from_json(v, options); k)?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+
+/// Converts `json` to a value of the enum `T`.
+///
+/// Names are converted by `from_name`, and numbers are converted by `Enum::from_i32`.
+/// This is used by the code generated by `codegen`.
+pub fn enum_from_json<T, F>(json: &JsonValue, enum_name: &str, from_name: F) -> Result<T>
+where
+    T: Enum,
+    F: FnOnce(&str) -> Option<T>,
+{
+    let value = match *json {
+        JsonValue::String(ref s) => from_name(s),
+        JsonValue::Number(_) => T::from_i32(track!(integer_from_json(json))?),
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected an enum value, but got {} (enum={})",
+            v.kind(),
+            enum_name
+        ),
+    };
+    let value = track_assert_some!(
+        value,
+        ErrorKind::InvalidInput,
+        "Unknown enum value: enum={}, value={}",
+        enum_name,
+        json
+    );
+    Ok(value)
+}
+
+/// Field of a message in the JSON mapping.
+///
+/// This is used by the code generated by `codegen`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JsonField {
+    /// Name of the field.
+    pub name: &'static str,
+
+    /// Name of the field used in the JSON mapping (i.e., the lowerCamelCase form of `name`).
+    pub json_name: &'static str,
+
+    /// Name of the oneof to which the field belongs.
+    pub oneof: Option<&'static str>,
+}
+
+/// Builder of the JSON objects that represent messages.
+///
+/// This is used by the code generated by `codegen`.
+#[derive(Debug)]
+pub struct JsonObjectWriter<'a> {
+    options: &'a JsonOptions,
+    members: Vec<(String, JsonValue)>,
+}
+impl<'a> JsonObjectWriter<'a> {
+    /// Makes a new `JsonObjectWriter` instance.
+    pub fn new(options: &'a JsonOptions) -> Self {
+        JsonObjectWriter {
+            options,
+            members: Vec::new(),
+        }
+    }
+
+    /// Adds a field that does not track presence.
+    ///
+    /// If the value is the default value, the field is omitted (unless `emit_defaults` is enabled).
+    pub fn field<T>(&mut self, field: &JsonField, value: &T) -> Result<()>
+    where
+        T: JsonMapping + Default + PartialEq,
+    {
+        if self.options.emit_defaults || *value != T::default() {
+            track!(self.push(field, value))?;
+        }
+        Ok(())
+    }
+
+    /// Adds a field that tracks presence.
+    ///
+    /// If the value is `None`, the field is omitted.
+    pub fn optional_field<T: JsonMapping>(
+        &mut self,
+        field: &JsonField,
+        value: Option<&T>,
+    ) -> Result<()> {
+        if let Some(value) = value {
+            track!(self.push(field, value))?;
+        }
+        Ok(())
+    }
+
+    /// Adds a repeated field.
+    ///
+    /// If there are no values, the field is omitted (unless `emit_defaults` is enabled).
+    pub fn repeated_field<T: JsonMapping>(
+        &mut self,
+        field: &JsonField,
+        values: &[T],
+    ) -> Result<()> {
+        if !self.options.emit_defaults && values.is_empty() {
+            return Ok(());
+        }
+        let mut array = Vec::with_capacity(values.len());
+        for v in values {
+            array.push(track!(v.to_json(self.options); field.name)?);
+        }
+        self.push_json(field, JsonValue::Array(array));
+        Ok(())
+    }
+
+    /// Finishes building the object.
+    pub fn finish(self) -> JsonValue {
+        JsonValue::Object(self.members)
+    }
+
+    fn push<T: JsonMapping>(&mut self, field: &JsonField, value: &T) -> Result<()> {
+        let json = track!(value.to_json(self.options); field.name)?;
+        self.push_json(field, json);
+        Ok(())
+    }
+
+    fn push_json(&mut self, field: &JsonField, json: JsonValue) {
+        let name = if self.options.preserve_proto_field_names {
+            field.name
+        } else {
+            field.json_name
+        };
+        self.members.push((name.to_owned(), json));
+    }
+}
+
+/// Reader of the JSON objects that represent messages.
+///
+/// This is used by the code generated by `codegen`.
+#[derive(Debug)]
+pub struct JsonObjectReader<'a> {
+    message_name: &'a str,
+    fields: &'a [JsonField],
+    options: &'a JsonOptions,
+    members: slice::Iter<'a, (String, JsonValue)>,
+    seen: Vec<bool>,
+    seen_oneofs: Vec<&'static str>,
+    current: Option<usize>,
+}
+impl<'a> JsonObjectReader<'a> {
+    /// Makes a new `JsonObjectReader` instance.
+    ///
+    /// `message_name` is the full name of the message used in error messages.
+    /// If `json` is not an object, this returns an `ErrorKind::InvalidInput` error.
+    pub fn new(
+        json: &'a JsonValue,
+        message_name: &'a str,
+        fields: &'a [JsonField],
+        options: &'a JsonOptions,
+    ) -> Result<Self> {
+        let members = track!(expect_object(json, message_name))?;
+        Ok(JsonObjectReader {
+            message_name,
+            fields,
+            options,
+            members: members.iter(),
+            seen: vec![false; fields.len()],
+            seen_oneofs: Vec::new(),
+            current: None,
+        })
+    }
+
+    /// Returns the index (in `fields`) and the value of the next field.
+    ///
+    /// Both the lowerCamelCase names and the original names are accepted.
+    /// Fields whose values are `null` are regarded as absent and skipped.
+    ///
+    /// # Errors
+    ///
+    /// If a field appears twice, multiple fields of a oneof appear,
+    /// or an unknown field appears (unless `ignore_unknown_fields` is enabled),
+    /// an `ErrorKind::InvalidInput` error will be returned.
+    pub fn next_field(&mut self) -> Result<Option<(usize, &'a JsonValue)>> {
+        for (name, value) in &mut self.members {
+            let index = self
+                .fields
+                .iter()
+                .position(|f| f.json_name == name || f.name == name);
+            let index = match index {
+                Some(i) => i,
+                None if self.options.ignore_unknown_fields => continue,
+                None => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "Unknown field: message={}, name={}",
+                    self.message_name,
+                    name
+                ),
+            };
+            let field = &self.fields[index];
+            track_assert!(
+                !self.seen[index],
+                ErrorKind::InvalidInput,
+                "Duplicate field: message={}, field={}",
+                self.message_name,
+                field.name
+            );
+            self.seen[index] = true;
+            if let JsonValue::Null = *value {
+                continue;
+            }
+            if let Some(oneof) = field.oneof {
+                track_assert!(
+                    !self.seen_oneofs.contains(&oneof),
+                    ErrorKind::InvalidInput,
+                    "Multiple fields of a oneof are set: message={}, oneof={}",
+                    self.message_name,
+                    oneof
+                );
+                self.seen_oneofs.push(oneof);
+            }
+            self.current = Some(index);
+            return Ok(Some((index, value)));
+        }
+        Ok(None)
+    }
+
+    /// Converts the value of the current field.
+    pub fn read<T: JsonMapping>(&self, json: &JsonValue) -> Result<T> {
+        track!(T::from_json(json, self.options); self.message_name, self.current_field())
+    }
+
+    /// Converts the value of the current field which is a repeated field.
+    pub fn read_repeated<T: JsonMapping>(&self, json: &JsonValue) -> Result<Vec<T>> {
+        track!(repeated_from_json(json, self.options); self.message_name, self.current_field())
+    }
+
+    fn current_field(&self) -> &str {
+        self.current.map_or("", |i| self.fields[i].name)
+    }
+}
+
+fn repeated_from_json<T: JsonMapping>(json: &JsonValue, options: &JsonOptions) -> Result<Vec<T>> {
+    match *json {
+        JsonValue::Array(ref values) => {
+            let mut items = Vec::with_capacity(values.len());
+            for v in values {
+                items.push(track!(T::from_json(v, options))?);
+            }
+            Ok(items)
+        }
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected an array, but got {}",
+            v.kind()
+        ),
+    }
+}
+
+fn expect_string<'a>(json: &'a JsonValue, expected: &str) -> Result<&'a str> {
+    match *json {
+        JsonValue::String(ref s) => Ok(s),
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected {}, but got {}",
+            expected,
+            v.kind()
+        ),
+    }
+}
+
+fn expect_object<'a>(json: &'a JsonValue, name: &str) -> Result<&'a [(String, JsonValue)]> {
+    match *json {
+        JsonValue::Object(ref members) => Ok(members),
+        ref v => track_panic!(
+            ErrorKind::InvalidInput,
+            "Expected an object, but got {} ({})",
+            v.kind(),
+            name
+        ),
+    }
+}
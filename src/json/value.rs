@@ -0,0 +1,396 @@
+use bytecodec::{ErrorKind, Result};
+use std::fmt::{self, Write};
+
+// The maximum depth of nested arrays and objects
+const MAX_DEPTH: usize = 100;
+
+/// JSON value.
+///
+/// Numbers are kept as their textual representations for preserving the precision of 64-bit integers.
+///
+/// `Display` formats the value as a compact JSON text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    /// `null`.
+    Null,
+
+    /// `true` or `false`.
+    Bool(bool),
+
+    /// Number (e.g., `-1.5e3`).
+    Number(String),
+
+    /// String.
+    String(String),
+
+    /// Array.
+    Array(Vec<JsonValue>),
+
+    /// Object.
+    ///
+    /// The members are kept in the order of their appearance.
+    Object(Vec<(String, JsonValue)>),
+}
+impl JsonValue {
+    /// Parses a JSON text.
+    ///
+    /// If the text is malformed or too deeply nested, this returns an `ErrorKind::InvalidInput` error.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            offset: 0,
+            depth: 0,
+        };
+        let value = track!(parser.value())?;
+        parser.skip_whitespaces();
+        track_assert_eq!(
+            parser.offset,
+            parser.text.len(),
+            ErrorKind::InvalidInput,
+            "Trailing characters"
+        );
+        Ok(value)
+    }
+
+    pub(crate) fn write(&self, buf: &mut String) {
+        match *self {
+            JsonValue::Null => buf.push_str("null"),
+            JsonValue::Bool(b) => buf.push_str(if b { "true" } else { "false" }),
+            JsonValue::Number(ref n) => buf.push_str(n),
+            JsonValue::String(ref s) => write_string(s, buf),
+            JsonValue::Array(ref values) => {
+                buf.push('[');
+                for (i, v) in values.iter().enumerate() {
+                    if i != 0 {
+                        buf.push(',');
+                    }
+                    v.write(buf);
+                }
+                buf.push(']');
+            }
+            JsonValue::Object(ref members) => {
+                buf.push('{');
+                for (i, (k, v)) in members.iter().enumerate() {
+                    if i != 0 {
+                        buf.push(',');
+                    }
+                    write_string(k, buf);
+                    buf.push(':');
+                    v.write(buf);
+                }
+                buf.push('}');
+            }
+        }
+    }
+
+    /// Returns the name of the kind of the value (e.g., `"object"`).
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "bool",
+            JsonValue::Number(_) => "number",
+            JsonValue::String(_) => "string",
+            JsonValue::Array(_) => "array",
+            JsonValue::Object(_) => "object",
+        }
+    }
+}
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = String::new();
+        self.write(&mut buf);
+        f.write_str(&buf)
+    }
+}
+
+fn write_string(s: &str, buf: &mut String) {
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            '\u{8}' => buf.push_str("\\b"),
+            '\u{c}' => buf.push_str("\\f"),
+            c if c.is_control() => {
+                let _ = write!(buf, "\\u{:04x}", c as u32);
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+#[derive(Debug)]
+struct Parser<'a> {
+    text: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+impl<'a> Parser<'a> {
+    fn value(&mut self) -> Result<JsonValue> {
+        self.skip_whitespaces();
+        let offset = self.offset;
+        match self.peek() {
+            Some(b'n') => track!(self.keyword("null", JsonValue::Null)),
+            Some(b't') => track!(self.keyword("true", JsonValue::Bool(true))),
+            Some(b'f') => track!(self.keyword("false", JsonValue::Bool(false))),
+            Some(b'"') => track!(self.string()).map(JsonValue::String),
+            Some(b'[') => track!(self.nested(Self::array); offset),
+            Some(b'{') => track!(self.nested(Self::object); offset),
+            Some(b'-') | Some(b'0'..=b'9') => track!(self.number()),
+            Some(b) => track_panic!(
+                ErrorKind::InvalidInput,
+                "Unexpected character: {:?} (offset={})",
+                b as char,
+                offset
+            ),
+            None => track_panic!(ErrorKind::UnexpectedEos),
+        }
+    }
+
+    fn nested<F>(&mut self, f: F) -> Result<JsonValue>
+    where
+        F: FnOnce(&mut Self) -> Result<JsonValue>,
+    {
+        track_assert!(
+            self.depth < MAX_DEPTH,
+            ErrorKind::InvalidInput,
+            "Too deep nesting"
+        );
+        self.depth += 1;
+        let value = track!(f(self))?;
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn array(&mut self) -> Result<JsonValue> {
+        self.offset += 1;
+        let mut values = Vec::new();
+        self.skip_whitespaces();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            values.push(track!(self.value())?);
+            self.skip_whitespaces();
+            match self.next() {
+                Some(b',') => {}
+                Some(b']') => return Ok(JsonValue::Array(values)),
+                _ => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "Expected ',' or ']' (offset={})",
+                    self.offset
+                ),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue> {
+        self.offset += 1;
+        let mut members = Vec::new();
+        self.skip_whitespaces();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespaces();
+            track_assert_eq!(
+                self.peek(),
+                Some(b'"'),
+                ErrorKind::InvalidInput,
+                "Expected a member name (offset={})",
+                self.offset
+            );
+            let name = track!(self.string())?;
+            self.skip_whitespaces();
+            track_assert_eq!(
+                self.next(),
+                Some(b':'),
+                ErrorKind::InvalidInput,
+                "Expected ':' (offset={})",
+                self.offset
+            );
+            let value = track!(self.value(); name)?;
+            members.push((name, value));
+            self.skip_whitespaces();
+            match self.next() {
+                Some(b',') => {}
+                Some(b'}') => return Ok(JsonValue::Object(members)),
+                _ => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "Expected ',' or '}}' (offset={})",
+                    self.offset
+                ),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.offset += 1;
+        let mut buf = Vec::new();
+        loop {
+            let b = track_assert_some!(self.next(), ErrorKind::UnexpectedEos);
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let b = track_assert_some!(self.next(), ErrorKind::UnexpectedEos);
+                    let c = match b {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => track!(self.unicode_escape())?,
+                        _ => track_panic!(
+                            ErrorKind::InvalidInput,
+                            "Unknown escape sequence: \\{} (offset={})",
+                            b as char,
+                            self.offset
+                        ),
+                    };
+                    let mut tmp = [0; 4];
+                    buf.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+                }
+                0x00..=0x1F => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "Unescaped control character (offset={})",
+                    self.offset
+                ),
+                b => buf.push(b),
+            }
+        }
+        let s = track_assert_some!(String::from_utf8(buf).ok(), ErrorKind::InvalidInput);
+        Ok(s)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char> {
+        let high = track!(self.hex4())?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            track_assert!(
+                self.text[self.offset..].starts_with(b"\\u"),
+                ErrorKind::InvalidInput,
+                "Unpaired surrogate (offset={})",
+                self.offset
+            );
+            self.offset += 2;
+            let low = track!(self.hex4())?;
+            track_assert!(
+                (0xDC00..0xE000).contains(&low),
+                ErrorKind::InvalidInput,
+                "Unpaired surrogate (offset={})",
+                self.offset
+            );
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        let c = track_assert_some!(
+            std::char::from_u32(code),
+            ErrorKind::InvalidInput,
+            "Invalid code point: {} (offset={})",
+            code,
+            self.offset
+        );
+        Ok(c)
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let end = self.offset + 4;
+        track_assert!(end <= self.text.len(), ErrorKind::UnexpectedEos);
+        let digits = std::str::from_utf8(&self.text[self.offset..end]).ok();
+        let n = track_assert_some!(
+            digits.and_then(|d| u32::from_str_radix(d, 16).ok()),
+            ErrorKind::InvalidInput,
+            "Invalid unicode escape (offset={})",
+            self.offset
+        );
+        self.offset = end;
+        Ok(n)
+    }
+
+    fn number(&mut self) -> Result<JsonValue> {
+        let start = self.offset;
+        if self.peek() == Some(b'-') {
+            self.offset += 1;
+        }
+        let int_start = self.offset;
+        self.skip_digits();
+        let int_len = self.offset - int_start;
+        track_assert!(
+            int_len != 0 && !(int_len > 1 && self.text[int_start] == b'0'),
+            ErrorKind::InvalidInput,
+            "Invalid number (offset={})",
+            start
+        );
+        if self.peek() == Some(b'.') {
+            self.offset += 1;
+            track_assert_ne!(
+                self.skip_digits(),
+                0,
+                ErrorKind::InvalidInput,
+                "Invalid number (offset={})",
+                start
+            );
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.offset += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.offset += 1;
+            }
+            track_assert_ne!(
+                self.skip_digits(),
+                0,
+                ErrorKind::InvalidInput,
+                "Invalid number (offset={})",
+                start
+            );
+        }
+        let n = String::from_utf8_lossy(&self.text[start..self.offset]).into_owned();
+        Ok(JsonValue::Number(n))
+    }
+
+    fn keyword(&mut self, keyword: &str, value: JsonValue) -> Result<JsonValue> {
+        track_assert!(
+            self.text[self.offset..].starts_with(keyword.as_bytes()),
+            ErrorKind::InvalidInput,
+            "Unexpected token (offset={})",
+            self.offset
+        );
+        self.offset += keyword.len();
+        Ok(value)
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.offset;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.offset += 1;
+        }
+        self.offset - start
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.offset).cloned()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let b = self.peek();
+        if b.is_some() {
+            self.offset += 1;
+        }
+        b
+    }
+}
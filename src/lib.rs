@@ -1,10 +1,22 @@
 //! Encoders and decoders for [Protocol Buffers][protobuf] based on [bytecodec] crate.
 //!
-//! # Limitation
+//! # Merging
 //!
-//! The current version does not support to merge duplicate messages.
-//! Although it is required by [the guide][encoding],
-//! `protobuf_codec` simply selects the last message instance of the same singular field.
+//! As required by [the guide][encoding], if a singular field appears more than once in an input stream,
+//! decoders select the last value for scalar fields and merge the instances for embedded message fields.
+//!
+//! # Unknown fields
+//!
+//! Fields that are not defined in the schema of a message are skipped by default.
+//! To preserve them (e.g., for forwarding messages defined by a newer schema),
+//! put `UnknownFieldsDecoder` and `UnknownFieldsEncoder` at the last of the fields of the message.
+//! The collected fields are re-emitted byte-exactly by the encoder.
+//!
+//! # Untrusted inputs
+//!
+//! Decoders trust the lengths and the nesting of values announced by inputs.
+//! When decoding untrusted inputs, wrap the decoder with `limit::LimitedDecoder`
+//! to bound the memory and the stack used for decoding.
 //!
 //! # Examples
 //!
@@ -54,6 +66,23 @@
 //! # }
 //! ```
 //!
+//! # Custom derive
+//!
+//! If the `derive` feature is enabled, `ProtobufMessage` and `ProtobufOneof` derive macros
+//! provided by [protobuf_codec_derive] crate are re-exported.
+//! They generate codecs whose items are user defined structs and enums.
+//!
+//! # Asynchronous IO
+//!
+//! If the `futures` feature is enabled, the `framed` module provides `Stream` and `Sink` adapters
+//! that read and write framed messages over `AsyncRead` and `AsyncWrite`.
+//!
+//! # Zero-copy decoding
+//!
+//! If the `bytes` feature is enabled, the `shared` module provides decoders
+//! that return `bytes` and `string` values as slices of a `bytes::Bytes` input.
+//! `wire::BorrowedFields` reads the fields of a fully-buffered message without copying them.
+//!
 //! # References
 //!
 //! - [Protocol Buffers: Language Guide (proto2)][proto2]
@@ -61,6 +90,7 @@
 //! - [Protocol Buffers: Encoding][encoding]
 //!
 //! [bytecodec]: https://github.com/sile/bytecodec
+//! [protobuf_codec_derive]: https://docs.rs/protobuf_codec_derive
 //! [protobuf]: https://developers.google.com/protocol-buffers/docs/overview
 //! [proto2]: https://developers.google.com/protocol-buffers/docs/proto
 //! [proto3]: https://developers.google.com/protocol-buffers/docs/proto3
@@ -74,16 +104,34 @@ extern crate trackable;
 #[macro_use]
 mod macros;
 
+pub mod codegen;
+pub mod delimited;
+pub mod dynamic;
 pub mod field;
+#[cfg(feature = "futures")]
+pub mod framed;
+pub mod grpc;
+pub mod json;
+pub mod limit;
 pub mod message;
+pub mod raw;
 pub mod scalar;
+pub mod schema;
+#[cfg(feature = "bytes")]
+pub mod shared;
+pub mod text;
 pub mod wellknown;
 pub mod wire;
 
+#[cfg(feature = "derive")]
+pub use protobuf_codec_derive::{ProtobufMessage, ProtobufOneof};
+
 mod field_num;
 mod fields;
+mod group_field;
 mod oneof;
 mod repeated_field;
+mod unknown_fields;
 mod value;
 
 #[cfg(test)]
@@ -188,7 +236,7 @@ mod tests {
                 102,
                 111,
                 111,
-                (11 << 3) | 0, // varint
+                11 << 3, // varint (wire type 0)
                 3,
                 (12 << 3) | 5, // 32-bit
                 10,
@@ -250,6 +298,96 @@ mod tests {
             [10, 19, 10, 3, 102, 111, 111, 18, 3, 49, 49, 49, 26, 1, 97, 26, 1, 98, 26, 1, 99]
         );
     }
+    #[test]
+    fn sized_repeated_encoder_works() {
+        use bytecodec::{Encode, Eos};
+
+        type SizedResultEncoder = MessageEncoder<
+            Fields<(
+                MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+                MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+                SizedRepeated<FieldEncoder<F3, StringEncoder>, Vec<String>>,
+            )>,
+        >;
+        type SizedSearchResponseEncoder =
+            MessageEncoder<SizedRepeated<MessageFieldEncoder<F1, SizedResultEncoder>, Vec<Result>>>;
+
+        let results = vec![
+            (s("foo"), s("111"), vec![s("a"), s("b"), s("c")]),
+            (s(""), s(""), vec![]),
+        ];
+        let bytes = [
+            10, 19, 10, 3, 102, 111, 111, 18, 3, 49, 49, 49, 26, 1, 97, 26, 1, 98, 26, 1, 99, 10, 0,
+        ];
+        assert_encode!(SizedSearchResponseEncoder, results.clone(), bytes);
+
+        let mut encoder: SizedSearchResponseEncoder =
+            track_try_unwrap!(EncodeExt::with_item(results.clone()));
+        assert_eq!(encoder.exact_requiring_bytes(), bytes.len() as u64);
+        let mut buf = [0; 10];
+        track_try_unwrap!(encoder.encode(&mut buf, Eos::new(false)));
+        assert_eq!(encoder.exact_requiring_bytes(), bytes.len() as u64 - 10);
+
+        // Reuse
+        track_try_unwrap!(encoder.encode_all(&mut Vec::new()));
+        assert_encode!(SizedSearchResponseEncoder, vec![], [0; 0]);
+        let mut buf = Vec::new();
+        track_try_unwrap!(encoder.start_encoding(results[..1].to_vec()));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, bytes[..21]);
+    }
+
+    #[test]
+    fn sized_repeated_encoder_recovers_from_errors() {
+        use bytecodec::{ByteCount, Encode, Eos, ErrorKind, Result};
+
+        #[derive(Debug, Default)]
+        struct NonZeroEncoder(Uint32Encoder);
+        impl Encode for NonZeroEncoder {
+            type Item = u32;
+
+            fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+                track!(self.0.encode(buf, eos))
+            }
+
+            fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+                track_assert_ne!(item, 0, ErrorKind::InvalidInput);
+                track!(self.0.start_encoding(item))
+            }
+
+            fn is_idle(&self) -> bool {
+                self.0.is_idle()
+            }
+
+            fn requiring_bytes(&self) -> ByteCount {
+                self.0.requiring_bytes()
+            }
+        }
+        impl SizedEncode for NonZeroEncoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                self.0.exact_requiring_bytes()
+            }
+        }
+        impl crate::value::ValueEncode for NonZeroEncoder {
+            fn wire_type(&self) -> crate::wire::WireType {
+                crate::wire::WireType::Varint
+            }
+        }
+
+        type Encoder = MessageEncoder<SizedRepeated<FieldEncoder<F1, NonZeroEncoder>, Vec<u32>>>;
+
+        let mut encoder = Encoder::default();
+        assert!(encoder.start_encoding(vec![1, 2, 0, 3]).is_err());
+        assert!(encoder.is_idle());
+        assert_eq!(encoder.exact_requiring_bytes(), 0);
+
+        let mut buf = Vec::new();
+        track_try_unwrap!(encoder.start_encoding(vec![1, 2]));
+        assert_eq!(encoder.exact_requiring_bytes(), 4);
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, [8, 1, 8, 2]);
+    }
+
     #[test]
     fn search_response_decoder_works() {
         assert_decode!(
@@ -305,6 +443,35 @@ mod tests {
         );
     }
     #[test]
+    fn sized_map_test_encoder_works() {
+        use bytecodec::SizedEncode;
+
+        type SizedMapTestEncoder =
+            MessageEncoder<SizedMapFieldEncoder<F5, Uint64Encoder, BoolEncoder, Vec<(u64, bool)>>>;
+        let entries = vec![(0, true), (11, false), (222, true)];
+        let bytes = [
+            42, 4, 8, 0, 16, 1, 42, 4, 8, 11, 16, 0, 42, 5, 8, 222, 1, 16, 1,
+        ];
+        assert_encode!(SizedMapTestEncoder, entries.clone(), bytes);
+        let encoder: SizedMapTestEncoder = track_try_unwrap!(EncodeExt::with_item(entries));
+        assert_eq!(encoder.exact_requiring_bytes(), bytes.len() as u64);
+
+        // map<string, SearchRequest> requests = 1;
+        type SizedMapMessageTestEncoder = MessageEncoder<
+            SizedMapMessageFieldEncoder<
+                F1,
+                StringEncoder,
+                SearchRequestEncoder,
+                Vec<(String, (String, i32, i32))>,
+            >,
+        >;
+        assert_encode!(
+            SizedMapMessageTestEncoder,
+            vec![(s("a"), (s("foo"), 3, 0))],
+            [10, 12, 10, 1, 97, 18, 7, 10, 3, 102, 111, 111, 16, 3]
+        );
+    }
+    #[test]
     fn map_test_decoder_works() {
         assert_decode!(
             MapTestDecoder,
@@ -376,6 +543,474 @@ mod tests {
         );
     }
 
+    // ```proto3
+    // message Fields9Test {
+    //   uint32 f1 = 1;
+    //   ...
+    //   uint32 f9 = 9;
+    // }
+    // ```
+    type Fields9TestEncoder = MessageEncoder<
+        Fields<(
+            MaybeDefault<FieldEncoder<F1, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F2, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F3, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F4, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F5, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F6, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F7, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F8, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F9, Uint32Encoder>>,
+        )>,
+    >;
+    type Fields9TestDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F2, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F3, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F4, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F5, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F6, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F7, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F8, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F9, Uint32Decoder>>,
+        )>,
+    >;
+
+    #[test]
+    fn fields9_test_works() {
+        let bytes = [8, 1, 16, 2, 24, 3, 32, 4, 40, 5, 48, 6, 56, 7, 64, 8, 72, 9];
+        assert_encode!(Fields9TestEncoder, (1, 2, 3, 4, 5, 6, 7, 8, 9), bytes);
+        assert_decode!(Fields9TestDecoder, (1, 2, 3, 4, 5, 6, 7, 8, 9), bytes);
+
+        // Fields in reverse order
+        let bytes = [72, 9, 64, 8, 56, 7, 48, 6, 40, 5, 32, 4, 24, 3, 16, 2, 8, 1];
+        assert_decode!(Fields9TestDecoder, (1, 2, 3, 4, 5, 6, 7, 8, 9), bytes);
+
+        assert_encode!(Fields9TestEncoder, (0, 0, 0, 0, 0, 0, 0, 0, 9), [72, 9]);
+        assert_decode!(Fields9TestDecoder, (0, 0, 0, 0, 0, 0, 0, 0, 9), [72, 9]);
+    }
+
+    // ```proto3
+    // message Fields32Test {
+    //   uint32 f1 = 1;
+    //   ...
+    //   uint32 f32 = 32;
+    // }
+    // ```
+    type Fields32TestEncoder = MessageEncoder<
+        Fields<(
+            MaybeDefault<FieldEncoder<F1, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F2, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F3, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F4, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F5, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F6, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F7, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F8, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F9, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F10, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F11, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F12, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F13, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F14, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F15, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<F16, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<17>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<18>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<19>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<20>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<21>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<22>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<23>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<24>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<25>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<26>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<27>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<28>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<29>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<30>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<31>, Uint32Encoder>>,
+            MaybeDefault<FieldEncoder<Fnum<32>, Uint32Encoder>>,
+        )>,
+    >;
+    type Fields32TestDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F2, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F3, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F4, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F5, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F6, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F7, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F8, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F9, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F10, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F11, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F12, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F13, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F14, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F15, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<F16, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<17>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<18>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<19>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<20>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<21>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<22>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<23>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<24>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<25>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<26>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<27>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<28>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<29>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<30>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<31>, Uint32Decoder>>,
+            MaybeDefault<FieldDecoder<Fnum<32>, Uint32Decoder>>,
+        )>,
+    >;
+
+    #[test]
+    fn fields32_test_works() {
+        // Tuples which have more than 12 elements do not implement `PartialEq`
+        fn decode(bytes: &[u8]) -> Vec<u32> {
+            let mut decoder = Fields32TestDecoder::default();
+            let (
+                v1,
+                v2,
+                v3,
+                v4,
+                v5,
+                v6,
+                v7,
+                v8,
+                v9,
+                v10,
+                v11,
+                v12,
+                v13,
+                v14,
+                v15,
+                v16,
+                v17,
+                v18,
+                v19,
+                v20,
+                v21,
+                v22,
+                v23,
+                v24,
+                v25,
+                v26,
+                v27,
+                v28,
+                v29,
+                v30,
+                v31,
+                v32,
+            ) = track_try_unwrap!(decoder.decode_exact(bytes));
+            vec![
+                v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12, v13, v14, v15, v16, v17, v18,
+                v19, v20, v21, v22, v23, v24, v25, v26, v27, v28, v29, v30, v31, v32,
+            ]
+        }
+
+        let bytes = [
+            8, 1, 16, 2, 24, 3, 32, 4, 40, 5, 48, 6, 56, 7, 64, 8, 72, 9, 80, 10, 88, 11, 96, 12,
+            104, 13, 112, 14, 120, 15, 128, 1, 16, 136, 1, 17, 144, 1, 18, 152, 1, 19, 160, 1, 20,
+            168, 1, 21, 176, 1, 22, 184, 1, 23, 192, 1, 24, 200, 1, 25, 208, 1, 26, 216, 1, 27,
+            224, 1, 28, 232, 1, 29, 240, 1, 30, 248, 1, 31, 128, 2, 32,
+        ];
+        assert_encode!(
+            Fields32TestEncoder,
+            (
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+                24, 25, 26, 27, 28, 29, 30, 31, 32
+            ),
+            bytes[..]
+        );
+        assert_eq!(decode(&bytes), (1..=32).collect::<Vec<_>>());
+
+        // Fields in reverse order
+        let bytes = [
+            128, 2, 32, 248, 1, 31, 240, 1, 30, 232, 1, 29, 224, 1, 28, 216, 1, 27, 208, 1, 26,
+            200, 1, 25, 192, 1, 24, 184, 1, 23, 176, 1, 22, 168, 1, 21, 160, 1, 20, 152, 1, 19,
+            144, 1, 18, 136, 1, 17, 128, 1, 16, 120, 15, 112, 14, 104, 13, 96, 12, 88, 11, 80, 10,
+            72, 9, 64, 8, 56, 7, 48, 6, 40, 5, 32, 4, 24, 3, 16, 2, 8, 1,
+        ];
+        assert_eq!(decode(&bytes), (1..=32).collect::<Vec<_>>());
+
+        let mut expected = vec![0; 32];
+        expected[31] = 7;
+        assert_eq!(decode(&[128, 2, 7]), expected);
+    }
+
+    // ```proto3
+    // message Oneof32Test {
+    //   oneof test_oneof {
+    //     uint32 f1 = 1;
+    //     ...
+    //     uint32 f32 = 32;
+    //   }
+    // }
+    // ```
+    type Oneof32TestEncoder = MessageEncoder<
+        Optional<
+            Oneof<(
+                FieldEncoder<F1, Uint32Encoder>,
+                FieldEncoder<F2, Uint32Encoder>,
+                FieldEncoder<F3, Uint32Encoder>,
+                FieldEncoder<F4, Uint32Encoder>,
+                FieldEncoder<F5, Uint32Encoder>,
+                FieldEncoder<F6, Uint32Encoder>,
+                FieldEncoder<F7, Uint32Encoder>,
+                FieldEncoder<F8, Uint32Encoder>,
+                FieldEncoder<F9, Uint32Encoder>,
+                FieldEncoder<F10, Uint32Encoder>,
+                FieldEncoder<F11, Uint32Encoder>,
+                FieldEncoder<F12, Uint32Encoder>,
+                FieldEncoder<F13, Uint32Encoder>,
+                FieldEncoder<F14, Uint32Encoder>,
+                FieldEncoder<F15, Uint32Encoder>,
+                FieldEncoder<F16, Uint32Encoder>,
+                FieldEncoder<Fnum<17>, Uint32Encoder>,
+                FieldEncoder<Fnum<18>, Uint32Encoder>,
+                FieldEncoder<Fnum<19>, Uint32Encoder>,
+                FieldEncoder<Fnum<20>, Uint32Encoder>,
+                FieldEncoder<Fnum<21>, Uint32Encoder>,
+                FieldEncoder<Fnum<22>, Uint32Encoder>,
+                FieldEncoder<Fnum<23>, Uint32Encoder>,
+                FieldEncoder<Fnum<24>, Uint32Encoder>,
+                FieldEncoder<Fnum<25>, Uint32Encoder>,
+                FieldEncoder<Fnum<26>, Uint32Encoder>,
+                FieldEncoder<Fnum<27>, Uint32Encoder>,
+                FieldEncoder<Fnum<28>, Uint32Encoder>,
+                FieldEncoder<Fnum<29>, Uint32Encoder>,
+                FieldEncoder<Fnum<30>, Uint32Encoder>,
+                FieldEncoder<Fnum<31>, Uint32Encoder>,
+                FieldEncoder<Fnum<32>, Uint32Encoder>,
+            )>,
+        >,
+    >;
+    type Oneof32 = Branch32<
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+    >;
+    type Oneof32TestDecoder = MessageDecoder<
+        Optional<
+            Oneof<(
+                FieldDecoder<F1, Uint32Decoder>,
+                FieldDecoder<F2, Uint32Decoder>,
+                FieldDecoder<F3, Uint32Decoder>,
+                FieldDecoder<F4, Uint32Decoder>,
+                FieldDecoder<F5, Uint32Decoder>,
+                FieldDecoder<F6, Uint32Decoder>,
+                FieldDecoder<F7, Uint32Decoder>,
+                FieldDecoder<F8, Uint32Decoder>,
+                FieldDecoder<F9, Uint32Decoder>,
+                FieldDecoder<F10, Uint32Decoder>,
+                FieldDecoder<F11, Uint32Decoder>,
+                FieldDecoder<F12, Uint32Decoder>,
+                FieldDecoder<F13, Uint32Decoder>,
+                FieldDecoder<F14, Uint32Decoder>,
+                FieldDecoder<F15, Uint32Decoder>,
+                FieldDecoder<F16, Uint32Decoder>,
+                FieldDecoder<Fnum<17>, Uint32Decoder>,
+                FieldDecoder<Fnum<18>, Uint32Decoder>,
+                FieldDecoder<Fnum<19>, Uint32Decoder>,
+                FieldDecoder<Fnum<20>, Uint32Decoder>,
+                FieldDecoder<Fnum<21>, Uint32Decoder>,
+                FieldDecoder<Fnum<22>, Uint32Decoder>,
+                FieldDecoder<Fnum<23>, Uint32Decoder>,
+                FieldDecoder<Fnum<24>, Uint32Decoder>,
+                FieldDecoder<Fnum<25>, Uint32Decoder>,
+                FieldDecoder<Fnum<26>, Uint32Decoder>,
+                FieldDecoder<Fnum<27>, Uint32Decoder>,
+                FieldDecoder<Fnum<28>, Uint32Decoder>,
+                FieldDecoder<Fnum<29>, Uint32Decoder>,
+                FieldDecoder<Fnum<30>, Uint32Decoder>,
+                FieldDecoder<Fnum<31>, Uint32Decoder>,
+                FieldDecoder<Fnum<32>, Uint32Decoder>,
+            )>,
+        >,
+    >;
+
+    #[test]
+    fn oneof32_test_works() {
+        let cases: Vec<(Oneof32, Vec<u8>)> = vec![
+            (Branch32::A(1), vec![8, 1]),
+            (Branch32::B(2), vec![16, 2]),
+            (Branch32::C(3), vec![24, 3]),
+            (Branch32::D(4), vec![32, 4]),
+            (Branch32::E(5), vec![40, 5]),
+            (Branch32::F(6), vec![48, 6]),
+            (Branch32::G(7), vec![56, 7]),
+            (Branch32::H(8), vec![64, 8]),
+            (Branch32::I(9), vec![72, 9]),
+            (Branch32::J(10), vec![80, 10]),
+            (Branch32::K(11), vec![88, 11]),
+            (Branch32::L(12), vec![96, 12]),
+            (Branch32::M(13), vec![104, 13]),
+            (Branch32::N(14), vec![112, 14]),
+            (Branch32::O(15), vec![120, 15]),
+            (Branch32::P(16), vec![128, 1, 16]),
+            (Branch32::Q(17), vec![136, 1, 17]),
+            (Branch32::R(18), vec![144, 1, 18]),
+            (Branch32::S(19), vec![152, 1, 19]),
+            (Branch32::T(20), vec![160, 1, 20]),
+            (Branch32::U(21), vec![168, 1, 21]),
+            (Branch32::V(22), vec![176, 1, 22]),
+            (Branch32::W(23), vec![184, 1, 23]),
+            (Branch32::X(24), vec![192, 1, 24]),
+            (Branch32::Y(25), vec![200, 1, 25]),
+            (Branch32::Z(26), vec![208, 1, 26]),
+            (Branch32::AA(27), vec![216, 1, 27]),
+            (Branch32::AB(28), vec![224, 1, 28]),
+            (Branch32::AC(29), vec![232, 1, 29]),
+            (Branch32::AD(30), vec![240, 1, 30]),
+            (Branch32::AE(31), vec![248, 1, 31]),
+            (Branch32::AF(32), vec![128, 2, 32]),
+        ];
+        for (value, bytes) in cases {
+            assert_encode!(Oneof32TestEncoder, Some(value), &bytes[..]);
+            assert_decode!(Oneof32TestDecoder, Some(value), &bytes[..]);
+        }
+        assert_encode!(Oneof32TestEncoder, None, []);
+        assert_decode!(Oneof32TestDecoder, None, []);
+
+        // The last field wins
+        assert_decode!(Oneof32TestDecoder, Some(Branch32::A(1)), [128, 2, 32, 8, 1]);
+    }
+
+    #[test]
+    fn fields_debug_works() {
+        mod derived {
+            #[derive(Debug)]
+            #[allow(dead_code)]
+            pub struct Fields<F> {
+                pub fields: F,
+                pub index: usize,
+            }
+        }
+        macro_rules! assert_debug {
+            ($fields:expr) => {
+                let expected = derived::Fields {
+                    fields: $fields,
+                    index: 0,
+                };
+                let actual = Fields::new($fields);
+                assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+                assert_eq!(format!("{:#?}", actual), format!("{:#?}", expected));
+            };
+        }
+        assert_debug!(());
+        assert_debug!((F1,));
+        assert_debug!((F1, F2));
+        assert_debug!((F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12));
+    }
+
+    // ```proto3
+    // message MergeTest {
+    //   int32 n = 1;
+    //   Inner inner = 2;
+    // }
+    //
+    // message Inner {
+    //   string name = 1;
+    //   repeated uint32 values = 2;
+    //   SearchRequest request = 3;
+    // }
+    // ```
+    type MergeTestDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, Int32Decoder>>,
+            Optional<MessageFieldDecoder<F2, InnerDecoder>>,
+        )>,
+    >;
+    type InnerDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+            Repeated<FieldDecoder<F2, Uint32Decoder>, Vec<u32>>,
+            MessageFieldDecoder<F3, SearchRequestDecoder>,
+        )>,
+    >;
+
+    #[test]
+    fn merge_test_decoder_works() {
+        // The last value of a scalar field wins
+        assert_decode!(MergeTestDecoder, (3, None), [8, 1, 8, 2, 8, 3]);
+
+        // Embedded messages are merged
+        assert_decode!(
+            MergeTestDecoder,
+            (2, Some((s("bar"), vec![1, 2, 3], (s("foo"), 4, 10)))),
+            [
+                8, 1, // n=1
+                18, 14, // inner
+                10, 3, 102, 111, 111, // name="foo"
+                16, 1, // values=[1]
+                26, 5, 10, 3, 102, 111, 111, // request.query="foo"
+                8, 2, // n=2
+                18, 15, // inner
+                10, 3, 98, 97, 114, // name="bar"
+                16, 2, 16, 3, // values=[2, 3]
+                26, 4, 16, 3, 24, 10, // request.page_number=3, request.result_per_page=10
+                18, 4, // inner
+                26, 2, 16, 4 // request.page_number=4
+            ]
+        );
+
+        // An empty instance does not clear the previous one
+        assert_decode!(
+            MergeTestDecoder,
+            (0, Some((s(""), vec![7], (s(""), 0, 0)))),
+            [18, 4, 16, 7, 26, 0, 18, 0]
+        );
+    }
+
+    #[test]
+    fn merge_oneof_test_decoder_works() {
+        assert_decode!(
+            OneofTestDecoder,
+            Some(Branch2::B((s("bar"), 3, 10))),
+            [
+                50, 7, 10, 3, 98, 97, 114, 16, 3, // B(("bar", 3, 0))
+                50, 2, 24, 10, // B(("", 0, 10))
+            ]
+        );
+    }
+
     // ```proto3
     // message EmptyRepeatedTest {
     //   repeated string names = 1;
@@ -396,12 +1031,12 @@ mod tests {
 
     fn seconds_decoder() -> impl MessageDecode<Item = Seconds> {
         let base = protobuf_message_decoder![(F1, Uint64Decoder::new())];
-        base.map(|x| Seconds(x))
+        base.map(Seconds)
     }
 
     fn seconds_encoder() -> impl SizedEncode<Item = Seconds> + MessageEncode<Item = Seconds> {
         let base = protobuf_message_encoder![(F1, Uint64Encoder::new())];
-        base.map_from(|x: Seconds| (x.0))
+        base.map_from(|x: Seconds| x.0)
     }
 
     #[test]
@@ -0,0 +1,679 @@
+//! Limits for decoding untrusted inputs.
+//!
+//! Decoders trust the lengths, counts and nesting found in an input stream.
+//! To protect them from memory and stack exhaustion caused by malicious inputs,
+//! wrap the outermost decoder with [`LimitedDecoder`](./struct.LimitedDecoder.html).
+//! The limits are enforced by all the decoders in this crate (including `DynamicMessageDecoder`)
+//! while they are driven by the `LimitedDecoder`.
+//!
+//! If a limit is exceeded, an `ErrorKind::InvalidInput` error caused by [`LimitExceeded`] is returned.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! use bytecodec::DecodeExt;
+//! use protobuf_codec::field::{Fields, FieldDecoder, MaybeDefault};
+//! use protobuf_codec::field::num::F1;
+//! use protobuf_codec::limit::{DecodeLimits, LimitExceeded, LimitedDecoder};
+//! use protobuf_codec::message::MessageDecoder;
+//! use protobuf_codec::scalar::StringDecoder;
+//!
+//! # fn main() {
+//! let limits = DecodeLimits {
+//!     max_length: Some(3),
+//!     ..DecodeLimits::default()
+//! };
+//! let decoder: MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, StringDecoder>>,)>> =
+//!     Default::default();
+//! let mut decoder = LimitedDecoder::new(decoder, limits);
+//!
+//! let item = decoder.decode_from_bytes(&[10, 3, 102, 111, 111]).unwrap();
+//! assert_eq!(item, ("foo".to_owned(),));
+//!
+//! let error = decoder.decode_from_bytes(&[10, 4, 102, 111, 111, 111]).err().unwrap();
+//! assert_eq!(LimitExceeded::from_error(&error), Some(LimitExceeded::Length));
+//! # }
+//! ```
+//!
+//! [`LimitExceeded`]: ./enum.LimitExceeded.html
+use crate::message::MessageDecode;
+use bytecodec::{ByteCount, Decode, Eos, Error, ErrorKind, Result};
+use std::cell::Cell;
+use std::cmp;
+use std::fmt;
+use trackable::error::ErrorKindExt;
+
+/// The default value of `DecodeLimits::max_total_bytes` (64 MiB).
+pub const DEFAULT_MAX_TOTAL_BYTES: u64 = 64 * 1024 * 1024;
+
+/// The default value of `DecodeLimits::max_depth`.
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
+/// Limits enforced while decoding.
+///
+/// `None` means that the corresponding quantity is not limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodeLimits {
+    /// Maximum number of bytes consumed by the outermost decoder.
+    ///
+    /// This also bounds the lengths of length-delimited values.
+    ///
+    /// The default value is `Some(DEFAULT_MAX_TOTAL_BYTES)`.
+    pub max_total_bytes: Option<u64>,
+
+    /// Maximum length of a length-delimited value (e.g., strings, bytes and embedded messages).
+    ///
+    /// The default value is `None`.
+    pub max_length: Option<u64>,
+
+    /// Maximum nesting depth of embedded messages and groups.
+    ///
+    /// The fields of the outermost message are at depth `0`.
+    ///
+    /// The default value is `Some(DEFAULT_MAX_DEPTH)`.
+    pub max_depth: Option<usize>,
+
+    /// Maximum number of elements of a repeated field.
+    ///
+    /// The default value is `None`.
+    pub max_repeated: Option<usize>,
+
+    /// Maximum number of entries of a map field.
+    ///
+    /// The default value is `None`.
+    pub max_map_entries: Option<usize>,
+}
+impl DecodeLimits {
+    /// Returns a `DecodeLimits` instance that does not limit anything.
+    pub fn unlimited() -> Self {
+        DecodeLimits {
+            max_total_bytes: None,
+            max_length: None,
+            max_depth: None,
+            max_repeated: None,
+            max_map_entries: None,
+        }
+    }
+}
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_total_bytes: Some(DEFAULT_MAX_TOTAL_BYTES),
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            ..Self::unlimited()
+        }
+    }
+}
+
+/// The cause of the errors returned when a limit in `DecodeLimits` is exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitExceeded {
+    /// `DecodeLimits::max_total_bytes` is exceeded.
+    TotalBytes,
+
+    /// `DecodeLimits::max_length` is exceeded.
+    Length,
+
+    /// `DecodeLimits::max_depth` is exceeded.
+    Depth,
+
+    /// `DecodeLimits::max_repeated` is exceeded.
+    Repeated,
+
+    /// `DecodeLimits::max_map_entries` is exceeded.
+    MapEntries,
+}
+impl LimitExceeded {
+    /// Returns the limit that caused `error`, or `None` if it was not caused by any limit.
+    pub fn from_error(error: &Error) -> Option<Self> {
+        error.concrete_cause::<LimitExceeded>().copied()
+    }
+}
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LimitExceeded::TotalBytes => write!(f, "Too many bytes"),
+            LimitExceeded::Length => write!(f, "Too long length-delimited value"),
+            LimitExceeded::Depth => write!(f, "Too deep nesting"),
+            LimitExceeded::Repeated => write!(f, "Too many repeated elements"),
+            LimitExceeded::MapEntries => write!(f, "Too many map entries"),
+        }
+    }
+}
+impl std::error::Error for LimitExceeded {}
+
+/// Decoder that enforces `DecodeLimits` on the inner decoder.
+#[derive(Debug, Default)]
+pub struct LimitedDecoder<D> {
+    inner: D,
+    limits: DecodeLimits,
+    consumed_bytes: u64,
+}
+impl<D: Decode> LimitedDecoder<D> {
+    /// Makes a new `LimitedDecoder` instance.
+    pub fn new(inner: D, limits: DecodeLimits) -> Self {
+        LimitedDecoder {
+            inner,
+            limits,
+            consumed_bytes: 0,
+        }
+    }
+
+    /// Returns the limits enforced by the decoder.
+    pub fn limits(&self) -> &DecodeLimits {
+        &self.limits
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of the instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+impl<D: Decode> Decode for LimitedDecoder<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, mut buf: &[u8], mut eos: Eos) -> Result<usize> {
+        let remaining_bytes = self
+            .limits
+            .max_total_bytes
+            .map(|max| max.saturating_sub(self.consumed_bytes));
+        let mut truncated = false;
+        if let Some(remaining) = remaining_bytes {
+            if let Some(n) = eos.remaining_bytes().to_u64() {
+                if !self.inner.is_idle() && buf.len() as u64 + n > remaining {
+                    return Err(limit_error(LimitExceeded::TotalBytes));
+                }
+            }
+            if buf.len() as u64 > remaining {
+                buf = &buf[..remaining as usize];
+                eos = Eos::new(false);
+                truncated = true;
+            }
+        }
+
+        let state = State {
+            limits: self.limits,
+            remaining_bytes,
+            depth: 0,
+            own_depth_limit: false,
+        };
+        let size = {
+            let _scope = Scope::enter(state);
+            track!(self.inner.decode(buf, eos))?
+        };
+        self.consumed_bytes += size as u64;
+        if truncated && size == buf.len() && !self.inner.is_idle() {
+            return Err(limit_error(LimitExceeded::TotalBytes));
+        }
+        Ok(size)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track!(self.inner.finish_decoding())?;
+        self.consumed_bytes = 0;
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl<D: MessageDecode> MessageDecode for LimitedDecoder<D> {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
+
+thread_local! {
+    static STATE: Cell<Option<State>> = const { Cell::new(None) };
+}
+
+// The limits of the `LimitedDecoder` that is running on the current thread
+#[derive(Debug, Clone, Copy)]
+struct State {
+    limits: DecodeLimits,
+    remaining_bytes: Option<u64>,
+    depth: usize,
+
+    // Whether a decoder has already applied its own depth limit by `with_max_depth`
+    own_depth_limit: bool,
+}
+
+#[derive(Debug)]
+struct Scope {
+    prev: Option<State>,
+}
+impl Scope {
+    fn enter(state: State) -> Self {
+        let prev = STATE.with(|s| s.replace(Some(state)));
+        Scope { prev }
+    }
+}
+impl Drop for Scope {
+    fn drop(&mut self) {
+        STATE.with(|s| s.set(self.prev));
+    }
+}
+
+/// Guard that represents a level of nesting.
+///
+/// The depth is decremented when this is dropped.
+#[derive(Debug)]
+pub(crate) struct Nested(());
+impl Drop for Nested {
+    fn drop(&mut self) {
+        STATE.with(|s| {
+            if let Some(mut state) = s.get() {
+                state.depth -= 1;
+                s.set(Some(state));
+            }
+        });
+    }
+}
+
+/// Increments the nesting depth until the returned guard is dropped.
+pub(crate) fn enter_nested() -> Result<Nested> {
+    STATE.with(|s| {
+        if let Some(mut state) = s.get() {
+            if state.limits.max_depth.is_some_and(|max| state.depth >= max) {
+                return Err(limit_error(LimitExceeded::Depth));
+            }
+            state.depth += 1;
+            s.set(Some(state));
+        }
+        Ok(Nested(()))
+    })
+}
+
+/// Runs `f` with the nesting depth limited to `max_depth` levels below the current depth.
+///
+/// This is used by the decoders that limit the depth by themselves even if they are not
+/// driven by a `LimitedDecoder`. The limit of an enclosing `LimitedDecoder` is still enforced,
+/// and only the limit of the outermost of such decoders is applied.
+pub(crate) fn with_max_depth<T, F>(max_depth: Option<usize>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let max_depth = match max_depth {
+        Some(max) => max,
+        None => return f(),
+    };
+    let state = match STATE.with(|s| s.get()) {
+        Some(state) if state.own_depth_limit => return f(),
+        Some(mut state) => {
+            let max = state.depth.saturating_add(max_depth);
+            state.limits.max_depth = Some(state.limits.max_depth.map_or(max, |m| cmp::min(m, max)));
+            state.own_depth_limit = true;
+            state
+        }
+        None => State {
+            limits: DecodeLimits {
+                max_depth: Some(max_depth),
+                ..DecodeLimits::unlimited()
+            },
+            remaining_bytes: None,
+            depth: 0,
+            own_depth_limit: true,
+        },
+    };
+    let _scope = Scope::enter(state);
+    f()
+}
+
+/// Checks the announced length of a length-delimited value.
+pub(crate) fn check_length(len: u64) -> Result<()> {
+    check(|state| {
+        if state.limits.max_length.is_some_and(|max| len > max) {
+            Some(LimitExceeded::Length)
+        } else if state.remaining_bytes.is_some_and(|max| len > max) {
+            Some(LimitExceeded::TotalBytes)
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the maximum length of a length-delimited value allowed at the current position.
+///
+/// This is `None` if the length is not limited.
+pub(crate) fn max_length() -> Option<u64> {
+    let state = STATE.with(|s| s.get())?;
+    match (state.limits.max_length, state.remaining_bytes) {
+        (Some(a), Some(b)) => Some(cmp::min(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Checks the number of the decoded elements of a repeated field.
+pub(crate) fn check_repeated(count: usize) -> Result<()> {
+    check(|state| {
+        if state.limits.max_repeated.is_some_and(|max| count > max) {
+            Some(LimitExceeded::Repeated)
+        } else {
+            None
+        }
+    })
+}
+
+/// Checks the number of the decoded entries of a map field.
+pub(crate) fn check_map_entries(count: usize) -> Result<()> {
+    check(|state| {
+        if state.limits.max_map_entries.is_some_and(|max| count > max) {
+            Some(LimitExceeded::MapEntries)
+        } else {
+            None
+        }
+    })
+}
+
+fn check<F>(f: F) -> Result<()>
+where
+    F: FnOnce(&State) -> Option<LimitExceeded>,
+{
+    match STATE.with(|s| s.get()).as_ref().and_then(f) {
+        Some(e) => Err(limit_error(e)),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn limit_error(e: LimitExceeded) -> Error {
+    track!(Error::from(ErrorKind::InvalidInput.cause(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::{DescriptorPool, DynamicMessageDecoder};
+    use crate::field::num::F1;
+    use crate::field::{
+        FieldDecoder, Fields, MapFieldDecoder, MaybeDefault, MessageFieldDecoder, Optional,
+        PackedFieldDecoder, Repeated,
+    };
+    use crate::message::MessageDecoder;
+    use crate::scalar::{BytesDecoder, Int32Decoder, StringDecoder};
+    use crate::schema;
+    use bytecodec::DecodeExt;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn limited<D: Decode>(decoder: D, f: impl FnOnce(&mut DecodeLimits)) -> LimitedDecoder<D> {
+        let mut limits = DecodeLimits::unlimited();
+        f(&mut limits);
+        LimitedDecoder::new(decoder, limits)
+    }
+
+    fn exceeded<T>(result: Result<T>) -> Option<LimitExceeded> {
+        result.err().and_then(|e| LimitExceeded::from_error(&e))
+    }
+
+    type BytesMessageDecoder =
+        MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, BytesDecoder>>,)>>;
+
+    #[test]
+    fn length_limit_works() {
+        let input = [10, 3, 1, 2, 3];
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| l.max_length = Some(3));
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_from_bytes(&input)),
+            (vec![1, 2, 3],)
+        );
+
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| l.max_length = Some(2));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&input)),
+            Some(LimitExceeded::Length)
+        );
+
+        // The announced length is checked before the payload arrives
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| {
+            l.max_length = Some(1024)
+        });
+        let huge = [10, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        assert_eq!(
+            exceeded(decoder.decode(&huge, Eos::new(false))),
+            Some(LimitExceeded::Length)
+        );
+
+        // Unknown fields are also checked
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| l.max_length = Some(2));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&[18, 3, 1, 2, 3])),
+            Some(LimitExceeded::Length)
+        );
+    }
+
+    #[test]
+    fn total_bytes_limit_works() {
+        let input = [10, 3, 1, 2, 3];
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| {
+            l.max_total_bytes = Some(5)
+        });
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_from_bytes(&input)),
+            (vec![1, 2, 3],)
+        );
+
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| {
+            l.max_total_bytes = Some(4)
+        });
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&input)),
+            Some(LimitExceeded::TotalBytes)
+        );
+
+        // Streaming input
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| {
+            l.max_total_bytes = Some(4)
+        });
+        assert_eq!(
+            track_try_unwrap!(decoder.decode(&input[..2], Eos::new(false))),
+            2
+        );
+        assert_eq!(
+            exceeded(decoder.decode(&input[2..], Eos::new(false))),
+            Some(LimitExceeded::TotalBytes)
+        );
+
+        // Length-delimited values that cannot fit in the rest of the budget
+        let mut decoder = limited(BytesMessageDecoder::default(), |l| {
+            l.max_total_bytes = Some(1024)
+        });
+        let huge = [10, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        assert_eq!(
+            exceeded(decoder.decode(&huge, Eos::new(false))),
+            Some(LimitExceeded::TotalBytes)
+        );
+    }
+
+    #[test]
+    fn depth_limit_works() {
+        type Inner = MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, Int32Decoder>>,)>>;
+        type Middle = MessageDecoder<Fields<(Optional<MessageFieldDecoder<F1, Inner>>,)>>;
+        type Outer = MessageDecoder<Fields<(Optional<MessageFieldDecoder<F1, Middle>>,)>>;
+
+        let input = [10, 4, 10, 2, 8, 1];
+        let mut decoder = limited(Outer::default(), |l| l.max_depth = Some(2));
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_from_bytes(&input)),
+            (Some((Some((1,)),)),)
+        );
+
+        let mut decoder = limited(Outer::default(), |l| l.max_depth = Some(1));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&input)),
+            Some(LimitExceeded::Depth)
+        );
+
+        // The limit is kept across the `decode` calls
+        let mut decoder = limited(Outer::default(), |l| l.max_depth = Some(1));
+        track_try_unwrap!(decoder.decode(&input[..3], Eos::new(false)));
+        assert_eq!(
+            exceeded(decoder.decode(&input[3..], Eos::new(true))),
+            Some(LimitExceeded::Depth)
+        );
+    }
+
+    #[test]
+    fn dynamic_depth_limit_works() {
+        let file = track_try_unwrap!(schema::parse(
+            "syntax = \"proto3\"; message Node { Node child = 1; }"
+        ));
+        let pool = Arc::new(track_try_unwrap!(DescriptorPool::from_files(&[file])));
+
+        let nested = |depth| {
+            let mut bytes = Vec::new();
+            for _ in 0..depth {
+                let mut outer = vec![10];
+                crate::wire::write_varint(&mut outer, bytes.len() as u64);
+                outer.extend_from_slice(&bytes);
+                bytes = outer;
+            }
+            bytes
+        };
+
+        let decoder = track_try_unwrap!(DynamicMessageDecoder::new(pool.clone(), "Node"));
+        let mut decoder = LimitedDecoder::new(decoder, DecodeLimits::default());
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&nested(10_000))),
+            Some(LimitExceeded::Depth)
+        );
+
+        let decoder = track_try_unwrap!(DynamicMessageDecoder::new(pool.clone(), "Node"));
+        let mut decoder = limited(decoder, |l| l.max_depth = Some(10));
+        assert!(decoder.decode_from_bytes(&nested(10)).is_ok());
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&nested(11))),
+            Some(LimitExceeded::Depth)
+        );
+
+        // The decoder has its own limit
+        let decoder = || track_try_unwrap!(DynamicMessageDecoder::new(pool.clone(), "Node"));
+        assert_eq!(decoder().max_depth(), Some(DEFAULT_MAX_DEPTH));
+        assert!(decoder()
+            .decode_from_bytes(&nested(DEFAULT_MAX_DEPTH))
+            .is_ok());
+        assert_eq!(
+            exceeded(decoder().decode_from_bytes(&nested(10_000))),
+            Some(LimitExceeded::Depth)
+        );
+
+        let mut d = decoder();
+        d.set_max_depth(Some(3));
+        assert!(d.decode_from_bytes(&nested(3)).is_ok());
+        assert_eq!(
+            exceeded(d.decode_from_bytes(&nested(4))),
+            Some(LimitExceeded::Depth)
+        );
+
+        let mut d = decoder();
+        d.set_max_depth(None);
+        assert!(d.decode_from_bytes(&nested(200)).is_ok());
+    }
+
+    #[test]
+    fn repeated_limit_works() {
+        type D = MessageDecoder<Fields<(Repeated<FieldDecoder<F1, StringDecoder>, Vec<String>>,)>>;
+        type P = MessageDecoder<Fields<(PackedFieldDecoder<F1, Int32Decoder, Vec<i32>>,)>>;
+
+        let input = [10, 1, 97, 10, 1, 98, 10, 1, 99];
+        let mut decoder = limited(D::default(), |l| l.max_repeated = Some(3));
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_from_bytes(&input)).0.len(),
+            3
+        );
+
+        let mut decoder = limited(D::default(), |l| l.max_repeated = Some(2));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&input)),
+            Some(LimitExceeded::Repeated)
+        );
+
+        // Packed
+        let input = [10, 2, 1, 2, 10, 1, 3];
+        let mut decoder = limited(P::default(), |l| l.max_repeated = Some(3));
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_from_bytes(&input)),
+            (vec![1, 2, 3],)
+        );
+
+        let mut decoder = limited(P::default(), |l| l.max_repeated = Some(2));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&input)),
+            Some(LimitExceeded::Repeated)
+        );
+
+        // The count is reset for each message
+        let mut decoder = limited(D::default(), |l| l.max_repeated = Some(1));
+        for _ in 0..3 {
+            track_try_unwrap!(decoder.decode_from_bytes(&[10, 1, 97]));
+        }
+    }
+
+    #[test]
+    fn map_entries_limit_works() {
+        type D = MessageDecoder<
+            Fields<(MapFieldDecoder<F1, Int32Decoder, Int32Decoder, HashMap<i32, i32>>,)>,
+        >;
+
+        let input = [10, 4, 8, 1, 16, 2, 10, 4, 8, 3, 16, 4];
+        let mut decoder = limited(D::default(), |l| {
+            l.max_map_entries = Some(2);
+            l.max_repeated = Some(1);
+        });
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_from_bytes(&input)).0.len(),
+            2
+        );
+
+        let mut decoder = limited(D::default(), |l| l.max_map_entries = Some(1));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&input)),
+            Some(LimitExceeded::MapEntries)
+        );
+
+        // Dynamic messages
+        let file = track_try_unwrap!(schema::parse(
+            "syntax = \"proto3\"; message M { map<int32, int32> m = 1; repeated int32 r = 2; }"
+        ));
+        let pool = Arc::new(track_try_unwrap!(DescriptorPool::from_files(&[file])));
+        let decoder = track_try_unwrap!(DynamicMessageDecoder::new(pool.clone(), "M"));
+        let mut decoder = limited(decoder, |l| l.max_map_entries = Some(1));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&input)),
+            Some(LimitExceeded::MapEntries)
+        );
+
+        let decoder = track_try_unwrap!(DynamicMessageDecoder::new(pool, "M"));
+        let mut decoder = limited(decoder, |l| l.max_repeated = Some(2));
+        assert_eq!(
+            exceeded(decoder.decode_from_bytes(&[18, 3, 1, 2, 3])),
+            Some(LimitExceeded::Repeated)
+        );
+    }
+
+    #[test]
+    fn limits_are_not_enforced_outside_limited_decoder() {
+        let mut decoder = BytesMessageDecoder::default();
+        assert!(decoder.decode_from_bytes(&[10, 3, 1, 2, 3]).is_ok());
+
+        let mut limited = limited(BytesMessageDecoder::default(), |l| l.max_length = Some(1));
+        assert!(limited.decode_from_bytes(&[10, 3, 1, 2, 3]).is_err());
+        assert!(decoder.decode_from_bytes(&[10, 3, 1, 2, 3]).is_ok());
+    }
+}
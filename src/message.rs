@@ -1,18 +1,37 @@
 //! Encoders, decoders and traits for messages.
 use crate::field::{FieldDecode, FieldEncode, UnknownFieldDecoder};
+use crate::limit;
 use crate::value::{ValueDecode, ValueEncode};
 use crate::wire::{LengthDelimitedDecoder, LengthDelimitedEncoder, TagDecoder, WireType};
 use bytecodec::combinator::{Map, MapErr, MapFrom, PreEncode, TryMap, TryMapFrom};
 use bytecodec::{ByteCount, Decode, Encode, Eos, Error, ErrorKind, Result, SizedEncode};
-use std;
 
 /// This trait allows for decoding messages.
-pub trait MessageDecode: Decode {}
+pub trait MessageDecode: Decode {
+    /// Resumes decoding of the message that has been decoded but not finished yet.
+    ///
+    /// This is called when an embedded message field appears more than once in an input stream.
+    /// After the call, the subsequent input is merged into the message as described in [the guide]:
+    /// singular scalar fields are overwritten, repeated fields are appended and
+    /// singular embedded message fields are merged recursively.
+    ///
+    /// The default implementation discards the previous message,
+    /// so the last instance of the field takes precedence.
+    ///
+    /// [the guide]: https://developers.google.com/protocol-buffers/docs/encoding#optional
+    fn resume_decoding(&mut self) -> Result<()> {
+        let _ = track!(self.finish_decoding())?;
+        Ok(())
+    }
+}
 impl<M, T, F> MessageDecode for Map<M, T, F>
 where
     M: MessageDecode,
     F: Fn(M::Item) -> T,
 {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner_mut().resume_decoding())
+    }
 }
 impl<M, T, E, F> MessageDecode for TryMap<M, T, E, F>
 where
@@ -20,6 +39,9 @@ where
     F: Fn(M::Item) -> std::result::Result<T, E>,
     Error: From<E>,
 {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner_mut().resume_decoding())
+    }
 }
 impl<M, E, F> MessageDecode for MapErr<M, E, F>
 where
@@ -27,6 +49,9 @@ where
     F: Fn(Error) -> E,
     Error: From<E>,
 {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner_mut().resume_decoding())
+    }
 }
 
 /// This trait allows for encoding messages.
@@ -54,6 +79,11 @@ where
 }
 
 /// Decoder for messages.
+///
+/// If a singular field appears more than once in an input stream,
+/// the last value is used for scalar fields and the instances are merged for embedded message fields.
+///
+/// Unknown fields are skipped unless `UnknownFieldsDecoder` is included in `F`.
 #[derive(Debug, Default)]
 pub struct MessageDecoder<F> {
     tag: TagDecoder,
@@ -150,20 +180,22 @@ impl<F: FieldDecode> Decode for MessageDecoder<F> {
         self.eos
     }
 }
-impl<F: FieldDecode> MessageDecode for MessageDecoder<F> {}
+impl<F: FieldDecode> MessageDecode for MessageDecoder<F> {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::IncompleteDecoding; self.target, self.started);
+        self.eos = false;
+        Ok(())
+    }
+}
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq)]
 enum DecodeTarget {
+    #[default]
     None,
     Tag,
     KnownField,
     UnknownField,
 }
-impl Default for DecodeTarget {
-    fn default() -> Self {
-        DecodeTarget::None
-    }
-}
 
 /// Decoder for embedded messages.
 #[derive(Debug, Default)]
@@ -173,11 +205,18 @@ impl<M: MessageDecode> EmbeddedMessageDecoder<M> {
     pub(crate) fn new(message_decoder: M) -> Self {
         EmbeddedMessageDecoder(LengthDelimitedDecoder::new(message_decoder))
     }
+
+    /// Prepares for merging the next instance of the embedded message into the current one.
+    pub(crate) fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.0.restart_length())?;
+        track!(self.0.inner_mut().resume_decoding())
+    }
 }
 impl<M: MessageDecode> Decode for EmbeddedMessageDecoder<M> {
     type Item = M::Item;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let _nested = track!(limit::enter_nested())?;
         track!(self.0.decode(buf, eos))
     }
 
@@ -238,6 +277,103 @@ impl<F: FieldEncode + SizedEncode> SizedEncode for MessageEncoder<F> {
 }
 impl<F: FieldEncode> MessageEncode for MessageEncoder<F> {}
 
+/// Decoder for messages that allocates the inner decoder on the heap when it is first used.
+///
+/// This is used for decoding recursive message types
+/// (e.g., `message Node { Node child = 1; }`) whose decoders cannot contain themselves directly.
+#[derive(Debug)]
+pub struct BoxedMessageDecoder<M>(Option<Box<M>>);
+impl<M: MessageDecode + Default> BoxedMessageDecoder<M> {
+    /// Makes a new `BoxedMessageDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn inner_mut(&mut self) -> &mut M {
+        self.0.get_or_insert_with(Box::default)
+    }
+}
+impl<M> Default for BoxedMessageDecoder<M> {
+    fn default() -> Self {
+        BoxedMessageDecoder(None)
+    }
+}
+impl<M: MessageDecode + Default> Decode for BoxedMessageDecoder<M> {
+    type Item = M::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner_mut().decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track!(self.inner_mut().finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0
+            .as_ref()
+            .map_or(ByteCount::Unknown, |m| m.requiring_bytes())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.as_ref().is_some_and(|m| m.is_idle())
+    }
+}
+impl<M: MessageDecode + Default> MessageDecode for BoxedMessageDecoder<M> {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner_mut().resume_decoding())
+    }
+}
+
+/// Encoder for messages that allocates the inner encoder on the heap when it is first used.
+///
+/// This is the encoding counterpart of `BoxedMessageDecoder`.
+#[derive(Debug)]
+pub struct BoxedMessageEncoder<M>(Option<Box<M>>);
+impl<M: MessageEncode + Default> BoxedMessageEncoder<M> {
+    /// Makes a new `BoxedMessageEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl<M> Default for BoxedMessageEncoder<M> {
+    fn default() -> Self {
+        BoxedMessageEncoder(None)
+    }
+}
+impl<M: MessageEncode + Default> Encode for BoxedMessageEncoder<M> {
+    type Item = M::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        if let Some(ref mut m) = self.0 {
+            track!(m.encode(buf, eos))
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let m = self.0.get_or_insert_with(Box::default);
+        track!(m.start_encoding(item))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.as_ref().is_none_or(|m| m.is_idle())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0
+            .as_ref()
+            .map_or(ByteCount::Finite(0), |m| m.requiring_bytes())
+    }
+}
+impl<M: MessageEncode + SizedEncode + Default> SizedEncode for BoxedMessageEncoder<M> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.as_ref().map_or(0, |m| m.exact_requiring_bytes())
+    }
+}
+impl<M: MessageEncode + Default> MessageEncode for BoxedMessageEncoder<M> {}
+
 /// Encoder for embedded messages.
 #[derive(Debug, Default)]
 pub(crate) struct EmbeddedMessageEncoder<M> {
@@ -1,87 +1,178 @@
-#![allow(clippy::single_match, clippy::block_in_if_condition_stmt)]
+#![allow(clippy::single_match, clippy::blocks_in_conditions)]
 use crate::field::{FieldDecode, FieldEncode, RequiredFieldDecode, RequiredFieldEncode};
+use crate::fields::DebugTuple;
 use crate::wire::Tag;
 use bytecodec::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
+use std::fmt;
 
-/// Value of `Oneof` that has 2-fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[allow(missing_docs)]
-pub enum Branch2<A, B> {
-    A(A),
-    B(B),
-}
-
-/// Value of `Oneof` that has 3-fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[allow(missing_docs)]
-pub enum Branch3<A, B, C> {
-    A(A),
-    B(B),
-    C(C),
-}
-
-/// Value of `Oneof` that has 4-fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[allow(missing_docs)]
-pub enum Branch4<A, B, C, D> {
-    A(A),
-    B(B),
-    C(C),
-    D(D),
-}
-
-/// Value of `Oneof` that has 5-fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[allow(missing_docs)]
-pub enum Branch5<A, B, C, D, E> {
-    A(A),
-    B(B),
-    C(C),
-    D(D),
-    E(E),
-}
-
-/// Value of `Oneof` that has 6-fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[allow(missing_docs)]
-pub enum Branch6<A, B, C, D, E, F> {
-    A(A),
-    B(B),
-    C(C),
-    D(D),
-    E(E),
-    F(F),
+macro_rules! define_branch {
+    ($name:ident, $doc:expr, [$($f:ident),*]) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[allow(missing_docs)]
+        pub enum $name<$($f),*> {
+            $($f($f)),*
+        }
+    };
 }
 
-/// Value of `Oneof` that has 7-fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[allow(missing_docs)]
-pub enum Branch7<A, B, C, D, E, F, G> {
-    A(A),
-    B(B),
-    C(C),
-    D(D),
-    E(E),
-    F(F),
-    G(G),
-}
-
-/// Value of `Oneof` that has 8-fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[allow(missing_docs)]
-pub enum Branch8<A, B, C, D, E, F, G, H> {
-    A(A),
-    B(B),
-    C(C),
-    D(D),
-    E(E),
-    F(F),
-    G(G),
-    H(H),
-}
+define_branch!(Branch2, "Value of `Oneof` that has 2-fields.", [A, B]);
+define_branch!(Branch3, "Value of `Oneof` that has 3-fields.", [A, B, C]);
+define_branch!(Branch4, "Value of `Oneof` that has 4-fields.", [A, B, C, D]);
+define_branch!(
+    Branch5,
+    "Value of `Oneof` that has 5-fields.",
+    [A, B, C, D, E]
+);
+define_branch!(
+    Branch6,
+    "Value of `Oneof` that has 6-fields.",
+    [A, B, C, D, E, F]
+);
+define_branch!(
+    Branch7,
+    "Value of `Oneof` that has 7-fields.",
+    [A, B, C, D, E, F, G]
+);
+define_branch!(
+    Branch8,
+    "Value of `Oneof` that has 8-fields.",
+    [A, B, C, D, E, F, G, H]
+);
+define_branch!(
+    Branch9,
+    "Value of `Oneof` that has 9-fields.",
+    [A, B, C, D, E, F, G, H, I]
+);
+define_branch!(
+    Branch10,
+    "Value of `Oneof` that has 10-fields.",
+    [A, B, C, D, E, F, G, H, I, J]
+);
+define_branch!(
+    Branch11,
+    "Value of `Oneof` that has 11-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K]
+);
+define_branch!(
+    Branch12,
+    "Value of `Oneof` that has 12-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L]
+);
+define_branch!(
+    Branch13,
+    "Value of `Oneof` that has 13-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M]
+);
+define_branch!(
+    Branch14,
+    "Value of `Oneof` that has 14-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N]
+);
+define_branch!(
+    Branch15,
+    "Value of `Oneof` that has 15-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O]
+);
+define_branch!(
+    Branch16,
+    "Value of `Oneof` that has 16-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P]
+);
+define_branch!(
+    Branch17,
+    "Value of `Oneof` that has 17-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q]
+);
+define_branch!(
+    Branch18,
+    "Value of `Oneof` that has 18-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R]
+);
+define_branch!(
+    Branch19,
+    "Value of `Oneof` that has 19-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S]
+);
+define_branch!(
+    Branch20,
+    "Value of `Oneof` that has 20-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T]
+);
+define_branch!(
+    Branch21,
+    "Value of `Oneof` that has 21-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U]
+);
+define_branch!(
+    Branch22,
+    "Value of `Oneof` that has 22-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V]
+);
+define_branch!(
+    Branch23,
+    "Value of `Oneof` that has 23-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W]
+);
+define_branch!(
+    Branch24,
+    "Value of `Oneof` that has 24-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X]
+);
+define_branch!(
+    Branch25,
+    "Value of `Oneof` that has 25-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y]
+);
+define_branch!(
+    Branch26,
+    "Value of `Oneof` that has 26-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z]
+);
+define_branch!(
+    Branch27,
+    "Value of `Oneof` that has 27-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA]
+);
+define_branch!(
+    Branch28,
+    "Value of `Oneof` that has 28-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB]
+);
+define_branch!(
+    Branch29,
+    "Value of `Oneof` that has 29-fields.",
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC]
+);
+define_branch!(
+    Branch30,
+    "Value of `Oneof` that has 30-fields.",
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD
+    ]
+);
+define_branch!(
+    Branch31,
+    "Value of `Oneof` that has 31-fields.",
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE
+    ]
+);
+define_branch!(
+    Branch32,
+    "Value of `Oneof` that has 32-fields.",
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE, AF
+    ]
+);
 
 /// Decoder and encoder for `Oneof` fields.
-#[derive(Debug, Default)]
+///
+/// `F` is a tuple of field decoders (or encoders) which has 2 to 32 elements.
+/// `Oneof`s can be nested for handling more fields.
 pub struct Oneof<F> {
     fields: F,
     index: usize,
@@ -93,6 +184,26 @@ impl<F> Oneof<F> {
     }
 }
 
+// See the comment of the same macro in `fields.rs`.
+macro_rules! impl_debug_and_default {
+    ($name:ident, [$($f:ident),*], [$($i:tt),*]) => {
+        impl<$($f: fmt::Debug),*> fmt::Debug for $name<($($f),*,)> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let fields: &[&dyn fmt::Debug] = &[$(&self.fields.$i),*];
+                f.debug_struct(stringify!($name))
+                    .field("fields", &DebugTuple(fields))
+                    .field("index", &self.index)
+                    .finish()
+            }
+        }
+        impl<$($f: Default),*> Default for $name<($($f),*,)> {
+            fn default() -> Self {
+                $name::new(($($f::default()),*,))
+            }
+        }
+    };
+}
+
 macro_rules! impl_field_decode {
     ($oneof:ident, [$($f:ident),*], [$($i:tt),*]) => {
         impl<$($f),*> Decode for Oneof<($($f),*,)>
@@ -146,19 +257,34 @@ macro_rules! impl_field_decode {
             $($f: RequiredFieldDecode),*
         {
             fn start_decoding(&mut self, tag: Tag) -> Result<bool> {
-                if self.index != 0 {
-                    match self.index - 1 {
-                        $($i => track!(self.fields.$i.finish_decoding()).map(|_| ())?),*,
-                        _ => {},
+                let prev = self.index;
+                if prev != 0 {
+                    let started = match prev - 1 {
+                        $($i => track!(self.fields.$i.start_decoding(tag); tag)?),*,
+                        _ => false,
+                    };
+                    if started {
+                        return Ok(true);
                     }
-                    self.index = 0;
                 }
 
-                $(if track!(self.fields.$i.start_decoding(tag); tag)? {
-                    self.index = $i + 1;
-                    return Ok(true);
+                let mut next = 0;
+                $(if next == 0 && prev != $i + 1 && track!(self.fields.$i.start_decoding(tag); tag)? {
+                    next = $i + 1;
                 })*
-                Ok(false)
+                if next == 0 {
+                    return Ok(false);
+                }
+
+                // The last field wins
+                if prev != 0 {
+                    match prev - 1 {
+                        $($i => track!(self.fields.$i.finish_decoding()).map(|_| ())?),*,
+                        _ => {},
+                    }
+                }
+                self.index = next;
+                Ok(true)
             }
         }
         impl<$($f),*> RequiredFieldDecode for Oneof<($($f),*,)>
@@ -247,6 +373,164 @@ macro_rules! impl_field_encode {
     };
 }
 
+impl_debug_and_default!(Oneof, [A, B], [0, 1]);
+impl_debug_and_default!(Oneof, [A, B, C], [0, 1, 2]);
+impl_debug_and_default!(Oneof, [A, B, C, D], [0, 1, 2, 3]);
+impl_debug_and_default!(Oneof, [A, B, C, D, E], [0, 1, 2, 3, 4]);
+impl_debug_and_default!(Oneof, [A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
+impl_debug_and_default!(Oneof, [A, B, C, D, E, F, G], [0, 1, 2, 3, 4, 5, 6]);
+impl_debug_and_default!(Oneof, [A, B, C, D, E, F, G, H], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25
+    ]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26
+    ]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27
+    ]
+);
+impl_debug_and_default!(
+    Oneof,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28
+    ]
+);
+impl_debug_and_default!(
+    Oneof,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29
+    ]
+);
+impl_debug_and_default!(
+    Oneof,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30
+    ]
+);
+impl_debug_and_default!(
+    Oneof,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE, AF
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31
+    ]
+);
+
 impl_field_decode!(Branch2, [A, B], [0, 1]);
 impl_field_decode!(Branch3, [A, B, C], [0, 1, 2]);
 impl_field_decode!(Branch4, [A, B, C, D], [0, 1, 2, 3]);
@@ -254,6 +538,156 @@ impl_field_decode!(Branch5, [A, B, C, D, E], [0, 1, 2, 3, 4]);
 impl_field_decode!(Branch6, [A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
 impl_field_decode!(Branch7, [A, B, C, D, E, F, G], [0, 1, 2, 3, 4, 5, 6]);
 impl_field_decode!(Branch8, [A, B, C, D, E, F, G, H], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_field_decode!(
+    Branch9,
+    [A, B, C, D, E, F, G, H, I],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8]
+);
+impl_field_decode!(
+    Branch10,
+    [A, B, C, D, E, F, G, H, I, J],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_field_decode!(
+    Branch11,
+    [A, B, C, D, E, F, G, H, I, J, K],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_field_decode!(
+    Branch12,
+    [A, B, C, D, E, F, G, H, I, J, K, L],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_field_decode!(
+    Branch13,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_field_decode!(
+    Branch14,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_field_decode!(
+    Branch15,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_field_decode!(
+    Branch16,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
+impl_field_decode!(
+    Branch17,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+);
+impl_field_decode!(
+    Branch18,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]
+);
+impl_field_decode!(
+    Branch19,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
+);
+impl_field_decode!(
+    Branch20,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]
+);
+impl_field_decode!(
+    Branch21,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]
+);
+impl_field_decode!(
+    Branch22,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21]
+);
+impl_field_decode!(
+    Branch23,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]
+);
+impl_field_decode!(
+    Branch24,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+);
+impl_field_decode!(
+    Branch25,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
+);
+impl_field_decode!(
+    Branch26,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25
+    ]
+);
+impl_field_decode!(
+    Branch27,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26
+    ]
+);
+impl_field_decode!(
+    Branch28,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27
+    ]
+);
+impl_field_decode!(
+    Branch29,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28
+    ]
+);
+impl_field_decode!(
+    Branch30,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29
+    ]
+);
+impl_field_decode!(
+    Branch31,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30
+    ]
+);
+impl_field_decode!(
+    Branch32,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE, AF
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31
+    ]
+);
 
 impl_field_encode!(Branch2, [A, B], [0, 1]);
 impl_field_encode!(Branch3, [A, B, C], [0, 1, 2]);
@@ -262,3 +696,153 @@ impl_field_encode!(Branch5, [A, B, C, D, E], [0, 1, 2, 3, 4]);
 impl_field_encode!(Branch6, [A, B, C, D, E, F], [0, 1, 2, 3, 4, 5]);
 impl_field_encode!(Branch7, [A, B, C, D, E, F, G], [0, 1, 2, 3, 4, 5, 6]);
 impl_field_encode!(Branch8, [A, B, C, D, E, F, G, H], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_field_encode!(
+    Branch9,
+    [A, B, C, D, E, F, G, H, I],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8]
+);
+impl_field_encode!(
+    Branch10,
+    [A, B, C, D, E, F, G, H, I, J],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_field_encode!(
+    Branch11,
+    [A, B, C, D, E, F, G, H, I, J, K],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_field_encode!(
+    Branch12,
+    [A, B, C, D, E, F, G, H, I, J, K, L],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_field_encode!(
+    Branch13,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_field_encode!(
+    Branch14,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_field_encode!(
+    Branch15,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_field_encode!(
+    Branch16,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
+impl_field_encode!(
+    Branch17,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+);
+impl_field_encode!(
+    Branch18,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]
+);
+impl_field_encode!(
+    Branch19,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
+);
+impl_field_encode!(
+    Branch20,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]
+);
+impl_field_encode!(
+    Branch21,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]
+);
+impl_field_encode!(
+    Branch22,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21]
+);
+impl_field_encode!(
+    Branch23,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]
+);
+impl_field_encode!(
+    Branch24,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+);
+impl_field_encode!(
+    Branch25,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
+);
+impl_field_encode!(
+    Branch26,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25
+    ]
+);
+impl_field_encode!(
+    Branch27,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26
+    ]
+);
+impl_field_encode!(
+    Branch28,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27
+    ]
+);
+impl_field_encode!(
+    Branch29,
+    [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28
+    ]
+);
+impl_field_encode!(
+    Branch30,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29
+    ]
+);
+impl_field_encode!(
+    Branch31,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30
+    ]
+);
+impl_field_encode!(
+    Branch32,
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC,
+        AD, AE, AF
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31
+    ]
+);
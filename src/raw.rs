@@ -0,0 +1,474 @@
+//! Schemaless decoder for the [binary wire format].
+//!
+//! [`RawMessageDecoder`](./struct.RawMessageDecoder.html) decodes arbitrary messages without their schemas
+//! like `protoc --decode_raw`, and it is mainly useful for debugging payloads.
+//!
+//! Since the wire format does not tell how the payloads of `Length-delimited` values should be interpreted,
+//! the decoder guesses it by the following rules (the first matching rule is applied):
+//!
+//! 1. Non-empty well-formed messages are regarded as `RawValue::Message`
+//! 2. Printable UTF-8 strings (i.e., strings that have no control characters except for `\t`, `\n` and `\r`)
+//!    are regarded as `RawValue::String`
+//! 3. Sequences of varints are regarded as `RawValue::Packed`
+//! 4. Otherwise, the payloads are regarded as `RawValue::Bytes`
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate protobuf_codec;
+//! use protobuf_codec::raw::{decode_raw, RawValue};
+//!
+//! # fn main() {
+//! let message = decode_raw(&[8, 150, 1, 18, 3, 102, 111, 111, 26, 2, 8, 1][..]).unwrap();
+//! assert_eq!(message.fields[0].value, RawValue::Varint(150));
+//! assert_eq!(message.fields[1].value, RawValue::String("foo".to_owned()));
+//! assert_eq!(message.to_string(), "1: 150\n2: \"foo\"\n3 {\n  1: 1\n}\n");
+//! # }
+//! ```
+//!
+//! [binary wire format]: https://developers.google.com/protocol-buffers/docs/encoding
+use crate::wire::{RawValueDecoder, Tag, TagDecoder, VarintDecoder, WireType};
+use bytecodec::io::IoDecodeExt;
+use bytecodec::{ByteCount, Decode, Eos, ErrorKind, Result};
+use std::fmt;
+use std::mem;
+
+// The maximum depth of nested messages and groups
+const MAX_DEPTH: usize = 100;
+
+/// Decodes `bytes` as a message whose schema is unknown.
+///
+/// This is equivalent to `RawMessageDecoder::new().decode_exact(bytes)`.
+pub fn decode_raw(bytes: &[u8]) -> Result<RawMessage> {
+    track!(RawMessageDecoder::new().decode_exact(bytes))
+}
+
+/// Message decoded without its schema.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RawMessage {
+    /// Fields of the message in the order in which they appeared in the input stream.
+    pub fields: Vec<RawField>,
+}
+impl RawMessage {
+    fn fmt_indented(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        for field in &self.fields {
+            write!(
+                f,
+                "{:indent$}{}",
+                "",
+                field.tag.field_num.as_u32(),
+                indent = indent
+            )?;
+            match field.value {
+                RawValue::Varint(n) => writeln!(f, ": {}", n)?,
+                RawValue::Bit32(n) => writeln!(f, ": 0x{:08x}", n)?,
+                RawValue::Bit64(n) => writeln!(f, ": 0x{:016x}", n)?,
+                RawValue::String(ref s) => {
+                    write!(f, ": \"")?;
+                    for c in s.chars() {
+                        match c {
+                            '"' => write!(f, "\\\"")?,
+                            '\\' => write!(f, "\\\\")?,
+                            '\n' => write!(f, "\\n")?,
+                            '\r' => write!(f, "\\r")?,
+                            '\t' => write!(f, "\\t")?,
+                            c => write!(f, "{}", c)?,
+                        }
+                    }
+                    writeln!(f, "\"")?;
+                }
+                RawValue::Bytes(ref b) => {
+                    write!(f, ": \"")?;
+                    for &b in b {
+                        match b {
+                            b'"' => write!(f, "\\\"")?,
+                            b'\\' => write!(f, "\\\\")?,
+                            0x20..=0x7E => write!(f, "{}", b as char)?,
+                            _ => write!(f, "\\{:03o}", b)?,
+                        }
+                    }
+                    writeln!(f, "\"")?;
+                }
+                RawValue::Packed(ref values) => {
+                    write!(f, ": [")?;
+                    for (i, n) in values.iter().enumerate() {
+                        if i != 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", n)?;
+                    }
+                    writeln!(f, "]")?;
+                }
+                RawValue::Message(ref m) | RawValue::Group(ref m) => {
+                    writeln!(f, " {{")?;
+                    m.fmt_indented(f, indent + 2)?;
+                    writeln!(f, "{:indent$}}}", "", indent = indent)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+impl fmt::Display for RawMessage {
+    /// Formats the message in the same way as `protoc --decode_raw`.
+    ///
+    /// Packed values are shown as lists (e.g., `4: [1, 2, 3]`).
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// Field decoded without its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawField {
+    /// Tag of the field.
+    pub tag: Tag,
+
+    /// Value of the field.
+    pub value: RawValue,
+}
+
+/// Value of a field decoded without its schema.
+///
+/// See [the module documentation](./index.html) for how `Length-delimited` values are interpreted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawValue {
+    /// `Varint` value.
+    Varint(u64),
+
+    /// `32-bit` value.
+    Bit32(u32),
+
+    /// `64-bit` value.
+    Bit64(u64),
+
+    /// `Length-delimited` value that seems to be a string.
+    String(String),
+
+    /// `Length-delimited` value that seems to be an embedded message.
+    Message(RawMessage),
+
+    /// `Length-delimited` value that seems to be packed varints.
+    Packed(Vec<u64>),
+
+    /// `Length-delimited` value that could not be interpreted.
+    Bytes(Vec<u8>),
+
+    /// Group value.
+    Group(RawMessage),
+}
+
+/// Decoder for messages whose schemas are unknown.
+///
+/// Like `MessageDecoder`, this regards the end of the input stream as the end of the message.
+#[derive(Debug, Default)]
+pub struct RawMessageDecoder {
+    message: RawMessage,
+    tag: TagDecoder,
+    reading_tag: bool,
+    value: RawValueDecoder,
+    current: Option<Tag>,
+    depth: usize,
+    started: bool,
+    eos: bool, // end-of-stream
+}
+impl RawMessageDecoder {
+    /// Makes a new `RawMessageDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn nested(depth: usize) -> Result<Self> {
+        track_assert!(
+            depth <= MAX_DEPTH,
+            ErrorKind::InvalidInput,
+            "Too deep nesting"
+        );
+        Ok(RawMessageDecoder {
+            depth,
+            ..Self::default()
+        })
+    }
+
+    fn finish_field(&mut self) -> Result<()> {
+        let tag = self.current.take().expect("Never fails");
+        let bytes = track!(self.value.finish_decoding())?;
+        let value = match tag.wire_type {
+            WireType::Varint => RawValue::Varint(track!(read_varint(&bytes))?.0),
+            WireType::Bit32 => {
+                let mut buf = [0; 4];
+                buf.copy_from_slice(&bytes);
+                RawValue::Bit32(u32::from_le_bytes(buf))
+            }
+            WireType::Bit64 => {
+                let mut buf = [0; 8];
+                buf.copy_from_slice(&bytes);
+                RawValue::Bit64(u64::from_le_bytes(buf))
+            }
+            WireType::LengthDelimited => {
+                let (_, size) = track!(read_varint(&bytes))?;
+                self.payload(bytes[size..].to_owned())
+            }
+            WireType::StartGroup => {
+                let body = &bytes[..self.value.last_tag_offset()];
+                let mut decoder = track!(Self::nested(self.depth + 1))?;
+                RawValue::Group(track!(decoder.decode_exact(body))?)
+            }
+            WireType::EndGroup => unreachable!(),
+        };
+        self.message.fields.push(RawField { tag, value });
+        Ok(())
+    }
+
+    fn payload(&self, bytes: Vec<u8>) -> RawValue {
+        if !bytes.is_empty() {
+            if let Ok(mut decoder) = Self::nested(self.depth + 1) {
+                if let Ok(m) = decoder.decode_exact(&bytes[..]) {
+                    return RawValue::Message(m);
+                }
+            }
+        }
+        let bytes = match String::from_utf8(bytes) {
+            Ok(s) if is_printable(&s) => return RawValue::String(s),
+            Ok(s) => s.into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+
+        let mut values = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            match read_varint(&bytes[offset..]) {
+                Ok((n, size)) => {
+                    values.push(n);
+                    offset += size;
+                }
+                Err(_) => return RawValue::Bytes(bytes),
+            }
+        }
+        RawValue::Packed(values)
+    }
+}
+impl Decode for RawMessageDecoder {
+    type Item = RawMessage;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        self.started = true;
+        if self.eos {
+            return Ok(0);
+        }
+
+        let mut offset = 0;
+        while offset < buf.len() {
+            if self.current.is_none() {
+                offset += track!(self.tag.decode(&buf[offset..], eos))?;
+                self.reading_tag = true;
+                if self.tag.is_idle() {
+                    self.reading_tag = false;
+                    let tag = track!(self.tag.finish_decoding())?;
+                    track!(self.value.start(tag))?;
+                    self.current = Some(tag);
+                }
+            } else {
+                bytecodec_try_decode!(self.value, offset, buf, eos);
+                track!(self.finish_field())?;
+            }
+        }
+        self.eos = eos.is_reached();
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(!self.started | self.eos, ErrorKind::IncompleteDecoding; self.started, self.eos);
+        track_assert!(!self.reading_tag, ErrorKind::IncompleteDecoding);
+        track_assert!(self.current.is_none(), ErrorKind::IncompleteDecoding; self.current);
+        self.started = false;
+        self.eos = false;
+        Ok(mem::take(&mut self.message))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.eos {
+            ByteCount::Finite(0)
+        } else if self.current.is_some() {
+            self.value.requiring_bytes()
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.eos
+    }
+}
+
+// Returns the value and the size of the varint at the head of `bytes`.
+fn read_varint(bytes: &[u8]) -> Result<(u64, usize)> {
+    let mut decoder = VarintDecoder::new();
+    let size = track!(decoder.decode(bytes, Eos::new(true)))?;
+    let n = track!(decoder.finish_decoding())?;
+    Ok((n, size))
+}
+
+fn is_printable(s: &str) -> bool {
+    s.chars()
+        .all(|c| !c.is_control() || c == '\t' || c == '\n' || c == '\r')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(n: u32, wire_type: WireType, value: RawValue) -> RawField {
+        let field_num = track_try_unwrap!(crate::field::num::FieldNum::new(n));
+        RawField {
+            tag: Tag::from((field_num, wire_type)),
+            value,
+        }
+    }
+
+    #[test]
+    fn decode_raw_works() {
+        let input = [
+            8, 150, 1, // f1=150
+            21, 1, 0, 0, 0, // f2=fixed32
+            25, 2, 0, 0, 0, 0, 0, 0, 0, // f3=fixed64
+            34, 4, 104, 105, 10, 34, // f4="hi\n\""
+            42, 4, 8, 1, 18, 0, // f5={f1=1, f2=""}
+            50, 4, 1, 2, 172, 2, // f6=[1, 2, 300]
+            58, 2, 255, 255, // f7=bytes
+            59, 8, 2, 60, // f7=group{f1=2}
+            66, 0, // f8=""
+        ];
+        let m = track_try_unwrap!(decode_raw(&input[..]));
+        let nested = RawMessage {
+            fields: vec![field(1, WireType::Varint, RawValue::Varint(2))],
+        };
+        let expected = vec![
+            field(1, WireType::Varint, RawValue::Varint(150)),
+            field(2, WireType::Bit32, RawValue::Bit32(1)),
+            field(3, WireType::Bit64, RawValue::Bit64(2)),
+            field(
+                4,
+                WireType::LengthDelimited,
+                RawValue::String("hi\n\"".to_owned()),
+            ),
+            field(
+                5,
+                WireType::LengthDelimited,
+                RawValue::Message(RawMessage {
+                    fields: vec![
+                        field(1, WireType::Varint, RawValue::Varint(1)),
+                        field(
+                            2,
+                            WireType::LengthDelimited,
+                            RawValue::String(String::new()),
+                        ),
+                    ],
+                }),
+            ),
+            field(
+                6,
+                WireType::LengthDelimited,
+                RawValue::Packed(vec![1, 2, 300]),
+            ),
+            field(
+                7,
+                WireType::LengthDelimited,
+                RawValue::Bytes(vec![255, 255]),
+            ),
+            field(7, WireType::StartGroup, RawValue::Group(nested)),
+            field(
+                8,
+                WireType::LengthDelimited,
+                RawValue::String(String::new()),
+            ),
+        ];
+        assert_eq!(m.fields, expected);
+        assert_eq!(
+            m.to_string(),
+            r#"1: 150
+2: 0x00000001
+3: 0x0000000000000002
+4: "hi\n\""
+5 {
+  1: 1
+  2: ""
+}
+6: [1, 2, 300]
+7: "\377\377"
+7 {
+  1: 2
+}
+8: ""
+"#
+        );
+    }
+
+    #[test]
+    fn decode_raw_prefers_messages_to_strings() {
+        // `{1: {1: "aaaa..."}}`: the payload of the outer field is printable (i.e., "\n aaaa...")
+        let mut input = vec![10, 34, 10, 32];
+        input.extend_from_slice(&[b'a'; 32]);
+        let m = track_try_unwrap!(decode_raw(&input));
+        let inner = RawMessage {
+            fields: vec![field(
+                1,
+                WireType::LengthDelimited,
+                RawValue::String("a".repeat(32)),
+            )],
+        };
+        assert_eq!(
+            m.fields,
+            [field(
+                1,
+                WireType::LengthDelimited,
+                RawValue::Message(inner)
+            )]
+        );
+        assert_eq!(
+            m.to_string(),
+            format!("1 {{\n  1: \"{}\"\n}}\n", "a".repeat(32))
+        );
+    }
+
+    #[test]
+    fn decode_raw_rejects_malformed_input() {
+        // Truncated
+        assert!(decode_raw(&[8][..]).is_err());
+        assert!(decode_raw(&[18, 3, 1][..]).is_err());
+        assert!(decode_raw(&[8, 1, 128][..]).is_err());
+
+        // Unknown wire type
+        assert!(decode_raw(&[15, 0][..]).is_err());
+
+        // Unmatched end group tag
+        assert!(decode_raw(&[11, 20][..]).is_err());
+
+        // Too deep nesting
+        let mut input = vec![11; 200];
+        input.extend(vec![12; 200]);
+        assert!(decode_raw(&input).is_err());
+
+        // Too deeply nested length-delimited values are not regarded as messages
+        let mut input = vec![8, 1];
+        for _ in 0..120 {
+            let len = input.len();
+            let mut outer = vec![10];
+            outer.extend(write_len(len));
+            outer.extend(input);
+            input = outer;
+        }
+        let mut decoder = RawMessageDecoder::new();
+        assert!(decoder.decode_exact(&input[..]).is_ok());
+    }
+
+    fn write_len(mut n: usize) -> Vec<u8> {
+        let mut buf = Vec::new();
+        while n >= 0x80 {
+            buf.push((n as u8) | 0x80);
+            n >>= 7;
+        }
+        buf.push(n as u8);
+        buf
+    }
+}
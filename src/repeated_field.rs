@@ -3,6 +3,7 @@ use crate::field::{
     FieldDecode, FieldDecoder, FieldEncode, FieldEncoder, Fields, MessageFieldDecoder,
     MessageFieldEncoder, RequiredFieldDecode, RequiredFieldEncode,
 };
+use crate::limit;
 use crate::message::{MessageDecode, MessageDecoder, MessageEncode, MessageEncoder};
 use crate::scalar::BytesEncoder;
 use crate::value::{
@@ -11,6 +12,7 @@ use crate::value::{
 use crate::wire::{LengthDelimitedDecoder, Tag, TagEncoder, WireType};
 use bytecodec::combinator::Collect;
 use bytecodec::{ByteCount, Decode, DecodeExt, Encode, Eos, ErrorKind, Result, SizedEncode};
+use std::marker::PhantomData;
 use std::{fmt, iter, mem};
 
 /// Decoder and encoder for repeated fields.
@@ -19,6 +21,7 @@ pub struct Repeated<T, V: IntoIterator> {
     inner: T,
     values: Option<V>,
     value_iter: Option<V::IntoIter>,
+    count: usize,
 }
 impl<T, V: IntoIterator> Repeated<T, V> {
     /// Makes a new `Repeated` instance.
@@ -27,6 +30,7 @@ impl<T, V: IntoIterator> Repeated<T, V> {
             inner,
             values: None,
             value_iter: None,
+            count: 0,
         }
     }
 
@@ -51,29 +55,48 @@ impl<T: Default, V: IntoIterator> Default for Repeated<T, V> {
             inner: T::default(),
             values: None,
             value_iter: None,
+            count: 0,
         }
     }
 }
-impl<D, V> Decode for Repeated<D, V>
+impl<D, V> Repeated<D, V>
 where
     D: RequiredFieldDecode,
     V: Default + Extend<D::Item> + IntoIterator<Item = D::Item>,
 {
-    type Item = V;
-
-    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+    // `check_count` is the limit on the number of the decoded values
+    fn decode_values(
+        &mut self,
+        buf: &[u8],
+        eos: Eos,
+        check_count: fn(usize) -> Result<()>,
+    ) -> Result<usize> {
         let size = track!(self.inner.decode(buf, eos))?;
         if self.inner.is_idle() {
             let value = track!(self.inner.finish_decoding())?;
             let values = track_assert_some!(self.values.as_mut(), ErrorKind::InconsistentState);
             values.extend(iter::once(value));
+            self.count += 1;
+            track!(check_count(self.count))?;
         }
         Ok(size)
     }
+}
+impl<D, V> Decode for Repeated<D, V>
+where
+    D: RequiredFieldDecode,
+    V: Default + Extend<D::Item> + IntoIterator<Item = D::Item>,
+{
+    type Item = V;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.decode_values(buf, eos, limit::check_repeated))
+    }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
         track_assert!(!self.inner.is_present(), ErrorKind::IncompleteDecoding);
-        let values = self.values.take().unwrap_or_else(V::default);
+        let values = self.values.take().unwrap_or_default();
+        self.count = 0;
         Ok(values)
     }
 
@@ -150,6 +173,107 @@ where
 {
 }
 
+/// Encoder for repeated fields whose total size is computed before encoding.
+///
+/// Unlike `Repeated`, this starts encoding all the values at once and keeps the encoders of them,
+/// so it implements `SizedEncode` if `E` does.
+/// For example, `SizedRepeated<MessageFieldEncoder<_, M>, _>` can be embedded in other messages
+/// without `PreEncode` that copies the encoded bytes at every level of nesting.
+///
+/// The encoders of the values are made by `E::default()`.
+#[derive(Debug)]
+pub struct SizedRepeated<E, V> {
+    encoders: Vec<E>,
+    len: usize,
+    index: usize,
+    remaining_bytes: u64,
+    _values: PhantomData<V>,
+}
+impl<E, V> SizedRepeated<E, V> {
+    /// Makes a new `SizedRepeated` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl<E, V> Default for SizedRepeated<E, V> {
+    fn default() -> Self {
+        SizedRepeated {
+            encoders: Vec::new(),
+            len: 0,
+            index: 0,
+            remaining_bytes: 0,
+            _values: PhantomData,
+        }
+    }
+}
+impl<E, V> Encode for SizedRepeated<E, V>
+where
+    E: RequiredFieldEncode + SizedEncode + Default,
+    V: IntoIterator<Item = E::Item>,
+{
+    type Item = V;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while self.index < self.len {
+            let size = track!(self.encoders[self.index].encode(&mut buf[offset..], eos))?;
+            offset += size;
+            self.remaining_bytes -= size as u64;
+            if !self.encoders[self.index].is_idle() {
+                break;
+            }
+            self.index += 1;
+        }
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        self.len = 0;
+        self.index = 0;
+        self.remaining_bytes = 0;
+        for value in item {
+            if self.len == self.encoders.len() {
+                self.encoders.push(E::default());
+            }
+            let encoder = &mut self.encoders[self.len];
+            if let Err(e) = track!(encoder.start_encoding(value)) {
+                // The encoders that have already started cannot be reused
+                self.encoders.clear();
+                self.len = 0;
+                self.remaining_bytes = 0;
+                return Err(e);
+            }
+            self.remaining_bytes += encoder.exact_requiring_bytes();
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.index == self.len
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        ByteCount::Finite(self.exact_requiring_bytes())
+    }
+}
+impl<E, V> SizedEncode for SizedRepeated<E, V>
+where
+    E: RequiredFieldEncode + SizedEncode + Default,
+    V: IntoIterator<Item = E::Item>,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.remaining_bytes
+    }
+}
+impl<E, V> FieldEncode for SizedRepeated<E, V>
+where
+    E: RequiredFieldEncode + SizedEncode + Default,
+    V: IntoIterator<Item = E::Item>,
+{
+}
+
 /// Decoder for packed repeated fields.
 ///
 /// Actually this can decode fields regardless of whether they are packed or not.
@@ -162,6 +286,7 @@ where
     num: F,
     decoder: LengthDelimitedDecoder<Collect<D, V>>,
     values: V,
+    count: usize,
     is_packed: bool,
     is_decoding: bool,
 }
@@ -176,6 +301,7 @@ where
             num: field_num,
             decoder: LengthDelimitedDecoder::new(value_decoder.collect()),
             values: V::default(),
+            count: 0,
             is_packed: false,
             is_decoding: false,
         }
@@ -197,19 +323,25 @@ where
         let mut size = 0;
         if self.is_packed {
             bytecodec_try_decode!(self.decoder, size, buf, eos; self.num.into());
-            self.values.extend(track!(self.decoder.finish_decoding())?);
+            let values = track!(self.decoder.finish_decoding())?;
+            let count = &mut self.count;
+            self.values
+                .extend(values.into_iter().inspect(|_| *count += 1));
         } else {
             bytecodec_try_decode!(self.decoder.inner_mut().inner_mut(), size, buf, eos; self.num.into());
             let v = track!(self.decoder.inner_mut().inner_mut().finish_decoding())?;
             self.values.extend(iter::once(v));
+            self.count += 1;
         }
         self.is_decoding = false;
+        track!(limit::check_repeated(self.count); self.num.into())?;
         Ok(size)
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
         track_assert!(!self.is_decoding, ErrorKind::IncompleteDecoding);
-        Ok(mem::replace(&mut self.values, V::default()))
+        self.count = 0;
+        Ok(mem::take(&mut self.values))
     }
 
     fn is_idle(&self) -> bool {
@@ -290,7 +422,7 @@ where
     type Item = M;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
-        track!(self.inner.decode(buf, eos))
+        track!(self.inner.decode_values(buf, eos, limit::check_map_entries))
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
@@ -364,7 +496,7 @@ where
     type Item = M;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
-        track!(self.inner.decode(buf, eos))
+        track!(self.inner.decode_values(buf, eos, limit::check_map_entries))
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
@@ -405,7 +537,7 @@ where
 #[derive(Debug)]
 pub struct PackedFieldEncoder<F, E, V: IntoIterator> {
     num: F,
-    values: Option<V::IntoIter>,
+    _values: PhantomData<V>,
     tag: TagEncoder,
     value: E,
     bytes: BytesEncoder,
@@ -415,7 +547,7 @@ impl<F, E, V: IntoIterator> PackedFieldEncoder<F, E, V> {
     pub fn new(field_num: F, value_encoder: E) -> Self {
         PackedFieldEncoder {
             num: field_num,
-            values: None,
+            _values: PhantomData,
             tag: TagEncoder::new(),
             value: value_encoder,
             bytes: BytesEncoder::new(),
@@ -426,7 +558,7 @@ impl<F: Default, E: Default, V: IntoIterator> Default for PackedFieldEncoder<F,
     fn default() -> Self {
         PackedFieldEncoder {
             num: F::default(),
-            values: None,
+            _values: PhantomData,
             tag: TagEncoder::new(),
             value: E::default(),
             bytes: BytesEncoder::default(),
@@ -449,8 +581,6 @@ where
     }
 
     fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
-        let tag = Tag::from((self.num.into(), self.bytes.wire_type()));
-        track!(self.tag.start_encoding(tag))?;
         let mut buf = Vec::new();
         for v in item {
             track!(self.value.start_encoding(v))?;
@@ -461,6 +591,13 @@ where
             buf.resize(new_len, 0);
             track!(self.value.encode(&mut buf[old_len..], eos))?;
         }
+        if buf.is_empty() {
+            // Empty packed fields are not emitted
+            return Ok(());
+        }
+
+        let tag = Tag::from((self.num.into(), self.bytes.wire_type()));
+        track!(self.tag.start_encoding(tag))?;
         track!(self.bytes.start_encoding(buf))?;
         Ok(())
     }
@@ -618,3 +755,155 @@ impl<F, K, V, M: IntoIterator> fmt::Debug for MapMessageFieldEncoder<F, K, V, M>
         write!(f, "MapMessageFieldEncoder {{ .. }}")
     }
 }
+
+/// Encoder for map fields which have scalar values, and whose total size is computed before encoding.
+///
+/// This is the `SizedRepeated` counterpart of `MapFieldEncoder`.
+/// The key and value encoders of the entries are made by `K::default()` and `V::default()`.
+pub struct SizedMapFieldEncoder<F, K, V, M> {
+    inner: SizedRepeated<MessageFieldEncoder<F, ScalarEntryEncoder<K, V>>, M>,
+}
+impl<F, K, V, M> SizedMapFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + ValueEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+    /// Makes a new `SizedMapFieldEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl<F, K, V, M> Default for SizedMapFieldEncoder<F, K, V, M> {
+    fn default() -> Self {
+        SizedMapFieldEncoder {
+            inner: SizedRepeated::default(),
+        }
+    }
+}
+impl<F, K, V, M> Encode for SizedMapFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + ValueEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+    type Item = M;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+}
+impl<F, K, V, M> SizedEncode for SizedMapFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + ValueEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+impl<F, K, V, M> FieldEncode for SizedMapFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + ValueEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+}
+impl<F, K, V, M> fmt::Debug for SizedMapFieldEncoder<F, K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SizedMapFieldEncoder {{ .. }}")
+    }
+}
+
+/// Encoder for map fields which have message values, and whose total size is computed before encoding.
+///
+/// This is the `SizedRepeated` counterpart of `MapMessageFieldEncoder`.
+/// The key and value encoders of the entries are made by `K::default()` and `V::default()`.
+pub struct SizedMapMessageFieldEncoder<F, K, V, M> {
+    inner: SizedRepeated<MessageFieldEncoder<F, MessageEntryEncoder<K, V>>, M>,
+}
+impl<F, K, V, M> SizedMapMessageFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + MessageEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+    /// Makes a new `SizedMapMessageFieldEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl<F, K, V, M> Default for SizedMapMessageFieldEncoder<F, K, V, M> {
+    fn default() -> Self {
+        SizedMapMessageFieldEncoder {
+            inner: SizedRepeated::default(),
+        }
+    }
+}
+impl<F, K, V, M> Encode for SizedMapMessageFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + MessageEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+    type Item = M;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+}
+impl<F, K, V, M> SizedEncode for SizedMapMessageFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + MessageEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+impl<F, K, V, M> FieldEncode for SizedMapMessageFieldEncoder<F, K, V, M>
+where
+    F: Copy + Default + Into<FieldNum>,
+    K: SizedEncode + MapKeyEncode + Default,
+    V: SizedEncode + MessageEncode + Default,
+    M: IntoIterator<Item = (K::Item, V::Item)>,
+{
+}
+impl<F, K, V, M> fmt::Debug for SizedMapMessageFieldEncoder<F, K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SizedMapMessageFieldEncoder {{ .. }}")
+    }
+}
@@ -14,7 +14,8 @@ use bytecodec::fixnum::{
     F32leDecoder, F32leEncoder, F64leDecoder, F64leEncoder, I32leDecoder, I32leEncoder,
     I64leDecoder, I64leEncoder, U32leDecoder, U32leEncoder, U64leDecoder, U64leEncoder,
 };
-use bytecodec::{ByteCount, Decode, Encode, Eos, Result, SizedEncode};
+use bytecodec::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
+use std::marker::PhantomData;
 
 macro_rules! impl_newtype_decode {
     ($decoder:ty, $item:ty, $wire:ident) => {
@@ -756,6 +757,112 @@ impl<S: AsRef<str>> ValueEncode for StringEncoder<S> {
 }
 impl<S: AsRef<str>> MapKeyEncode for StringEncoder<S> {}
 
+/// This trait allows for converting enum values from/to their numbers.
+///
+/// The enum types generated by [`codegen`](../codegen/index.html) implement this trait.
+pub trait Enum: Sized {
+    /// Returns the enum value which has the number `n`.
+    ///
+    /// If there is no such value, this will return `None`.
+    /// Open enums (e.g., the proto3 enums generated by `codegen`) may keep unknown numbers instead.
+    fn from_i32(n: i32) -> Option<Self>;
+
+    /// Returns the number of the value.
+    fn to_i32(&self) -> i32;
+}
+
+/// Decoder for `enum` values.
+#[derive(Debug)]
+pub struct EnumDecoder<T>(Int32Decoder, PhantomData<T>);
+impl<T: Enum> EnumDecoder<T> {
+    /// Makes a new `EnumDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl<T> Default for EnumDecoder<T> {
+    fn default() -> Self {
+        EnumDecoder(Int32Decoder::default(), PhantomData)
+    }
+}
+impl<T: Enum> Decode for EnumDecoder<T> {
+    type Item = T;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let n = track!(self.0.finish_decoding())?;
+        let value = track_assert_some!(
+            T::from_i32(n),
+            ErrorKind::InvalidInput,
+            "Unknown enum value: {}",
+            n
+        );
+        Ok(value)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+}
+impl<T: Enum> ValueDecode for EnumDecoder<T> {
+    fn wire_type(&self) -> WireType {
+        WireType::Varint
+    }
+}
+impl<T: Enum> NumericValueDecode for EnumDecoder<T> {}
+
+/// Encoder for `enum` values.
+#[derive(Debug)]
+pub struct EnumEncoder<T>(Int32Encoder, PhantomData<T>);
+impl<T: Enum> EnumEncoder<T> {
+    /// Makes a new `EnumEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl<T> Default for EnumEncoder<T> {
+    fn default() -> Self {
+        EnumEncoder(Int32Encoder::default(), PhantomData)
+    }
+}
+impl<T: Enum> Encode for EnumEncoder<T> {
+    type Item = T;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.0.start_encoding(item.to_i32()))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+}
+impl<T: Enum> SizedEncode for EnumEncoder<T> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl<T: Enum> ValueEncode for EnumEncoder<T> {
+    fn wire_type(&self) -> WireType {
+        WireType::Varint
+    }
+}
+impl<T: Enum> NumericValueEncode for EnumEncoder<T> {}
+
 #[cfg(test)]
 mod tests {
     use bytecodec::io::{IoDecodeExt, IoEncodeExt};
@@ -980,4 +1087,45 @@ mod tests {
     fn string_encoder_works() {
         assert_encode!(StringEncoder, "foo", [3, 102, 111, 111]);
     }
+
+    #[derive(Debug, PartialEq)]
+    enum Color {
+        Red,
+        Blue,
+    }
+    impl Enum for Color {
+        fn from_i32(n: i32) -> Option<Self> {
+            match n {
+                0 => Some(Color::Red),
+                2 => Some(Color::Blue),
+                _ => None,
+            }
+        }
+
+        fn to_i32(&self) -> i32 {
+            match *self {
+                Color::Red => 0,
+                Color::Blue => 2,
+            }
+        }
+    }
+
+    #[test]
+    fn enum_decoder_works() {
+        let mut decoder = EnumDecoder::<Color>::new();
+        let item = track_try_unwrap!(decoder.decode_exact(&[2][..]));
+        assert_eq!(item, Color::Blue);
+
+        let mut decoder = EnumDecoder::<Color>::new();
+        let e = decoder.decode_exact(&[1][..]).err().unwrap();
+        assert_eq!(*e.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn enum_encoder_works() {
+        let mut buf = Vec::new();
+        let mut encoder = track_try_unwrap!(EnumEncoder::with_item(Color::Blue));
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, [2]);
+    }
 }
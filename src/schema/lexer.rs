@@ -0,0 +1,449 @@
+use super::Position;
+use bytecodec::{ErrorKind, Result};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Int(u64),
+    Float(f64),
+    Str(Vec<u8>),
+    Symbol(char),
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub position: Position,
+
+    // Byte offsets in the source text
+    pub start: usize,
+    pub end: usize,
+}
+
+pub fn tokenize(text: &str) -> Result<Vec<Token>> {
+    track!(Lexer::new(text, false).tokenize())
+}
+
+/// Tokenizes a text in the protobuf text format.
+///
+/// Comments start with `#`, and floating point literals can have `f` suffixes (e.g., `1.5f`).
+pub fn tokenize_text_format(text: &str) -> Result<Vec<Token>> {
+    track!(Lexer::new(text, true).tokenize())
+}
+
+#[derive(Debug)]
+struct Lexer<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    line: usize,
+    column: usize,
+    text_format: bool,
+}
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str, text_format: bool) -> Self {
+        Lexer {
+            text,
+            chars: text.char_indices().peekable(),
+            line: 1,
+            column: 1,
+            text_format,
+        }
+    }
+
+    fn tokenize(&mut self) -> Result<Vec<Token>> {
+        let mut tokens = Vec::new();
+        loop {
+            let token = track!(self.next_token())?;
+            let eof = token.kind == TokenKind::Eof;
+            tokens.push(token);
+            if eof {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.text.len(), |&(i, _)| i)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn peek2(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next().map(|(_, c)| c)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let (_, c) = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn next_token(&mut self) -> Result<Token> {
+        track!(self.skip_whitespaces_and_comments())?;
+
+        let position = self.position();
+        let start = self.offset();
+        let kind = match self.peek() {
+            None => TokenKind::Eof,
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => self.read_ident(),
+            Some(c) if c.is_ascii_digit() => track!(self.read_number(position))?,
+            Some('.') if self.peek2().is_some_and(|c| c.is_ascii_digit()) => {
+                track!(self.read_number(position))?
+            }
+            Some(c) if c == '"' || c == '\'' => track!(self.read_string(position))?,
+            Some(c) => {
+                self.bump();
+                TokenKind::Symbol(c)
+            }
+        };
+        let end = self.offset();
+        Ok(Token {
+            kind,
+            position,
+            start,
+            end,
+        })
+    }
+
+    fn skip_whitespaces_and_comments(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('#') if self.text_format => {
+                    while let Some(c) = self.bump() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some('/') if !self.text_format && self.peek2() == Some('/') => {
+                    while let Some(c) = self.bump() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some('/') if !self.text_format && self.peek2() == Some('*') => {
+                    let position = self.position();
+                    self.bump();
+                    self.bump();
+                    loop {
+                        match self.bump() {
+                            None => {
+                                track_panic!(
+                                    ErrorKind::InvalidInput,
+                                    "{}: Unterminated block comment",
+                                    position
+                                );
+                            }
+                            Some('*') if self.peek() == Some('/') => {
+                                self.bump();
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn read_ident(&mut self) -> TokenKind {
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                s.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        TokenKind::Ident(s)
+    }
+
+    fn read_number(&mut self, position: Position) -> Result<TokenKind> {
+        let mut s = String::new();
+        let mut is_float = false;
+        if self.peek() == Some('0') && (self.peek2() == Some('x') || self.peek2() == Some('X')) {
+            self.bump();
+            self.bump();
+            while let Some(c) = self.peek() {
+                if !c.is_ascii_hexdigit() {
+                    break;
+                }
+                s.push(c);
+                self.bump();
+            }
+            let n = track_assert_some!(
+                u64::from_str_radix(&s, 16).ok(),
+                ErrorKind::InvalidInput,
+                "{}: Invalid hexadecimal literal",
+                position
+            );
+            track!(self.check_number_end(position))?;
+            return Ok(TokenKind::Int(n));
+        }
+
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() {
+                s.push(c);
+            } else if c == '.' && !is_float {
+                is_float = true;
+                s.push(c);
+            } else if c == 'e' || c == 'E' {
+                is_float = true;
+                s.push(c);
+                self.bump();
+                if let Some(sign) = self.peek().filter(|&c| c == '+' || c == '-') {
+                    s.push(sign);
+                    self.bump();
+                }
+                continue;
+            } else {
+                break;
+            }
+            self.bump();
+        }
+        if self.text_format && (self.peek() == Some('f') || self.peek() == Some('F')) {
+            is_float = true;
+            self.bump();
+        }
+        track!(self.check_number_end(position))?;
+
+        if is_float {
+            let f = track_assert_some!(
+                s.parse::<f64>().ok(),
+                ErrorKind::InvalidInput,
+                "{}: Invalid floating point literal: {:?}",
+                position,
+                s
+            );
+            Ok(TokenKind::Float(f))
+        } else if s.len() > 1 && s.starts_with('0') {
+            let n = track_assert_some!(
+                u64::from_str_radix(&s[1..], 8).ok(),
+                ErrorKind::InvalidInput,
+                "{}: Invalid octal literal: {:?}",
+                position,
+                s
+            );
+            Ok(TokenKind::Int(n))
+        } else {
+            let n = track_assert_some!(
+                s.parse::<u64>().ok(),
+                ErrorKind::InvalidInput,
+                "{}: Invalid decimal literal: {:?}",
+                position,
+                s
+            );
+            Ok(TokenKind::Int(n))
+        }
+    }
+
+    fn check_number_end(&mut self, position: Position) -> Result<()> {
+        if let Some(c) = self.peek() {
+            track_assert!(
+                !(c.is_ascii_alphanumeric() || c == '_'),
+                ErrorKind::InvalidInput,
+                "{}: Invalid number literal",
+                position
+            );
+        }
+        Ok(())
+    }
+
+    fn read_string(&mut self, position: Position) -> Result<TokenKind> {
+        let quote = self.bump().expect("Never fails");
+        let mut bytes = Vec::new();
+        loop {
+            let c = match self.bump() {
+                None | Some('\n') => {
+                    track_panic!(
+                        ErrorKind::InvalidInput,
+                        "{}: Unterminated string literal",
+                        position
+                    );
+                }
+                Some(c) => c,
+            };
+            if c == quote {
+                return Ok(TokenKind::Str(bytes));
+            }
+            if c != '\\' {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                continue;
+            }
+
+            let escape_position = self.position();
+            let c = track_assert_some!(
+                self.bump(),
+                ErrorKind::InvalidInput,
+                "{}: Unterminated string literal",
+                position
+            );
+            match c {
+                'a' => bytes.push(0x07),
+                'b' => bytes.push(0x08),
+                'f' => bytes.push(0x0C),
+                'n' => bytes.push(b'\n'),
+                'r' => bytes.push(b'\r'),
+                't' => bytes.push(b'\t'),
+                'v' => bytes.push(0x0B),
+                '\\' | '\'' | '"' | '?' => bytes.push(c as u8),
+                'x' | 'X' => {
+                    let n = track!(self.read_digits(16, 2, escape_position))?;
+                    bytes.push(n as u8);
+                }
+                '0'..='7' => {
+                    let mut n = c.to_digit(8).expect("Never fails");
+                    for _ in 0..2 {
+                        match self.peek().and_then(|c| c.to_digit(8)) {
+                            Some(d) => {
+                                n = n * 8 + d;
+                                self.bump();
+                            }
+                            None => break,
+                        }
+                    }
+                    track_assert!(
+                        n <= 0xFF,
+                        ErrorKind::InvalidInput,
+                        "{}: Too large octal escape",
+                        escape_position
+                    );
+                    bytes.push(n as u8);
+                }
+                'u' | 'U' => {
+                    let digits = if c == 'u' { 4 } else { 8 };
+                    let n = track!(self.read_digits(16, digits, escape_position))?;
+                    let c = track_assert_some!(
+                        std::char::from_u32(n),
+                        ErrorKind::InvalidInput,
+                        "{}: Invalid unicode escape",
+                        escape_position
+                    );
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                _ => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Unknown escape sequence: \\{}",
+                    escape_position,
+                    c
+                ),
+            }
+        }
+    }
+
+    fn read_digits(&mut self, radix: u32, max_digits: usize, position: Position) -> Result<u32> {
+        let mut n = 0;
+        let mut count = 0;
+        while count < max_digits {
+            match self.peek().and_then(|c| c.to_digit(radix)) {
+                Some(d) => {
+                    n = n * radix + d;
+                    count += 1;
+                    self.bump();
+                }
+                None => break,
+            }
+        }
+        track_assert_ne!(
+            count,
+            0,
+            ErrorKind::InvalidInput,
+            "{}: Invalid escape sequence",
+            position
+        );
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<TokenKind> {
+        track_try_unwrap!(tokenize(text))
+            .into_iter()
+            .map(|t| t.kind)
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_works() {
+        assert_eq!(
+            kinds("foo = 0x1F; // comment\n /* block\n comment */ bar 017 1.5e3 'a\\x41\\101\\n'"),
+            vec![
+                TokenKind::Ident("foo".to_owned()),
+                TokenKind::Symbol('='),
+                TokenKind::Int(31),
+                TokenKind::Symbol(';'),
+                TokenKind::Ident("bar".to_owned()),
+                TokenKind::Int(15),
+                TokenKind::Float(1500.0),
+                TokenKind::Str(b"aAA\n".to_vec()),
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_text_format_works() {
+        let tokens = track_try_unwrap!(tokenize_text_format("a: 1.5f # comment\n b: 2F /"));
+        let kinds = tokens.into_iter().map(|t| t.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Ident("a".to_owned()),
+                TokenKind::Symbol(':'),
+                TokenKind::Float(1.5),
+                TokenKind::Ident("b".to_owned()),
+                TokenKind::Symbol(':'),
+                TokenKind::Float(2.0),
+                TokenKind::Symbol('/'),
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn token_positions_work() {
+        let tokens = track_try_unwrap!(tokenize("a\n  b /* x\n */ c"));
+        let positions = tokens
+            .iter()
+            .map(|t| (t.position.line, t.position.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, [(1, 1), (2, 3), (3, 5), (3, 6)]);
+    }
+
+    #[test]
+    fn unterminated_string_is_rejected() {
+        let e = tokenize("foo = \"bar").err().unwrap();
+        assert_eq!(*e.kind(), ErrorKind::InvalidInput);
+        assert!(e.to_string().contains("1:7: Unterminated string literal"));
+    }
+}
@@ -0,0 +1,458 @@
+//! In-memory model of `.proto` schema files.
+//!
+//! [`parse`](./fn.parse.html) converts the source text of a `.proto` file written in
+//! [proto2] or [proto3] syntax into a [`ProtoFile`](./struct.ProtoFile.html).
+//!
+//! Errors are reported as `ErrorKind::InvalidInput` errors which
+//! contain the position (i.e., `line:column`) where the problem was detected.
+//!
+//! # Examples
+//!
+//! ```
+//! use protobuf_codec::schema::{self, FieldType, Syntax};
+//!
+//! let file = schema::parse(r#"
+//!     syntax = "proto3";
+//!     package foo.bar;
+//!
+//!     message SearchRequest {
+//!       string query = 1;
+//!       int32 page_number = 2;
+//!       map<string, int32> counts = 3;
+//!     }
+//! "#).unwrap();
+//!
+//! assert_eq!(file.syntax, Syntax::Proto3);
+//! assert_eq!(file.package.as_ref().map(|p| p.as_str()), Some("foo.bar"));
+//!
+//! let m = &file.messages[0];
+//! assert_eq!(m.name, "SearchRequest");
+//! assert_eq!(m.fields[1].ty, FieldType::Int32);
+//! assert_eq!(m.fields[2].number, 3);
+//! ```
+//!
+//! [proto2]: https://developers.google.com/protocol-buffers/docs/reference/proto2-spec
+//! [proto3]: https://developers.google.com/protocol-buffers/docs/reference/proto3-spec
+use std::fmt;
+
+pub use self::parser::parse;
+
+pub(crate) mod lexer;
+mod parser;
+
+/// Position in a source text.
+///
+/// Both `line` and `column` start from `1`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    /// Line number.
+    pub line: usize,
+
+    /// Column number (counted in characters).
+    pub column: usize,
+}
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Syntax version of a `.proto` file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Syntax {
+    #[default]
+    Proto2,
+    Proto3,
+}
+
+/// Parsed `.proto` file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProtoFile {
+    /// Syntax version.
+    ///
+    /// If the file does not have the `syntax` statement, `Syntax::Proto2` is used.
+    pub syntax: Syntax,
+
+    /// Package name.
+    pub package: Option<String>,
+
+    /// Import statements.
+    pub imports: Vec<Import>,
+
+    /// File level options.
+    pub options: Vec<OptionDef>,
+
+    /// Top level messages.
+    pub messages: Vec<Message>,
+
+    /// Top level enums.
+    pub enums: Vec<Enum>,
+
+    /// Services.
+    pub services: Vec<Service>,
+
+    /// Top level `extend` blocks.
+    pub extends: Vec<Extend>,
+}
+
+/// Import statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Import {
+    /// Path of the imported file.
+    pub path: String,
+
+    /// Kind of the import statement.
+    pub kind: ImportKind,
+
+    /// Position of the statement.
+    pub position: Position,
+}
+
+/// Kind of an import statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum ImportKind {
+    Default,
+    Public,
+    Weak,
+}
+
+/// Option definition (e.g., `option java_package = "foo";` or `[packed = true]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionDef {
+    /// Option name.
+    ///
+    /// Custom option names keep the parentheses (e.g., `(my.option).field`).
+    pub name: String,
+
+    /// Option value.
+    pub value: Constant,
+
+    /// Position of the option.
+    pub position: Position,
+}
+
+/// Constant value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    /// Identifier (e.g., an enum value name).
+    Ident(String),
+
+    /// Integer.
+    Int(i128),
+
+    /// Floating point number (including `inf` and `nan`).
+    Float(f64),
+
+    /// String.
+    Str(String),
+
+    /// String literal that is not a valid UTF-8 sequence (e.g., `"\377"`).
+    Bytes(Vec<u8>),
+
+    /// Boolean.
+    Bool(bool),
+
+    /// Aggregate value written in the text format (e.g., `{ foo: 1 }`).
+    ///
+    /// The text between the braces is kept as is.
+    Aggregate(String),
+}
+
+/// Message definition.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Message {
+    /// Message name.
+    pub name: String,
+
+    /// Fields that do not belong to any oneof.
+    pub fields: Vec<Field>,
+
+    /// Oneofs.
+    pub oneofs: Vec<Oneof>,
+
+    /// Nested messages (including the ones defined by `group` fields).
+    pub messages: Vec<Message>,
+
+    /// Nested enums.
+    pub enums: Vec<Enum>,
+
+    /// Nested `extend` blocks.
+    pub extends: Vec<Extend>,
+
+    /// Extension ranges.
+    pub extension_ranges: Vec<Range>,
+
+    /// Reserved field numbers and names.
+    pub reserved: Vec<Reserved>,
+
+    /// Message options.
+    pub options: Vec<OptionDef>,
+
+    /// Position of the definition.
+    pub position: Position,
+}
+
+/// Field definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// Field label.
+    ///
+    /// This is `None` for proto3 singular fields, map fields and oneof fields.
+    pub label: Option<Label>,
+
+    /// Field type.
+    pub ty: FieldType,
+
+    /// Field name.
+    pub name: String,
+
+    /// Field number.
+    pub number: u32,
+
+    /// Field options.
+    pub options: Vec<OptionDef>,
+
+    /// Position of the definition.
+    pub position: Position,
+}
+impl Field {
+    /// Returns `true` if the field is a repeated field, otherwise `false`.
+    ///
+    /// Note that map fields are not regarded as repeated fields.
+    pub fn is_repeated(&self) -> bool {
+        self.label == Some(Label::Repeated)
+    }
+
+    /// Returns `true` if the field is encoded in the packed format when it is a repeated numeric field.
+    ///
+    /// Repeated numeric fields are packed by default in proto3, and the `packed` option overrides the default.
+    pub fn is_packed(&self, syntax: Syntax) -> bool {
+        match self.option("packed") {
+            Some(Constant::Bool(b)) => *b,
+            _ => syntax == Syntax::Proto3,
+        }
+    }
+
+    /// Returns the value of the option named `name` if it is specified.
+    pub fn option(&self, name: &str) -> Option<&Constant> {
+        self.options
+            .iter()
+            .rev()
+            .find(|o| o.name == name)
+            .map(|o| &o.value)
+    }
+}
+
+/// Field label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Label {
+    Required,
+    Optional,
+    Repeated,
+}
+
+/// Field type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum FieldType {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+
+    /// Message or enum type.
+    ///
+    /// The name is kept as written in the source text (e.g., `Foo.Bar` or `.pkg.Foo`).
+    Named(String),
+
+    /// Map type.
+    Map(Box<FieldType>, Box<FieldType>),
+
+    /// Group type (proto2 only).
+    ///
+    /// The body of the group is stored as a nested message that has the given name.
+    Group(String),
+}
+impl FieldType {
+    /// Returns the scalar type that has the given name.
+    pub fn from_scalar_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => FieldType::Double,
+            "float" => FieldType::Float,
+            "int32" => FieldType::Int32,
+            "int64" => FieldType::Int64,
+            "uint32" => FieldType::Uint32,
+            "uint64" => FieldType::Uint64,
+            "sint32" => FieldType::Sint32,
+            "sint64" => FieldType::Sint64,
+            "fixed32" => FieldType::Fixed32,
+            "fixed64" => FieldType::Fixed64,
+            "sfixed32" => FieldType::Sfixed32,
+            "sfixed64" => FieldType::Sfixed64,
+            "bool" => FieldType::Bool,
+            "string" => FieldType::String,
+            "bytes" => FieldType::Bytes,
+            _ => return None,
+        })
+    }
+
+    /// Returns `true` if this is a scalar type, otherwise `false`.
+    pub fn is_scalar(&self) -> bool {
+        !matches!(
+            *self,
+            FieldType::Named(_) | FieldType::Map(..) | FieldType::Group(_)
+        )
+    }
+
+    /// Returns `true` if this is a scalar numeric type (i.e., a type that can be packed), otherwise `false`.
+    pub fn is_numeric(&self) -> bool {
+        match *self {
+            FieldType::String | FieldType::Bytes => false,
+            _ => self.is_scalar(),
+        }
+    }
+}
+
+/// Oneof definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oneof {
+    /// Oneof name.
+    pub name: String,
+
+    /// Fields of the oneof.
+    pub fields: Vec<Field>,
+
+    /// Oneof options.
+    pub options: Vec<OptionDef>,
+
+    /// Position of the definition.
+    pub position: Position,
+}
+
+/// Enum definition.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Enum {
+    /// Enum name.
+    pub name: String,
+
+    /// Enum values.
+    pub values: Vec<EnumValue>,
+
+    /// Reserved values and names.
+    pub reserved: Vec<Reserved>,
+
+    /// Enum options.
+    pub options: Vec<OptionDef>,
+
+    /// Position of the definition.
+    pub position: Position,
+}
+
+/// Enum value definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValue {
+    /// Name of the value.
+    pub name: String,
+
+    /// Number of the value.
+    pub number: i32,
+
+    /// Options of the value.
+    pub options: Vec<OptionDef>,
+
+    /// Position of the definition.
+    pub position: Position,
+}
+
+/// Inclusive range of field numbers or enum values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range {
+    /// Start of the range.
+    pub start: i64,
+
+    /// End of the range (inclusive).
+    ///
+    /// `max` is converted to the maximum value allowed in the context.
+    pub end: i64,
+}
+
+/// Reserved statement entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Reserved {
+    /// Reserved numbers.
+    Range(Range),
+
+    /// Reserved name.
+    Name(String),
+}
+
+/// `extend` block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extend {
+    /// Name of the extended message.
+    pub extendee: String,
+
+    /// Extension fields.
+    pub fields: Vec<Field>,
+
+    /// Messages defined by `group` fields in the block.
+    pub messages: Vec<Message>,
+
+    /// Position of the block.
+    pub position: Position,
+}
+
+/// Service definition.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Service {
+    /// Service name.
+    pub name: String,
+
+    /// RPC methods.
+    pub methods: Vec<Method>,
+
+    /// Service options.
+    pub options: Vec<OptionDef>,
+
+    /// Position of the definition.
+    pub position: Position,
+}
+
+/// RPC method definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Method {
+    /// Method name.
+    pub name: String,
+
+    /// Request message type.
+    pub input_type: String,
+
+    /// Whether the request is streamed.
+    pub client_streaming: bool,
+
+    /// Response message type.
+    pub output_type: String,
+
+    /// Whether the response is streamed.
+    pub server_streaming: bool,
+
+    /// Method options.
+    pub options: Vec<OptionDef>,
+
+    /// Position of the definition.
+    pub position: Position,
+}
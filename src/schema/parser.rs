@@ -0,0 +1,1202 @@
+use super::lexer::{self, Token, TokenKind};
+use super::{
+    Constant, Enum, EnumValue, Extend, Field, FieldType, Import, ImportKind, Label, Message,
+    Method, Oneof, OptionDef, Position, ProtoFile, Range, Reserved, Service, Syntax,
+};
+use crate::field::num::FieldNum;
+use bytecodec::{ErrorKind, Result};
+use std::collections::HashMap;
+
+const MAX_FIELD_NUM: i64 = (1 << 29) - 1;
+
+/// Parses the source text of a `.proto` file.
+///
+/// # Errors
+///
+/// If `text` is not a valid `.proto` file,
+/// an `ErrorKind::InvalidInput` error that contains the position of the problem will be returned.
+pub fn parse(text: &str) -> Result<ProtoFile> {
+    let tokens = track!(lexer::tokenize(text))?;
+    let mut parser = Parser {
+        text,
+        tokens,
+        index: 0,
+        syntax: Syntax::Proto2,
+    };
+    track!(parser.parse_file())
+}
+
+#[derive(Debug)]
+struct Parser<'a> {
+    text: &'a str,
+    tokens: Vec<Token>,
+    index: usize,
+    syntax: Syntax,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index]
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        let i = std::cmp::min(self.index + n, self.tokens.len() - 1);
+        &self.tokens[i]
+    }
+
+    fn position(&self) -> Position {
+        self.peek().position
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.index].clone();
+        if token.kind != TokenKind::Eof {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn is_symbol(&self, c: char) -> bool {
+        self.peek().kind == TokenKind::Symbol(c)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match self.peek().kind {
+            TokenKind::Ident(ref s) => s == keyword,
+            _ => false,
+        }
+    }
+
+    fn try_symbol(&mut self, c: char) -> bool {
+        if self.is_symbol(c) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn try_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, c: char) -> Result<()> {
+        let token = self.next();
+        track_assert_eq!(
+            token.kind,
+            TokenKind::Symbol(c),
+            ErrorKind::InvalidInput,
+            "{}: Expected `{}`",
+            token.position,
+            c
+        );
+        Ok(())
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        let position = self.position();
+        let ident = track!(self.expect_ident())?;
+        track_assert_eq!(
+            ident,
+            keyword,
+            ErrorKind::InvalidInput,
+            "{}: Expected `{}`",
+            position,
+            keyword
+        );
+        Ok(())
+    }
+
+    fn expect_ident(&mut self) -> Result<String> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Ident(s) => Ok(s),
+            kind => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Expected an identifier, but got {}",
+                token.position,
+                describe(&kind)
+            ),
+        }
+    }
+
+    fn expect_string(&mut self) -> Result<String> {
+        let position = self.position();
+        let bytes = track!(self.expect_string_bytes())?;
+        let s = track_assert_some!(
+            String::from_utf8(bytes).ok(),
+            ErrorKind::InvalidInput,
+            "{}: Invalid UTF-8 string",
+            position
+        );
+        Ok(s)
+    }
+
+    fn expect_string_bytes(&mut self) -> Result<Vec<u8>> {
+        let token = self.next();
+        let mut bytes = match token.kind {
+            TokenKind::Str(b) => b,
+            kind => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Expected a string literal, but got {}",
+                token.position,
+                describe(&kind)
+            ),
+        };
+
+        // Adjacent string literals are concatenated
+        while let TokenKind::Str(ref b) = self.peek().kind {
+            bytes.extend_from_slice(b);
+            self.next();
+        }
+        Ok(bytes)
+    }
+
+    fn expect_int(&mut self) -> Result<i128> {
+        let negative = self.try_symbol('-');
+        let token = self.next();
+        match token.kind {
+            TokenKind::Int(n) if negative => Ok(-i128::from(n)),
+            TokenKind::Int(n) => Ok(i128::from(n)),
+            kind => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Expected an integer literal, but got {}",
+                token.position,
+                describe(&kind)
+            ),
+        }
+    }
+
+    fn expect_field_number(&mut self) -> Result<u32> {
+        let position = self.position();
+        let n = track!(self.expect_int())?;
+        track_assert!(
+            0 < n && n <= i128::from(u32::MAX),
+            ErrorKind::InvalidInput,
+            "{}: Invalid field number: {}",
+            position,
+            n
+        );
+        track_assert!(
+            FieldNum::new(n as u32).is_ok(),
+            ErrorKind::InvalidInput,
+            "{}: Invalid field number: {}",
+            position,
+            n
+        );
+        Ok(n as u32)
+    }
+
+    // fullIdent = ident { "." ident }
+    fn expect_full_ident(&mut self) -> Result<String> {
+        let mut name = track!(self.expect_ident())?;
+        while self.is_symbol('.') {
+            self.next();
+            name.push('.');
+            name.push_str(&track!(self.expect_ident())?);
+        }
+        Ok(name)
+    }
+
+    // messageType = [ "." ] { ident "." } messageName
+    fn expect_type_name(&mut self) -> Result<String> {
+        let mut name = String::new();
+        if self.try_symbol('.') {
+            name.push('.');
+        }
+        name.push_str(&track!(self.expect_full_ident())?);
+        Ok(name)
+    }
+
+    fn parse_file(&mut self) -> Result<ProtoFile> {
+        let mut file = ProtoFile::default();
+        if self.is_keyword("syntax") {
+            self.next();
+            track!(self.expect_symbol('='))?;
+            let syntax_position = self.position();
+            let syntax = track!(self.expect_string())?;
+            track!(self.expect_symbol(';'))?;
+            file.syntax = match syntax.as_str() {
+                "proto2" => Syntax::Proto2,
+                "proto3" => Syntax::Proto3,
+                _ => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Unknown syntax: {:?}",
+                    syntax_position,
+                    syntax
+                ),
+            };
+        }
+        self.syntax = file.syntax;
+
+        loop {
+            let position = self.position();
+            let token = self.peek().clone();
+            let keyword = match token.kind {
+                TokenKind::Eof => break,
+                TokenKind::Symbol(';') => {
+                    self.next();
+                    continue;
+                }
+                TokenKind::Ident(ref s) => s.clone(),
+                ref kind => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Unexpected {}",
+                    position,
+                    describe(kind)
+                ),
+            };
+            match keyword.as_str() {
+                "import" => {
+                    self.next();
+                    let kind = if self.try_keyword("public") {
+                        ImportKind::Public
+                    } else if self.try_keyword("weak") {
+                        ImportKind::Weak
+                    } else {
+                        ImportKind::Default
+                    };
+                    let path = track!(self.expect_string())?;
+                    track!(self.expect_symbol(';'))?;
+                    file.imports.push(Import {
+                        path,
+                        kind,
+                        position,
+                    });
+                }
+                "package" => {
+                    self.next();
+                    track_assert!(
+                        file.package.is_none(),
+                        ErrorKind::InvalidInput,
+                        "{}: Duplicate package statement",
+                        position
+                    );
+                    file.package = Some(track!(self.expect_full_ident())?);
+                    track!(self.expect_symbol(';'))?;
+                }
+                "option" => file.options.push(track!(self.parse_option_statement())?),
+                "message" => file.messages.push(track!(self.parse_message())?),
+                "enum" => file.enums.push(track!(self.parse_enum())?),
+                "service" => file.services.push(track!(self.parse_service())?),
+                "extend" => file.extends.push(track!(self.parse_extend())?),
+                "syntax" => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: The syntax statement must be the first statement of the file",
+                    position
+                ),
+                _ => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Unexpected identifier: {:?}",
+                    position,
+                    keyword
+                ),
+            }
+        }
+        Ok(file)
+    }
+
+    // option = "option" optionName  "=" constant ";"
+    fn parse_option_statement(&mut self) -> Result<OptionDef> {
+        track!(self.expect_keyword("option"))?;
+        let option = track!(self.parse_option())?;
+        track!(self.expect_symbol(';'))?;
+        Ok(option)
+    }
+
+    // optionName = ( ident | "(" fullIdent ")" ) { "." ident }
+    fn parse_option(&mut self) -> Result<OptionDef> {
+        let position = self.position();
+        let mut name = String::new();
+        if self.try_symbol('(') {
+            name.push('(');
+            if self.try_symbol('.') {
+                name.push('.');
+            }
+            name.push_str(&track!(self.expect_full_ident())?);
+            track!(self.expect_symbol(')'))?;
+            name.push(')');
+        } else {
+            name.push_str(&track!(self.expect_ident())?);
+        }
+        while self.try_symbol('.') {
+            name.push('.');
+            if self.try_symbol('(') {
+                name.push('(');
+                name.push_str(&track!(self.expect_type_name())?);
+                track!(self.expect_symbol(')'))?;
+                name.push(')');
+            } else {
+                name.push_str(&track!(self.expect_ident())?);
+            }
+        }
+        track!(self.expect_symbol('='))?;
+        let value = track!(self.parse_constant())?;
+        Ok(OptionDef {
+            name,
+            value,
+            position,
+        })
+    }
+
+    // fieldOptions = "[" fieldOption { ","  fieldOption } "]"
+    fn parse_field_options(&mut self) -> Result<Vec<OptionDef>> {
+        let mut options = Vec::new();
+        if self.try_symbol('[') {
+            loop {
+                options.push(track!(self.parse_option())?);
+                if !self.try_symbol(',') {
+                    break;
+                }
+            }
+            track!(self.expect_symbol(']'))?;
+        }
+        Ok(options)
+    }
+
+    // constant = fullIdent | ( [ "-" | "+" ] intLit ) | ( [ "-" | "+" ] floatLit ) | strLit | boolLit
+    fn parse_constant(&mut self) -> Result<Constant> {
+        let position = self.position();
+        if self.is_symbol('{') {
+            return track!(self.parse_aggregate());
+        }
+
+        let negative = if self.try_symbol('-') {
+            true
+        } else {
+            self.try_symbol('+');
+            false
+        };
+        let token = self.next();
+        let sign = if negative { -1.0 } else { 1.0 };
+        let constant = match token.kind {
+            TokenKind::Int(n) if negative => Constant::Int(-i128::from(n)),
+            TokenKind::Int(n) => Constant::Int(i128::from(n)),
+            TokenKind::Float(f) => Constant::Float(sign * f),
+            TokenKind::Ident(ref s) if s == "inf" => Constant::Float(sign * f64::INFINITY),
+            TokenKind::Ident(ref s) if s == "nan" => Constant::Float(f64::NAN),
+            TokenKind::Ident(_) if negative => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Expected a numeric literal",
+                position
+            ),
+            TokenKind::Ident(ref s) if s == "true" => Constant::Bool(true),
+            TokenKind::Ident(ref s) if s == "false" => Constant::Bool(false),
+            TokenKind::Ident(s) => {
+                let mut name = s;
+                while self.try_symbol('.') {
+                    name.push('.');
+                    name.push_str(&track!(self.expect_ident())?);
+                }
+                Constant::Ident(name)
+            }
+            TokenKind::Str(_) if !negative => {
+                self.index -= 1;
+                let bytes = track!(self.expect_string_bytes())?;
+                match String::from_utf8(bytes) {
+                    Ok(s) => Constant::Str(s),
+                    Err(e) => Constant::Bytes(e.into_bytes()),
+                }
+            }
+            kind => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Expected a constant, but got {}",
+                token.position,
+                describe(&kind)
+            ),
+        };
+        Ok(constant)
+    }
+
+    fn parse_aggregate(&mut self) -> Result<Constant> {
+        let open = self.next();
+        let mut depth = 1;
+        loop {
+            let token = self.next();
+            match token.kind {
+                TokenKind::Symbol('{') => depth += 1,
+                TokenKind::Symbol('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let text = self.text[open.end..token.start].trim();
+                        return Ok(Constant::Aggregate(text.to_owned()));
+                    }
+                }
+                TokenKind::Eof => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Unterminated aggregate value",
+                    open.position
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    // message = "message" messageName messageBody
+    fn parse_message(&mut self) -> Result<Message> {
+        let position = self.position();
+        track!(self.expect_keyword("message"))?;
+        let name = track!(self.expect_ident())?;
+        let mut message = Message {
+            name,
+            position,
+            ..Message::default()
+        };
+        track!(self.parse_message_body(&mut message))?;
+        Ok(message)
+    }
+
+    // messageBody = "{" { field | enum | message | extend | extensions | group |
+    //                     option | oneof | mapField | reserved | emptyStatement } "}"
+    fn parse_message_body(&mut self, message: &mut Message) -> Result<()> {
+        track!(self.expect_symbol('{'))?;
+        loop {
+            let position = self.position();
+            if self.try_symbol('}') {
+                break;
+            }
+            if self.try_symbol(';') {
+                continue;
+            }
+            let keyword = match self.peek().kind {
+                TokenKind::Ident(ref s) => s.clone(),
+                TokenKind::Symbol('.') => String::new(),
+                ref kind => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Unexpected {}",
+                    position,
+                    describe(kind)
+                ),
+            };
+            let next_is_ident = matches!(self.peek_nth(1).kind, TokenKind::Ident(_));
+            match keyword.as_str() {
+                "message" if next_is_ident => message.messages.push(track!(self.parse_message())?),
+                "enum" if next_is_ident => message.enums.push(track!(self.parse_enum())?),
+                "extend" if next_is_ident => message.extends.push(track!(self.parse_extend())?),
+                "oneof" if next_is_ident => message.oneofs.push(track!(self.parse_oneof())?),
+                "option" if next_is_ident || self.peek_nth(1).kind == TokenKind::Symbol('(') => {
+                    message.options.push(track!(self.parse_option_statement())?)
+                }
+                "extensions" if !next_is_ident => {
+                    self.next();
+                    let ranges = track!(self.parse_ranges(MAX_FIELD_NUM))?;
+                    let _ = track!(self.parse_field_options())?;
+                    track!(self.expect_symbol(';'))?;
+                    message.extension_ranges.extend(ranges);
+                }
+                "reserved" if !next_is_ident => {
+                    self.next();
+                    let reserved = track!(self.parse_reserved(MAX_FIELD_NUM))?;
+                    message.reserved.extend(reserved);
+                }
+                _ => {
+                    let field = track!(self.parse_field(&mut message.messages, true))?;
+                    message.fields.push(field);
+                }
+            }
+        }
+        let fields = message
+            .fields
+            .iter()
+            .chain(message.oneofs.iter().flat_map(|o| o.fields.iter()));
+        track!(check_fields(fields, &message.reserved))?;
+        Ok(())
+    }
+
+    // field = [ label ] type fieldName "=" fieldNumber [ "[" fieldOptions "]" ] ";"
+    // group = label "group" groupName "=" fieldNumber messageBody
+    // mapField = "map" "<" keyType "," type ">" mapName "=" fieldNumber [ "[" fieldOptions "]" ] ";"
+    fn parse_field(&mut self, messages: &mut Vec<Message>, allow_label: bool) -> Result<Field> {
+        let position = self.position();
+        let label = if !allow_label {
+            None
+        } else if self.try_keyword("required") {
+            Some(Label::Required)
+        } else if self.try_keyword("optional") {
+            Some(Label::Optional)
+        } else if self.try_keyword("repeated") {
+            Some(Label::Repeated)
+        } else {
+            None
+        };
+        if self.syntax == Syntax::Proto2 && allow_label && !self.is_map_type() {
+            track_assert!(
+                label.is_some(),
+                ErrorKind::InvalidInput,
+                "{}: Missing field label",
+                position
+            );
+        }
+        track_assert_ne!(
+            (self.syntax, label),
+            (Syntax::Proto3, Some(Label::Required)),
+            ErrorKind::InvalidInput,
+            "{}: Required fields are not allowed in proto3",
+            position
+        );
+
+        if self.is_keyword("group")
+            && self.peek_nth(1).kind != TokenKind::Symbol('=')
+            && self.peek_nth(1).kind != TokenKind::Symbol('.')
+        {
+            track_assert_eq!(
+                self.syntax,
+                Syntax::Proto2,
+                ErrorKind::InvalidInput,
+                "{}: Groups are not allowed in proto3",
+                position
+            );
+            self.next();
+            let name_position = self.position();
+            let name = track!(self.expect_ident())?;
+            track_assert!(
+                name.starts_with(|c: char| c.is_ascii_uppercase()),
+                ErrorKind::InvalidInput,
+                "{}: Group names must start with a capital letter",
+                name_position
+            );
+            track!(self.expect_symbol('='))?;
+            let number = track!(self.expect_field_number())?;
+            let options = track!(self.parse_field_options())?;
+            let mut group = Message {
+                name: name.clone(),
+                position,
+                ..Message::default()
+            };
+            track!(self.parse_message_body(&mut group))?;
+            messages.push(group);
+            return Ok(Field {
+                label,
+                ty: FieldType::Group(name.clone()),
+                name: name.to_lowercase(),
+                number,
+                options,
+                position,
+            });
+        }
+
+        let ty = if self.is_map_type() {
+            track_assert!(
+                label.is_none(),
+                ErrorKind::InvalidInput,
+                "{}: Map fields cannot have labels",
+                position
+            );
+            self.next();
+            track!(self.expect_symbol('<'))?;
+            let key_position = self.position();
+            let key = track!(self.parse_type())?;
+            let is_valid_key = match key {
+                FieldType::Double | FieldType::Float | FieldType::Bytes => false,
+                ref ty => ty.is_scalar(),
+            };
+            track_assert!(
+                is_valid_key,
+                ErrorKind::InvalidInput,
+                "{}: Invalid map key type: {:?}",
+                key_position,
+                key
+            );
+            track!(self.expect_symbol(','))?;
+            let value_position = self.position();
+            let value = track!(self.parse_type())?;
+            if let FieldType::Map(..) = value {
+                track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Map values cannot be maps",
+                    value_position
+                );
+            }
+            track!(self.expect_symbol('>'))?;
+            FieldType::Map(Box::new(key), Box::new(value))
+        } else {
+            track!(self.parse_type())?
+        };
+        let name = track!(self.expect_ident())?;
+        track!(self.expect_symbol('='))?;
+        let number = track!(self.expect_field_number())?;
+        let options = track!(self.parse_field_options())?;
+        track!(self.expect_symbol(';'))?;
+        Ok(Field {
+            label,
+            ty,
+            name,
+            number,
+            options,
+            position,
+        })
+    }
+
+    fn is_map_type(&self) -> bool {
+        self.is_keyword("map") && self.peek_nth(1).kind == TokenKind::Symbol('<')
+    }
+
+    fn parse_type(&mut self) -> Result<FieldType> {
+        if let TokenKind::Ident(ref s) = self.peek().kind {
+            if let Some(ty) = FieldType::from_scalar_name(s) {
+                if self.peek_nth(1).kind != TokenKind::Symbol('.') {
+                    self.next();
+                    return Ok(ty);
+                }
+            }
+        }
+        let name = track!(self.expect_type_name())?;
+        Ok(FieldType::Named(name))
+    }
+
+    // oneof = "oneof" oneofName "{" { option | oneofField | emptyStatement } "}"
+    fn parse_oneof(&mut self) -> Result<Oneof> {
+        let position = self.position();
+        track!(self.expect_keyword("oneof"))?;
+        let name = track!(self.expect_ident())?;
+        let mut oneof = Oneof {
+            name,
+            fields: Vec::new(),
+            options: Vec::new(),
+            position,
+        };
+        track!(self.expect_symbol('{'))?;
+        loop {
+            let position = self.position();
+            if self.try_symbol('}') {
+                break;
+            }
+            if self.try_symbol(';') {
+                continue;
+            }
+            if self.is_keyword("option") {
+                oneof.options.push(track!(self.parse_option_statement())?);
+                continue;
+            }
+            track_assert!(
+                !(self.is_keyword("required")
+                    || self.is_keyword("optional")
+                    || self.is_keyword("repeated")),
+                ErrorKind::InvalidInput,
+                "{}: Oneof fields cannot have labels",
+                position
+            );
+            track_assert!(
+                !self.is_map_type(),
+                ErrorKind::InvalidInput,
+                "{}: Map fields are not allowed in oneofs",
+                position
+            );
+            let mut messages = Vec::new();
+            let field = track!(self.parse_field(&mut messages, false))?;
+            oneof.fields.push(field);
+        }
+        track_assert!(
+            !oneof.fields.is_empty(),
+            ErrorKind::InvalidInput,
+            "{}: Empty oneof",
+            oneof.position
+        );
+        Ok(oneof)
+    }
+
+    // ranges = range { "," range }
+    // range =  intLit [ "to" ( intLit | "max" ) ]
+    fn parse_ranges(&mut self, max: i64) -> Result<Vec<Range>> {
+        let mut ranges = Vec::new();
+        loop {
+            let position = self.position();
+            let start = track!(self.expect_int())?;
+            let end = if self.try_keyword("to") {
+                if self.try_keyword("max") {
+                    i128::from(max)
+                } else {
+                    track!(self.expect_int())?
+                }
+            } else {
+                start
+            };
+            track_assert!(
+                start <= end && end <= i128::from(max) && -i128::from(max) - 1 <= start,
+                ErrorKind::InvalidInput,
+                "{}: Invalid range: {} to {}",
+                position,
+                start,
+                end
+            );
+            ranges.push(Range {
+                start: start as i64,
+                end: end as i64,
+            });
+            if !self.try_symbol(',') {
+                return Ok(ranges);
+            }
+        }
+    }
+
+    // reserved = "reserved" ( ranges | strFieldNames ) ";"
+    fn parse_reserved(&mut self, max: i64) -> Result<Vec<Reserved>> {
+        let reserved = if let TokenKind::Str(_) = self.peek().kind {
+            let mut names = Vec::new();
+            loop {
+                names.push(Reserved::Name(track!(self.expect_string())?));
+                if !self.try_symbol(',') {
+                    break;
+                }
+            }
+            names
+        } else {
+            let ranges = track!(self.parse_ranges(max))?;
+            ranges.into_iter().map(Reserved::Range).collect()
+        };
+        track!(self.expect_symbol(';'))?;
+        Ok(reserved)
+    }
+
+    // enum = "enum" enumName enumBody
+    // enumBody = "{" { option | enumField | reserved | emptyStatement } "}"
+    // enumField = ident "=" [ "-" ] intLit [ "[" enumValueOption { ","  enumValueOption } "]" ]";"
+    fn parse_enum(&mut self) -> Result<Enum> {
+        let position = self.position();
+        track!(self.expect_keyword("enum"))?;
+        let name = track!(self.expect_ident())?;
+        let mut e = Enum {
+            name,
+            position,
+            ..Enum::default()
+        };
+        track!(self.expect_symbol('{'))?;
+        loop {
+            let position = self.position();
+            if self.try_symbol('}') {
+                break;
+            }
+            if self.try_symbol(';') {
+                continue;
+            }
+            let next_is_assign = self.peek_nth(1).kind == TokenKind::Symbol('=');
+            if self.is_keyword("option") && !next_is_assign {
+                e.options.push(track!(self.parse_option_statement())?);
+            } else if self.is_keyword("reserved") && !next_is_assign {
+                self.next();
+                let max = i64::from(i32::MAX);
+                e.reserved.extend(track!(self.parse_reserved(max))?);
+            } else {
+                let name = track!(self.expect_ident())?;
+                track!(self.expect_symbol('='))?;
+                let number_position = self.position();
+                let number = track!(self.expect_int())?;
+                track_assert!(
+                    i128::from(i32::MIN) <= number && number <= i128::from(i32::MAX),
+                    ErrorKind::InvalidInput,
+                    "{}: Enum value out of range: {}",
+                    number_position,
+                    number
+                );
+                let options = track!(self.parse_field_options())?;
+                track!(self.expect_symbol(';'))?;
+                e.values.push(EnumValue {
+                    name,
+                    number: number as i32,
+                    options,
+                    position,
+                });
+            }
+        }
+        if self.syntax == Syntax::Proto3 {
+            track_assert!(
+                e.values.first().map(|v| v.number) == Some(0),
+                ErrorKind::InvalidInput,
+                "{}: The first enum value must be zero in proto3",
+                e.position
+            );
+        }
+        Ok(e)
+    }
+
+    // extend = "extend" messageType "{" {field | group | emptyStatement} "}"
+    fn parse_extend(&mut self) -> Result<Extend> {
+        let position = self.position();
+        track!(self.expect_keyword("extend"))?;
+        let extendee = track!(self.expect_type_name())?;
+        let mut extend = Extend {
+            extendee,
+            fields: Vec::new(),
+            messages: Vec::new(),
+            position,
+        };
+        track!(self.expect_symbol('{'))?;
+        loop {
+            if self.try_symbol('}') {
+                break;
+            }
+            if self.try_symbol(';') {
+                continue;
+            }
+            let field = track!(self.parse_field(&mut extend.messages, true))?;
+            extend.fields.push(field);
+        }
+        track!(check_fields(&extend.fields, &[]))?;
+        Ok(extend)
+    }
+
+    // service = "service" serviceName "{" { option | rpc | emptyStatement } "}"
+    // rpc = "rpc" rpcName "(" [ "stream" ] messageType ")" "returns" "(" [ "stream" ]
+    //       messageType ")" (( "{" {option | emptyStatement } "}" ) | ";")
+    fn parse_service(&mut self) -> Result<Service> {
+        let position = self.position();
+        track!(self.expect_keyword("service"))?;
+        let name = track!(self.expect_ident())?;
+        let mut service = Service {
+            name,
+            position,
+            ..Service::default()
+        };
+        track!(self.expect_symbol('{'))?;
+        loop {
+            let position = self.position();
+            if self.try_symbol('}') {
+                break;
+            }
+            if self.try_symbol(';') {
+                continue;
+            }
+            if self.is_keyword("option") {
+                service.options.push(track!(self.parse_option_statement())?);
+                continue;
+            }
+            track!(self.expect_keyword("rpc"))?;
+            let name = track!(self.expect_ident())?;
+            track!(self.expect_symbol('('))?;
+            let client_streaming = self.is_keyword("stream")
+                && self.peek_nth(1).kind != TokenKind::Symbol(')')
+                && self.try_keyword("stream");
+            let input_type = track!(self.expect_type_name())?;
+            track!(self.expect_symbol(')'))?;
+            track!(self.expect_keyword("returns"))?;
+            track!(self.expect_symbol('('))?;
+            let server_streaming = self.is_keyword("stream")
+                && self.peek_nth(1).kind != TokenKind::Symbol(')')
+                && self.try_keyword("stream");
+            let output_type = track!(self.expect_type_name())?;
+            track!(self.expect_symbol(')'))?;
+            let mut options = Vec::new();
+            if self.try_symbol('{') {
+                loop {
+                    if self.try_symbol('}') {
+                        break;
+                    }
+                    if self.try_symbol(';') {
+                        continue;
+                    }
+                    options.push(track!(self.parse_option_statement())?);
+                }
+            } else {
+                track!(self.expect_symbol(';'))?;
+            }
+            service.methods.push(Method {
+                name,
+                input_type,
+                client_streaming,
+                output_type,
+                server_streaming,
+                options,
+                position,
+            });
+        }
+        Ok(service)
+    }
+}
+
+// Rejects duplicate field numbers and fields that use reserved numbers or names.
+fn check_fields<'a, I>(fields: I, reserved: &[Reserved]) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Field>,
+{
+    let mut fields = fields.into_iter().collect::<Vec<_>>();
+    fields.sort_by_key(|f| f.position);
+    let mut numbers = HashMap::new();
+    for field in fields {
+        if let Some(other) = numbers.insert(field.number, &field.name) {
+            track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Field number {} is already used by `{}`",
+                field.position,
+                field.number,
+                other
+            );
+        }
+        for r in reserved {
+            match *r {
+                Reserved::Range(ref range) => track_assert!(
+                    !(range.start..=range.end).contains(&i64::from(field.number)),
+                    ErrorKind::InvalidInput,
+                    "{}: Field number {} is reserved",
+                    field.position,
+                    field.number
+                ),
+                Reserved::Name(ref name) => track_assert!(
+                    *name != field.name,
+                    ErrorKind::InvalidInput,
+                    "{}: Field name `{}` is reserved",
+                    field.position,
+                    field.name
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match *kind {
+        TokenKind::Ident(ref s) => format!("identifier {:?}", s),
+        TokenKind::Int(n) => format!("integer {}", n),
+        TokenKind::Float(f) => format!("float {}", f),
+        TokenKind::Str(ref b) => format!("string {:?}", String::from_utf8_lossy(b)),
+        TokenKind::Symbol(c) => format!("`{}`", c),
+        TokenKind::Eof => "end of file".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proto3_works() {
+        let file = track_try_unwrap!(parse(
+            r#"
+syntax = "proto3";
+
+package foo.bar;
+
+import "google/protobuf/duration.proto";
+import public "other.proto";
+
+option java_package = "com.example.foo";
+option (my_option).a = { b: 1 c: "x" };
+
+// A message
+message SearchRequest {
+  string query = 1;
+  int32 page_number = 2 [deprecated = true];
+  repeated int32 samples = 3 [packed = false];
+  map<string, Project> projects = 4;
+  .foo.bar.Corpus corpus = 5;
+  optional sint64 n = 6;
+  reserved 9, 10 to 11, 100 to max;
+  reserved "foo", "bar";
+
+  message Project {
+    google.protobuf.Duration elapsed = 1;
+  }
+
+  oneof test_oneof {
+    string name = 7;
+    Project sub_message = 8;
+  }
+}
+
+enum Corpus {
+  option allow_alias = true;
+  UNIVERSAL = 0;
+  WEB = 1;
+  IMAGES = -2 [deprecated = true];
+  reserved 5 to 6;
+}
+
+service SearchService {
+  rpc Search (SearchRequest) returns (stream Corpus);
+  rpc Ping (stream .foo.bar.Corpus) returns (Corpus) { option deadline = 1.5; }
+}
+"#
+        ));
+        assert_eq!(file.syntax, Syntax::Proto3);
+        assert_eq!(file.package, Some("foo.bar".to_owned()));
+        assert_eq!(file.imports.len(), 2);
+        assert_eq!(file.imports[1].kind, ImportKind::Public);
+        assert_eq!(file.imports[1].position, Position { line: 7, column: 1 });
+        assert_eq!(file.options[0].name, "java_package");
+        assert_eq!(
+            file.options[0].value,
+            Constant::Str("com.example.foo".to_owned())
+        );
+        assert_eq!(file.options[1].name, "(my_option).a");
+        assert_eq!(
+            file.options[1].value,
+            Constant::Aggregate("b: 1 c: \"x\"".to_owned())
+        );
+
+        let m = &file.messages[0];
+        assert_eq!(m.name, "SearchRequest");
+        assert_eq!(
+            m.position,
+            Position {
+                line: 13,
+                column: 1
+            }
+        );
+        assert_eq!(m.fields.len(), 6);
+        assert_eq!(m.fields[0].label, None);
+        assert_eq!(
+            m.fields[1].option("deprecated"),
+            Some(&Constant::Bool(true))
+        );
+        assert!(m.fields[2].is_repeated());
+        assert_eq!(
+            m.fields[3].ty,
+            FieldType::Map(
+                Box::new(FieldType::String),
+                Box::new(FieldType::Named("Project".to_owned()))
+            )
+        );
+        assert_eq!(
+            m.fields[4].ty,
+            FieldType::Named(".foo.bar.Corpus".to_owned())
+        );
+        assert_eq!(m.fields[5].label, Some(Label::Optional));
+        assert_eq!(
+            m.reserved,
+            vec![
+                Reserved::Range(Range { start: 9, end: 9 }),
+                Reserved::Range(Range { start: 10, end: 11 }),
+                Reserved::Range(Range {
+                    start: 100,
+                    end: MAX_FIELD_NUM
+                }),
+                Reserved::Name("foo".to_owned()),
+                Reserved::Name("bar".to_owned()),
+            ]
+        );
+        assert_eq!(m.messages[0].name, "Project");
+        assert_eq!(
+            m.messages[0].fields[0].ty,
+            FieldType::Named("google.protobuf.Duration".to_owned())
+        );
+        assert_eq!(m.oneofs[0].name, "test_oneof");
+        assert_eq!(m.oneofs[0].fields[1].number, 8);
+
+        let e = &file.enums[0];
+        assert_eq!(e.values.len(), 3);
+        assert_eq!(e.values[2].number, -2);
+        assert_eq!(e.options[0].value, Constant::Bool(true));
+
+        let s = &file.services[0];
+        assert_eq!(s.methods[0].input_type, "SearchRequest");
+        assert!(!s.methods[0].client_streaming);
+        assert!(s.methods[0].server_streaming);
+        assert!(s.methods[1].client_streaming);
+        assert_eq!(s.methods[1].options[0].value, Constant::Float(1.5));
+    }
+
+    #[test]
+    fn parse_proto2_works() {
+        let file = track_try_unwrap!(parse(
+            r#"
+message Foo {
+  required int32 a = 1 [default = -10];
+  optional bytes b = 2 [default = "\377"];
+  repeated group Item = 3 {
+    optional string name = 1;
+  }
+  extensions 100 to 199;
+}
+
+extend Foo {
+  optional int32 bar = 100;
+}
+"#
+        ));
+        assert_eq!(file.syntax, Syntax::Proto2);
+
+        let m = &file.messages[0];
+        assert_eq!(m.fields[0].label, Some(Label::Required));
+        assert_eq!(m.fields[0].option("default"), Some(&Constant::Int(-10)));
+        assert_eq!(
+            m.fields[1].option("default"),
+            Some(&Constant::Bytes(vec![0xFF]))
+        );
+        assert_eq!(m.fields[2].ty, FieldType::Group("Item".to_owned()));
+        assert_eq!(m.fields[2].name, "item");
+        assert_eq!(m.messages[0].name, "Item");
+        assert_eq!(
+            m.extension_ranges,
+            vec![Range {
+                start: 100,
+                end: 199
+            }]
+        );
+        assert_eq!(file.extends[0].extendee, "Foo");
+        assert_eq!(file.extends[0].fields[0].number, 100);
+    }
+
+    #[test]
+    fn errors_have_positions() {
+        fn error(text: &str) -> String {
+            parse(text).err().map(|e| e.to_string()).unwrap_or_default()
+        }
+
+        assert!(
+            error("syntax = \"proto3\";\nmessage Foo {\n  int32 a = 1\n}")
+                .contains("4:1: Expected `;`")
+        );
+        assert!(
+            error("syntax = \"proto3\";\nmessage Foo { int32 a = 19000; }")
+                .contains("2:25: Invalid field number: 19000")
+        );
+        assert!(error("message Foo { int32 a = 1; }").contains("1:15: Missing field label"));
+        assert!(error("syntax = \"proto4\";").contains("1:10: Unknown syntax"));
+        assert!(error("syntax = \"proto3\";\nenum E { A = 1; }")
+            .contains("2:1: The first enum value must be zero"));
+        assert!(
+            error("syntax = \"proto3\";\nmessage Foo { map<float, int32> a = 1; }")
+                .contains("2:19: Invalid map key type")
+        );
+    }
+
+    fn invalid_input(text: &str) -> String {
+        match parse(text) {
+            Ok(_) => panic!("Unexpectedly parsed: {:?}", text),
+            Err(e) => {
+                assert_eq!(*e.kind(), ErrorKind::InvalidInput);
+                e.to_string()
+            }
+        }
+    }
+
+    #[test]
+    fn duplicate_field_numbers_are_rejected() {
+        assert!(invalid_input(
+            "syntax = \"proto3\";\nmessage Foo {\n  int32 a = 1;\n  int32 b = 1;\n}"
+        )
+        .contains("4:3: Field number 1 is already used by `a`"));
+        assert!(invalid_input(
+            "syntax = \"proto3\";\nmessage Foo {\n  int32 a = 1;\n  oneof o { int32 b = 1; }\n}"
+        )
+        .contains("4:13: Field number 1 is already used by `a`"));
+        assert!(invalid_input(
+            "message Foo { extensions 100 to 199; }\nextend Foo {\n  optional int32 a = 100;\n  optional int32 b = 100;\n}"
+        )
+        .contains("4:3: Field number 100 is already used by `a`"));
+    }
+
+    #[test]
+    fn reserved_fields_are_rejected() {
+        assert!(invalid_input(
+            "syntax = \"proto3\";\nmessage Foo {\n  int32 a = 1;\n  reserved 1;\n}"
+        )
+        .contains("3:3: Field number 1 is reserved"));
+        assert!(invalid_input(
+            "syntax = \"proto3\";\nmessage Foo {\n  reserved 2 to 4;\n  oneof o { int32 b = 3; }\n}"
+        )
+        .contains("4:13: Field number 3 is reserved"));
+        assert!(invalid_input(
+            "syntax = \"proto3\";\nmessage Foo {\n  reserved \"a\";\n  int32 a = 1;\n}"
+        )
+        .contains("4:3: Field name `a` is reserved"));
+        assert!(invalid_input(
+            "syntax = \"proto3\";\nmessage Foo {\n  reserved \"b\";\n  oneof o { int32 b = 1; }\n}"
+        )
+        .contains("4:13: Field name `b` is reserved"));
+    }
+}
@@ -0,0 +1,347 @@
+//! Zero-copy decoding of `bytes` and `string` values into `bytes::Bytes`.
+//!
+//! This module is available only if the `bytes` feature is enabled.
+//!
+//! `SharedBytesDecoder` and `SharedStringDecoder` can be used in place of `BytesDecoder` and `StringDecoder`.
+//! When an input is decoded by `decode_shared`,
+//! their payloads are returned as slices of the input buffer instead of being copied.
+//! Otherwise (e.g., when an input stream is decoded incrementally), the payloads are copied as usual.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytes;
+//! # extern crate protobuf_codec;
+//! use bytes::Bytes;
+//! use protobuf_codec::field::{Fields, FieldDecoder, MaybeDefault};
+//! use protobuf_codec::field::num::{F1, F2};
+//! use protobuf_codec::message::MessageDecoder;
+//! use protobuf_codec::shared::{decode_shared, SharedBytesDecoder, SharedStringDecoder};
+//!
+//! # fn main() {
+//! // message Blob { string name = 1; bytes data = 2; }
+//! type BlobDecoder = MessageDecoder<
+//!     Fields<(
+//!         MaybeDefault<FieldDecoder<F1, SharedStringDecoder>>,
+//!         MaybeDefault<FieldDecoder<F2, SharedBytesDecoder>>,
+//!     )>,
+//! >;
+//!
+//! let input = Bytes::from(vec![10, 3, 102, 111, 111, 18, 2, 0, 1]);
+//! let (name, data) = decode_shared(&mut BlobDecoder::default(), &input).unwrap();
+//! assert_eq!(name, "foo");
+//! assert_eq!(data, [0, 1][..]);
+//! assert_eq!(data.as_ptr(), input[7..].as_ptr());
+//! # }
+//! ```
+use crate::value::{MapKeyDecode, ValueDecode};
+use crate::wire::{LengthDelimitedDecoder, WireType};
+use bytecodec::{ByteCount, Decode, DecodeExt, Eos, ErrorKind, Result};
+use bytes::Bytes;
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use std::str;
+use trackable::error::ErrorKindExt;
+
+thread_local! {
+    static SOURCE: RefCell<Option<Bytes>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug)]
+struct Scope {
+    prev: Option<Bytes>,
+}
+impl Scope {
+    fn enter(source: Bytes) -> Self {
+        let prev = SOURCE.with(|s| s.replace(Some(source)));
+        Scope { prev }
+    }
+}
+impl Drop for Scope {
+    fn drop(&mut self) {
+        SOURCE.with(|s| *s.borrow_mut() = self.prev.take());
+    }
+}
+
+// Returns the slice of the input of the running `decode_shared` if `buf` is a part of it.
+fn source_slice(buf: &[u8]) -> Option<Bytes> {
+    SOURCE.with(|s| {
+        let s = s.borrow();
+        let source = s.as_ref()?;
+        let start = source.as_ptr() as usize;
+        let p = buf.as_ptr() as usize;
+        if start <= p && p + buf.len() <= start + source.len() {
+            Some(source.slice_ref(buf))
+        } else {
+            None
+        }
+    })
+}
+
+/// Decodes the whole of `bytes` by using `decoder`.
+///
+/// The payloads decoded by `SharedBytesDecoder` and `SharedStringDecoder` during the call
+/// share the memory with `bytes`.
+pub fn decode_shared<D: Decode>(decoder: &mut D, bytes: &Bytes) -> Result<D::Item> {
+    let _scope = Scope::enter(bytes.clone());
+    track!(decoder.decode_from_bytes(bytes))
+}
+
+/// Decoder for `bytes` values that returns `Bytes` instead of `Vec<u8>`.
+///
+/// See [the module documentation](./index.html) for when the payloads are not copied.
+#[derive(Debug, Default)]
+pub struct SharedBytesDecoder(LengthDelimitedDecoder<PayloadDecoder>);
+impl SharedBytesDecoder {
+    /// Makes a new `SharedBytesDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for SharedBytesDecoder {
+    type Item = Bytes;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track!(self.0.finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+}
+impl ValueDecode for SharedBytesDecoder {
+    fn wire_type(&self) -> WireType {
+        WireType::LengthDelimited
+    }
+}
+
+/// Decoder for `string` values that returns `SharedStr` instead of `String`.
+///
+/// See [the module documentation](./index.html) for when the payloads are not copied.
+#[derive(Debug, Default)]
+pub struct SharedStringDecoder(SharedBytesDecoder);
+impl SharedStringDecoder {
+    /// Makes a new `SharedStringDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for SharedStringDecoder {
+    type Item = SharedStr;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let bytes = track!(self.0.finish_decoding())?;
+        track!(SharedStr::from_utf8(bytes))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+}
+impl ValueDecode for SharedStringDecoder {
+    fn wire_type(&self) -> WireType {
+        WireType::LengthDelimited
+    }
+}
+impl MapKeyDecode for SharedStringDecoder {}
+
+/// UTF-8 string backed by `Bytes`.
+///
+/// This can be encoded by `StringEncoder<SharedStr>`.
+#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedStr(Bytes);
+impl SharedStr {
+    /// Makes a new `SharedStr` instance if `bytes` is a valid UTF-8 string.
+    pub fn from_utf8(bytes: Bytes) -> Result<Self> {
+        track!(str::from_utf8(&bytes).map_err(|e| ErrorKind::InvalidInput.cause(e)))?;
+        Ok(SharedStr(bytes))
+    }
+
+    /// Returns the string slice.
+    pub fn as_str(&self) -> &str {
+        // The bytes have been validated when the instance was made
+        unsafe { str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// Takes ownership of the instance and returns the underlying bytes.
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+}
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl AsRef<[u8]> for SharedStr {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+impl From<String> for SharedStr {
+    fn from(f: String) -> Self {
+        SharedStr(Bytes::from(f))
+    }
+}
+impl From<&'static str> for SharedStr {
+    fn from(f: &'static str) -> Self {
+        SharedStr(Bytes::from_static(f.as_bytes()))
+    }
+}
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+impl<'a> PartialEq<&'a str> for SharedStr {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+#[derive(Debug, Default)]
+struct PayloadDecoder {
+    buf: Vec<u8>,
+    shared: Option<Bytes>,
+    eos: bool,
+}
+impl Decode for PayloadDecoder {
+    type Item = Bytes;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        if self.eos {
+            return Ok(0);
+        }
+        if self.buf.is_empty() && eos.is_reached() {
+            // The whole of the payload is given at once
+            self.shared = source_slice(buf);
+        }
+        if self.shared.is_none() {
+            self.buf.extend_from_slice(buf);
+        }
+        self.eos = eos.is_reached();
+        Ok(buf.len())
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(self.eos, ErrorKind::IncompleteDecoding);
+        self.eos = false;
+        if let Some(bytes) = self.shared.take() {
+            Ok(bytes)
+        } else {
+            Ok(Bytes::from(mem::take(&mut self.buf)))
+        }
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.eos {
+            ByteCount::Finite(0)
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.eos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::num::{F1, F2};
+    use crate::field::{FieldDecoder, Fields, MapFieldDecoder, MaybeDefault, Repeated};
+    use crate::message::MessageDecoder;
+    use crate::scalar::Uint32Decoder;
+    use bytecodec::io::IoDecodeExt;
+    use std::collections::HashMap;
+
+    type BlobDecoder = MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, SharedStringDecoder>>,
+            Repeated<FieldDecoder<F2, SharedBytesDecoder>, Vec<Bytes>>,
+        )>,
+    >;
+
+    fn is_shared(bytes: &[u8], input: &Bytes) -> bool {
+        let start = input.as_ptr() as usize;
+        let p = bytes.as_ptr() as usize;
+        start <= p && p + bytes.len() <= start + input.len()
+    }
+
+    #[test]
+    fn shared_decoders_work() {
+        let input = Bytes::from(vec![10, 3, 102, 111, 111, 18, 2, 0, 1, 18, 0, 18, 1, 2]);
+        let (name, blobs) = track_try_unwrap!(decode_shared(&mut BlobDecoder::default(), &input));
+        assert_eq!(name, "foo");
+        assert_eq!(blobs, [&[0, 1][..], &[][..], &[2][..]]);
+        assert!(is_shared(name.as_bytes(), &input));
+        assert!(is_shared(&blobs[0], &input));
+        assert!(is_shared(&blobs[2], &input));
+
+        // Without `decode_shared`
+        let (name, blobs) = track_try_unwrap!(BlobDecoder::default().decode_exact(&input[..]));
+        assert_eq!(name, "foo");
+        assert_eq!(blobs, [&[0, 1][..], &[][..], &[2][..]]);
+        assert!(!is_shared(name.as_bytes(), &input));
+
+        // Byte-by-byte
+        let mut decoder = BlobDecoder::default();
+        for b in input.iter() {
+            track_try_unwrap!(decoder.decode(&[*b], Eos::new(false)));
+        }
+        track_try_unwrap!(decoder.decode(&[], Eos::new(true)));
+        let (name, blobs) = track_try_unwrap!(decoder.finish_decoding());
+        assert_eq!(name, "foo");
+        assert_eq!(blobs, [&[0, 1][..], &[][..], &[2][..]]);
+
+        // Invalid UTF-8
+        let input = Bytes::from(vec![10, 1, 0xFF]);
+        assert!(decode_shared(&mut BlobDecoder::default(), &input).is_err());
+    }
+
+    #[test]
+    fn shared_str_map_keys_work() {
+        type Decoder = MessageDecoder<
+            MapFieldDecoder<F1, SharedStringDecoder, Uint32Decoder, HashMap<SharedStr, u32>>,
+        >;
+        let input = Bytes::from(vec![10, 5, 10, 1, 97, 16, 1]);
+        let map = track_try_unwrap!(decode_shared(&mut Decoder::default(), &input));
+        assert_eq!(map.get(&SharedStr::from("a")), Some(&1));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::dynamic::{
+    DescriptorPool, DynamicMessageDecoder, DynamicMessageEncoder, MessageDescriptor,
+};
+use crate::message::{MessageDecode, MessageEncode};
+use bytecodec::bytes::{BytesEncoder, RemainingBytesDecoder};
+use bytecodec::io::IoEncodeExt;
+use bytecodec::{
+    ByteCount, Decode, DecodeExt, Encode, EncodeExt, Eos, ErrorKind, Result, SizedEncode,
+};
+use std::sync::Arc;
+
+/// Encoder that emits the text format representations of messages encoded by `E`.
+///
+/// The messages are encoded by `E` into the binary format at first,
+/// and then converted to the text format in accordance with the descriptor of the message.
+#[derive(Debug)]
+pub struct TextMessageEncoder<E> {
+    inner: E,
+    decoder: DynamicMessageDecoder,
+    pool: Arc<DescriptorPool>,
+    bytes: BytesEncoder<Vec<u8>>,
+}
+impl<E: MessageEncode> TextMessageEncoder<E> {
+    /// Makes a new `TextMessageEncoder` instance.
+    ///
+    /// `message_name` is the full name of the message encoded by `inner`.
+    /// If `pool` does not have such message, this returns an `ErrorKind::InvalidInput` error.
+    pub fn new(inner: E, pool: Arc<DescriptorPool>, message_name: &str) -> Result<Self> {
+        let decoder = track!(DynamicMessageDecoder::new(pool.clone(), message_name))?;
+        Ok(TextMessageEncoder {
+            inner,
+            decoder,
+            pool,
+            bytes: BytesEncoder::new(),
+        })
+    }
+
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+}
+impl<E: MessageEncode> Encode for TextMessageEncoder<E> {
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        track!(self.inner.start_encoding(item))?;
+        let mut binary = Vec::new();
+        track!(self.inner.encode_all(&mut binary))?;
+        let message = track!(self.decoder.decode_from_bytes(&binary))?;
+        let text = track!(super::to_string(&message, &self.pool))?;
+        track!(self.bytes.start_encoding(text.into_bytes()))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+}
+impl<E: MessageEncode> SizedEncode for TextMessageEncoder<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.bytes.exact_requiring_bytes()
+    }
+}
+
+/// Decoder that parses the text format representations of messages and decodes them by `D`.
+///
+/// Like `MessageDecoder`, this regards the end of the input stream as the end of the text.
+/// The text is converted to the binary format in accordance with the descriptor of the message,
+/// and then decoded by `D`.
+#[derive(Debug)]
+pub struct TextMessageDecoder<D> {
+    inner: D,
+    descriptor: Arc<MessageDescriptor>,
+    pool: Arc<DescriptorPool>,
+    bytes: RemainingBytesDecoder,
+}
+impl<D: MessageDecode> TextMessageDecoder<D> {
+    /// Makes a new `TextMessageDecoder` instance.
+    ///
+    /// `message_name` is the full name of the message decoded by `inner`.
+    /// If `pool` does not have such message, this returns an `ErrorKind::InvalidInput` error.
+    pub fn new(inner: D, pool: Arc<DescriptorPool>, message_name: &str) -> Result<Self> {
+        let descriptor = track_assert_some!(
+            pool.message(message_name).cloned(),
+            ErrorKind::InvalidInput,
+            "Unknown message: {}",
+            message_name
+        );
+        Ok(TextMessageDecoder {
+            inner,
+            descriptor,
+            pool,
+            bytes: RemainingBytesDecoder::new(),
+        })
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+}
+impl<D: MessageDecode> Decode for TextMessageDecoder<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let bytes = track!(self.bytes.finish_decoding())?;
+        let text = track_assert_some!(
+            String::from_utf8(bytes).ok(),
+            ErrorKind::InvalidInput,
+            "Invalid UTF-8 text"
+        );
+        let message = track!(super::from_str(&text, &self.pool, &self.descriptor.name,))?;
+        let binary = track!(DynamicMessageEncoder::new().encode_into_bytes(message))?;
+        track!(self.inner.decode_from_bytes(&binary))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+}
@@ -0,0 +1,323 @@
+//! [Text format] of protocol buffer messages.
+//!
+//! [`TextMessageEncoder`](./struct.TextMessageEncoder.html) and
+//! [`TextMessageDecoder`](./struct.TextMessageDecoder.html) can be attached to
+//! existing message encoders and decoders (e.g., `MessageEncoder` and `MessageDecoder`).
+//! Like the [JSON mapping](../json/index.html), the field names and enum value names are taken from
+//! the descriptor of the message registered in a [`DescriptorPool`].
+//!
+//! The parser accepts the syntax written by `protoc` and the official libraries:
+//!
+//! - Comments start with `#` and continue to the end of the line
+//! - The `:` after a field name can be omitted if the value is a message (`{ ... }` or `< ... >`)
+//! - Fields can be separated by `;` or `,`
+//! - Repeated fields can be written either one value per field or as lists (e.g., `samples: [1, 2]`)
+//! - Map fields are written as repeated messages that have `key` and `value` fields
+//! - String and bytes values support C-style escape sequences and adjacent literals are concatenated
+//! - Enum values can be written by their names or numbers
+//!
+//! Parse errors report the line and column of the offending token (e.g., `3:10: Unknown field: ...`).
+//! Unknown fields of the binary messages cannot be represented in the text format, so they are dropped.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytecodec;
+//! # extern crate protobuf_codec;
+//! use bytecodec::EncodeExt;
+//! use bytecodec::io::IoDecodeExt;
+//! use protobuf_codec::dynamic::DescriptorPool;
+//! use protobuf_codec::field::{Fields, FieldDecoder, FieldEncoder, MaybeDefault};
+//! use protobuf_codec::field::{PackedFieldDecoder, PackedFieldEncoder};
+//! use protobuf_codec::field::num::{F1, F2};
+//! use protobuf_codec::message::{MessageDecoder, MessageEncoder};
+//! use protobuf_codec::scalar::{Int64Decoder, Int64Encoder, StringDecoder, StringEncoder};
+//! use protobuf_codec::schema;
+//! use protobuf_codec::text::{TextMessageDecoder, TextMessageEncoder};
+//! use std::sync::Arc;
+//!
+//! # fn main() {
+//! let file = schema::parse(r#"
+//!     syntax = "proto3";
+//!     message Item {
+//!       string name = 1;
+//!       repeated int64 prices = 2;
+//!     }
+//! "#).unwrap();
+//! let pool = Arc::new(DescriptorPool::from_files(&[file]).unwrap());
+//!
+//! let encoder = MessageEncoder::new(Fields::new((
+//!     MaybeDefault::<FieldEncoder<F1, StringEncoder>>::default(),
+//!     PackedFieldEncoder::<F2, Int64Encoder, Vec<_>>::default(),
+//! )));
+//! let mut encoder = TextMessageEncoder::new(encoder, pool.clone(), "Item").unwrap();
+//! let text = encoder.encode_into_bytes(("foo".to_owned(), vec![1, 2])).unwrap();
+//! assert_eq!(text, b"name: \"foo\"\nprices: 1\nprices: 2\n");
+//!
+//! let decoder = MessageDecoder::new(Fields::new((
+//!     MaybeDefault::<FieldDecoder<F1, StringDecoder>>::default(),
+//!     PackedFieldDecoder::<F2, Int64Decoder, Vec<_>>::default(),
+//! )));
+//! let mut decoder = TextMessageDecoder::new(decoder, pool, "Item").unwrap();
+//! let item = decoder.decode_exact(&b"name: 'bar' # comment\nprices: [3, -4]"[..]).unwrap();
+//! assert_eq!(item, ("bar".to_owned(), vec![3, -4]));
+//! # }
+//! ```
+//!
+//! [Text format]: https://protobuf.dev/reference/protobuf/textformat-spec/
+//! [`DescriptorPool`]: ../dynamic/struct.DescriptorPool.html
+use self::printer::Printer;
+use crate::dynamic::{DescriptorPool, DynamicMessage};
+use bytecodec::{ErrorKind, Result};
+
+pub use self::codec::{TextMessageDecoder, TextMessageEncoder};
+
+mod codec;
+mod parser;
+mod printer;
+
+/// Converts `message` to a text in the text format.
+///
+/// The enum types referred from the message are looked up from `pool`.
+pub fn to_string(message: &DynamicMessage, pool: &DescriptorPool) -> Result<String> {
+    let mut printer = Printer {
+        pool,
+        text: String::new(),
+        indent: 0,
+    };
+    track!(printer.print_message(message))?;
+    Ok(printer.text)
+}
+
+/// Parses a text in the text format as the message named `message_name`.
+///
+/// If `pool` does not have such message or the text is malformed,
+/// this returns an `ErrorKind::InvalidInput` error.
+pub fn from_str(text: &str, pool: &DescriptorPool, message_name: &str) -> Result<DynamicMessage> {
+    let descriptor = track_assert_some!(
+        pool.message(message_name),
+        ErrorKind::InvalidInput,
+        "Unknown message: {}",
+        message_name
+    );
+    track!(parser::parse(text, pool, descriptor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::Value;
+    use crate::field::num::{F1, F2};
+    use crate::field::{FieldDecoder, FieldEncoder, Fields, MaybeDefault, MessageFieldDecoder};
+    use crate::field::{MessageFieldEncoder, Repeated};
+    use crate::message::{MessageDecoder, MessageEncoder};
+    use crate::scalar::{BytesDecoder, BytesEncoder, StringDecoder, StringEncoder};
+    use crate::schema;
+    use bytecodec::io::IoDecodeExt;
+    use bytecodec::EncodeExt;
+    use std::sync::Arc;
+
+    const PROTO: &str = r#"
+        syntax = "proto2";
+        package example;
+
+        enum Corpus {
+          UNIVERSAL = 0;
+          WEB = 1;
+        }
+
+        message SearchRequest {
+          message Project {
+            optional string name = 1;
+          }
+          optional string query = 1;
+          optional int32 page_number = 2;
+          optional Corpus corpus = 3;
+          repeated sint64 samples = 4;
+          map<int32, Project> projects = 5;
+          oneof source {
+            string url = 6;
+            Project project = 7;
+          }
+          optional bytes payload = 8;
+          optional double score = 9;
+          optional group Result = 10 {
+            optional uint32 rank = 11;
+          }
+          optional bool exact = 12;
+        }
+    "#;
+
+    fn pool() -> Arc<DescriptorPool> {
+        let file = track_try_unwrap!(schema::parse(PROTO));
+        Arc::new(track_try_unwrap!(DescriptorPool::from_files(&[file])))
+    }
+
+    #[test]
+    fn text_format_works() {
+        let pool = pool();
+        let text = r#"
+            # A search request
+            query: "foo\n\"bar\"" 'baz'
+            page_number: -3;
+            corpus: WEB,
+            samples: [-1, 0x10]
+            samples: 3
+            projects { key: 10 value { name: "a" } }
+            projects < key: -2 >
+            project: { name: "b" }
+            payload: "\000\001\xff\u00e9"
+            score: -inf
+            Result { rank: 7 }
+            exact: t
+        "#;
+        let m = track_try_unwrap!(from_str(text, &pool, "example.SearchRequest"));
+        assert_eq!(
+            m.get_by_name("query"),
+            Some(&Value::String("foo\n\"bar\"baz".to_owned()))
+        );
+        assert_eq!(m.get_by_name("page_number"), Some(&Value::I32(-3)));
+        assert_eq!(m.get_by_name("corpus"), Some(&Value::Enum(1)));
+        assert_eq!(
+            m.get_by_name("samples"),
+            Some(&Value::List(vec![
+                Value::I64(-1),
+                Value::I64(16),
+                Value::I64(3)
+            ]))
+        );
+        assert_eq!(
+            m.get_by_name("payload"),
+            Some(&Value::Bytes(vec![0, 1, 255, 0xC3, 0xA9]))
+        );
+
+        let text = track_try_unwrap!(to_string(&m, &pool));
+        assert_eq!(
+            text,
+            r#"query: "foo\n\"bar\"baz"
+page_number: -3
+corpus: WEB
+samples: -1
+samples: 16
+samples: 3
+projects {
+  key: -2
+  value {
+  }
+}
+projects {
+  key: 10
+  value {
+    name: "a"
+  }
+}
+project {
+  name: "b"
+}
+payload: "\000\001\377\303\251"
+score: -inf
+Result {
+  rank: 7
+}
+exact: true
+"#
+        );
+        let m2 = track_try_unwrap!(from_str(&text, &pool, "example.SearchRequest"));
+        assert_eq!(m2, m);
+    }
+
+    #[test]
+    fn text_format_rejects_invalid_input() {
+        let pool = pool();
+        let name = "example.SearchRequest";
+        for text in &[
+            "query: 1",
+            "query \"a\"",
+            "page_number: 3000000000",
+            "page_number: 1.5",
+            "corpus: UNKNOWN",
+            "query: [\"a\"]",
+            "url: \"a\" project {}",
+            "query: \"a\" query: \"b\"",
+            "exact: 2",
+            "unknown: 1",
+            "project { name: \"a\"",
+            "project { name: \"a\" >",
+            "payload: \"\\q\"",
+            "query: \"\\377\"",
+        ] {
+            assert!(from_str(text, &pool, name).is_err(), "{}", text);
+        }
+
+        let e = from_str("query: \"a\"\n  page: 1", &pool, name)
+            .err()
+            .unwrap();
+        assert!(
+            e.to_string().contains("2:3: Unknown field"),
+            "{}",
+            e.to_string()
+        );
+        let e = from_str("# comment\nquery: 'a\nb'", &pool, name)
+            .err()
+            .unwrap();
+        assert!(e.to_string().contains("2:8: Unterminated string literal"));
+    }
+
+    #[test]
+    fn text_message_codec_works() {
+        let file = track_try_unwrap!(schema::parse(
+            r#"
+            syntax = "proto3";
+            message Person {
+              message Address {
+                string city = 1;
+              }
+              bytes id = 1;
+              repeated Address addresses = 2;
+            }
+        "#
+        ));
+        let pool = Arc::new(track_try_unwrap!(DescriptorPool::from_files(&[file])));
+
+        let encoder = MessageEncoder::new(Fields::new((
+            MaybeDefault::<FieldEncoder<F1, BytesEncoder>>::default(),
+            Repeated::<
+                MessageFieldEncoder<
+                    F2,
+                    MessageEncoder<Fields<(MaybeDefault<FieldEncoder<F1, StringEncoder>>,)>>,
+                >,
+                Vec<_>,
+            >::default(),
+        )));
+        let mut encoder =
+            track_try_unwrap!(TextMessageEncoder::new(encoder, pool.clone(), "Person"));
+        let text = track_try_unwrap!(encoder.encode_into_bytes((
+            b"\x01a".to_vec(),
+            vec![("Tokyo".to_owned(),), ("".to_owned(),)]
+        )));
+        assert_eq!(
+            text,
+            &b"id: \"\\001a\"\naddresses {\n  city: \"Tokyo\"\n}\naddresses {\n}\n"[..]
+        );
+
+        let decoder = MessageDecoder::new(Fields::new((
+            MaybeDefault::<FieldDecoder<F1, BytesDecoder>>::default(),
+            Repeated::<
+                MessageFieldDecoder<
+                    F2,
+                    MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, StringDecoder>>,)>>,
+                >,
+                Vec<_>,
+            >::default(),
+        )));
+        let mut decoder = track_try_unwrap!(TextMessageDecoder::new(decoder, pool, "Person"));
+        let item = track_try_unwrap!(decoder.decode_exact(&text[..]));
+        assert_eq!(
+            item,
+            (
+                b"\x01a".to_vec(),
+                vec![("Tokyo".to_owned(),), ("".to_owned(),)]
+            )
+        );
+    }
+}
@@ -0,0 +1,579 @@
+use crate::dynamic::{
+    DescriptorPool, DynamicMessage, FieldDescriptor, FieldType, MapKey, MessageDescriptor, Value,
+};
+use crate::schema::lexer::{self, Token, TokenKind};
+use crate::schema::Position;
+use bytecodec::{ErrorKind, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+// The maximum depth of nested messages
+const MAX_DEPTH: usize = 100;
+
+pub fn parse(
+    text: &str,
+    pool: &DescriptorPool,
+    descriptor: &Arc<MessageDescriptor>,
+) -> Result<DynamicMessage> {
+    let tokens = track!(lexer::tokenize_text_format(text))?;
+    let mut parser = Parser {
+        pool,
+        tokens,
+        index: 0,
+        depth: 0,
+    };
+    let message = track!(parser.parse_message(descriptor, None))?;
+    Ok(message)
+}
+
+#[derive(Debug)]
+struct Parser<'a> {
+    pool: &'a DescriptorPool,
+    tokens: Vec<Token>,
+    index: usize,
+    depth: usize,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index]
+    }
+
+    fn next(&mut self) -> &Token {
+        let i = self.index;
+        if self.tokens[i].kind != TokenKind::Eof {
+            self.index += 1;
+        }
+        &self.tokens[i]
+    }
+
+    fn position(&self) -> Position {
+        self.peek().position
+    }
+
+    fn is_symbol(&self, c: char) -> bool {
+        self.peek().kind == TokenKind::Symbol(c)
+    }
+
+    fn consume_symbol(&mut self, c: char) -> bool {
+        if self.is_symbol(c) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, c: char) -> Result<()> {
+        let position = self.position();
+        track_assert!(
+            self.consume_symbol(c),
+            ErrorKind::InvalidInput,
+            "{}: Expected {:?}, but got {}",
+            position,
+            c,
+            describe(&self.peek().kind)
+        );
+        Ok(())
+    }
+
+    // message = { field [ ";" | "," ] }
+    fn parse_message(
+        &mut self,
+        descriptor: &Arc<MessageDescriptor>,
+        close: Option<char>,
+    ) -> Result<DynamicMessage> {
+        let position = self.position();
+        track_assert!(
+            self.depth < MAX_DEPTH,
+            ErrorKind::InvalidInput,
+            "{}: Too deep nesting",
+            position
+        );
+        self.depth += 1;
+
+        let mut message = DynamicMessage::new(descriptor.clone());
+        let mut seen = HashSet::new();
+        let mut seen_oneofs = HashSet::new();
+        loop {
+            match (close, &self.peek().kind) {
+                (Some(c), TokenKind::Symbol(d)) if c == *d => {
+                    self.index += 1;
+                    break;
+                }
+                (None, TokenKind::Eof) => break,
+                _ => {}
+            }
+
+            let position = self.position();
+            let name = match self.next().kind {
+                TokenKind::Ident(ref name) => name.clone(),
+                ref kind => track_panic!(
+                    ErrorKind::InvalidInput,
+                    "{}: Expected a field name, but got {}",
+                    position,
+                    describe(kind)
+                ),
+            };
+            let field = descriptor
+                .fields
+                .iter()
+                .find(|f| f.name == name || group_name(f) == Some(&name));
+            let field = track_assert_some!(
+                field,
+                ErrorKind::InvalidInput,
+                "{}: Unknown field: message={}, name={}",
+                position,
+                descriptor.name,
+                name
+            );
+            track_assert!(
+                seen.insert(field.number) || field.is_repeated() || field.is_map(),
+                ErrorKind::InvalidInput,
+                "{}: Non-repeated field is specified multiple times: {}",
+                position,
+                name
+            );
+            if let Some(ref oneof) = field.oneof {
+                track_assert!(
+                    seen_oneofs.insert(oneof),
+                    ErrorKind::InvalidInput,
+                    "{}: Multiple fields of a oneof are specified: {}",
+                    position,
+                    oneof
+                );
+            }
+
+            track!(self.parse_field(&mut message, field))?;
+            if !self.consume_symbol(';') {
+                self.consume_symbol(',');
+            }
+        }
+
+        self.depth -= 1;
+        Ok(message)
+    }
+
+    // field = name ":" value | name [ ":" ] message_value
+    fn parse_field(&mut self, message: &mut DynamicMessage, field: &FieldDescriptor) -> Result<()> {
+        let is_message = matches!(
+            field.ty,
+            FieldType::Message(_) | FieldType::Group(_) | FieldType::Map(..)
+        );
+        let position = self.position();
+        if !self.consume_symbol(':') {
+            track_assert!(
+                is_message,
+                ErrorKind::InvalidInput,
+                "{}: Expected ':', but got {}",
+                position,
+                describe(&self.peek().kind)
+            );
+        }
+
+        let mut values = Vec::new();
+        if self.consume_symbol('[') {
+            track_assert!(
+                field.is_repeated() || field.is_map(),
+                ErrorKind::InvalidInput,
+                "{}: List values are only allowed for repeated fields: {}",
+                position,
+                field.name
+            );
+            if !self.consume_symbol(']') {
+                loop {
+                    values.push(track!(self.parse_value(field))?);
+                    if self.consume_symbol(']') {
+                        break;
+                    }
+                    track!(self.expect_symbol(','))?;
+                }
+            }
+        } else {
+            values.push(track!(self.parse_value(field))?);
+        }
+
+        let value = if field.is_map() {
+            let mut entries = BTreeMap::new();
+            for v in values {
+                if let Value::Map(v) = v {
+                    entries.extend(v);
+                }
+            }
+            Value::Map(entries)
+        } else if field.is_repeated() {
+            Value::List(values)
+        } else {
+            values.pop().expect("Never fails")
+        };
+        message.merge_field(field.number, value);
+        Ok(())
+    }
+
+    fn parse_value(&mut self, field: &FieldDescriptor) -> Result<Value> {
+        let position = self.position();
+        match field.ty {
+            FieldType::Map(ref key_type, ref value_type) => {
+                let descriptor =
+                    Arc::new(MessageDescriptor::map_entry(field).expect("Never fails"));
+                let mut entry = track!(self.parse_message_value(&descriptor))?;
+                let key = match entry.remove(1) {
+                    Some(key) => key,
+                    None => track!(Value::default_of(key_type, self.pool))?,
+                };
+                let key = track_assert_some!(
+                    MapKey::from_value(key),
+                    ErrorKind::InvalidInput,
+                    "{}: Invalid map key type: {}",
+                    position,
+                    field.name
+                );
+                let value = match entry.remove(2) {
+                    Some(value) => value,
+                    None => track!(Value::default_of(value_type, self.pool))?,
+                };
+                let mut entries = BTreeMap::new();
+                entries.insert(key, value);
+                Ok(Value::Map(entries))
+            }
+            FieldType::Message(ref name) | FieldType::Group(ref name) => {
+                let descriptor = track_assert_some!(
+                    self.pool.message(name),
+                    ErrorKind::InvalidInput,
+                    "{}: Unknown message: {}",
+                    position,
+                    name
+                );
+                let m = track!(self.parse_message_value(descriptor))?;
+                Ok(Value::Message(m))
+            }
+            ref ty => track!(self.parse_scalar(ty)),
+        }
+    }
+
+    // message_value = "{" message "}" | "<" message ">"
+    fn parse_message_value(
+        &mut self,
+        descriptor: &Arc<MessageDescriptor>,
+    ) -> Result<DynamicMessage> {
+        let position = self.position();
+        let close = if self.consume_symbol('{') {
+            '}'
+        } else if self.consume_symbol('<') {
+            '>'
+        } else {
+            track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Expected '{{' or '<', but got {}",
+                position,
+                describe(&self.peek().kind)
+            );
+        };
+        track!(self.parse_message(descriptor, Some(close)))
+    }
+
+    fn parse_scalar(&mut self, ty: &FieldType) -> Result<Value> {
+        let position = self.position();
+        match *ty {
+            FieldType::String => {
+                let bytes = track!(self.parse_bytes())?;
+                let s = track_assert_some!(
+                    String::from_utf8(bytes).ok(),
+                    ErrorKind::InvalidInput,
+                    "{}: Invalid UTF-8 string",
+                    position
+                );
+                return Ok(Value::String(s));
+            }
+            FieldType::Bytes => return Ok(Value::Bytes(track!(self.parse_bytes())?)),
+            _ => {}
+        }
+
+        let negative = self.consume_symbol('-');
+        let token = self.next().clone();
+        let value = match (ty, &token.kind) {
+            (FieldType::Bool, TokenKind::Ident(s)) if !negative => match s.as_str() {
+                "true" | "True" | "t" => Some(Value::Bool(true)),
+                "false" | "False" | "f" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            (FieldType::Bool, &TokenKind::Int(n)) if !negative && n <= 1 => {
+                Some(Value::Bool(n == 1))
+            }
+            (FieldType::Int32, &TokenKind::Int(n))
+            | (FieldType::Sint32, &TokenKind::Int(n))
+            | (FieldType::Sfixed32, &TokenKind::Int(n)) => {
+                signed(n, negative, i64::from(i32::MAX)).map(|n| Value::I32(n as i32))
+            }
+            (FieldType::Int64, &TokenKind::Int(n))
+            | (FieldType::Sint64, &TokenKind::Int(n))
+            | (FieldType::Sfixed64, &TokenKind::Int(n)) => {
+                signed(n, negative, i64::MAX).map(Value::I64)
+            }
+            (FieldType::Uint32, &TokenKind::Int(n)) | (FieldType::Fixed32, &TokenKind::Int(n))
+                if !negative && n <= u64::from(u32::MAX) =>
+            {
+                Some(Value::U32(n as u32))
+            }
+            (FieldType::Uint64, &TokenKind::Int(n)) | (FieldType::Fixed64, &TokenKind::Int(n))
+                if !negative =>
+            {
+                Some(Value::U64(n))
+            }
+            (FieldType::Float, kind) => float(kind, negative).map(|v| Value::F32(v as f32)),
+            (FieldType::Double, kind) => float(kind, negative).map(Value::F64),
+            (FieldType::Enum(name), TokenKind::Ident(s)) if !negative => self
+                .pool
+                .enum_type(name)
+                .and_then(|e| e.value_by_name(s))
+                .map(|v| Value::Enum(v.number)),
+            (FieldType::Enum(_), &TokenKind::Int(n)) => {
+                signed(n, negative, i64::from(i32::MAX)).map(|n| Value::Enum(n as i32))
+            }
+            _ => None,
+        };
+        let value = track_assert_some!(
+            value,
+            ErrorKind::InvalidInput,
+            "{}: Invalid value for {:?}: {}",
+            position,
+            ty,
+            describe_value(&token.kind, negative)
+        );
+        Ok(value)
+    }
+
+    // Adjacent string literals are concatenated
+    fn parse_bytes(&mut self) -> Result<Vec<u8>> {
+        let position = self.position();
+        let mut bytes = match self.next().kind {
+            TokenKind::Str(ref b) => b.clone(),
+            ref kind => track_panic!(
+                ErrorKind::InvalidInput,
+                "{}: Expected a string, but got {}",
+                position,
+                describe(kind)
+            ),
+        };
+        while let TokenKind::Str(ref b) = self.peek().kind {
+            bytes.extend_from_slice(b);
+            self.index += 1;
+        }
+        Ok(bytes)
+    }
+}
+
+fn group_name(field: &FieldDescriptor) -> Option<&str> {
+    if let FieldType::Group(ref name) = field.ty {
+        name.rsplit('.').next()
+    } else {
+        None
+    }
+}
+
+fn signed(n: u64, negative: bool, max: i64) -> Option<i64> {
+    if negative {
+        if n <= max as u64 + 1 {
+            Some((n as i64).wrapping_neg())
+        } else {
+            None
+        }
+    } else if n <= max as u64 {
+        Some(n as i64)
+    } else {
+        None
+    }
+}
+
+fn float(kind: &TokenKind, negative: bool) -> Option<f64> {
+    let v = match *kind {
+        TokenKind::Int(n) => n as f64,
+        TokenKind::Float(v) => v,
+        TokenKind::Ident(ref s) => match s.to_lowercase().as_str() {
+            "inf" | "infinity" => f64::INFINITY,
+            "nan" => f64::NAN,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(if negative { -v } else { v })
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match *kind {
+        TokenKind::Ident(ref s) => format!("identifier {:?}", s),
+        TokenKind::Int(n) => format!("integer {}", n),
+        TokenKind::Float(n) => format!("float {}", n),
+        TokenKind::Str(ref b) => format!("string {:?}", String::from_utf8_lossy(b)),
+        TokenKind::Symbol(c) => format!("{:?}", c),
+        TokenKind::Eof => "end of input".to_owned(),
+    }
+}
+
+fn describe_value(kind: &TokenKind, negative: bool) -> String {
+    match *kind {
+        _ if !negative => describe(kind),
+        TokenKind::Int(n) => format!("integer -{}", n),
+        TokenKind::Float(n) => format!("float -{}", n),
+        _ => format!("'-' followed by {}", describe(kind)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema;
+
+    const PROTO: &str = r#"
+        syntax = "proto2";
+
+        message Node {
+          optional string name = 1;
+          optional bytes data = 2;
+          optional Node child = 3;
+          repeated int32 values = 4;
+          repeated Node children = 5;
+          map<string, int32> counts = 6;
+          optional float ratio = 7;
+        }
+    "#;
+
+    fn pool() -> DescriptorPool {
+        let file = track_try_unwrap!(schema::parse(PROTO));
+        track_try_unwrap!(DescriptorPool::from_files(&[file]))
+    }
+
+    fn parse_node(text: &str) -> Result<DynamicMessage> {
+        let pool = pool();
+        let descriptor = pool.message("Node").expect("Never fails").clone();
+        parse(text, &pool, &descriptor)
+    }
+
+    fn error(text: &str) -> String {
+        let e = parse_node(text).err().unwrap();
+        e.to_string()
+    }
+
+    fn s(s: &str) -> Value {
+        Value::String(s.to_owned())
+    }
+
+    #[test]
+    fn escape_sequences_work() {
+        let m = track_try_unwrap!(parse_node(
+            r#"name: "\a\b\f\n\r\t\v\\\'\"\?" data: "\0\12\101\x41\xfFé\U0001F600""#
+        ));
+        assert_eq!(m.get(1), Some(&s("\x07\x08\x0C\n\r\t\x0B\\'\"?")));
+        assert_eq!(
+            m.get(2),
+            Some(&Value::Bytes(
+                b"\x00\n\x41\x41\xFF\xC3\xA9\xF0\x9F\x98\x80".to_vec()
+            ))
+        );
+
+        // Single quotes and adjacent literals
+        let m = track_try_unwrap!(parse_node(r#"name: 'a"b' "c'd" 'e'"#));
+        assert_eq!(m.get(1), Some(&s("a\"bc'de")));
+
+        // Non-ASCII characters are kept as they are
+        let m = track_try_unwrap!(parse_node("name: \"é\""));
+        assert_eq!(m.get(1), Some(&s("é")));
+    }
+
+    #[test]
+    fn nested_messages_work() {
+        let m = track_try_unwrap!(parse_node(
+            "name: 'a' child { name: 'b' child < name: 'c' child: {} > }"
+        ));
+        let b = match m.get(3) {
+            Some(Value::Message(b)) => b,
+            v => panic!("{:?}", v),
+        };
+        assert_eq!(b.get(1), Some(&s("b")));
+        let c = match b.get(3) {
+            Some(Value::Message(c)) => c,
+            v => panic!("{:?}", v),
+        };
+        assert_eq!(c.get(1), Some(&s("c")));
+        match c.get(3) {
+            Some(Value::Message(d)) => assert!(d.get(1).is_none()),
+            v => panic!("{:?}", v),
+        }
+    }
+
+    #[test]
+    fn repeated_fields_work() {
+        let m = track_try_unwrap!(parse_node(
+            "values: 1; values: [2, -3], values: [] values: 0x4"
+        ));
+        let values = [1, 2, -3, 4].iter().map(|&n| Value::I32(n)).collect();
+        assert_eq!(m.get(4), Some(&Value::List(values)));
+
+        let m = track_try_unwrap!(parse_node(
+            "children { name: 'a' } children: [{ name: 'b' }, < name: 'c' >]"
+        ));
+        let names = match m.get(5) {
+            Some(Value::List(children)) => children
+                .iter()
+                .map(|c| match c {
+                    Value::Message(c) => c.get(1).cloned(),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            v => panic!("{:?}", v),
+        };
+        assert_eq!(names, [Some(s("a")), Some(s("b")), Some(s("c"))]);
+
+        let m = track_try_unwrap!(parse_node(
+            "counts { key: 'x' value: 1 } counts: [{ key: 'y' }, { key: 'x' value: 3 }]"
+        ));
+        let counts = match m.get(6) {
+            Some(Value::Map(entries)) => entries
+                .iter()
+                .map(|(k, v)| (k.to_value(), v.clone()))
+                .collect::<Vec<_>>(),
+            v => panic!("{:?}", v),
+        };
+        assert_eq!(counts, [(s("x"), Value::I32(3)), (s("y"), Value::I32(0))]);
+    }
+
+    #[test]
+    fn errors_have_positions() {
+        assert!(error("name: 'a'\n  foo: 1").contains("2:3: Unknown field"));
+        assert!(error("values: [1\n 2]").contains("2:2: Expected ','"));
+        assert!(error("values 1").contains("1:8: Expected ':'"));
+        assert!(error("child { name: 'a'").contains("1:18: Expected a field name"));
+        assert!(error("child: 1").contains("1:8: Expected '{' or '<'"));
+        assert!(error("name: 1").contains("1:7: Expected a string"));
+        assert!(error("name: 'a' name: 'b'").contains("1:11: Non-repeated field"));
+        assert!(error("name: [\n'a']").contains("1:5: List values are only allowed"));
+        assert!(error("\n\n   values: 3000000000").contains("3:12: Invalid value"));
+        assert!(error("values: -3000000000")
+            .contains("1:9: Invalid value for Int32: integer -3000000000"));
+        assert!(
+            error("ratio: -'a'").contains("1:8: Invalid value for Float: '-' followed by string")
+        );
+        assert!(error("data: '\\xZZ'").contains("1:9: Invalid escape sequence"));
+        assert!(error("data: '\\q'").contains("1:9: Unknown escape sequence"));
+        assert!(error("data: '\\400'").contains("1:9: Too large octal escape"));
+        assert!(error("name: '\\xff'").contains("1:7: Invalid UTF-8 string"));
+
+        let deep = "child { ".repeat(MAX_DEPTH - 1) + &"}".repeat(MAX_DEPTH - 1);
+        assert!(parse_node(&deep).is_ok());
+        let deep = "child { ".repeat(MAX_DEPTH) + &"}".repeat(MAX_DEPTH);
+        assert!(error(&deep).contains(&format!("1:{}: Too deep nesting", MAX_DEPTH * 8 + 1)));
+    }
+
+    #[test]
+    fn special_values_work() {
+        let m = track_try_unwrap!(parse_node("ratio: -inf"));
+        assert_eq!(m.get(7), Some(&Value::F32(f32::NEG_INFINITY)));
+        let m = track_try_unwrap!(parse_node("ratio: 1.5f"));
+        assert_eq!(m.get(7), Some(&Value::F32(1.5)));
+        let m = track_try_unwrap!(parse_node("ratio: -2"));
+        assert_eq!(m.get(7), Some(&Value::F32(-2.0)));
+        match track_try_unwrap!(parse_node("ratio: NaN")).get(7) {
+            Some(Value::F32(v)) => assert!(v.is_nan()),
+            v => panic!("{:?}", v),
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::dynamic::{DescriptorPool, DynamicMessage, FieldDescriptor, FieldType, Value};
+use bytecodec::{ErrorKind, Result};
+use std::fmt::Write;
+
+#[derive(Debug)]
+pub struct Printer<'a> {
+    pub pool: &'a DescriptorPool,
+    pub text: String,
+    pub indent: usize,
+}
+impl<'a> Printer<'a> {
+    pub fn print_message(&mut self, message: &DynamicMessage) -> Result<()> {
+        let descriptor = message.descriptor();
+        for f in &descriptor.fields {
+            match message.get(f.number) {
+                Some(v) if f.has_presence() || !v.is_default() => {
+                    track!(self.print_field(f, v); descriptor.name, f.name)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn print_field(&mut self, field: &FieldDescriptor, value: &Value) -> Result<()> {
+        let name = match field.ty {
+            FieldType::Group(ref name) => name.rsplit('.').next().expect("Never fails"),
+            _ => &field.name,
+        };
+        match (&field.ty, value) {
+            (FieldType::Map(key_type, value_type), Value::Map(entries)) => {
+                for (k, v) in entries {
+                    self.start_line(name);
+                    self.text.push_str(" {\n");
+                    self.indent += 1;
+                    track!(self.print_value("key", key_type, &k.to_value()))?;
+                    track!(self.print_value("value", value_type, v))?;
+                    self.indent -= 1;
+                    self.start_line("}\n");
+                }
+            }
+            (ty, Value::List(values)) => {
+                for v in values {
+                    track!(self.print_value(name, ty, v))?;
+                }
+            }
+            (ty, v) => track!(self.print_value(name, ty, v))?,
+        }
+        Ok(())
+    }
+
+    fn print_value(&mut self, name: &str, ty: &FieldType, value: &Value) -> Result<()> {
+        self.start_line(name);
+        if let Value::Message(ref m) = *value {
+            self.text.push_str(" {\n");
+            self.indent += 1;
+            track!(self.print_message(m))?;
+            self.indent -= 1;
+            self.start_line("}\n");
+            return Ok(());
+        }
+
+        self.text.push_str(": ");
+        match *value {
+            Value::Bool(v) => write!(self.text, "{}", v),
+            Value::I32(v) => write!(self.text, "{}", v),
+            Value::I64(v) => write!(self.text, "{}", v),
+            Value::U32(v) => write!(self.text, "{}", v),
+            Value::U64(v) => write!(self.text, "{}", v),
+            Value::F32(v) => write_float(&mut self.text, v, f64::from(v)),
+            Value::F64(v) => write_float(&mut self.text, v, v),
+            Value::String(ref v) => write_escaped_str(&mut self.text, v),
+            Value::Bytes(ref v) => write_escaped(&mut self.text, v),
+            Value::Enum(v) => {
+                let name = match *ty {
+                    FieldType::Enum(ref name) => self
+                        .pool
+                        .enum_type(name)
+                        .and_then(|e| e.value(v))
+                        .map(|v| v.name.clone()),
+                    _ => None,
+                };
+                match name {
+                    Some(name) => write!(self.text, "{}", name),
+                    None => write!(self.text, "{}", v),
+                }
+            }
+            Value::Message(_) | Value::List(_) | Value::Map(_) => {
+                track_panic!(ErrorKind::InvalidInput, "Unexpected nested list or map")
+            }
+        }
+        .expect("Never fails");
+        self.text.push('\n');
+        Ok(())
+    }
+
+    fn start_line(&mut self, s: &str) {
+        for _ in 0..self.indent {
+            self.text.push_str("  ");
+        }
+        self.text.push_str(s);
+    }
+}
+
+fn write_float<T: std::fmt::Display>(text: &mut String, v: T, f: f64) -> std::fmt::Result {
+    if f.is_nan() {
+        write!(text, "nan")
+    } else if f.is_infinite() {
+        write!(text, "{}", if f > 0.0 { "inf" } else { "-inf" })
+    } else {
+        write!(text, "{}", v)
+    }
+}
+
+// Printable ASCII characters are emitted as they are, and the others are escaped in octal
+fn write_escaped(text: &mut String, bytes: &[u8]) -> std::fmt::Result {
+    text.push('"');
+    for &b in bytes {
+        write_escaped_byte(text, b)?;
+    }
+    text.push('"');
+    Ok(())
+}
+
+// Non-control characters are emitted as they are, and control characters are escaped
+fn write_escaped_str(text: &mut String, s: &str) -> std::fmt::Result {
+    text.push('"');
+    for c in s.chars() {
+        if c.is_ascii() || c.is_control() {
+            let mut buf = [0; 4];
+            for &b in c.encode_utf8(&mut buf).as_bytes() {
+                write_escaped_byte(text, b)?;
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    text.push('"');
+    Ok(())
+}
+
+fn write_escaped_byte(text: &mut String, b: u8) -> std::fmt::Result {
+    match b {
+        b'\n' => text.push_str("\\n"),
+        b'\r' => text.push_str("\\r"),
+        b'\t' => text.push_str("\\t"),
+        b'"' => text.push_str("\\\""),
+        b'\'' => text.push_str("\\'"),
+        b'\\' => text.push_str("\\\\"),
+        0x20..=0x7E => text.push(b as char),
+        _ => write!(text, "\\{:03o}", b)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::MapKey;
+    use crate::schema;
+    use crate::text::parser;
+    use std::collections::BTreeMap;
+
+    const PROTO: &str = r#"
+        syntax = "proto2";
+
+        enum Color {
+          RED = 0;
+          GREEN = 1;
+        }
+
+        message Node {
+          optional string name = 1;
+          optional bytes data = 2;
+          optional Node child = 3;
+          repeated int32 values = 4;
+          repeated Node children = 5;
+          map<string, int32> counts = 6;
+          repeated double ratios = 7;
+          repeated Color colors = 8;
+        }
+    "#;
+
+    fn pool() -> DescriptorPool {
+        let file = track_try_unwrap!(schema::parse(PROTO));
+        track_try_unwrap!(DescriptorPool::from_files(&[file]))
+    }
+
+    fn node(pool: &DescriptorPool) -> DynamicMessage {
+        DynamicMessage::new(pool.message("Node").expect("Never fails").clone())
+    }
+
+    fn print(pool: &DescriptorPool, message: &DynamicMessage) -> String {
+        let mut printer = Printer {
+            pool,
+            text: String::new(),
+            indent: 0,
+        };
+        track_try_unwrap!(printer.print_message(message));
+        printer.text
+    }
+
+    // Checks that `message` is printed as `text` and `text` is parsed as `message`
+    fn assert_roundtrip(pool: &DescriptorPool, message: &DynamicMessage, text: &str) {
+        assert_eq!(print(pool, message), text);
+        let descriptor = message.descriptor().clone();
+        let parsed = track_try_unwrap!(parser::parse(text, pool, &descriptor));
+        assert_eq!(&parsed, message);
+    }
+
+    #[test]
+    fn escapes_roundtrip() {
+        let pool = pool();
+        let mut m = node(&pool);
+        track_try_unwrap!(m.set(1, Value::String("a\n\r\t\"'\\é\x07\u{85}日本".to_owned())));
+        track_try_unwrap!(m.set(2, Value::Bytes(vec![0, 0x7E, 0x7F, 0xFF, b' '])));
+        assert_roundtrip(
+            &pool,
+            &m,
+            r#"name: "a\n\r\t\"\'\\é\007\302\205日本"
+data: "\000~\177\377 "
+"#,
+        );
+    }
+
+    #[test]
+    fn nested_messages_roundtrip() {
+        let pool = pool();
+        let mut c = node(&pool);
+        track_try_unwrap!(c.set(1, Value::String("c".to_owned())));
+        let mut b = node(&pool);
+        track_try_unwrap!(b.set(3, Value::Message(c)));
+        let mut a = node(&pool);
+        track_try_unwrap!(a.set(3, Value::Message(b)));
+        track_try_unwrap!(a.set(5, Value::List(vec![Value::Message(node(&pool))])));
+        assert_roundtrip(
+            &pool,
+            &a,
+            "child {\n  child {\n    name: \"c\"\n  }\n}\nchildren {\n}\n",
+        );
+    }
+
+    #[test]
+    fn repeated_fields_roundtrip() {
+        let pool = pool();
+        let mut m = node(&pool);
+        let values = vec![Value::I32(-1), Value::I32(0), Value::I32(2)];
+        track_try_unwrap!(m.set(4, Value::List(values)));
+        let mut counts = BTreeMap::new();
+        counts.insert(MapKey::String("y".to_owned()), Value::I32(2));
+        counts.insert(MapKey::String("x".to_owned()), Value::I32(0));
+        track_try_unwrap!(m.set(6, Value::Map(counts)));
+        let ratios = vec![Value::F64(0.5), Value::F64(-1.0), Value::F64(f64::INFINITY)];
+        track_try_unwrap!(m.set(7, Value::List(ratios)));
+        track_try_unwrap!(m.set(8, Value::List(vec![Value::Enum(1), Value::Enum(0)])));
+        assert_roundtrip(
+            &pool,
+            &m,
+            r#"values: -1
+values: 0
+values: 2
+counts {
+  key: "x"
+  value: 0
+}
+counts {
+  key: "y"
+  value: 2
+}
+ratios: 0.5
+ratios: -1
+ratios: inf
+colors: GREEN
+colors: RED
+"#,
+        );
+    }
+
+    #[test]
+    fn special_values_are_printed() {
+        let pool = pool();
+        let mut m = node(&pool);
+        let ratios = vec![Value::F64(f64::NAN), Value::F64(f64::NEG_INFINITY)];
+        track_try_unwrap!(m.set(7, Value::List(ratios)));
+
+        // Unknown enum values are printed as numbers
+        track_try_unwrap!(m.set(8, Value::List(vec![Value::Enum(5)])));
+        assert_eq!(print(&pool, &m), "ratios: nan\nratios: -inf\ncolors: 5\n");
+
+        // Empty repeated fields and maps are omitted
+        let mut m = node(&pool);
+        track_try_unwrap!(m.set(4, Value::List(Vec::new())));
+        track_try_unwrap!(m.set(6, Value::Map(BTreeMap::new())));
+        assert_eq!(print(&pool, &m), "");
+    }
+}
@@ -0,0 +1,248 @@
+use crate::field::{FieldDecode, FieldEncode};
+use crate::wire::{RawValueDecoder, Tag, TagEncoder};
+use bytecodec::bytes::BytesEncoder;
+use bytecodec::{ByteCount, Decode, Encode, EncodeExt, Eos, ErrorKind, Result, SizedEncode};
+use std::mem;
+use std::slice;
+use std::vec;
+
+/// Field which is not defined in the schema of the message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownField {
+    /// Tag of the field.
+    pub tag: Tag,
+
+    /// Raw bytes of the value of the field.
+    ///
+    /// For `Length-delimited` values, this contains the length prefix too.
+    /// For groups, this contains the nested fields and the `EndGroup` tag.
+    pub value: Vec<u8>,
+}
+
+/// Unknown fields of a message.
+///
+/// The fields are kept in the order in which they appeared in the input stream.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownFields(Vec<UnknownField>);
+impl UnknownFields {
+    /// Makes a new empty `UnknownFields` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a field.
+    pub fn push(&mut self, field: UnknownField) {
+        self.0.push(field);
+    }
+
+    /// Returns the number of the fields.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no fields, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the fields.
+    pub fn iter(&self) -> slice::Iter<'_, UnknownField> {
+        self.0.iter()
+    }
+}
+impl From<Vec<UnknownField>> for UnknownFields {
+    fn from(f: Vec<UnknownField>) -> Self {
+        UnknownFields(f)
+    }
+}
+impl Extend<UnknownField> for UnknownFields {
+    fn extend<T: IntoIterator<Item = UnknownField>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+impl IntoIterator for UnknownFields {
+    type Item = UnknownField;
+    type IntoIter = vec::IntoIter<UnknownField>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<'a> IntoIterator for &'a UnknownFields {
+    type Item = &'a UnknownField;
+    type IntoIter = slice::Iter<'a, UnknownField>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Decoder that collects unknown fields.
+///
+/// This accepts any tags, so it must be placed at the last of the fields of a message
+/// (e.g., `MessageDecoder<Fields<(FieldDecoder<F1, Int32Decoder>, UnknownFieldsDecoder)>>`).
+/// Then the fields that are not accepted by the preceding decoders are preserved with their raw bytes.
+///
+/// If an embedded message is merged, the unknown fields of the instances are concatenated.
+#[derive(Debug, Default)]
+pub struct UnknownFieldsDecoder {
+    fields: UnknownFields,
+    tag: Option<Tag>,
+    value: RawValueDecoder,
+}
+impl UnknownFieldsDecoder {
+    /// Makes a new `UnknownFieldsDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for UnknownFieldsDecoder {
+    type Item = UnknownFields;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        if self.tag.is_none() {
+            return Ok(0);
+        }
+
+        let size = track!(self.value.decode(buf, eos))?;
+        if self.value.is_idle() {
+            let tag = self.tag.take().expect("Never fails");
+            let value = track!(self.value.finish_decoding())?;
+            self.fields.push(UnknownField { tag, value });
+        }
+        Ok(size)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(self.tag.is_none(), ErrorKind::IncompleteDecoding; self.tag);
+        Ok(mem::take(&mut self.fields))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.tag.is_none()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.tag.is_none() {
+            ByteCount::Finite(0)
+        } else {
+            self.value.requiring_bytes()
+        }
+    }
+}
+impl FieldDecode for UnknownFieldsDecoder {
+    fn start_decoding(&mut self, tag: Tag) -> Result<bool> {
+        track_assert!(self.tag.is_none(), ErrorKind::Other; self.tag, tag);
+        track!(self.value.start(tag))?;
+        self.tag = Some(tag);
+        Ok(true)
+    }
+}
+
+/// Encoder that re-emits unknown fields.
+///
+/// The fields are emitted in the same order and with the same bytes as they were decoded by `UnknownFieldsDecoder`.
+#[derive(Debug, Default)]
+pub struct UnknownFieldsEncoder(BytesEncoder<Vec<u8>>);
+impl UnknownFieldsEncoder {
+    /// Makes a new `UnknownFieldsEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for UnknownFieldsEncoder {
+    type Item = UnknownFields;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let mut tag_encoder = TagEncoder::new();
+        let mut bytes = Vec::new();
+        for field in item {
+            bytes.extend(track!(tag_encoder.encode_into_bytes(field.tag))?);
+            bytes.extend(field.value);
+        }
+        track!(self.0.start_encoding(bytes))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+}
+impl SizedEncode for UnknownFieldsEncoder {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl FieldEncode for UnknownFieldsEncoder {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::num::F1;
+    use crate::field::{FieldDecoder, FieldEncoder, Fields, MaybeDefault};
+    use crate::message::{MessageDecode, MessageDecoder, MessageEncoder};
+    use crate::scalar::{Int32Decoder, Int32Encoder};
+    use bytecodec::io::{IoDecodeExt, IoEncodeExt};
+
+    #[test]
+    fn unknown_fields_roundtrip_works() {
+        let input = [
+            8, 1, // f1=1 (known)
+            16, 255, 1, // f2=255
+            26, 2, 104, 105, // f3="hi"
+            37, 1, 2, 3, 4, // f4=fixed32
+            41, 1, 2, 3, 4, 5, 6, 7, 8, // f5=fixed64
+            50, 3, 1, 2, 3, // f6=bytes
+            56, 128, 0, // f7=0 (non-canonical varint)
+        ];
+
+        type Known = (
+            MaybeDefault<FieldDecoder<F1, Int32Decoder>>,
+            UnknownFieldsDecoder,
+        );
+        let mut decoder = MessageDecoder::new(Fields::<Known>::default());
+        let (f1, unknown) = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(f1, 1);
+        assert_eq!(unknown.len(), 6);
+        assert_eq!(
+            unknown.iter().nth(1).map(|f| &f.value[..]),
+            Some(&[2, 104, 105][..])
+        );
+
+        let mut encoder = MessageEncoder::new(Fields::new((
+            MaybeDefault::<FieldEncoder<F1, Int32Encoder>>::default(),
+            UnknownFieldsEncoder::new(),
+        )));
+        track_try_unwrap!(encoder.start_encoding((f1, unknown)));
+        assert_eq!(encoder.exact_requiring_bytes(), input.len() as u64);
+
+        let mut buf = Vec::new();
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, &input[..]);
+
+        // Merged instances
+        let mut decoder = MessageDecoder::new(Fields::<Known>::default());
+        track_try_unwrap!(decoder.decode(&[8, 1, 16, 2][..], Eos::new(true)));
+        track_try_unwrap!(decoder.resume_decoding());
+        track_try_unwrap!(decoder.decode(&[24, 3][..], Eos::new(true)));
+        let (_, unknown) = track_try_unwrap!(decoder.finish_decoding());
+        let nums = unknown
+            .iter()
+            .map(|f| f.tag.field_num.as_u32())
+            .collect::<Vec<_>>();
+        assert_eq!(nums, [2, 3]);
+    }
+
+    #[test]
+    fn truncated_unknown_field_is_rejected() {
+        let mut decoder = MessageDecoder::new(Fields::new((UnknownFieldsDecoder::new(),)));
+        assert!(decoder.decode_exact(&[18, 3, 1, 2][..]).is_err());
+    }
+}
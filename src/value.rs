@@ -1,7 +1,6 @@
 use crate::wire::WireType;
 use bytecodec::combinator::{Map, MapErr, MapFrom, Peekable, TryMap, TryMapFrom};
 use bytecodec::{Decode, Encode, Error, SizedEncode};
-use std;
 
 /// This trait allows for decoding field values.
 ///
@@ -4,3 +4,4 @@
 //!
 //! [google]: https://github.com/google/protobuf/tree/master/src/google
 pub mod protobuf;
+pub mod rpc;
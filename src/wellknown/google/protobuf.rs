@@ -4,12 +4,50 @@
 //!
 //! [google/protobuf]: https://github.com/google/protobuf/tree/master/src/google/protobuf
 #![allow(clippy::type_complexity)]
-use crate::field::num::{F1, F2};
-use crate::field::{FieldDecoder, FieldEncoder, Fields, MaybeDefault};
-use crate::message::{MessageDecode, MessageDecoder, MessageEncode, MessageEncoder};
+use crate::dynamic::{self, DescriptorPool, DynamicMessage, FieldType, MessageDescriptor};
+use crate::field::branch::Branch6;
+use crate::field::num::{F1, F2, F3, F4, F5, F6};
+use crate::field::{
+    FieldDecoder, FieldEncoder, Fields, MapMessageFieldDecoder, MaybeDefault, MessageFieldDecoder,
+    MessageFieldEncoder, Oneof, Optional, Repeated, SizedMapMessageFieldEncoder, SizedRepeated,
+};
+use crate::limit;
+use crate::message::{
+    BoxedMessageDecoder, BoxedMessageEncoder, MessageDecode, MessageDecoder, MessageEncode,
+    MessageEncoder,
+};
+use crate::scalar::{BoolDecoder, BoolEncoder, DoubleDecoder, DoubleEncoder};
+use crate::scalar::{BytesDecoder, BytesEncoder, CustomBytesDecoder, CustomBytesEncoder};
+use crate::scalar::{
+    Enum, EnumDecoder, EnumEncoder, FloatDecoder, FloatEncoder, StringDecoder, StringEncoder,
+};
 use crate::scalar::{Int32Decoder, Int32Encoder, Int64Decoder, Int64Encoder};
-use bytecodec::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
-use std::time::Duration;
+use crate::scalar::{Uint32Decoder, Uint32Encoder, Uint64Decoder, Uint64Encoder};
+use crate::wire::{self, WireType};
+use bytecodec::{
+    ByteCount, Decode, DecodeExt, Encode, EncodeExt, Eos, ErrorKind, Result, SizedEncode,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub use self::descriptor::{
+    DescriptorProto, DescriptorProtoDecoder, DescriptorProtoEncoder, EnumDescriptorProto,
+    EnumDescriptorProtoDecoder, EnumDescriptorProtoEncoder, EnumReservedRange,
+    EnumReservedRangeDecoder, EnumReservedRangeEncoder, EnumValueDescriptorProto,
+    EnumValueDescriptorProtoDecoder, EnumValueDescriptorProtoEncoder, ExtensionRange,
+    ExtensionRangeDecoder, ExtensionRangeEncoder, FieldDescriptorProto,
+    FieldDescriptorProtoDecoder, FieldDescriptorProtoEncoder, FieldDescriptorProtoLabel,
+    FieldDescriptorProtoType, FileDescriptorProto, FileDescriptorProtoDecoder,
+    FileDescriptorProtoEncoder, FileDescriptorSet, FileDescriptorSetDecoder,
+    FileDescriptorSetEncoder, MethodDescriptorProto, MethodDescriptorProtoDecoder,
+    MethodDescriptorProtoEncoder, OneofDescriptorProto, OneofDescriptorProtoDecoder,
+    OneofDescriptorProtoEncoder, ReservedRange, ReservedRangeDecoder, ReservedRangeEncoder,
+    ServiceDescriptorProto, ServiceDescriptorProtoDecoder, ServiceDescriptorProtoEncoder,
+};
+
+mod descriptor;
 
 /// Decoder for [Empty] Message.
 ///
@@ -41,7 +79,11 @@ impl Decode for EmptyMessageDecoder {
         self.0.is_idle()
     }
 }
-impl MessageDecode for EmptyMessageDecoder {}
+impl MessageDecode for EmptyMessageDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.0.resume_decoding())
+    }
+}
 
 /// Encoder for [Empty] Message.
 ///
@@ -184,7 +226,11 @@ impl Decode for DurationMessageDecoder {
         self.inner.is_idle()
     }
 }
-impl MessageDecode for DurationMessageDecoder {}
+impl MessageDecode for DurationMessageDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
 
 /// Encoder for [Duration] message.
 ///
@@ -263,7 +309,11 @@ impl Decode for StdDurationDecoder {
         self.0.is_idle()
     }
 }
-impl MessageDecode for StdDurationDecoder {}
+impl MessageDecode for StdDurationDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.0.resume_decoding())
+    }
+}
 
 /// Encoder for `std::time::Duration`.
 ///
@@ -303,19 +353,1961 @@ impl SizedEncode for StdDurationEncoder {
 }
 impl MessageEncode for StdDurationEncoder {}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::field::{num::F2, MessageFieldDecoder};
-    use crate::message::MessageDecoder;
-    use bytecodec::DecodeExt;
+/// [Timestamp] message.
+///
+/// [Timestamp]: https://github.com/google/protobuf/blob/master/src/google/protobuf/timestamp.proto
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimestampMessage {
+    seconds: i64,
+    nanos: i32,
+}
+impl TimestampMessage {
+    /// Minimum value of the seconds part of `TimestampMessage` (i.e., `0001-01-01T00:00:00Z`).
+    pub const MIN_SECONDS: i64 = -62_135_596_800;
 
-    #[test]
-    fn duration_decoder_works() {
-        let mut decoder: MessageDecoder<MessageFieldDecoder<F2, StdDurationDecoder>> =
-            Default::default();
+    /// Maximum value of the seconds part of `TimestampMessage` (i.e., `9999-12-31T23:59:59Z`).
+    pub const MAX_SECONDS: i64 = 253_402_300_799;
 
-        let input = [18, 0];
-        track_try_unwrap!(decoder.decode_from_bytes(&input[..]));
+    /// Minimum value of the nanoseconds part of `TimestampMessage`.
+    pub const MIN_NANOS: i32 = 0;
+
+    /// Maximum value of the nanoseconds part of `TimestampMessage`.
+    pub const MAX_NANOS: i32 = 999_999_999;
+
+    /// Makes a new `TimestampMessage` instance.
+    ///
+    /// `seconds` is the number of seconds since the Unix epoch (`1970-01-01T00:00:00Z`).
+    ///
+    /// # Errors
+    ///
+    /// If `seconds` or `nanos` are too small or too large,
+    /// an `ErrorKind::InvalidInput` error will be returned.
+    pub fn new(seconds: i64, nanos: i32) -> Result<Self> {
+        track_assert!(Self::MIN_SECONDS <= seconds, ErrorKind::InvalidInput; seconds, nanos);
+        track_assert!(seconds <= Self::MAX_SECONDS, ErrorKind::InvalidInput; seconds, nanos);
+        track_assert!(Self::MIN_NANOS <= nanos, ErrorKind::InvalidInput; seconds, nanos);
+        track_assert!(nanos <= Self::MAX_NANOS, ErrorKind::InvalidInput; seconds, nanos);
+        Ok(TimestampMessage { seconds, nanos })
+    }
+
+    /// Makes a new `TimestampMessage` instance from `SystemTime`.
+    ///
+    /// # Errors
+    ///
+    /// If `t` is out of the range of `TimestampMessage`, an `ErrorKind::InvalidInput` error will be returned.
+    pub fn from_system_time(t: SystemTime) -> Result<Self> {
+        match t.duration_since(UNIX_EPOCH) {
+            Ok(d) => {
+                let seconds = track_assert_some!(
+                    i64::try_from(d.as_secs()).ok(),
+                    ErrorKind::InvalidInput; d
+                );
+                track!(Self::new(seconds, d.subsec_nanos() as i32))
+            }
+            Err(e) => {
+                let d = e.duration();
+                let seconds = track_assert_some!(
+                    i64::try_from(d.as_secs()).ok(),
+                    ErrorKind::InvalidInput; d
+                );
+                if d.subsec_nanos() == 0 {
+                    track!(Self::new(-seconds, 0))
+                } else {
+                    let nanos = 1_000_000_000 - d.subsec_nanos() as i32;
+                    track!(Self::new(-seconds - 1, nanos))
+                }
+            }
+        }
+    }
+
+    /// Returns the seconds part of the instance.
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Returns the nanoseconds part of the instance.
+    pub fn nanos(&self) -> i32 {
+        self.nanos
+    }
+
+    /// Converts `TimestampMessage` to `std::time::SystemTime`.
+    ///
+    /// If the instance cannot be represented by `SystemTime` on the current platform,
+    /// this method will return `None`.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        let nanos = Duration::from_nanos(self.nanos as u64);
+        if self.seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(self.seconds as u64, 0) + nanos)
+        } else {
+            UNIX_EPOCH
+                .checked_sub(Duration::new(self.seconds.unsigned_abs(), 0))?
+                .checked_add(nanos)
+        }
+    }
+
+    /// Formats the instance in the RFC 3339 format (e.g., `1972-01-01T10:00:20.021Z`).
+    ///
+    /// The result is always normalized to UTC ("Z") and has 0, 3, 6 or 9 fractional digits.
+    pub fn to_rfc3339(&self) -> String {
+        let days = self.seconds.div_euclid(86_400);
+        let secs = self.seconds.rem_euclid(86_400);
+        let (year, month, day) = civil_from_days(days);
+        let mut s = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        );
+        let nanos = self.nanos;
+        if nanos % 1_000_000_000 == 0 {
+            s.push('Z');
+        } else if nanos % 1_000_000 == 0 {
+            s.push_str(&format!(".{:03}Z", nanos / 1_000_000));
+        } else if nanos % 1_000 == 0 {
+            s.push_str(&format!(".{:06}Z", nanos / 1_000));
+        } else {
+            s.push_str(&format!(".{:09}Z", nanos));
+        }
+        s
+    }
+
+    /// Parses an RFC 3339 timestamp (e.g., `1972-01-01T10:00:20.021+05:30`).
+    ///
+    /// Both "Z" and numeric UTC offsets are accepted.
+    ///
+    /// # Errors
+    ///
+    /// If `s` is malformed or out of the range of `TimestampMessage`,
+    /// an `ErrorKind::InvalidInput` error will be returned.
+    pub fn parse_rfc3339(s: &str) -> Result<Self> {
+        let (seconds, nanos) = track_assert_some!(
+            parse_rfc3339(s),
+            ErrorKind::InvalidInput,
+            "Invalid RFC 3339 timestamp: {:?}",
+            s
+        );
+        track!(Self::new(seconds, nanos))
+    }
+}
+
+// Converts a date in the proleptic Gregorian calendar to the number of days since 1970-01-01.
+//
+// See: http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn parse_rfc3339(s: &str) -> Option<(i64, i32)> {
+    let b = s.as_bytes();
+    let digits = |i: usize, n: usize| -> Option<i64> {
+        let d = b.get(i..i + n)?;
+        if d.iter().all(u8::is_ascii_digit) {
+            std::str::from_utf8(d).ok()?.parse().ok()
+        } else {
+            None
+        }
+    };
+    let symbol = |i: usize, c: &[u8]| b.get(i).is_some_and(|x| c.contains(x));
+    let separators = [(4, "-"), (7, "-"), (10, "Tt"), (13, ":"), (16, ":")];
+    if !separators.iter().all(|&(i, c)| symbol(i, c.as_bytes())) {
+        return None;
+    }
+    let (year, month, day) = (digits(0, 4)?, digits(5, 2)?, digits(8, 2)?);
+    let (hour, minute, second) = (digits(11, 2)?, digits(14, 2)?, digits(17, 2)?);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if day < 1 || day > days_in_month || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let mut i = 19;
+    let mut nanos = 0;
+    if symbol(i, b".") {
+        i += 1;
+        let start = i;
+        while b.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if i == start || i - start > 9 {
+            return None;
+        }
+        nanos = format!("{:0<9}", &s[start..i]).parse::<i32>().ok()?;
+    }
+
+    let offset = if symbol(i, b"Zz") && b.len() == i + 1 {
+        0
+    } else if symbol(i, b"+-") && symbol(i + 3, b":") && b.len() == i + 6 {
+        let (h, m) = (digits(i + 1, 2)?, digits(i + 4, 2)?);
+        if h > 23 || m > 59 {
+            return None;
+        }
+        let offset = h * 3600 + m * 60;
+        if b[i] == b'-' {
+            -offset
+        } else {
+            offset
+        }
+    } else {
+        return None;
+    };
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+    Some((seconds - offset, nanos))
+}
+
+/// Decoder for [Timestamp] message.
+///
+/// [Timestamp]: https://github.com/google/protobuf/blob/master/src/google/protobuf/timestamp.proto
+#[derive(Debug, Default)]
+pub struct TimestampMessageDecoder {
+    inner: MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, Int64Decoder>>,
+            MaybeDefault<FieldDecoder<F2, Int32Decoder>>,
+        )>,
+    >,
+}
+impl TimestampMessageDecoder {
+    /// Makes a new `TimestampMessageDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for TimestampMessageDecoder {
+    type Item = TimestampMessage;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let (seconds, nanos) = track!(self.inner.finish_decoding())?;
+        track!(TimestampMessage::new(seconds, nanos))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl MessageDecode for TimestampMessageDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
+
+/// Encoder for [Timestamp] message.
+///
+/// [Timestamp]: https://github.com/google/protobuf/blob/master/src/google/protobuf/timestamp.proto
+#[derive(Debug, Default)]
+pub struct TimestampMessageEncoder {
+    inner: MessageEncoder<
+        Fields<(
+            MaybeDefault<FieldEncoder<F1, Int64Encoder>>,
+            MaybeDefault<FieldEncoder<F2, Int32Encoder>>,
+        )>,
+    >,
+}
+impl TimestampMessageEncoder {
+    /// Makes a new `TimestampMessageEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for TimestampMessageEncoder {
+    type Item = TimestampMessage;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let item = (item.seconds, item.nanos);
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl SizedEncode for TimestampMessageEncoder {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+impl MessageEncode for TimestampMessageEncoder {}
+
+/// Decoder for `std::time::SystemTime`.
+///
+/// This is based on [TimestampMessageDecoder](./struct.TimestampMessageDecoder.html).
+#[derive(Debug, Default)]
+pub struct SystemTimeDecoder(TimestampMessageDecoder);
+impl SystemTimeDecoder {
+    /// Makes a new `SystemTimeDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for SystemTimeDecoder {
+    type Item = SystemTime;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let m = track!(self.0.finish_decoding())?;
+        let t = track_assert_some!(m.to_system_time(), ErrorKind::InvalidInput; m);
+        Ok(t)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+}
+impl MessageDecode for SystemTimeDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.0.resume_decoding())
+    }
+}
+
+/// Encoder for `std::time::SystemTime`.
+///
+/// This is based on [TimestampMessageEncoder](./struct.TimestampMessageEncoder.html).
+#[derive(Debug, Default)]
+pub struct SystemTimeEncoder(TimestampMessageEncoder);
+impl SystemTimeEncoder {
+    /// Makes a new `SystemTimeEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for SystemTimeEncoder {
+    type Item = SystemTime;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let item = track!(TimestampMessage::from_system_time(item))?;
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+}
+impl SizedEncode for SystemTimeEncoder {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl MessageEncode for SystemTimeEncoder {}
+
+/// [Any] message.
+///
+/// The packed message is kept as raw bytes, so it can be decoded lazily
+/// (e.g., by using [`AnyRegistry`](./struct.AnyRegistry.html)).
+///
+/// [Any]: https://github.com/google/protobuf/blob/master/src/google/protobuf/any.proto
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct AnyMessage {
+    type_url: String,
+    value: Vec<u8>,
+}
+impl AnyMessage {
+    /// The prefix of the type URLs made by `AnyMessage::pack` and `AnyEncoder`.
+    pub const TYPE_URL_PREFIX: &'static str = "type.googleapis.com/";
+
+    /// Makes a new `AnyMessage` instance.
+    pub fn new(type_url: &str, value: Vec<u8>) -> Self {
+        AnyMessage {
+            type_url: type_url.to_owned(),
+            value,
+        }
+    }
+
+    /// Encodes `item` by using `encoder` and packs the resulting bytes into a new `AnyMessage` instance.
+    ///
+    /// `type_name` is the full name of the message (e.g., `google.protobuf.Duration`).
+    pub fn pack<E: MessageEncode>(type_name: &str, encoder: &mut E, item: E::Item) -> Result<Self> {
+        let value = track!(encoder.encode_into_bytes(item))?;
+        Ok(AnyMessage {
+            type_url: format!("{}{}", Self::TYPE_URL_PREFIX, type_name),
+            value,
+        })
+    }
+
+    /// Decodes the packed message by using `decoder`.
+    ///
+    /// # Errors
+    ///
+    /// If the type name of the instance is not `type_name`,
+    /// an `ErrorKind::InvalidInput` error will be returned.
+    pub fn unpack<D: MessageDecode>(&self, type_name: &str, decoder: &mut D) -> Result<D::Item> {
+        track_assert!(
+            self.is(type_name),
+            ErrorKind::InvalidInput,
+            "Unexpected type: actual={}, expected={}",
+            self.type_url,
+            type_name
+        );
+        track!(decoder.decode_from_bytes(&self.value))
+    }
+
+    /// Returns the type URL of the instance.
+    pub fn type_url(&self) -> &str {
+        &self.type_url
+    }
+
+    /// Returns the type name of the instance.
+    ///
+    /// This is the part of the type URL after the last `/`.
+    pub fn type_name(&self) -> &str {
+        type_name(&self.type_url)
+    }
+
+    /// Returns `true` if the type name of the instance is `type_name`, otherwise `false`.
+    pub fn is(&self, type_name: &str) -> bool {
+        self.type_name() == type_name
+    }
+
+    /// Returns the encoded bytes of the packed message.
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+
+    /// Takes ownership of the instance and returns the encoded bytes of the packed message.
+    pub fn into_value(self) -> Vec<u8> {
+        self.value
+    }
+}
+
+fn type_name(type_url: &str) -> &str {
+    type_url.rsplit('/').next().expect("Never fails")
+}
+
+/// Decoder for [Any] message.
+///
+/// [Any]: https://github.com/google/protobuf/blob/master/src/google/protobuf/any.proto
+#[derive(Debug, Default)]
+pub struct AnyMessageDecoder {
+    inner: MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+            MaybeDefault<FieldDecoder<F2, BytesDecoder>>,
+        )>,
+    >,
+}
+impl AnyMessageDecoder {
+    /// Makes a new `AnyMessageDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for AnyMessageDecoder {
+    type Item = AnyMessage;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let (type_url, value) = track!(self.inner.finish_decoding())?;
+        Ok(AnyMessage { type_url, value })
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl MessageDecode for AnyMessageDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
+
+/// Encoder for [Any] message.
+///
+/// [Any]: https://github.com/google/protobuf/blob/master/src/google/protobuf/any.proto
+#[derive(Debug, Default)]
+pub struct AnyMessageEncoder {
+    inner: MessageEncoder<
+        Fields<(
+            MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+            MaybeDefault<FieldEncoder<F2, BytesEncoder>>,
+        )>,
+    >,
+}
+impl AnyMessageEncoder {
+    /// Makes a new `AnyMessageEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for AnyMessageEncoder {
+    type Item = AnyMessage;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let item = (item.type_url, item.value);
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl SizedEncode for AnyMessageEncoder {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+impl MessageEncode for AnyMessageEncoder {}
+
+/// Decoder for [Any] messages that pack messages of a specific type.
+///
+/// Unlike `AnyMessageDecoder`, the packed message is decoded by `D` directly
+/// (via `CustomBytesDecoder`) without buffering the encoded bytes.
+///
+/// If the `value` field is missing (i.e., the packed message is empty), the default item is returned.
+///
+/// [Any]: https://github.com/google/protobuf/blob/master/src/google/protobuf/any.proto
+#[derive(Debug)]
+pub struct AnyDecoder<D>
+where
+    D: MessageDecode,
+    D::Item: Default,
+{
+    type_name: String,
+    inner: MessageDecoder<
+        Fields<(
+            MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+            MaybeDefault<FieldDecoder<F2, CustomBytesDecoder<D>>>,
+        )>,
+    >,
+}
+impl<D> AnyDecoder<D>
+where
+    D: MessageDecode,
+    D::Item: Default,
+{
+    /// Makes a new `AnyDecoder` instance.
+    ///
+    /// `type_name` is the full name of the message decoded by `inner`.
+    pub fn new(type_name: &str, inner: D) -> Self {
+        let fields = Fields::new((
+            Default::default(),
+            MaybeDefault::new(FieldDecoder::new(F2, CustomBytesDecoder::new(inner))),
+        ));
+        AnyDecoder {
+            type_name: type_name.to_owned(),
+            inner: MessageDecoder::new(fields),
+        }
+    }
+}
+impl<D> Decode for AnyDecoder<D>
+where
+    D: MessageDecode,
+    D::Item: Default,
+{
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let (type_url, item) = track!(self.inner.finish_decoding())?;
+        track_assert_eq!(
+            type_name(&type_url),
+            self.type_name,
+            ErrorKind::InvalidInput;
+            type_url
+        );
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl<D> MessageDecode for AnyDecoder<D>
+where
+    D: MessageDecode,
+    D::Item: Default,
+{
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
+
+/// Encoder that packs messages encoded by `E` into [Any] messages.
+///
+/// Unlike `AnyMessage::pack`, the packed message is encoded by `E` directly
+/// (via `CustomBytesEncoder`) without buffering the encoded bytes.
+///
+/// [Any]: https://github.com/google/protobuf/blob/master/src/google/protobuf/any.proto
+#[derive(Debug)]
+pub struct AnyEncoder<E: MessageEncode + SizedEncode> {
+    type_url: String,
+    inner: MessageEncoder<
+        Fields<(
+            FieldEncoder<F1, StringEncoder>,
+            FieldEncoder<F2, CustomBytesEncoder<E>>,
+        )>,
+    >,
+}
+impl<E: MessageEncode + SizedEncode> AnyEncoder<E> {
+    /// Makes a new `AnyEncoder` instance.
+    ///
+    /// `type_name` is the full name of the message encoded by `inner`.
+    /// The type URLs of the resulting messages are prefixed by `AnyMessage::TYPE_URL_PREFIX`.
+    pub fn new(type_name: &str, inner: E) -> Self {
+        let fields = Fields::new((
+            FieldEncoder::new(F1, StringEncoder::new()),
+            FieldEncoder::new(F2, CustomBytesEncoder::new(inner)),
+        ));
+        AnyEncoder {
+            type_url: format!("{}{}", AnyMessage::TYPE_URL_PREFIX, type_name),
+            inner: MessageEncoder::new(fields),
+        }
+    }
+}
+impl<E: MessageEncode + SizedEncode> Encode for AnyEncoder<E> {
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let item = (self.type_url.clone(), item);
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl<E: MessageEncode + SizedEncode> SizedEncode for AnyEncoder<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+impl<E: MessageEncode + SizedEncode> MessageEncode for AnyEncoder<E> {}
+
+type UnpackFn = Box<dyn Fn(&[u8]) -> Result<Box<dyn std::any::Any + Send>> + Send + Sync>;
+
+/// Registry that maps the types of [Any] messages to the decoders of the packed messages.
+///
+/// The types are identified by their names (i.e., the part of the type URL after the last `/`),
+/// so type URLs that have different prefixes are regarded as the same type.
+///
+/// [Any]: https://github.com/google/protobuf/blob/master/src/google/protobuf/any.proto
+#[derive(Default)]
+pub struct AnyRegistry {
+    decoders: HashMap<String, UnpackFn>,
+}
+impl AnyRegistry {
+    /// Makes a new empty `AnyRegistry` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the message type named `type_name`.
+    ///
+    /// `factory` is called to create a decoder every time a message of the type is unpacked.
+    /// If the type has already been registered, the old factory is replaced.
+    pub fn register<D, F>(&mut self, type_name: &str, factory: F)
+    where
+        D: MessageDecode,
+        D::Item: Send + 'static,
+        F: Fn() -> D + Send + Sync + 'static,
+    {
+        let unpack = move |bytes: &[u8]| -> Result<Box<dyn std::any::Any + Send>> {
+            let item = track!(factory().decode_from_bytes(bytes))?;
+            Ok(Box::new(item))
+        };
+        self.decoders.insert(type_name.to_owned(), Box::new(unpack));
+    }
+
+    /// Returns `true` if the type of `type_url` has been registered, otherwise `false`.
+    ///
+    /// `type_url` can be either a type URL or a type name.
+    pub fn is_registered(&self, type_url: &str) -> bool {
+        self.decoders.contains_key(type_name(type_url))
+    }
+
+    /// Decodes the message packed in `any` by using the decoder registered for the type.
+    ///
+    /// # Errors
+    ///
+    /// If the type has not been registered, an `ErrorKind::InvalidInput` error will be returned.
+    pub fn unpack(&self, any: &AnyMessage) -> Result<Box<dyn std::any::Any + Send>> {
+        let unpack = track_assert_some!(
+            self.decoders.get(any.type_name()),
+            ErrorKind::InvalidInput,
+            "Unregistered type: {}",
+            any.type_url
+        );
+        track!(unpack(&any.value); any.type_url)
+    }
+
+    /// Decodes the message packed in `any` and downcasts it to `T`.
+    ///
+    /// # Errors
+    ///
+    /// If the type has not been registered or the decoded item is not a `T`,
+    /// an `ErrorKind::InvalidInput` error will be returned.
+    pub fn unpack_as<T: 'static>(&self, any: &AnyMessage) -> Result<T> {
+        let item = track!(self.unpack(any))?;
+        match item.downcast::<T>() {
+            Ok(item) => Ok(*item),
+            Err(_) => track_panic!(
+                ErrorKind::InvalidInput,
+                "Unexpected item type: type_url={}",
+                any.type_url
+            ),
+        }
+    }
+}
+impl fmt::Debug for AnyRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut types = self.decoders.keys().collect::<Vec<_>>();
+        types.sort();
+        f.debug_struct("AnyRegistry")
+            .field("types", &types)
+            .finish()
+    }
+}
+
+/// Value of [Value] message.
+///
+/// This is also used for representing the fields of [Struct] and the elements of [ListValue].
+///
+/// [Value]: https://github.com/google/protobuf/blob/master/src/google/protobuf/struct.proto
+/// [Struct]: https://github.com/google/protobuf/blob/master/src/google/protobuf/struct.proto
+/// [ListValue]: https://github.com/google/protobuf/blob/master/src/google/protobuf/struct.proto
+#[derive(Debug, Default, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum Value {
+    #[default]
+    Null,
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Struct(BTreeMap<String, Value>),
+    List(Vec<Value>),
+}
+
+/// [NullValue] enum.
+///
+/// This can be used with `EnumDecoder` and `EnumEncoder`.
+///
+/// [NullValue]: https://github.com/google/protobuf/blob/master/src/google/protobuf/struct.proto
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NullValue;
+impl Enum for NullValue {
+    fn from_i32(n: i32) -> Option<Self> {
+        if n == 0 {
+            Some(NullValue)
+        } else {
+            None
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        0
+    }
+}
+
+type ValueKind = Branch6<NullValue, f64, String, bool, BTreeMap<String, Value>, Vec<Value>>;
+
+fn value_from_kind(kind: Option<ValueKind>) -> Value {
+    match kind {
+        None | Some(Branch6::A(NullValue)) => Value::Null,
+        Some(Branch6::B(v)) => Value::Number(v),
+        Some(Branch6::C(v)) => Value::String(v),
+        Some(Branch6::D(v)) => Value::Bool(v),
+        Some(Branch6::E(v)) => Value::Struct(v),
+        Some(Branch6::F(v)) => Value::List(v),
+    }
+}
+
+fn kind_from_value(value: Value) -> Option<ValueKind> {
+    Some(match value {
+        Value::Null => Branch6::A(NullValue),
+        Value::Number(v) => Branch6::B(v),
+        Value::String(v) => Branch6::C(v),
+        Value::Bool(v) => Branch6::D(v),
+        Value::Struct(v) => Branch6::E(v),
+        Value::List(v) => Branch6::F(v),
+    })
+}
+
+// Messages in `struct.proto` are mutually recursive,
+// so `Value` refers to `Struct` and `ListValue` through boxed codecs.
+type StructInnerDecoder = MessageDecoder<
+    Fields<(MapMessageFieldDecoder<F1, StringDecoder, ValueDecoder, BTreeMap<String, Value>>,)>,
+>;
+type StructInnerEncoder = MessageEncoder<
+    Fields<
+        (SizedMapMessageFieldEncoder<F1, StringEncoder, ValueEncoder, BTreeMap<String, Value>>,),
+    >,
+>;
+type ValueInnerDecoder = MessageDecoder<
+    Fields<(
+        Optional<
+            Oneof<(
+                FieldDecoder<F1, EnumDecoder<NullValue>>,
+                FieldDecoder<F2, DoubleDecoder>,
+                FieldDecoder<F3, StringDecoder>,
+                FieldDecoder<F4, BoolDecoder>,
+                MessageFieldDecoder<F5, BoxedMessageDecoder<StructDecoder>>,
+                MessageFieldDecoder<F6, BoxedMessageDecoder<ListValueDecoder>>,
+            )>,
+        >,
+    )>,
+>;
+type ValueInnerEncoder = MessageEncoder<
+    Fields<(
+        Optional<
+            Oneof<(
+                FieldEncoder<F1, EnumEncoder<NullValue>>,
+                FieldEncoder<F2, DoubleEncoder>,
+                FieldEncoder<F3, StringEncoder>,
+                FieldEncoder<F4, BoolEncoder>,
+                MessageFieldEncoder<F5, BoxedMessageEncoder<StructEncoder>>,
+                MessageFieldEncoder<F6, BoxedMessageEncoder<ListValueEncoder>>,
+            )>,
+        >,
+    )>,
+>;
+type ListValueInnerDecoder =
+    MessageDecoder<Fields<(Repeated<MessageFieldDecoder<F1, ValueDecoder>, Vec<Value>>,)>>;
+type ListValueInnerEncoder =
+    MessageEncoder<Fields<(SizedRepeated<MessageFieldEncoder<F1, ValueEncoder>, Vec<Value>>,)>>;
+
+macro_rules! impl_struct_message_codec {
+    ($decoder:ident, $encoder:ident, $inner_decoder:ty, $inner_encoder:ty, $item:ty, $message:expr,
+     |$decoded:pat_param| $from_decoded:expr, |$item_var:ident| $to_encoded:expr) => {
+        #[doc = "Decoder for [`"]
+        #[doc = $message]
+        #[doc = "`](https://github.com/google/protobuf/blob/master/src/google/protobuf/struct.proto) message."]
+        ///
+        /// The nesting depth of `Struct`, `Value` and `ListValue` messages is limited to
+        /// `limit::DEFAULT_MAX_DEPTH` by default, even if the decoder is not driven by a `LimitedDecoder`.
+        /// If the limit is exceeded, an `ErrorKind::InvalidInput` error caused by `LimitExceeded::Depth` is returned.
+        #[derive(Debug)]
+        pub struct $decoder {
+            inner: $inner_decoder,
+            max_depth: Option<usize>,
+        }
+        impl $decoder {
+            #[doc = "Makes a new `"]
+            #[doc = stringify!($decoder)]
+            #[doc = "` instance."]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Returns the maximum nesting depth of embedded messages.
+            pub fn max_depth(&self) -> Option<usize> {
+                self.max_depth
+            }
+
+            /// Sets the maximum nesting depth of embedded messages.
+            ///
+            /// The fields of the outermost message are at depth `0`.
+            /// `None` means that the depth is not limited by the decoder itself
+            /// (the limit of an enclosing `LimitedDecoder` is still enforced).
+            pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+                self.max_depth = max_depth;
+            }
+        }
+        impl Default for $decoder {
+            fn default() -> Self {
+                $decoder {
+                    inner: Default::default(),
+                    max_depth: Some(limit::DEFAULT_MAX_DEPTH),
+                }
+            }
+        }
+        impl Decode for $decoder {
+            type Item = $item;
+
+            fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+                let inner = &mut self.inner;
+                track!(limit::with_max_depth(self.max_depth, || inner.decode(buf, eos)))
+            }
+
+            fn finish_decoding(&mut self) -> Result<Self::Item> {
+                let $decoded = track!(self.inner.finish_decoding())?;
+                Ok($from_decoded)
+            }
+
+            fn requiring_bytes(&self) -> ByteCount {
+                self.inner.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.inner.is_idle()
+            }
+        }
+        impl MessageDecode for $decoder {
+            fn resume_decoding(&mut self) -> Result<()> {
+                track!(self.inner.resume_decoding())
+            }
+        }
+
+        #[doc = "Encoder for [`"]
+        #[doc = $message]
+        #[doc = "`](https://github.com/google/protobuf/blob/master/src/google/protobuf/struct.proto) message."]
+        #[derive(Debug, Default)]
+        pub struct $encoder {
+            inner: $inner_encoder,
+        }
+        impl $encoder {
+            #[doc = "Makes a new `"]
+            #[doc = stringify!($encoder)]
+            #[doc = "` instance."]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl Encode for $encoder {
+            type Item = $item;
+
+            fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+                track!(self.inner.encode(buf, eos))
+            }
+
+            fn start_encoding(&mut self, $item_var: Self::Item) -> Result<()> {
+                track!(self.inner.start_encoding($to_encoded))
+            }
+
+            fn requiring_bytes(&self) -> ByteCount {
+                self.inner.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.inner.is_idle()
+            }
+        }
+        impl SizedEncode for $encoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                self.inner.exact_requiring_bytes()
+            }
+        }
+        impl MessageEncode for $encoder {}
+    };
+}
+impl_struct_message_codec!(
+    StructDecoder,
+    StructEncoder,
+    StructInnerDecoder,
+    StructInnerEncoder,
+    BTreeMap<String, Value>,
+    "Struct",
+    |(fields,)| fields,
+    |item| (item,)
+);
+impl_struct_message_codec!(
+    ValueDecoder,
+    ValueEncoder,
+    ValueInnerDecoder,
+    ValueInnerEncoder,
+    Value,
+    "Value",
+    |(kind,)| value_from_kind(kind),
+    |item| (kind_from_value(item),)
+);
+impl_struct_message_codec!(
+    ListValueDecoder,
+    ListValueEncoder,
+    ListValueInnerDecoder,
+    ListValueInnerEncoder,
+    Vec<Value>,
+    "ListValue",
+    |(values,)| values,
+    |item| (item,)
+);
+
+macro_rules! impl_wrapper_codec {
+    ($decoder:ident, $encoder:ident, $value_decoder:ty, $value_encoder:ty, $item:ty, $message:expr) => {
+        #[doc = "Decoder for [`"]
+        #[doc = $message]
+        #[doc = "`](https://github.com/google/protobuf/blob/master/src/google/protobuf/wrappers.proto) message."]
+        ///
+        /// The item is the wrapped value itself, so `Optional<MessageFieldDecoder<_, Self>>`
+        /// yields `Option<T>` for nullable fields.
+        #[derive(Debug, Default)]
+        pub struct $decoder {
+            inner: MessageDecoder<Fields<(MaybeDefault<FieldDecoder<F1, $value_decoder>>,)>>,
+        }
+        impl $decoder {
+            #[doc = "Makes a new `"]
+            #[doc = stringify!($decoder)]
+            #[doc = "` instance."]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl Decode for $decoder {
+            type Item = $item;
+
+            fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+                track!(self.inner.decode(buf, eos))
+            }
+
+            fn finish_decoding(&mut self) -> Result<Self::Item> {
+                let (value,) = track!(self.inner.finish_decoding())?;
+                Ok(value)
+            }
+
+            fn requiring_bytes(&self) -> ByteCount {
+                self.inner.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.inner.is_idle()
+            }
+        }
+        impl MessageDecode for $decoder {
+            fn resume_decoding(&mut self) -> Result<()> {
+                track!(self.inner.resume_decoding())
+            }
+        }
+
+        #[doc = "Encoder for [`"]
+        #[doc = $message]
+        #[doc = "`](https://github.com/google/protobuf/blob/master/src/google/protobuf/wrappers.proto) message."]
+        ///
+        /// The item is the wrapped value itself, so `Optional<MessageFieldEncoder<_, Self>>`
+        /// accepts `Option<T>` for nullable fields.
+        #[derive(Debug, Default)]
+        pub struct $encoder {
+            inner: MessageEncoder<Fields<(MaybeDefault<FieldEncoder<F1, $value_encoder>>,)>>,
+        }
+        impl $encoder {
+            #[doc = "Makes a new `"]
+            #[doc = stringify!($encoder)]
+            #[doc = "` instance."]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl Encode for $encoder {
+            type Item = $item;
+
+            fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+                track!(self.inner.encode(buf, eos))
+            }
+
+            fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+                track!(self.inner.start_encoding((item,)))
+            }
+
+            fn requiring_bytes(&self) -> ByteCount {
+                self.inner.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.inner.is_idle()
+            }
+        }
+        impl SizedEncode for $encoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                self.inner.exact_requiring_bytes()
+            }
+        }
+        impl MessageEncode for $encoder {}
+    };
+}
+impl_wrapper_codec!(
+    DoubleValueDecoder,
+    DoubleValueEncoder,
+    DoubleDecoder,
+    DoubleEncoder,
+    f64,
+    "DoubleValue"
+);
+impl_wrapper_codec!(
+    FloatValueDecoder,
+    FloatValueEncoder,
+    FloatDecoder,
+    FloatEncoder,
+    f32,
+    "FloatValue"
+);
+impl_wrapper_codec!(
+    Int64ValueDecoder,
+    Int64ValueEncoder,
+    Int64Decoder,
+    Int64Encoder,
+    i64,
+    "Int64Value"
+);
+impl_wrapper_codec!(
+    Uint64ValueDecoder,
+    Uint64ValueEncoder,
+    Uint64Decoder,
+    Uint64Encoder,
+    u64,
+    "UInt64Value"
+);
+impl_wrapper_codec!(
+    Int32ValueDecoder,
+    Int32ValueEncoder,
+    Int32Decoder,
+    Int32Encoder,
+    i32,
+    "Int32Value"
+);
+impl_wrapper_codec!(
+    Uint32ValueDecoder,
+    Uint32ValueEncoder,
+    Uint32Decoder,
+    Uint32Encoder,
+    u32,
+    "UInt32Value"
+);
+impl_wrapper_codec!(
+    BoolValueDecoder,
+    BoolValueEncoder,
+    BoolDecoder,
+    BoolEncoder,
+    bool,
+    "BoolValue"
+);
+impl_wrapper_codec!(
+    StringValueDecoder,
+    StringValueEncoder,
+    StringDecoder,
+    StringEncoder,
+    String,
+    "StringValue"
+);
+impl_wrapper_codec!(
+    BytesValueDecoder,
+    BytesValueEncoder,
+    BytesDecoder,
+    BytesEncoder,
+    Vec<u8>,
+    "BytesValue"
+);
+
+/// [FieldMask] message.
+///
+/// A path is a sequence of field names separated by dots (e.g., `"user.display_name"`).
+/// A path covers the field it refers to and all the fields nested in that field.
+///
+/// [FieldMask]: https://github.com/google/protobuf/blob/master/src/google/protobuf/field_mask.proto
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct FieldMaskMessage {
+    paths: Vec<String>,
+}
+impl FieldMaskMessage {
+    /// Makes a new `FieldMaskMessage` instance.
+    pub fn new<I, S>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        FieldMaskMessage {
+            paths: paths.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the paths of the mask.
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Takes ownership of the instance, and returns the paths of the mask.
+    pub fn into_paths(self) -> Vec<String> {
+        self.paths
+    }
+
+    /// Returns `true` if the given path is covered by this mask.
+    pub fn contains(&self, path: &str) -> bool {
+        self.paths.iter().any(|p| covers(p, path))
+    }
+
+    /// Normalizes the mask.
+    ///
+    /// The paths are sorted, and the paths covered by other paths (including duplicates) are removed.
+    pub fn normalize(&mut self) {
+        self.paths.sort();
+        let mut paths: Vec<String> = Vec::with_capacity(self.paths.len());
+        for path in self.paths.drain(..) {
+            if !paths.last().is_some_and(|last| covers(last, &path)) {
+                paths.push(path);
+            }
+        }
+        self.paths = paths;
+    }
+
+    /// Returns the normalized mask that covers the paths covered by either `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut mask = FieldMaskMessage::new(self.paths.iter().chain(other.paths.iter()).cloned());
+        mask.normalize();
+        mask
+    }
+
+    /// Returns the normalized mask that covers the paths covered by both `self` and `other`.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut paths = Vec::new();
+        for a in &self.paths {
+            for b in &other.paths {
+                if covers(a, b) {
+                    paths.push(b.clone());
+                } else if covers(b, a) {
+                    paths.push(a.clone());
+                }
+            }
+        }
+        let mut mask = FieldMaskMessage::new(paths);
+        mask.normalize();
+        mask
+    }
+
+    /// Returns a copy of `message` that only has the fields covered by this mask.
+    ///
+    /// Unknown fields are dropped.
+    ///
+    /// If a path refers to a field that does not exist, or traverses a field that is not
+    /// a singular message field, this returns an `ErrorKind::InvalidInput` error.
+    pub fn apply_to_message(&self, message: &DynamicMessage) -> Result<DynamicMessage> {
+        track!(mask_message(message, &self.path_tree()))
+    }
+
+    /// Filters the encoded message `bytes`, and returns the encoded message that only has
+    /// the fields covered by this mask.
+    ///
+    /// `message_name` is the full name of the message, and the names in the paths are
+    /// resolved by using the descriptors in `pool`. Unknown fields are dropped.
+    /// The fields are not decoded unless paths traverse them, so the covered fields are
+    /// emitted as they are.
+    ///
+    /// If `pool` does not have the message, a path is invalid (see `apply_to_message`) or
+    /// `bytes` is malformed, this returns an `ErrorKind::InvalidInput` error.
+    pub fn apply_to_bytes(
+        &self,
+        bytes: &[u8],
+        pool: &DescriptorPool,
+        message_name: &str,
+    ) -> Result<Vec<u8>> {
+        let descriptor = track_assert_some!(
+            pool.message(message_name),
+            ErrorKind::InvalidInput,
+            "Unknown message: {}",
+            message_name
+        );
+        let mut buf = Vec::with_capacity(bytes.len());
+        track!(mask_bytes(
+            bytes,
+            descriptor,
+            pool,
+            &self.path_tree(),
+            &mut buf
+        ))?;
+        Ok(buf)
+    }
+
+    fn path_tree(&self) -> PathTree {
+        let mut mask = self.clone();
+        mask.normalize();
+
+        let mut root = PathTree::default();
+        for path in &mask.paths {
+            let mut node = &mut root;
+            for name in path.split('.') {
+                node = node.0.entry(name.to_owned()).or_default();
+            }
+        }
+        root
+    }
+}
+
+fn covers(path: &str, other: &str) -> bool {
+    other.starts_with(path) && (other.len() == path.len() || other.as_bytes()[path.len()] == b'.')
+}
+
+// Children of a node. A node that has no children covers the whole field.
+#[derive(Debug, Default)]
+struct PathTree(BTreeMap<String, PathTree>);
+
+fn masked_field<'a>(
+    descriptor: &'a MessageDescriptor,
+    name: &str,
+    tree: &PathTree,
+) -> Result<&'a dynamic::FieldDescriptor> {
+    let field = track_assert_some!(
+        descriptor.field_by_name(name),
+        ErrorKind::InvalidInput,
+        "Unknown field: message={}, name={}",
+        descriptor.name,
+        name
+    );
+    if !tree.0.is_empty() {
+        track_assert!(
+            !field.is_repeated() && matches!(field.ty, FieldType::Message(_) | FieldType::Group(_)),
+            ErrorKind::InvalidInput,
+            "Cannot traverse a non singular message field: message={}, field={}",
+            descriptor.name,
+            name
+        );
+    }
+    Ok(field)
+}
+
+fn mask_message(message: &DynamicMessage, tree: &PathTree) -> Result<DynamicMessage> {
+    let descriptor = message.descriptor();
+    let mut masked = DynamicMessage::new(descriptor.clone());
+    for (name, subtree) in &tree.0 {
+        let field = track!(masked_field(descriptor, name, subtree))?;
+        let value = match (message.get(field.number), subtree.0.is_empty()) {
+            (None, _) => continue,
+            (Some(v), true) => v.clone(),
+            (Some(dynamic::Value::Message(m)), false) => {
+                dynamic::Value::Message(track!(mask_message(m, subtree); name)?)
+            }
+            (Some(v), false) => track_panic!(ErrorKind::InvalidInput, "Unexpected value: {:?}", v),
+        };
+        track!(masked.set(field.number, value))?;
+    }
+    Ok(masked)
+}
+
+fn mask_bytes(
+    mut bytes: &[u8],
+    descriptor: &MessageDescriptor,
+    pool: &DescriptorPool,
+    tree: &PathTree,
+    buf: &mut Vec<u8>,
+) -> Result<()> {
+    let mut fields = HashMap::new();
+    for (name, subtree) in &tree.0 {
+        let field = track!(masked_field(descriptor, name, subtree))?;
+        fields.insert(field.number, (field, subtree));
+    }
+
+    while !bytes.is_empty() {
+        let start = bytes;
+        let (tag, value) = track!(wire::read_field(&mut bytes))?;
+        let number = tag.field_num.as_u32();
+        let (field, subtree) = match fields.get(&number) {
+            None => continue,
+            Some(&(_, subtree)) if subtree.0.is_empty() => {
+                buf.extend_from_slice(&start[..start.len() - bytes.len()]);
+                continue;
+            }
+            Some(&x) => x,
+        };
+
+        let name = match field.ty {
+            FieldType::Message(ref name) | FieldType::Group(ref name) => name,
+            _ => unreachable!(),
+        };
+        let nested = track_assert_some!(
+            pool.message(name),
+            ErrorKind::InvalidInput,
+            "Unknown message: {}",
+            name
+        );
+        match tag.wire_type {
+            WireType::LengthDelimited => {
+                let mut masked = Vec::new();
+                track!(mask_bytes(value, nested, pool, subtree, &mut masked); field.name)?;
+                wire::write_tag(buf, number, WireType::LengthDelimited);
+                wire::write_varint(buf, masked.len() as u64);
+                buf.extend_from_slice(&masked);
+            }
+            WireType::StartGroup => {
+                wire::write_tag(buf, number, WireType::StartGroup);
+                track!(mask_bytes(value, nested, pool, subtree, buf); field.name)?;
+                wire::write_tag(buf, number, WireType::EndGroup);
+            }
+            wire_type => track_panic!(
+                ErrorKind::InvalidInput,
+                "Unexpected wire type: message={}, field={}, wire_type={:?}",
+                descriptor.name,
+                field.name,
+                wire_type
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Decoder for [FieldMask] message.
+///
+/// [FieldMask]: https://github.com/google/protobuf/blob/master/src/google/protobuf/field_mask.proto
+#[derive(Debug, Default)]
+pub struct FieldMaskMessageDecoder {
+    inner: MessageDecoder<Fields<(Repeated<FieldDecoder<F1, StringDecoder>, Vec<String>>,)>>,
+}
+impl FieldMaskMessageDecoder {
+    /// Makes a new `FieldMaskMessageDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for FieldMaskMessageDecoder {
+    type Item = FieldMaskMessage;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let (paths,) = track!(self.inner.finish_decoding())?;
+        Ok(FieldMaskMessage { paths })
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl MessageDecode for FieldMaskMessageDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
+
+/// Encoder for [FieldMask] message.
+///
+/// [FieldMask]: https://github.com/google/protobuf/blob/master/src/google/protobuf/field_mask.proto
+#[derive(Debug, Default)]
+pub struct FieldMaskMessageEncoder {
+    inner: MessageEncoder<Fields<(Repeated<FieldEncoder<F1, StringEncoder>, Vec<String>>,)>>,
+}
+impl FieldMaskMessageEncoder {
+    /// Makes a new `FieldMaskMessageEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for FieldMaskMessageEncoder {
+    type Item = FieldMaskMessage;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.inner.start_encoding((item.paths,)))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl MessageEncode for FieldMaskMessageEncoder {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{num::F2, MessageFieldDecoder};
+    use crate::message::MessageDecoder;
+    use bytecodec::DecodeExt;
+
+    #[test]
+    fn duration_decoder_works() {
+        let mut decoder: MessageDecoder<MessageFieldDecoder<F2, StdDurationDecoder>> =
+            Default::default();
+
+        let input = [18, 0];
+        track_try_unwrap!(decoder.decode_from_bytes(&input[..]));
+    }
+
+    #[test]
+    fn timestamp_rfc3339_works() {
+        for &(s, seconds, nanos, formatted) in &[
+            ("1970-01-01T00:00:00Z", 0, 0, "1970-01-01T00:00:00Z"),
+            (
+                "1972-01-01T10:00:20.021Z",
+                63_108_020,
+                21_000_000,
+                "1972-01-01T10:00:20.021Z",
+            ),
+            (
+                "2000-02-29t23:59:59.000001+09:00",
+                951_836_399,
+                1_000,
+                "2000-02-29T14:59:59.000001Z",
+            ),
+            (
+                "1969-12-31T23:59:59.5-00:30",
+                1_799,
+                500_000_000,
+                "1970-01-01T00:29:59.500Z",
+            ),
+            (
+                "0001-01-01T00:00:00Z",
+                -62_135_596_800,
+                0,
+                "0001-01-01T00:00:00Z",
+            ),
+            (
+                "9999-12-31T23:59:59.999999999Z",
+                253_402_300_799,
+                999_999_999,
+                "9999-12-31T23:59:59.999999999Z",
+            ),
+        ] {
+            let t = track_try_unwrap!(TimestampMessage::parse_rfc3339(s));
+            assert_eq!((t.seconds(), t.nanos()), (seconds, nanos), "{}", s);
+            assert_eq!(t.to_rfc3339(), formatted);
+        }
+
+        for s in &[
+            "1970-01-01T00:00:00",
+            "1970-01-01 00:00:00Z",
+            "1970-13-01T00:00:00Z",
+            "2001-02-29T00:00:00Z",
+            "1970-01-01T24:00:00Z",
+            "1970-01-01T00:00:00.Z",
+            "1970-01-01T00:00:00.0000000001Z",
+            "1970-01-01T00:00:00+0900",
+            "0001-01-01T00:00:00+00:01",
+        ] {
+            assert!(TimestampMessage::parse_rfc3339(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn system_time_codec_works() {
+        use crate::field::MessageFieldEncoder;
+        use crate::message::MessageEncoder;
+        use bytecodec::EncodeExt;
+
+        let mut encoder: MessageEncoder<MessageFieldEncoder<F2, SystemTimeEncoder>> =
+            Default::default();
+        let mut decoder: MessageDecoder<MessageFieldDecoder<F2, SystemTimeDecoder>> =
+            Default::default();
+        for &t in &[
+            UNIX_EPOCH + Duration::new(1_500_000_000, 123),
+            UNIX_EPOCH - Duration::new(10, 1),
+            UNIX_EPOCH,
+        ] {
+            let bytes = track_try_unwrap!(encoder.encode_into_bytes(t));
+            assert_eq!(track_try_unwrap!(decoder.decode_from_bytes(&bytes)), t);
+        }
+
+        let m = track_try_unwrap!(TimestampMessage::from_system_time(
+            UNIX_EPOCH - Duration::new(10, 1)
+        ));
+        assert_eq!((m.seconds(), m.nanos()), (-11, 999_999_999));
+
+        assert!(TimestampMessage::new(TimestampMessage::MAX_SECONDS + 1, 0).is_err());
+        assert!(TimestampMessage::new(0, -1).is_err());
+        let far_future = UNIX_EPOCH + Duration::from_secs(300_000_000_000);
+        assert!(encoder.encode_into_bytes(far_future).is_err());
+    }
+
+    #[test]
+    fn any_works() {
+        let d = track_try_unwrap!(DurationMessage::new(3, 4));
+        let any = track_try_unwrap!(AnyMessage::pack(
+            "google.protobuf.Duration",
+            &mut DurationMessageEncoder::new(),
+            d
+        ));
+        assert_eq!(
+            any.type_url(),
+            "type.googleapis.com/google.protobuf.Duration"
+        );
+        assert!(any.is("google.protobuf.Duration"));
+        assert_eq!(
+            track_try_unwrap!(any.unpack(
+                "google.protobuf.Duration",
+                &mut DurationMessageDecoder::new()
+            )),
+            d
+        );
+        assert!(any
+            .unpack(
+                "google.protobuf.Timestamp",
+                &mut TimestampMessageDecoder::new()
+            )
+            .is_err());
+
+        // `AnyEncoder` and `AnyMessageEncoder` produce the same bytes
+        let bytes = track_try_unwrap!(AnyMessageEncoder::new().encode_into_bytes(any.clone()));
+        let mut encoder = AnyEncoder::new("google.protobuf.Duration", StdDurationEncoder::new());
+        assert_eq!(
+            track_try_unwrap!(encoder.encode_into_bytes(Duration::new(3, 4))),
+            bytes
+        );
+
+        let mut decoder =
+            AnyDecoder::new("google.protobuf.Duration", DurationMessageDecoder::new());
+        assert_eq!(track_try_unwrap!(decoder.decode_from_bytes(&bytes)), d);
+        let mut decoder = AnyDecoder::new("google.protobuf.Empty", EmptyMessageDecoder::new());
+        assert!(decoder.decode_from_bytes(&bytes).is_err());
+        assert_eq!(
+            track_try_unwrap!(AnyMessageDecoder::new().decode_from_bytes(&bytes)),
+            any
+        );
+    }
+
+    #[test]
+    fn any_registry_works() {
+        let mut registry = AnyRegistry::new();
+        registry.register("google.protobuf.Duration", StdDurationDecoder::new);
+        registry.register("google.protobuf.Timestamp", SystemTimeDecoder::new);
+        assert!(registry.is_registered("type.googleapis.com/google.protobuf.Duration"));
+        assert!(!registry.is_registered("google.protobuf.Empty"));
+
+        let mut encoder = AnyEncoder::new("google.protobuf.Timestamp", SystemTimeEncoder::new());
+        let bytes = track_try_unwrap!(encoder.encode_into_bytes(UNIX_EPOCH));
+        let any = track_try_unwrap!(AnyMessageDecoder::new().decode_from_bytes(&bytes));
+        let item = track_try_unwrap!(registry.unpack(&any));
+        assert_eq!(item.downcast_ref::<SystemTime>(), Some(&UNIX_EPOCH));
+        assert_eq!(
+            track_try_unwrap!(registry.unpack_as::<SystemTime>(&any)),
+            UNIX_EPOCH
+        );
+        assert!(registry.unpack_as::<Duration>(&any).is_err());
+
+        let any = AnyMessage::new("example.com/google.protobuf.Duration", vec![8, 1]);
+        assert_eq!(
+            track_try_unwrap!(registry.unpack_as::<Duration>(&any)),
+            Duration::from_secs(1)
+        );
+        let any = AnyMessage::new("example.com/google.protobuf.Empty", Vec::new());
+        assert!(registry.unpack(&any).is_err());
+    }
+
+    #[test]
+    fn struct_codecs_work() {
+        let mut fields = BTreeMap::new();
+        fields.insert("null".to_owned(), Value::Null);
+        fields.insert("number".to_owned(), Value::Number(1.5));
+        fields.insert("string".to_owned(), Value::String("foo".to_owned()));
+        fields.insert(
+            "list".to_owned(),
+            Value::List(vec![Value::Bool(true), Value::Struct(BTreeMap::new())]),
+        );
+        let value = Value::Struct(fields.clone());
+
+        let bytes = track_try_unwrap!(StructEncoder::new().encode_into_bytes(fields.clone()));
+        assert_eq!(
+            track_try_unwrap!(StructDecoder::new().decode_from_bytes(&bytes)),
+            fields
+        );
+
+        let bytes = track_try_unwrap!(ValueEncoder::new().encode_into_bytes(value.clone()));
+        assert_eq!(
+            track_try_unwrap!(ValueDecoder::new().decode_from_bytes(&bytes)),
+            value
+        );
+        assert_eq!(bytes[..2], [42, bytes.len() as u8 - 2], "struct_value = 5");
+
+        // The nested messages are decoded without buffering the whole input
+        let mut decoder = ValueDecoder::new();
+        for b in bytes.chunks(1) {
+            assert_eq!(track_try_unwrap!(decoder.decode(b, Eos::new(false))), 1);
+        }
+        track_try_unwrap!(decoder.decode(&[], Eos::new(true)));
+        assert_eq!(track_try_unwrap!(decoder.finish_decoding()), value);
+
+        let list = vec![Value::Null, Value::Number(-0.0)];
+        let bytes = track_try_unwrap!(ListValueEncoder::new().encode_into_bytes(list.clone()));
+        assert_eq!(bytes, [10, 2, 8, 0, 10, 9, 17, 0, 0, 0, 0, 0, 0, 0, 128]);
+        assert_eq!(
+            track_try_unwrap!(ListValueDecoder::new().decode_from_bytes(&bytes)),
+            list
+        );
+
+        // The last field of the oneof takes precedence, and embedded messages are merged
+        let bytes = [
+            26, 3, 102, 111, 111, 32, 1, 42, 0, 42, 7, 10, 5, 10, 1, 97, 18, 0,
+        ];
+        let mut expected = BTreeMap::new();
+        expected.insert("a".to_owned(), Value::Null);
+        assert_eq!(
+            track_try_unwrap!(ValueDecoder::new().decode_from_bytes(&bytes)),
+            Value::Struct(expected)
+        );
+        assert_eq!(
+            track_try_unwrap!(ValueDecoder::new().decode_from_bytes(&[])),
+            Value::Null
+        );
+        assert!(ValueDecoder::new().decode_from_bytes(&[8, 1]).is_err());
+        assert!(ValueDecoder::new().decode_from_bytes(&[26, 2, 97]).is_err());
+    }
+
+    #[test]
+    fn struct_depth_is_limited() {
+        use crate::limit::{DecodeLimits, LimitExceeded, LimitedDecoder};
+
+        let mut value = Value::Null;
+        for _ in 0..10 {
+            value = Value::List(vec![value]);
+        }
+        let bytes = track_try_unwrap!(ValueEncoder::new().encode_into_bytes(value.clone()));
+        let limits = |max_depth| DecodeLimits {
+            max_depth: Some(max_depth),
+            ..DecodeLimits::default()
+        };
+
+        // Each `ListValue` adds two levels (`ListValue.values` and `Value.list_value`)
+        let mut decoder = LimitedDecoder::new(ValueDecoder::new(), limits(20));
+        assert_eq!(track_try_unwrap!(decoder.decode_from_bytes(&bytes)), value);
+
+        let mut decoder = LimitedDecoder::new(ValueDecoder::new(), limits(19));
+        let error = decoder.decode_from_bytes(&bytes).err().unwrap();
+        assert_eq!(
+            LimitExceeded::from_error(&error),
+            Some(LimitExceeded::Depth)
+        );
+
+        // A deeply nested input does not overflow the stack
+        let mut nested = vec![50, 0];
+        for _ in 0..1000 {
+            let mut outer = vec![50];
+            wire::write_varint(&mut outer, nested.len() as u64);
+            outer.extend_from_slice(&nested);
+            nested = vec![10];
+            wire::write_varint(&mut nested, outer.len() as u64);
+            nested.extend_from_slice(&outer);
+        }
+        let mut decoder = LimitedDecoder::new(ListValueDecoder::new(), DecodeLimits::default());
+        let error = decoder.decode_from_bytes(&nested).err().unwrap();
+        assert_eq!(
+            LimitExceeded::from_error(&error),
+            Some(LimitExceeded::Depth)
+        );
+    }
+
+    #[test]
+    fn struct_depth_is_limited_without_limited_decoder() {
+        use crate::limit::{DecodeLimits, LimitExceeded, LimitedDecoder, DEFAULT_MAX_DEPTH};
+
+        let mut value = Value::Null;
+        for _ in 0..10 {
+            value = Value::List(vec![value]);
+        }
+        let bytes = track_try_unwrap!(ValueEncoder::new().encode_into_bytes(value.clone()));
+
+        let decoder = |max_depth| {
+            let mut decoder = ValueDecoder::new();
+            decoder.set_max_depth(max_depth);
+            decoder
+        };
+        assert_eq!(ValueDecoder::new().max_depth(), Some(DEFAULT_MAX_DEPTH));
+        assert_eq!(
+            track_try_unwrap!(ValueDecoder::new().decode_from_bytes(&bytes)),
+            value
+        );
+        assert_eq!(
+            track_try_unwrap!(decoder(Some(20)).decode_from_bytes(&bytes)),
+            value
+        );
+        let error = decoder(Some(19)).decode_from_bytes(&bytes).err().unwrap();
+        assert_eq!(
+            LimitExceeded::from_error(&error),
+            Some(LimitExceeded::Depth)
+        );
+
+        // The limit of the decoder is also enforced under a `LimitedDecoder` without depth limits
+        let mut limited = LimitedDecoder::new(decoder(Some(19)), DecodeLimits::unlimited());
+        assert!(limited.decode_from_bytes(&bytes).is_err());
+        let mut limited = LimitedDecoder::new(decoder(None), DecodeLimits::unlimited());
+        assert_eq!(track_try_unwrap!(limited.decode_from_bytes(&bytes)), value);
+
+        // 20,000 levels of `ListValue` and `Value` (about 120 KB) do not overflow the stack
+        let mut nested = Vec::new();
+        for i in 0..20_000 {
+            // Builds the message from the innermost one (in reverse order)
+            let field = if i % 2 == 0 { 50 } else { 10 };
+            let mut len = Vec::new();
+            wire::write_varint(&mut len, nested.len() as u64);
+            nested.extend(len.iter().rev());
+            nested.push(field);
+        }
+        nested.reverse();
+        let error = ListValueDecoder::new()
+            .decode_from_bytes(&nested)
+            .err()
+            .unwrap();
+        assert_eq!(
+            LimitExceeded::from_error(&error),
+            Some(LimitExceeded::Depth)
+        );
+    }
+
+    #[test]
+    fn wrapper_codecs_work() {
+        use crate::field::num::F3;
+        use crate::field::{MessageFieldEncoder, Optional};
+        use crate::message::MessageEncoder;
+
+        type Decoder = MessageDecoder<
+            Fields<(
+                Optional<MessageFieldDecoder<F1, Int32ValueDecoder>>,
+                Optional<MessageFieldDecoder<F2, StringValueDecoder>>,
+                Optional<MessageFieldDecoder<F3, DoubleValueDecoder>>,
+            )>,
+        >;
+        type Encoder = MessageEncoder<
+            Fields<(
+                Optional<MessageFieldEncoder<F1, Int32ValueEncoder>>,
+                Optional<MessageFieldEncoder<F2, StringValueEncoder>>,
+                Optional<MessageFieldEncoder<F3, DoubleValueEncoder>>,
+            )>,
+        >;
+
+        let item = (Some(0), Some("foo".to_owned()), None);
+        let bytes = track_try_unwrap!(Encoder::default().encode_into_bytes(item.clone()));
+        assert_eq!(bytes, [10, 0, 18, 5, 10, 3, 102, 111, 111]);
+        assert_eq!(
+            track_try_unwrap!(Decoder::default().decode_from_bytes(&bytes)),
+            item
+        );
+
+        let bytes = [10, 2, 8, 7, 26, 9, 9, 0, 0, 0, 0, 0, 0, 248, 63];
+        assert_eq!(
+            track_try_unwrap!(Decoder::default().decode_from_bytes(&bytes)),
+            (Some(7), None, Some(1.5))
+        );
+    }
+
+    #[test]
+    fn field_mask_works() {
+        let mut mask = FieldMaskMessage::new(vec!["b.c", "a", "b", "a.x", "a"]);
+        mask.normalize();
+        assert_eq!(mask.paths(), ["a", "b"]);
+        assert!(mask.contains("a.x.y"));
+        assert!(!mask.contains("ab"));
+
+        let a = FieldMaskMessage::new(vec!["user.name", "user.email", "title"]);
+        let b = FieldMaskMessage::new(vec!["user", "body"]);
+        assert_eq!(a.union(&b).paths(), ["body", "title", "user"]);
+        assert_eq!(a.intersect(&b).paths(), ["user.email", "user.name"]);
+
+        let bytes = track_try_unwrap!(FieldMaskMessageEncoder::new().encode_into_bytes(b.clone()));
+        assert_eq!(bytes, b"\x0a\x04user\x0a\x04body");
+        assert_eq!(
+            track_try_unwrap!(FieldMaskMessageDecoder::new().decode_from_bytes(&bytes)),
+            b
+        );
+    }
+
+    #[test]
+    fn field_mask_application_works() {
+        use crate::dynamic::{DynamicMessageDecoder, DynamicMessageEncoder};
+        use crate::schema;
+        use std::sync::Arc;
+
+        let file = track_try_unwrap!(schema::parse(
+            r#"
+            syntax = "proto3";
+            message User {
+              string name = 1;
+              string email = 2;
+            }
+            message Post {
+              string title = 1;
+              User author = 2;
+              repeated User readers = 3;
+              int64 views = 4;
+            }
+        "#
+        ));
+        let pool = Arc::new(track_try_unwrap!(DescriptorPool::from_files(&[file])));
+
+        // title: "foo", author: {name: "a", email: "b"}, readers: [{name: "c"}], views: 3, 100: 1
+        let bytes = [
+            10, 3, 102, 111, 111, 18, 6, 10, 1, 97, 18, 1, 98, 26, 3, 10, 1, 99, 32, 3, 160, 6, 1,
+        ];
+        let mask = FieldMaskMessage::new(vec!["author.email", "views", "readers"]);
+        let masked = track_try_unwrap!(mask.apply_to_bytes(&bytes, &pool, "Post"));
+        assert_eq!(masked, [18, 3, 18, 1, 98, 26, 3, 10, 1, 99, 32, 3]);
+
+        let mut decoder = track_try_unwrap!(DynamicMessageDecoder::new(pool.clone(), "Post"));
+        let message = track_try_unwrap!(decoder.decode_from_bytes(&bytes));
+        let message = track_try_unwrap!(mask.apply_to_message(&message));
+        let encoded = track_try_unwrap!(DynamicMessageEncoder::new().encode_into_bytes(message));
+        assert_eq!(encoded, masked);
+
+        for path in &["unknown", "title.x", "readers.name"] {
+            let mask = FieldMaskMessage::new(vec![*path]);
+            assert!(mask.apply_to_bytes(&bytes, &pool, "Post").is_err());
+        }
     }
 }
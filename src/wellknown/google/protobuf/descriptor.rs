@@ -0,0 +1,755 @@
+//! Messages defined in [descriptor.proto].
+//!
+//! The `*Options` and `SourceCodeInfo` messages are kept as their encoded bytes,
+//! so that the custom options and the other unknown fields are preserved as they are.
+//!
+//! [descriptor.proto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+use crate::field::num::{Fnum, F1, F10, F11, F12, F2, F3, F4, F5, F6, F7, F8, F9};
+use crate::field::{
+    FieldDecoder, FieldEncoder, Fields, MessageFieldDecoder, MessageFieldEncoder, Optional,
+    Repeated,
+};
+use crate::message::{MessageDecode, MessageDecoder, MessageEncode, MessageEncoder};
+use crate::scalar::{BoolDecoder, BoolEncoder, BytesDecoder, BytesEncoder, Enum};
+use crate::scalar::{EnumDecoder, EnumEncoder, Int32Decoder, Int32Encoder};
+use crate::scalar::{StringDecoder, StringEncoder};
+use bytecodec::combinator::PreEncode;
+use bytecodec::{
+    ByteCount, Decode, DecodeExt, Encode, EncodeExt, Eos, ErrorKind, Result, SizedEncode,
+};
+
+// The maximum depth of nested `DescriptorProto` messages
+const MAX_NESTING_DEPTH: usize = 100;
+
+/// [FileDescriptorSet] message.
+///
+/// This is the format of the files written by `protoc --descriptor_set_out`.
+///
+/// [FileDescriptorSet]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileDescriptorSet {
+    pub file: Vec<FileDescriptorProto>,
+}
+
+/// [FileDescriptorProto] message.
+///
+/// [FileDescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileDescriptorProto {
+    pub name: Option<String>,
+    pub package: Option<String>,
+    pub dependency: Vec<String>,
+    pub message_type: Vec<DescriptorProto>,
+    pub enum_type: Vec<EnumDescriptorProto>,
+    pub service: Vec<ServiceDescriptorProto>,
+    pub extension: Vec<FieldDescriptorProto>,
+
+    /// Encoded `FileOptions` message.
+    pub options: Option<Vec<u8>>,
+
+    /// Encoded `SourceCodeInfo` message.
+    pub source_code_info: Option<Vec<u8>>,
+    pub public_dependency: Vec<i32>,
+    pub weak_dependency: Vec<i32>,
+    pub syntax: Option<String>,
+}
+
+/// [DescriptorProto] message.
+///
+/// [DescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DescriptorProto {
+    pub name: Option<String>,
+    pub field: Vec<FieldDescriptorProto>,
+    pub nested_type: Vec<DescriptorProto>,
+    pub enum_type: Vec<EnumDescriptorProto>,
+    pub extension_range: Vec<ExtensionRange>,
+    pub extension: Vec<FieldDescriptorProto>,
+
+    /// Encoded `MessageOptions` message.
+    pub options: Option<Vec<u8>>,
+    pub oneof_decl: Vec<OneofDescriptorProto>,
+    pub reserved_range: Vec<ReservedRange>,
+    pub reserved_name: Vec<String>,
+}
+
+/// `DescriptorProto.ExtensionRange` message.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExtensionRange {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+
+    /// Encoded `ExtensionRangeOptions` message.
+    pub options: Option<Vec<u8>>,
+}
+
+/// `DescriptorProto.ReservedRange` message.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReservedRange {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+}
+
+/// [FieldDescriptorProto] message.
+///
+/// [FieldDescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldDescriptorProto {
+    pub name: Option<String>,
+    pub extendee: Option<String>,
+    pub number: Option<i32>,
+    pub label: Option<FieldDescriptorProtoLabel>,
+
+    /// `type` field.
+    pub ty: Option<FieldDescriptorProtoType>,
+    pub type_name: Option<String>,
+    pub default_value: Option<String>,
+
+    /// Encoded `FieldOptions` message.
+    pub options: Option<Vec<u8>>,
+    pub oneof_index: Option<i32>,
+    pub json_name: Option<String>,
+    pub proto3_optional: Option<bool>,
+}
+
+/// `FieldDescriptorProto.Type` enum.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldDescriptorProtoType {
+    Double = 1,
+    Float = 2,
+    Int64 = 3,
+    Uint64 = 4,
+    Int32 = 5,
+    Fixed64 = 6,
+    Fixed32 = 7,
+    Bool = 8,
+    String = 9,
+    Group = 10,
+    Message = 11,
+    Bytes = 12,
+    Uint32 = 13,
+    Enum = 14,
+    Sfixed32 = 15,
+    Sfixed64 = 16,
+    Sint32 = 17,
+    Sint64 = 18,
+}
+impl Enum for FieldDescriptorProtoType {
+    fn from_i32(n: i32) -> Option<Self> {
+        use self::FieldDescriptorProtoType::*;
+        Some(match n {
+            1 => Double,
+            2 => Float,
+            3 => Int64,
+            4 => Uint64,
+            5 => Int32,
+            6 => Fixed64,
+            7 => Fixed32,
+            8 => Bool,
+            9 => String,
+            10 => Group,
+            11 => Message,
+            12 => Bytes,
+            13 => Uint32,
+            14 => Enum,
+            15 => Sfixed32,
+            16 => Sfixed64,
+            17 => Sint32,
+            18 => Sint64,
+            _ => return None,
+        })
+    }
+
+    fn to_i32(&self) -> i32 {
+        *self as i32
+    }
+}
+
+/// `FieldDescriptorProto.Label` enum.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldDescriptorProtoLabel {
+    Optional = 1,
+    Required = 2,
+    Repeated = 3,
+}
+impl Enum for FieldDescriptorProtoLabel {
+    fn from_i32(n: i32) -> Option<Self> {
+        match n {
+            1 => Some(FieldDescriptorProtoLabel::Optional),
+            2 => Some(FieldDescriptorProtoLabel::Required),
+            3 => Some(FieldDescriptorProtoLabel::Repeated),
+            _ => None,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        *self as i32
+    }
+}
+
+/// [OneofDescriptorProto] message.
+///
+/// [OneofDescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OneofDescriptorProto {
+    pub name: Option<String>,
+
+    /// Encoded `OneofOptions` message.
+    pub options: Option<Vec<u8>>,
+}
+
+/// [EnumDescriptorProto] message.
+///
+/// [EnumDescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EnumDescriptorProto {
+    pub name: Option<String>,
+    pub value: Vec<EnumValueDescriptorProto>,
+
+    /// Encoded `EnumOptions` message.
+    pub options: Option<Vec<u8>>,
+    pub reserved_range: Vec<EnumReservedRange>,
+    pub reserved_name: Vec<String>,
+}
+
+/// `EnumDescriptorProto.EnumReservedRange` message.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EnumReservedRange {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+}
+
+/// [EnumValueDescriptorProto] message.
+///
+/// [EnumValueDescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EnumValueDescriptorProto {
+    pub name: Option<String>,
+    pub number: Option<i32>,
+
+    /// Encoded `EnumValueOptions` message.
+    pub options: Option<Vec<u8>>,
+}
+
+/// [ServiceDescriptorProto] message.
+///
+/// [ServiceDescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ServiceDescriptorProto {
+    pub name: Option<String>,
+    pub method: Vec<MethodDescriptorProto>,
+
+    /// Encoded `ServiceOptions` message.
+    pub options: Option<Vec<u8>>,
+}
+
+/// [MethodDescriptorProto] message.
+///
+/// [MethodDescriptorProto]: https://github.com/google/protobuf/blob/master/src/google/protobuf/descriptor.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MethodDescriptorProto {
+    pub name: Option<String>,
+    pub input_type: Option<String>,
+    pub output_type: Option<String>,
+
+    /// Encoded `MethodOptions` message.
+    pub options: Option<Vec<u8>>,
+    pub client_streaming: Option<bool>,
+    pub server_streaming: Option<bool>,
+}
+
+impl_message_codec!(
+    FileDescriptorSet,
+    FileDescriptorSetDecoder((
+        Repeated<MessageFieldDecoder<F1, FileDescriptorProtoDecoder>, Vec<FileDescriptorProto>>,
+    )) => |(file,)| FileDescriptorSet { file },
+    FileDescriptorSetEncoder((
+        Repeated<
+            MessageFieldEncoder<F1, PreEncode<FileDescriptorProtoEncoder>>,
+            Vec<FileDescriptorProto>,
+        >,
+    )) => |m| (m.file,)
+);
+
+impl_message_codec!(
+    FileDescriptorProto,
+    FileDescriptorProtoDecoder((
+        Optional<FieldDecoder<F1, StringDecoder>>,
+        Optional<FieldDecoder<F2, StringDecoder>>,
+        Repeated<FieldDecoder<F3, StringDecoder>, Vec<String>>,
+        Repeated<MessageFieldDecoder<F4, DescriptorProtoDecoder>, Vec<DescriptorProto>>,
+        Repeated<MessageFieldDecoder<F5, EnumDescriptorProtoDecoder>, Vec<EnumDescriptorProto>>,
+        Repeated<
+            MessageFieldDecoder<F6, ServiceDescriptorProtoDecoder>,
+            Vec<ServiceDescriptorProto>,
+        >,
+        Repeated<MessageFieldDecoder<F7, FieldDescriptorProtoDecoder>, Vec<FieldDescriptorProto>>,
+        Optional<FieldDecoder<F8, BytesDecoder>>,
+        Optional<FieldDecoder<F9, BytesDecoder>>,
+        Repeated<FieldDecoder<F10, Int32Decoder>, Vec<i32>>,
+        Repeated<FieldDecoder<F11, Int32Decoder>, Vec<i32>>,
+        Optional<FieldDecoder<F12, StringDecoder>>,
+    )) => |(
+        name,
+        package,
+        dependency,
+        message_type,
+        enum_type,
+        service,
+        extension,
+        options,
+        source_code_info,
+        public_dependency,
+        weak_dependency,
+        syntax,
+    )| FileDescriptorProto {
+        name,
+        package,
+        dependency,
+        message_type,
+        enum_type,
+        service,
+        extension,
+        options,
+        source_code_info,
+        public_dependency,
+        weak_dependency,
+        syntax,
+    },
+    FileDescriptorProtoEncoder((
+        Optional<FieldEncoder<F1, StringEncoder>>,
+        Optional<FieldEncoder<F2, StringEncoder>>,
+        Repeated<FieldEncoder<F3, StringEncoder>, Vec<String>>,
+        Repeated<MessageFieldEncoder<F4, PreEncode<DescriptorProtoEncoder>>, Vec<DescriptorProto>>,
+        Repeated<
+            MessageFieldEncoder<F5, PreEncode<EnumDescriptorProtoEncoder>>,
+            Vec<EnumDescriptorProto>,
+        >,
+        Repeated<
+            MessageFieldEncoder<F6, PreEncode<ServiceDescriptorProtoEncoder>>,
+            Vec<ServiceDescriptorProto>,
+        >,
+        Repeated<MessageFieldEncoder<F7, FieldDescriptorProtoEncoder>, Vec<FieldDescriptorProto>>,
+        Optional<FieldEncoder<F8, BytesEncoder>>,
+        Optional<FieldEncoder<F9, BytesEncoder>>,
+        Repeated<FieldEncoder<F10, Int32Encoder>, Vec<i32>>,
+        Repeated<FieldEncoder<F11, Int32Encoder>, Vec<i32>>,
+        Optional<FieldEncoder<F12, StringEncoder>>,
+    )) => |m| (
+        m.name,
+        m.package,
+        m.dependency,
+        m.message_type,
+        m.enum_type,
+        m.service,
+        m.extension,
+        m.options,
+        m.source_code_info,
+        m.public_dependency,
+        m.weak_dependency,
+        m.syntax,
+    )
+);
+
+// `DescriptorProto` is a recursive message, so the nested types are decoded from
+// (and encoded to) their bytes by other instances of the decoder (and the encoder).
+
+/// Decoder for `DescriptorProto`.
+#[derive(Debug, Default)]
+pub struct DescriptorProtoDecoder {
+    inner: MessageDecoder<
+        Fields<(
+            Optional<FieldDecoder<F1, StringDecoder>>,
+            Repeated<
+                MessageFieldDecoder<F2, FieldDescriptorProtoDecoder>,
+                Vec<FieldDescriptorProto>,
+            >,
+            Repeated<FieldDecoder<F3, BytesDecoder>, Vec<Vec<u8>>>,
+            Repeated<MessageFieldDecoder<F4, EnumDescriptorProtoDecoder>, Vec<EnumDescriptorProto>>,
+            Repeated<MessageFieldDecoder<F5, ExtensionRangeDecoder>, Vec<ExtensionRange>>,
+            Repeated<
+                MessageFieldDecoder<F6, FieldDescriptorProtoDecoder>,
+                Vec<FieldDescriptorProto>,
+            >,
+            Optional<FieldDecoder<F7, BytesDecoder>>,
+            Repeated<
+                MessageFieldDecoder<F8, OneofDescriptorProtoDecoder>,
+                Vec<OneofDescriptorProto>,
+            >,
+            Repeated<MessageFieldDecoder<F9, ReservedRangeDecoder>, Vec<ReservedRange>>,
+            Repeated<FieldDecoder<F10, StringDecoder>, Vec<String>>,
+        )>,
+    >,
+    depth: usize,
+}
+impl DescriptorProtoDecoder {
+    /// Makes a new `DescriptorProtoDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for DescriptorProtoDecoder {
+    type Item = DescriptorProto;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let (
+            name,
+            field,
+            nested_type_bytes,
+            enum_type,
+            extension_range,
+            extension,
+            options,
+            oneof_decl,
+            reserved_range,
+            reserved_name,
+        ) = track!(self.inner.finish_decoding())?;
+
+        let mut nested_type = Vec::with_capacity(nested_type_bytes.len());
+        for bytes in nested_type_bytes {
+            track_assert!(
+                self.depth < MAX_NESTING_DEPTH,
+                ErrorKind::InvalidInput,
+                "Too deep nesting"
+            );
+            let mut decoder = DescriptorProtoDecoder {
+                inner: Default::default(),
+                depth: self.depth + 1,
+            };
+            nested_type.push(track!(decoder.decode_from_bytes(&bytes))?);
+        }
+        Ok(DescriptorProto {
+            name,
+            field,
+            nested_type,
+            enum_type,
+            extension_range,
+            extension,
+            options,
+            oneof_decl,
+            reserved_range,
+            reserved_name,
+        })
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl MessageDecode for DescriptorProtoDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
+
+/// Encoder for `DescriptorProto`.
+#[derive(Debug, Default)]
+pub struct DescriptorProtoEncoder {
+    inner: MessageEncoder<
+        Fields<(
+            Optional<FieldEncoder<F1, StringEncoder>>,
+            Repeated<
+                MessageFieldEncoder<F2, FieldDescriptorProtoEncoder>,
+                Vec<FieldDescriptorProto>,
+            >,
+            Repeated<FieldEncoder<F3, BytesEncoder>, Vec<Vec<u8>>>,
+            Repeated<
+                MessageFieldEncoder<F4, PreEncode<EnumDescriptorProtoEncoder>>,
+                Vec<EnumDescriptorProto>,
+            >,
+            Repeated<MessageFieldEncoder<F5, ExtensionRangeEncoder>, Vec<ExtensionRange>>,
+            Repeated<
+                MessageFieldEncoder<F6, FieldDescriptorProtoEncoder>,
+                Vec<FieldDescriptorProto>,
+            >,
+            Optional<FieldEncoder<F7, BytesEncoder>>,
+            Repeated<
+                MessageFieldEncoder<F8, OneofDescriptorProtoEncoder>,
+                Vec<OneofDescriptorProto>,
+            >,
+            Repeated<MessageFieldEncoder<F9, ReservedRangeEncoder>, Vec<ReservedRange>>,
+            Repeated<FieldEncoder<F10, StringEncoder>, Vec<String>>,
+        )>,
+    >,
+}
+impl DescriptorProtoEncoder {
+    /// Makes a new `DescriptorProtoEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for DescriptorProtoEncoder {
+    type Item = DescriptorProto;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let mut nested_type = Vec::with_capacity(item.nested_type.len());
+        for m in item.nested_type {
+            nested_type.push(track!(DescriptorProtoEncoder::new().encode_into_bytes(m))?);
+        }
+        let item = (
+            item.name,
+            item.field,
+            nested_type,
+            item.enum_type,
+            item.extension_range,
+            item.extension,
+            item.options,
+            item.oneof_decl,
+            item.reserved_range,
+            item.reserved_name,
+        );
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+}
+impl MessageEncode for DescriptorProtoEncoder {}
+
+impl_message_codec!(
+    ExtensionRange,
+    ExtensionRangeDecoder((
+        Optional<FieldDecoder<F1, Int32Decoder>>,
+        Optional<FieldDecoder<F2, Int32Decoder>>,
+        Optional<FieldDecoder<F3, BytesDecoder>>,
+    )) => |(start, end, options)| ExtensionRange { start, end, options },
+    ExtensionRangeEncoder((
+        Optional<FieldEncoder<F1, Int32Encoder>>,
+        Optional<FieldEncoder<F2, Int32Encoder>>,
+        Optional<FieldEncoder<F3, BytesEncoder>>,
+    )) => |m| (m.start, m.end, m.options),
+    SizedEncode
+);
+
+impl_message_codec!(
+    ReservedRange,
+    ReservedRangeDecoder((
+        Optional<FieldDecoder<F1, Int32Decoder>>,
+        Optional<FieldDecoder<F2, Int32Decoder>>,
+    )) => |(start, end)| ReservedRange { start, end },
+    ReservedRangeEncoder((
+        Optional<FieldEncoder<F1, Int32Encoder>>,
+        Optional<FieldEncoder<F2, Int32Encoder>>,
+    )) => |m| (m.start, m.end),
+    SizedEncode
+);
+
+impl_message_codec!(
+    FieldDescriptorProto,
+    FieldDescriptorProtoDecoder((
+        Optional<FieldDecoder<F1, StringDecoder>>,
+        Optional<FieldDecoder<F2, StringDecoder>>,
+        Optional<FieldDecoder<F3, Int32Decoder>>,
+        Optional<FieldDecoder<F4, EnumDecoder<FieldDescriptorProtoLabel>>>,
+        Optional<FieldDecoder<F5, EnumDecoder<FieldDescriptorProtoType>>>,
+        Optional<FieldDecoder<F6, StringDecoder>>,
+        Optional<FieldDecoder<F7, StringDecoder>>,
+        Optional<FieldDecoder<F8, BytesDecoder>>,
+        Optional<FieldDecoder<F9, Int32Decoder>>,
+        Optional<FieldDecoder<F10, StringDecoder>>,
+        Optional<FieldDecoder<Fnum<17>, BoolDecoder>>,
+    )) => |(
+        name,
+        extendee,
+        number,
+        label,
+        ty,
+        type_name,
+        default_value,
+        options,
+        oneof_index,
+        json_name,
+        proto3_optional,
+    )| FieldDescriptorProto {
+        name,
+        extendee,
+        number,
+        label,
+        ty,
+        type_name,
+        default_value,
+        options,
+        oneof_index,
+        json_name,
+        proto3_optional,
+    },
+    FieldDescriptorProtoEncoder((
+        Optional<FieldEncoder<F1, StringEncoder>>,
+        Optional<FieldEncoder<F2, StringEncoder>>,
+        Optional<FieldEncoder<F3, Int32Encoder>>,
+        Optional<FieldEncoder<F4, EnumEncoder<FieldDescriptorProtoLabel>>>,
+        Optional<FieldEncoder<F5, EnumEncoder<FieldDescriptorProtoType>>>,
+        Optional<FieldEncoder<F6, StringEncoder>>,
+        Optional<FieldEncoder<F7, StringEncoder>>,
+        Optional<FieldEncoder<F8, BytesEncoder>>,
+        Optional<FieldEncoder<F9, Int32Encoder>>,
+        Optional<FieldEncoder<F10, StringEncoder>>,
+        Optional<FieldEncoder<Fnum<17>, BoolEncoder>>,
+    )) => |m| (
+        m.name,
+        m.extendee,
+        m.number,
+        m.label,
+        m.ty,
+        m.type_name,
+        m.default_value,
+        m.options,
+        m.oneof_index,
+        m.json_name,
+        m.proto3_optional,
+    ),
+    SizedEncode
+);
+
+impl_message_codec!(
+    OneofDescriptorProto,
+    OneofDescriptorProtoDecoder((
+        Optional<FieldDecoder<F1, StringDecoder>>,
+        Optional<FieldDecoder<F2, BytesDecoder>>,
+    )) => |(name, options)| OneofDescriptorProto { name, options },
+    OneofDescriptorProtoEncoder((
+        Optional<FieldEncoder<F1, StringEncoder>>,
+        Optional<FieldEncoder<F2, BytesEncoder>>,
+    )) => |m| (m.name, m.options),
+    SizedEncode
+);
+
+impl_message_codec!(
+    EnumDescriptorProto,
+    EnumDescriptorProtoDecoder((
+        Optional<FieldDecoder<F1, StringDecoder>>,
+        Repeated<
+            MessageFieldDecoder<F2, EnumValueDescriptorProtoDecoder>,
+            Vec<EnumValueDescriptorProto>,
+        >,
+        Optional<FieldDecoder<F3, BytesDecoder>>,
+        Repeated<MessageFieldDecoder<F4, EnumReservedRangeDecoder>, Vec<EnumReservedRange>>,
+        Repeated<FieldDecoder<F5, StringDecoder>, Vec<String>>,
+    )) => |(name, value, options, reserved_range, reserved_name)| EnumDescriptorProto {
+        name,
+        value,
+        options,
+        reserved_range,
+        reserved_name,
+    },
+    EnumDescriptorProtoEncoder((
+        Optional<FieldEncoder<F1, StringEncoder>>,
+        Repeated<
+            MessageFieldEncoder<F2, EnumValueDescriptorProtoEncoder>,
+            Vec<EnumValueDescriptorProto>,
+        >,
+        Optional<FieldEncoder<F3, BytesEncoder>>,
+        Repeated<MessageFieldEncoder<F4, EnumReservedRangeEncoder>, Vec<EnumReservedRange>>,
+        Repeated<FieldEncoder<F5, StringEncoder>, Vec<String>>,
+    )) => |m| (m.name, m.value, m.options, m.reserved_range, m.reserved_name)
+);
+
+impl_message_codec!(
+    EnumReservedRange,
+    EnumReservedRangeDecoder((
+        Optional<FieldDecoder<F1, Int32Decoder>>,
+        Optional<FieldDecoder<F2, Int32Decoder>>,
+    )) => |(start, end)| EnumReservedRange { start, end },
+    EnumReservedRangeEncoder((
+        Optional<FieldEncoder<F1, Int32Encoder>>,
+        Optional<FieldEncoder<F2, Int32Encoder>>,
+    )) => |m| (m.start, m.end),
+    SizedEncode
+);
+
+impl_message_codec!(
+    EnumValueDescriptorProto,
+    EnumValueDescriptorProtoDecoder((
+        Optional<FieldDecoder<F1, StringDecoder>>,
+        Optional<FieldDecoder<F2, Int32Decoder>>,
+        Optional<FieldDecoder<F3, BytesDecoder>>,
+    )) => |(name, number, options)| EnumValueDescriptorProto { name, number, options },
+    EnumValueDescriptorProtoEncoder((
+        Optional<FieldEncoder<F1, StringEncoder>>,
+        Optional<FieldEncoder<F2, Int32Encoder>>,
+        Optional<FieldEncoder<F3, BytesEncoder>>,
+    )) => |m| (m.name, m.number, m.options),
+    SizedEncode
+);
+
+impl_message_codec!(
+    ServiceDescriptorProto,
+    ServiceDescriptorProtoDecoder((
+        Optional<FieldDecoder<F1, StringDecoder>>,
+        Repeated<MessageFieldDecoder<F2, MethodDescriptorProtoDecoder>, Vec<MethodDescriptorProto>>,
+        Optional<FieldDecoder<F3, BytesDecoder>>,
+    )) => |(name, method, options)| ServiceDescriptorProto { name, method, options },
+    ServiceDescriptorProtoEncoder((
+        Optional<FieldEncoder<F1, StringEncoder>>,
+        Repeated<MessageFieldEncoder<F2, MethodDescriptorProtoEncoder>, Vec<MethodDescriptorProto>>,
+        Optional<FieldEncoder<F3, BytesEncoder>>,
+    )) => |m| (m.name, m.method, m.options)
+);
+
+impl_message_codec!(
+    MethodDescriptorProto,
+    MethodDescriptorProtoDecoder((
+        Optional<FieldDecoder<F1, StringDecoder>>,
+        Optional<FieldDecoder<F2, StringDecoder>>,
+        Optional<FieldDecoder<F3, StringDecoder>>,
+        Optional<FieldDecoder<F4, BytesDecoder>>,
+        Optional<FieldDecoder<F5, BoolDecoder>>,
+        Optional<FieldDecoder<F6, BoolDecoder>>,
+    )) => |(name, input_type, output_type, options, client_streaming, server_streaming)| {
+        MethodDescriptorProto {
+            name,
+            input_type,
+            output_type,
+            options,
+            client_streaming,
+            server_streaming,
+        }
+    },
+    MethodDescriptorProtoEncoder((
+        Optional<FieldEncoder<F1, StringEncoder>>,
+        Optional<FieldEncoder<F2, StringEncoder>>,
+        Optional<FieldEncoder<F3, StringEncoder>>,
+        Optional<FieldEncoder<F4, BytesEncoder>>,
+        Optional<FieldEncoder<F5, BoolEncoder>>,
+        Optional<FieldEncoder<F6, BoolEncoder>>,
+    )) => |m| (
+        m.name,
+        m.input_type,
+        m.output_type,
+        m.options,
+        m.client_streaming,
+        m.server_streaming,
+    ),
+    SizedEncode
+);
@@ -0,0 +1,639 @@
+//! `package google.rpc;`
+//!
+//! See also [google/rpc].
+//!
+//! [google/rpc]: https://github.com/googleapis/googleapis/tree/master/google/rpc
+#![allow(clippy::type_complexity)]
+use crate::field::num::{F1, F2, F3, F4};
+use crate::field::{
+    FieldDecoder, FieldEncoder, Fields, MapFieldDecoder, MapFieldEncoder, MaybeDefault,
+    MessageFieldDecoder, MessageFieldEncoder, Optional, Repeated,
+};
+use crate::message::{MessageDecode, MessageDecoder, MessageEncode, MessageEncoder};
+use crate::scalar::{Enum, Int32Decoder, Int32Encoder, StringDecoder, StringEncoder};
+use crate::wellknown::google::protobuf::{AnyMessage, AnyMessageDecoder, AnyMessageEncoder};
+use crate::wellknown::google::protobuf::{StdDurationDecoder, StdDurationEncoder};
+use crate::wellknown::protobuf_codec::protobuf::trackable::error_cause;
+use crate::wellknown::protobuf_codec::protobuf::trackable::{ErrorDecoder, ErrorEncoder};
+use bytecodec::{ByteCount, Decode, Encode, Eos, Result, SizedEncode};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use trackable::error::{ErrorKind, ErrorKindExt, TrackableError};
+use trackable::Trackable;
+
+/// The type name of the details that hold the errors made by `StatusMessage::from_trackable_error`.
+///
+/// The details are encoded in accordance with [trackable.proto].
+///
+/// [trackable.proto]: https://github.com/sile/protobuf_codec/blob/master/protobuf/trackable.proto
+pub const TRACKABLE_ERROR_TYPE_NAME: &str = "protobuf_codec.protobuf.trackable.Error";
+
+/// [Code] enum.
+///
+/// This can be used with `EnumDecoder` and `EnumEncoder`.
+///
+/// [Code]: https://github.com/googleapis/googleapis/blob/master/google/rpc/code.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Code {
+    #[default]
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    AlreadyExists = 6,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    OutOfRange = 11,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    DataLoss = 15,
+    Unauthenticated = 16,
+}
+impl Enum for Code {
+    fn from_i32(n: i32) -> Option<Self> {
+        Some(match n {
+            0 => Code::Ok,
+            1 => Code::Cancelled,
+            2 => Code::Unknown,
+            3 => Code::InvalidArgument,
+            4 => Code::DeadlineExceeded,
+            5 => Code::NotFound,
+            6 => Code::AlreadyExists,
+            7 => Code::PermissionDenied,
+            8 => Code::ResourceExhausted,
+            9 => Code::FailedPrecondition,
+            10 => Code::Aborted,
+            11 => Code::OutOfRange,
+            12 => Code::Unimplemented,
+            13 => Code::Internal,
+            14 => Code::Unavailable,
+            15 => Code::DataLoss,
+            16 => Code::Unauthenticated,
+            _ => return None,
+        })
+    }
+
+    fn to_i32(&self) -> i32 {
+        *self as i32
+    }
+}
+
+/// [Status] message.
+///
+/// [Status]: https://github.com/googleapis/googleapis/blob/master/google/rpc/status.proto
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StatusMessage {
+    /// Status code (see [`Code`](./enum.Code.html)).
+    pub code: i32,
+
+    /// Developer-facing error message.
+    pub message: String,
+
+    /// Messages that carry the error details (e.g., `ErrorInfoMessage`).
+    pub details: Vec<AnyMessage>,
+}
+impl StatusMessage {
+    /// Makes a new `StatusMessage` instance that has no details.
+    pub fn new<S: Into<String>>(code: Code, message: S) -> Self {
+        StatusMessage {
+            code: code.to_i32(),
+            message: message.into(),
+            details: Vec::new(),
+        }
+    }
+
+    /// Makes a new `StatusMessage` instance from `error`.
+    ///
+    /// The message of the status is the kind and the cause of the error,
+    /// and the whole error (including its history) is added as a detail
+    /// of the type [`TRACKABLE_ERROR_TYPE_NAME`](./constant.TRACKABLE_ERROR_TYPE_NAME.html).
+    pub fn from_trackable_error<K: ErrorKind>(
+        code: Code,
+        error: &TrackableError<K>,
+    ) -> Result<Self> {
+        let kind = KindDisplay(error.kind()).to_string();
+        let cause = error_cause(error);
+        let message = match cause {
+            None => kind.clone(),
+            Some(ref cause) => format!("{} (cause; {})", kind, cause),
+        };
+
+        let mut e = match cause {
+            None => kind.error(),
+            Some(cause) => kind.cause(cause),
+        };
+        if let (Some(h), Some(events)) = (e.history_mut(), error.history()) {
+            for l in events.events() {
+                h.add(l.clone());
+            }
+        }
+        let detail = track!(AnyMessage::pack(
+            TRACKABLE_ERROR_TYPE_NAME,
+            &mut ErrorEncoder::new(),
+            e
+        ))?;
+
+        Ok(StatusMessage {
+            code: code.to_i32(),
+            message,
+            details: vec![detail],
+        })
+    }
+
+    /// Returns the error held by the first detail of the type
+    /// [`TRACKABLE_ERROR_TYPE_NAME`](./constant.TRACKABLE_ERROR_TYPE_NAME.html).
+    pub fn trackable_error(&self) -> Result<Option<TrackableError<String>>> {
+        track!(self.detail(TRACKABLE_ERROR_TYPE_NAME, &mut ErrorDecoder::new()))
+    }
+
+    /// Encodes `item` by using `encoder`, and appends it to the details of the status.
+    ///
+    /// `type_name` is the full name of the message (e.g., `google.rpc.ErrorInfo`).
+    pub fn push_detail<E: MessageEncode>(
+        &mut self,
+        type_name: &str,
+        encoder: &mut E,
+        item: E::Item,
+    ) -> Result<()> {
+        let detail = track!(AnyMessage::pack(type_name, encoder, item))?;
+        self.details.push(detail);
+        Ok(())
+    }
+
+    /// Decodes the first detail that has the type name `type_name` by using `decoder`.
+    ///
+    /// If there is no such detail, this returns `Ok(None)`.
+    pub fn detail<D: MessageDecode>(
+        &self,
+        type_name: &str,
+        decoder: &mut D,
+    ) -> Result<Option<D::Item>> {
+        match self.details.iter().find(|d| d.is(type_name)) {
+            None => Ok(None),
+            Some(d) => track!(d.unpack(type_name, decoder)).map(Some),
+        }
+    }
+}
+
+struct KindDisplay<'a, K>(&'a K);
+impl<'a, K: ErrorKind> fmt::Display for KindDisplay<'a, K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.display(f)
+    }
+}
+
+/// [ErrorInfo] message.
+///
+/// [ErrorInfo]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ErrorInfoMessage {
+    pub reason: String,
+    pub domain: String,
+    pub metadata: BTreeMap<String, String>,
+}
+impl ErrorInfoMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.ErrorInfo";
+}
+
+/// [RetryInfo] message.
+///
+/// [RetryInfo]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RetryInfoMessage {
+    pub retry_delay: Option<Duration>,
+}
+impl RetryInfoMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.RetryInfo";
+}
+
+/// [DebugInfo] message.
+///
+/// [DebugInfo]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DebugInfoMessage {
+    pub stack_entries: Vec<String>,
+    pub detail: String,
+}
+impl DebugInfoMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.DebugInfo";
+}
+
+/// [QuotaFailure] message.
+///
+/// [QuotaFailure]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QuotaFailureMessage {
+    pub violations: Vec<QuotaViolationMessage>,
+}
+impl QuotaFailureMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.QuotaFailure";
+}
+
+/// `QuotaFailure.Violation` message.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QuotaViolationMessage {
+    pub subject: String,
+    pub description: String,
+}
+
+/// [PreconditionFailure] message.
+///
+/// [PreconditionFailure]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PreconditionFailureMessage {
+    pub violations: Vec<PreconditionViolationMessage>,
+}
+impl PreconditionFailureMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.PreconditionFailure";
+}
+
+/// `PreconditionFailure.Violation` message.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PreconditionViolationMessage {
+    /// `type` field.
+    pub ty: String,
+    pub subject: String,
+    pub description: String,
+}
+
+/// [BadRequest] message.
+///
+/// [BadRequest]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BadRequestMessage {
+    pub field_violations: Vec<FieldViolationMessage>,
+}
+impl BadRequestMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.BadRequest";
+}
+
+/// `BadRequest.FieldViolation` message.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldViolationMessage {
+    pub field: String,
+    pub description: String,
+}
+
+/// [RequestInfo] message.
+///
+/// [RequestInfo]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RequestInfoMessage {
+    pub request_id: String,
+    pub serving_data: String,
+}
+impl RequestInfoMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.RequestInfo";
+}
+
+/// [ResourceInfo] message.
+///
+/// [ResourceInfo]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ResourceInfoMessage {
+    pub resource_type: String,
+    pub resource_name: String,
+    pub owner: String,
+    pub description: String,
+}
+impl ResourceInfoMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.ResourceInfo";
+}
+
+/// [Help] message.
+///
+/// [Help]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HelpMessage {
+    pub links: Vec<LinkMessage>,
+}
+impl HelpMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.Help";
+}
+
+/// `Help.Link` message.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinkMessage {
+    pub description: String,
+    pub url: String,
+}
+
+/// [LocalizedMessage] message.
+///
+/// [LocalizedMessage]: https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LocalizedMessage {
+    pub locale: String,
+    pub message: String,
+}
+impl LocalizedMessage {
+    /// The full name of the message.
+    pub const TYPE_NAME: &'static str = "google.rpc.LocalizedMessage";
+}
+
+impl_message_codec!(
+    StatusMessage,
+    StatusMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, Int32Decoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+        Repeated<MessageFieldDecoder<F3, AnyMessageDecoder>, Vec<AnyMessage>>,
+    )) => |(code, message, details)| StatusMessage { code, message, details },
+    StatusMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, Int32Encoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+        Repeated<MessageFieldEncoder<F3, AnyMessageEncoder>, Vec<AnyMessage>>,
+    )) => |m| (m.code, m.message, m.details)
+);
+
+impl_message_codec!(
+    ErrorInfoMessage,
+    ErrorInfoMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+        MapFieldDecoder<F3, StringDecoder, StringDecoder, BTreeMap<String, String>>,
+    )) => |(reason, domain, metadata)| ErrorInfoMessage { reason, domain, metadata },
+    ErrorInfoMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+        MapFieldEncoder<F3, StringEncoder, StringEncoder, BTreeMap<String, String>>,
+    )) => |m| (m.reason, m.domain, m.metadata)
+);
+
+impl_message_codec!(
+    RetryInfoMessage,
+    RetryInfoMessageDecoder((
+        Optional<MessageFieldDecoder<F1, StdDurationDecoder>>,
+    )) => |(retry_delay,)| RetryInfoMessage { retry_delay },
+    RetryInfoMessageEncoder((
+        Optional<MessageFieldEncoder<F1, StdDurationEncoder>>,
+    )) => |m| (m.retry_delay,),
+    SizedEncode
+);
+
+impl_message_codec!(
+    DebugInfoMessage,
+    DebugInfoMessageDecoder((
+        Repeated<FieldDecoder<F1, StringDecoder>, Vec<String>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+    )) => |(stack_entries, detail)| DebugInfoMessage { stack_entries, detail },
+    DebugInfoMessageEncoder((
+        Repeated<FieldEncoder<F1, StringEncoder>, Vec<String>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+    )) => |m| (m.stack_entries, m.detail)
+);
+
+impl_message_codec!(
+    QuotaFailureMessage,
+    QuotaFailureMessageDecoder((
+        Repeated<MessageFieldDecoder<F1, QuotaViolationMessageDecoder>, Vec<QuotaViolationMessage>>,
+    )) => |(violations,)| QuotaFailureMessage { violations },
+    QuotaFailureMessageEncoder((
+        Repeated<MessageFieldEncoder<F1, QuotaViolationMessageEncoder>, Vec<QuotaViolationMessage>>,
+    )) => |m| (m.violations,)
+);
+
+impl_message_codec!(
+    QuotaViolationMessage,
+    QuotaViolationMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+    )) => |(subject, description)| QuotaViolationMessage { subject, description },
+    QuotaViolationMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+    )) => |m| (m.subject, m.description),
+    SizedEncode
+);
+
+impl_message_codec!(
+    PreconditionFailureMessage,
+    PreconditionFailureMessageDecoder((
+        Repeated<
+            MessageFieldDecoder<F1, PreconditionViolationMessageDecoder>,
+            Vec<PreconditionViolationMessage>,
+        >,
+    )) => |(violations,)| PreconditionFailureMessage { violations },
+    PreconditionFailureMessageEncoder((
+        Repeated<
+            MessageFieldEncoder<F1, PreconditionViolationMessageEncoder>,
+            Vec<PreconditionViolationMessage>,
+        >,
+    )) => |m| (m.violations,)
+);
+
+impl_message_codec!(
+    PreconditionViolationMessage,
+    PreconditionViolationMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F3, StringDecoder>>,
+    )) => |(ty, subject, description)| PreconditionViolationMessage { ty, subject, description },
+    PreconditionViolationMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F3, StringEncoder>>,
+    )) => |m| (m.ty, m.subject, m.description),
+    SizedEncode
+);
+
+impl_message_codec!(
+    BadRequestMessage,
+    BadRequestMessageDecoder((
+        Repeated<MessageFieldDecoder<F1, FieldViolationMessageDecoder>, Vec<FieldViolationMessage>>,
+    )) => |(field_violations,)| BadRequestMessage { field_violations },
+    BadRequestMessageEncoder((
+        Repeated<MessageFieldEncoder<F1, FieldViolationMessageEncoder>, Vec<FieldViolationMessage>>,
+    )) => |m| (m.field_violations,)
+);
+
+impl_message_codec!(
+    FieldViolationMessage,
+    FieldViolationMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+    )) => |(field, description)| FieldViolationMessage { field, description },
+    FieldViolationMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+    )) => |m| (m.field, m.description),
+    SizedEncode
+);
+
+impl_message_codec!(
+    RequestInfoMessage,
+    RequestInfoMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+    )) => |(request_id, serving_data)| RequestInfoMessage { request_id, serving_data },
+    RequestInfoMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+    )) => |m| (m.request_id, m.serving_data),
+    SizedEncode
+);
+
+impl_message_codec!(
+    ResourceInfoMessage,
+    ResourceInfoMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F3, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F4, StringDecoder>>,
+    )) => |(resource_type, resource_name, owner, description)| ResourceInfoMessage {
+        resource_type,
+        resource_name,
+        owner,
+        description,
+    },
+    ResourceInfoMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F3, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F4, StringEncoder>>,
+    )) => |m| (m.resource_type, m.resource_name, m.owner, m.description),
+    SizedEncode
+);
+
+impl_message_codec!(
+    HelpMessage,
+    HelpMessageDecoder((
+        Repeated<MessageFieldDecoder<F1, LinkMessageDecoder>, Vec<LinkMessage>>,
+    )) => |(links,)| HelpMessage { links },
+    HelpMessageEncoder((
+        Repeated<MessageFieldEncoder<F1, LinkMessageEncoder>, Vec<LinkMessage>>,
+    )) => |m| (m.links,)
+);
+
+impl_message_codec!(
+    LinkMessage,
+    LinkMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+    )) => |(description, url)| LinkMessage { description, url },
+    LinkMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+    )) => |m| (m.description, m.url),
+    SizedEncode
+);
+
+impl_message_codec!(
+    LocalizedMessage,
+    LocalizedMessageDecoder((
+        MaybeDefault<FieldDecoder<F1, StringDecoder>>,
+        MaybeDefault<FieldDecoder<F2, StringDecoder>>,
+    )) => |(locale, message)| LocalizedMessage { locale, message },
+    LocalizedMessageEncoder((
+        MaybeDefault<FieldEncoder<F1, StringEncoder>>,
+        MaybeDefault<FieldEncoder<F2, StringEncoder>>,
+    )) => |m| (m.locale, m.message),
+    SizedEncode
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytecodec::{DecodeExt, EncodeExt, ErrorKind};
+
+    #[test]
+    fn status_works() {
+        let mut status = StatusMessage::new(Code::InvalidArgument, "bad request");
+        let bad_request = BadRequestMessage {
+            field_violations: vec![FieldViolationMessage {
+                field: "name".to_owned(),
+                description: "empty".to_owned(),
+            }],
+        };
+        track_try_unwrap!(status.push_detail(
+            BadRequestMessage::TYPE_NAME,
+            &mut BadRequestMessageEncoder::new(),
+            bad_request.clone()
+        ));
+        let retry = RetryInfoMessage {
+            retry_delay: Some(Duration::from_millis(1500)),
+        };
+        track_try_unwrap!(status.push_detail(
+            RetryInfoMessage::TYPE_NAME,
+            &mut RetryInfoMessageEncoder::new(),
+            retry.clone()
+        ));
+
+        let bytes =
+            track_try_unwrap!(StatusMessageEncoder::new().encode_into_bytes(status.clone()));
+        let decoded = track_try_unwrap!(StatusMessageDecoder::new().decode_from_bytes(&bytes));
+        assert_eq!(decoded, status);
+        assert_eq!(Code::from_i32(decoded.code), Some(Code::InvalidArgument));
+        assert_eq!(
+            track_try_unwrap!(decoded.detail(
+                BadRequestMessage::TYPE_NAME,
+                &mut BadRequestMessageDecoder::new()
+            )),
+            Some(bad_request)
+        );
+        assert_eq!(
+            track_try_unwrap!(decoded.detail(
+                RetryInfoMessage::TYPE_NAME,
+                &mut RetryInfoMessageDecoder::new()
+            )),
+            Some(retry)
+        );
+        assert_eq!(
+            track_try_unwrap!(
+                decoded.detail(HelpMessage::TYPE_NAME, &mut HelpMessageDecoder::new())
+            ),
+            None
+        );
+        assert!(track_try_unwrap!(decoded.trackable_error()).is_none());
+    }
+
+    #[test]
+    fn status_from_trackable_error_works() {
+        let e: bytecodec::Error = ErrorKind::InvalidInput.cause("something wrong").into();
+        let e = track!(e, "I passed here");
+        let status = track_try_unwrap!(StatusMessage::from_trackable_error(
+            Code::InvalidArgument,
+            &e
+        ));
+        assert_eq!(status.code, 3);
+        assert_eq!(status.message, "InvalidInput (cause; something wrong)");
+
+        let bytes = track_try_unwrap!(StatusMessageEncoder::new().encode_into_bytes(status));
+        let status = track_try_unwrap!(StatusMessageDecoder::new().decode_from_bytes(&bytes));
+        let detail = track_try_unwrap!(status.trackable_error()).unwrap();
+        assert_eq!(detail.kind(), "InvalidInput");
+        assert_eq!(error_cause(&detail), Some("something wrong".to_owned()));
+        let events = detail.history().unwrap().events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].message(), "I passed here");
+    }
+}
@@ -1,4 +1,99 @@
 //! Encoders and decoders for the well-known protocol buffer messages.
 
+// Defines the decoder and encoder of a message type.
+//
+// The items of the inner `MessageDecoder` and `MessageEncoder` are converted from/to the message
+// by the given expressions. `SizedEncode` is implemented if it is given at the end.
+macro_rules! impl_message_codec {
+    (
+        $message:ty,
+        $decoder:ident($decode_fields:ty) => |$d:pat| $from:expr,
+        $encoder:ident($encode_fields:ty) => |$e:ident| $into:expr
+        $(, $sized:ident)?
+    ) => {
+        #[doc = "Decoder for `"]
+        #[doc = stringify!($message)]
+        #[doc = "`."]
+        #[derive(Debug, Default)]
+        pub struct $decoder {
+            inner: MessageDecoder<Fields<$decode_fields>>,
+        }
+        impl $decoder {
+            #[doc = "Makes a new `"]
+            #[doc = stringify!($decoder)]
+            #[doc = "` instance."]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl Decode for $decoder {
+            type Item = $message;
+
+            fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+                track!(self.inner.decode(buf, eos))
+            }
+
+            fn finish_decoding(&mut self) -> Result<Self::Item> {
+                let $d = track!(self.inner.finish_decoding())?;
+                Ok($from)
+            }
+
+            fn requiring_bytes(&self) -> ByteCount {
+                self.inner.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.inner.is_idle()
+            }
+        }
+        impl MessageDecode for $decoder {
+            fn resume_decoding(&mut self) -> Result<()> {
+                track!(self.inner.resume_decoding())
+            }
+        }
+
+        #[doc = "Encoder for `"]
+        #[doc = stringify!($message)]
+        #[doc = "`."]
+        #[derive(Debug, Default)]
+        pub struct $encoder {
+            inner: MessageEncoder<Fields<$encode_fields>>,
+        }
+        impl $encoder {
+            #[doc = "Makes a new `"]
+            #[doc = stringify!($encoder)]
+            #[doc = "` instance."]
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+        impl Encode for $encoder {
+            type Item = $message;
+
+            fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+                track!(self.inner.encode(buf, eos))
+            }
+
+            fn start_encoding(&mut self, $e: Self::Item) -> Result<()> {
+                track!(self.inner.start_encoding($into))
+            }
+
+            fn requiring_bytes(&self) -> ByteCount {
+                self.inner.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.inner.is_idle()
+            }
+        }
+        $(impl $sized for $encoder {
+            fn exact_requiring_bytes(&self) -> u64 {
+                self.inner.exact_requiring_bytes()
+            }
+        })?
+        impl MessageEncode for $encoder {}
+    };
+}
+
 pub mod google;
 pub mod protobuf_codec;
@@ -13,7 +13,7 @@ use crate::message::{MessageDecode, MessageDecoder, MessageEncode, MessageEncode
 use crate::scalar::{StringDecoder, StringEncoder, Uint32Decoder, Uint32Encoder};
 use bytecodec::{ByteCount, Decode, Encode, Eos, Result, SizedEncode};
 use std::error::Error;
-use trackable::error::{ErrorKindExt, TrackableError};
+use trackable::error::{ErrorKind, ErrorKindExt, TrackableError};
 use trackable::{Location, Trackable};
 
 /// Decoder for [TrackableError].
@@ -68,7 +68,11 @@ impl Decode for ErrorDecoder {
         self.inner.is_idle()
     }
 }
-impl MessageDecode for ErrorDecoder {}
+impl MessageDecode for ErrorDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
 
 /// Encoder for [TrackableError].
 ///
@@ -102,9 +106,7 @@ impl Encode for ErrorEncoder {
     fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
         let item = (
             item.kind().clone(),
-            item.source()
-                .map(ToString::to_string)
-                .unwrap_or_else(String::new),
+            error_cause(&item).unwrap_or_else(String::new),
             item.history()
                 .map(|h| h.events().to_owned())
                 .unwrap_or_else(Vec::new),
@@ -122,6 +124,13 @@ impl Encode for ErrorEncoder {
 }
 impl MessageEncode for ErrorEncoder {}
 
+// `TrackableError` does not implement `Error::source` (it always returns `None`),
+// so its cause is available only through the deprecated `Error::cause` method.
+#[allow(deprecated)]
+pub(crate) fn error_cause<K: ErrorKind>(error: &TrackableError<K>) -> Option<String> {
+    Error::cause(error).map(ToString::to_string)
+}
+
 /// Decoder for [Location].
 ///
 /// See also [trackable.proto].
@@ -165,7 +174,11 @@ impl Decode for LocationDecoder {
         self.inner.is_idle()
     }
 }
-impl MessageDecode for LocationDecoder {}
+impl MessageDecode for LocationDecoder {
+    fn resume_decoding(&mut self) -> Result<()> {
+        track!(self.inner.resume_decoding())
+    }
+}
 
 /// Encoder for [Location].
 ///
@@ -221,3 +234,23 @@ impl SizedEncode for LocationEncoder {
     }
 }
 impl MessageEncode for LocationEncoder {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytecodec::{DecodeExt, EncodeExt};
+
+    #[test]
+    fn error_encoder_keeps_cause() {
+        let e: TrackableError<String> = "Failed".to_owned().cause("something wrong");
+        let e = track!(e, "I passed here");
+        assert!(e.source().is_none());
+        assert_eq!(error_cause(&e), Some("something wrong".to_owned()));
+
+        let bytes = track_try_unwrap!(ErrorEncoder::new().encode_into_bytes(e));
+        let e = track_try_unwrap!(ErrorDecoder::new().decode_from_bytes(&bytes));
+        assert_eq!(e.kind(), "Failed");
+        assert_eq!(error_cause(&e), Some("something wrong".to_owned()));
+        assert_eq!(e.history().map(|h| h.events().len()), Some(1));
+    }
+}
@@ -4,9 +4,12 @@
 //!
 //! [binary wire format]: https://developers.google.com/protocol-buffers/docs/encoding
 use crate::field::num::FieldNum;
+use crate::limit;
 use bytecodec::bytes::BytesEncoder;
-use bytecodec::combinator::{Length, Peekable};
-use bytecodec::{ByteCount, Decode, DecodeExt, Encode, Eos, ErrorKind, Result, SizedEncode};
+use bytecodec::combinator::Peekable;
+use bytecodec::{ByteCount, Decode, Encode, Eos, Error, ErrorKind, Result, SizedEncode};
+use std::{cmp, mem};
+use trackable::error::ErrorKindExt;
 
 /// Field tag.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -28,18 +31,22 @@ impl From<(FieldNum, WireType)> for Tag {
 
 /// Wire type.
 ///
-/// `protobuf_codec` does not support deprecated types (i.e., "Start group" and "End group").
+/// `StartGroup` and `EndGroup` are deprecated types which are used for the fields of proto2 groups.
+/// This enum is non-exhaustive, so matching on it outside of this crate requires a wildcard arm.
 ///
 /// See [Message Structure] for information about each types.
 ///
 /// [Message Structure]: https://developers.google.com/protocol-buffers/docs/encoding#structure
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[allow(missing_docs)]
+#[non_exhaustive]
 pub enum WireType {
     Varint = 0,
     Bit32 = 5,
     Bit64 = 1,
     LengthDelimited = 2,
+    StartGroup = 3,
+    EndGroup = 4,
 }
 
 /// Decoder for tags.
@@ -60,23 +67,7 @@ impl Decode for TagDecoder {
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
         let n = track!(self.0.finish_decoding())?;
-        let field_num = n >> 3;
-        track_assert!(field_num <= 0xFFFF_FFFF, ErrorKind::InvalidInput; field_num);
-
-        let wire_type = match n & 0b111 {
-            0 => WireType::Varint,
-            5 => WireType::Bit32,
-            1 => WireType::Bit64,
-            2 => WireType::LengthDelimited,
-            wire_type => {
-                track_panic!(ErrorKind::InvalidInput, "Unknown wire type"; wire_type, field_num);
-            }
-        };
-        let field_num = track!(FieldNum::new(field_num as u32))?;
-        Ok(Tag {
-            field_num,
-            wire_type,
-        })
+        track!(decode_tag(n))
     }
 
     fn requiring_bytes(&self) -> ByteCount {
@@ -88,6 +79,28 @@ impl Decode for TagDecoder {
     }
 }
 
+fn decode_tag(n: u64) -> Result<Tag> {
+    let field_num = n >> 3;
+    track_assert!(field_num <= 0xFFFF_FFFF, ErrorKind::InvalidInput; field_num);
+
+    let wire_type = match n & 0b111 {
+        0 => WireType::Varint,
+        5 => WireType::Bit32,
+        1 => WireType::Bit64,
+        2 => WireType::LengthDelimited,
+        3 => WireType::StartGroup,
+        4 => WireType::EndGroup,
+        wire_type => {
+            track_panic!(ErrorKind::InvalidInput, "Unknown wire type"; wire_type, field_num);
+        }
+    };
+    let field_num = track!(FieldNum::new(field_num as u32))?;
+    Ok(Tag {
+        field_num,
+        wire_type,
+    })
+}
+
 /// Encoder for tags.
 #[derive(Debug, Default)]
 pub struct TagEncoder(VarintEncoder);
@@ -248,30 +261,41 @@ impl AsRef<[u8]> for VarintBuf {
 #[derive(Debug, Default)]
 pub struct LengthDelimitedDecoder<D> {
     len: Peekable<VarintDecoder>,
-    inner: Length<D>,
+    remaining_bytes: u64,
+    inner: D,
 }
 impl<D: Decode> LengthDelimitedDecoder<D> {
     /// Makes a new `LengthDelimitedDecoder` instance.
     pub fn new(inner: D) -> Self {
         LengthDelimitedDecoder {
             len: Default::default(),
-            inner: inner.length(0),
+            remaining_bytes: 0,
+            inner,
         }
     }
 
     /// Returns a reference to the inner decoder.
     pub fn inner_ref(&self) -> &D {
-        self.inner.inner_ref()
+        &self.inner
     }
 
     /// Returns a mutable reference to the inner decoder.
     pub fn inner_mut(&mut self) -> &mut D {
-        self.inner.inner_mut()
+        &mut self.inner
     }
 
     /// Takes ownership of the instance and returns the inner decoder.
     pub fn into_inner(self) -> D {
-        self.inner.into_inner()
+        self.inner
+    }
+
+    /// Prepares for decoding the next length-delimited value without finishing the inner decoder.
+    ///
+    /// The payload of the next value will be fed to the inner decoder as a continuation of the previous one.
+    pub(crate) fn restart_length(&mut self) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::IncompleteDecoding);
+        let _ = track!(self.len.finish_decoding())?;
+        Ok(())
     }
 }
 impl<D: Decode> Decode for LengthDelimitedDecoder<D> {
@@ -281,15 +305,28 @@ impl<D: Decode> Decode for LengthDelimitedDecoder<D> {
         let mut offset = 0;
         if !self.len.is_idle() {
             bytecodec_try_decode!(self.len, offset, buf, eos);
-            let len = self.len.peek().expect("Never fails");
-            track!(self.inner.set_expected_bytes(*len))?;
+            self.remaining_bytes = *self.len.peek().expect("Never fails");
+            track!(limit::check_length(self.remaining_bytes))?;
+        }
+        if !self.inner.is_idle() {
+            let buf = &buf[offset..];
+            let limit = cmp::min(buf.len() as u64, self.remaining_bytes) as usize;
+            let required = self.remaining_bytes - limit as u64;
+            if let Some(remaining) = eos.remaining_bytes().to_u64() {
+                track_assert!(remaining >= required, ErrorKind::UnexpectedEos; remaining, required);
+            }
+
+            let inner_eos = Eos::with_remaining_bytes(ByteCount::Finite(required));
+            let size = track!(self.inner.decode(&buf[..limit], inner_eos))?;
+            self.remaining_bytes -= size as u64;
+            offset += size;
         }
-        bytecodec_try_decode!(self.inner, offset, buf, eos);
         Ok(offset)
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
         let _ = track!(self.len.finish_decoding())?;
+        track_assert_eq!(self.remaining_bytes, 0, ErrorKind::IncompleteDecoding);
         let item = track!(self.inner.finish_decoding())?;
         Ok(item)
     }
@@ -297,7 +334,7 @@ impl<D: Decode> Decode for LengthDelimitedDecoder<D> {
     fn requiring_bytes(&self) -> ByteCount {
         self.len
             .requiring_bytes()
-            .add_for_decoding(self.inner.requiring_bytes())
+            .add_for_decoding(ByteCount::Finite(self.remaining_bytes))
     }
 
     fn is_idle(&self) -> bool {
@@ -306,10 +343,16 @@ impl<D: Decode> Decode for LengthDelimitedDecoder<D> {
 }
 
 /// Encoder for `Length-delimited` values.
+///
+/// The size of the inner value is computed only once when starting encoding,
+/// and the progress of the encoding is tracked by this encoder.
+/// So the cost of querying the state of nested values does not grow with their depth.
 #[derive(Debug, Default)]
 pub struct LengthDelimitedEncoder<E> {
     len: VarintEncoder,
     inner: E,
+    remaining_bytes: u64,
+    is_encoding: bool,
 }
 impl<E: SizedEncode> LengthDelimitedEncoder<E> {
     /// Makes a new `LengthDelimitedEncoder` instance.
@@ -317,6 +360,8 @@ impl<E: SizedEncode> LengthDelimitedEncoder<E> {
         LengthDelimitedEncoder {
             len: Default::default(),
             inner,
+            remaining_bytes: 0,
+            is_encoding: false,
         }
     }
 
@@ -339,20 +384,30 @@ impl<E: SizedEncode> Encode for LengthDelimitedEncoder<E> {
     type Item = E::Item;
 
     fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
-        let mut offset = track!(self.len.encode(buf, eos))?;
-        offset += track!(self.inner.encode(&mut buf[offset..], eos))?;
-        Ok(offset)
+        if !self.is_encoding {
+            return Ok(0);
+        }
+
+        let offset = track!(self.len.encode(buf, eos))?;
+        let size = track!(self.inner.encode(&mut buf[offset..], eos))?;
+        self.remaining_bytes -= size as u64;
+        if self.remaining_bytes == 0 && self.len.is_idle() && self.inner.is_idle() {
+            self.is_encoding = false;
+        }
+        Ok(offset + size)
     }
 
     fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
         track_assert!(self.is_idle(), ErrorKind::EncoderFull);
         track!(self.inner.start_encoding(item))?;
-        track!(self.len.start_encoding(self.inner.exact_requiring_bytes()))?;
+        self.remaining_bytes = self.inner.exact_requiring_bytes();
+        track!(self.len.start_encoding(self.remaining_bytes))?;
+        self.is_encoding = true;
         Ok(())
     }
 
     fn is_idle(&self) -> bool {
-        self.len.is_idle() && self.inner.is_idle()
+        !self.is_encoding
     }
 
     fn requiring_bytes(&self) -> ByteCount {
@@ -361,9 +416,396 @@ impl<E: SizedEncode> Encode for LengthDelimitedEncoder<E> {
 }
 impl<E: SizedEncode> SizedEncode for LengthDelimitedEncoder<E> {
     fn exact_requiring_bytes(&self) -> u64 {
-        self.len.exact_requiring_bytes() + self.inner.exact_requiring_bytes()
+        self.len.exact_requiring_bytes() + self.remaining_bytes
+    }
+}
+
+/// Decoder that reads the raw bytes of a value.
+///
+/// The bytes of a group value consist of the nested fields and the `EndGroup` tag.
+#[derive(Debug, Default)]
+pub(crate) struct RawValueDecoder {
+    bytes: Vec<u8>,
+    discard: bool,
+    state: RawValueState,
+
+    // Field numbers of the groups being read
+    groups: Vec<FieldNum>,
+
+    // Position of the last tag in `bytes`
+    tag_offset: usize,
+}
+impl RawValueDecoder {
+    /// Makes a new `RawValueDecoder` instance.
+    ///
+    /// If `discard` is `true`, the read bytes are discarded.
+    pub(crate) fn new(discard: bool) -> Self {
+        RawValueDecoder {
+            discard,
+            ..Self::default()
+        }
+    }
+
+    /// Starts reading the value of a field which has the given tag.
+    pub(crate) fn start(&mut self, tag: Tag) -> Result<()> {
+        self.bytes.clear();
+        self.groups.clear();
+        self.state = match tag.wire_type {
+            WireType::EndGroup => {
+                track_panic!(ErrorKind::InvalidInput, "Unexpected end group tag"; tag);
+            }
+            WireType::StartGroup => {
+                self.groups.push(tag.field_num);
+                self.tag_state()
+            }
+            wire_type => RawValueState::value(wire_type),
+        };
+        Ok(())
+    }
+
+    /// Returns the position of the last tag in the read bytes.
+    ///
+    /// After a group value has been read, this is the position of the `EndGroup` tag of the group.
+    pub(crate) fn last_tag_offset(&self) -> usize {
+        self.tag_offset
+    }
+
+    fn tag_state(&mut self) -> RawValueState {
+        self.tag_offset = self.bytes.len();
+        RawValueState::Varint(VarintPart::Tag, 0, 0)
+    }
+
+    fn finish_value(&mut self) -> RawValueState {
+        if self.groups.is_empty() {
+            RawValueState::Idle
+        } else {
+            self.tag_state()
+        }
+    }
+
+    fn finish_varint(&mut self, part: VarintPart, n: u64) -> Result<RawValueState> {
+        Ok(match part {
+            VarintPart::Value => self.finish_value(),
+            VarintPart::Length if n == 0 => self.finish_value(),
+            VarintPart::Length => {
+                track!(limit::check_length(n))?;
+                RawValueState::Bytes(n)
+            }
+            VarintPart::Tag => {
+                let tag = track!(decode_tag(n))?;
+                match tag.wire_type {
+                    WireType::StartGroup => {
+                        self.groups.push(tag.field_num);
+                        self.tag_state()
+                    }
+                    WireType::EndGroup => {
+                        let start = self.groups.pop();
+                        track_assert_eq!(
+                            start,
+                            Some(tag.field_num),
+                            ErrorKind::InvalidInput,
+                            "Unmatched end group tag"
+                        );
+                        self.finish_value()
+                    }
+                    wire_type => RawValueState::value(wire_type),
+                }
+            }
+        })
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        if !self.discard {
+            self.bytes.extend_from_slice(bytes);
+        }
+    }
+}
+impl Decode for RawValueDecoder {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while offset < buf.len() {
+            match self.state {
+                RawValueState::Idle => break,
+                RawValueState::Varint(part, n, i) => {
+                    let b = buf[offset];
+                    offset += 1;
+                    self.push(&[b]);
+                    track_assert_ne!(i, 10, ErrorKind::InvalidInput);
+
+                    let n = n | (u64::from(b & 0b0111_1111) << (7 * i));
+                    self.state = if (b & 0b1000_0000) == 0 {
+                        track!(self.finish_varint(part, n))?
+                    } else {
+                        RawValueState::Varint(part, n, i + 1)
+                    };
+                }
+                RawValueState::Bytes(remaining) => {
+                    let size = cmp::min(remaining, (buf.len() - offset) as u64) as usize;
+                    self.push(&buf[offset..][..size]);
+                    offset += size;
+                    self.state = if size as u64 == remaining {
+                        self.finish_value()
+                    } else {
+                        RawValueState::Bytes(remaining - size as u64)
+                    };
+                }
+            }
+        }
+        if self.state != RawValueState::Idle {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos);
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert_eq!(
+            self.state,
+            RawValueState::Idle,
+            ErrorKind::IncompleteDecoding
+        );
+        Ok(mem::take(&mut self.bytes))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        match self.state {
+            RawValueState::Idle => ByteCount::Finite(0),
+            RawValueState::Bytes(n) if self.groups.is_empty() => ByteCount::Finite(n),
+            _ => ByteCount::Unknown,
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.state == RawValueState::Idle
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum RawValueState {
+    #[default]
+    Idle,
+
+    // A varint which is being read (the part, the value and the number of the read bytes)
+    Varint(VarintPart, u64, usize),
+
+    // The number of the remaining bytes of a fixed length value or a length-delimited value
+    Bytes(u64),
+}
+impl RawValueState {
+    fn value(wire_type: WireType) -> Self {
+        match wire_type {
+            WireType::Varint => RawValueState::Varint(VarintPart::Value, 0, 0),
+            WireType::Bit32 => RawValueState::Bytes(4),
+            WireType::Bit64 => RawValueState::Bytes(8),
+            WireType::LengthDelimited => RawValueState::Varint(VarintPart::Length, 0, 0),
+            WireType::StartGroup | WireType::EndGroup => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VarintPart {
+    Value,
+    Length,
+    Tag,
+}
+
+/// Iterator over the fields of a fully-buffered message.
+///
+/// The values of the fields are borrowed from the input instead of being copied.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate protobuf_codec;
+/// use protobuf_codec::wire::BorrowedFields;
+///
+/// # fn main() {
+/// let input = [8, 150, 1, 18, 3, 102, 111, 111];
+/// let mut fields = BorrowedFields::new(&input);
+///
+/// let field = fields.next().unwrap().unwrap();
+/// assert_eq!(field.tag.field_num.as_u32(), 1);
+/// assert_eq!(field.as_varint().unwrap(), 150);
+///
+/// let field = fields.next().unwrap().unwrap();
+/// assert_eq!(field.tag.field_num.as_u32(), 2);
+/// assert_eq!(field.as_str().unwrap(), "foo");
+/// assert_eq!(field.as_str().unwrap().as_ptr(), input[5..].as_ptr());
+///
+/// assert!(fields.next().is_none());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BorrowedFields<'a> {
+    buf: &'a [u8],
+}
+impl<'a> BorrowedFields<'a> {
+    /// Makes a new `BorrowedFields` instance that reads the fields of the message encoded in `buf`.
+    pub fn new(buf: &'a [u8]) -> Self {
+        BorrowedFields { buf }
+    }
+
+    /// Returns the bytes that have not been read yet.
+    pub fn remaining_bytes(&self) -> &'a [u8] {
+        self.buf
     }
 }
+impl<'a> Iterator for BorrowedFields<'a> {
+    type Item = Result<BorrowedField<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        match read_field(&mut self.buf) {
+            Ok((tag, value)) => Some(Ok(BorrowedField { tag, value })),
+            Err(e) => {
+                self.buf = &[];
+                Some(Err(track!(e)))
+            }
+        }
+    }
+}
+
+/// Field borrowed from a fully-buffered message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorrowedField<'a> {
+    /// Tag of the field.
+    pub tag: Tag,
+
+    /// Encoded value of the field.
+    ///
+    /// This is the varint bytes, the fixed length bytes, the payload of a length-delimited value
+    /// or the body of a group (excluding the end tag).
+    pub value: &'a [u8],
+}
+impl<'a> BorrowedField<'a> {
+    /// Returns the value of a `Varint` field.
+    pub fn as_varint(&self) -> Result<u64> {
+        track!(self.check_wire_type(WireType::Varint))?;
+        track!(read_varint(&mut { self.value }))
+    }
+
+    /// Returns the value of a `Bit32` field.
+    pub fn as_bit32(&self) -> Result<u32> {
+        track!(self.check_wire_type(WireType::Bit32))?;
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.value);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Returns the value of a `Bit64` field.
+    pub fn as_bit64(&self) -> Result<u64> {
+        track!(self.check_wire_type(WireType::Bit64))?;
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.value);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Returns the payload of a `LengthDelimited` field.
+    pub fn as_bytes(&self) -> Result<&'a [u8]> {
+        track!(self.check_wire_type(WireType::LengthDelimited))?;
+        Ok(self.value)
+    }
+
+    /// Returns the payload of a `LengthDelimited` field as a string.
+    pub fn as_str(&self) -> Result<&'a str> {
+        let bytes = track!(self.as_bytes())?;
+        std::str::from_utf8(bytes)
+            .map_err(|e| track!(Error::from(ErrorKind::InvalidInput.cause(e))))
+    }
+
+    /// Returns the fields of the embedded message or the group.
+    pub fn as_message(&self) -> Result<BorrowedFields<'a>> {
+        if self.tag.wire_type != WireType::StartGroup {
+            track!(self.check_wire_type(WireType::LengthDelimited))?;
+        }
+        Ok(BorrowedFields::new(self.value))
+    }
+
+    fn check_wire_type(&self, expected: WireType) -> Result<()> {
+        track_assert_eq!(self.tag.wire_type, expected, ErrorKind::InvalidInput; self.tag);
+        Ok(())
+    }
+}
+
+pub(crate) fn write_tag(buf: &mut Vec<u8>, number: u32, wire_type: WireType) {
+    write_varint(buf, (u64::from(number) << 3) | wire_type as u64);
+}
+
+pub(crate) fn write_varint(buf: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        buf.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+/// Reads a field from the head of `buf` and advances `buf` to the next field.
+///
+/// The returned slice is the encoded value of the field:
+/// the varint bytes, the fixed length bytes, the payload of a length-delimited value
+/// or the body of a group (excluding the end tag).
+pub(crate) fn read_field<'a>(buf: &mut &'a [u8]) -> Result<(Tag, &'a [u8])> {
+    let tag = track!(read_tag(buf))?;
+    track_assert_ne!(tag.wire_type, WireType::EndGroup, ErrorKind::InvalidInput; tag);
+    let value = track!(read_value(tag, buf))?;
+    Ok((tag, value))
+}
+
+fn read_tag(buf: &mut &[u8]) -> Result<Tag> {
+    let n = track!(read_varint(buf))?;
+    track!(decode_tag(n))
+}
+
+pub(crate) fn read_varint(buf: &mut &[u8]) -> Result<u64> {
+    let mut decoder = VarintDecoder::new();
+    let size = track!(decoder.decode(buf, Eos::new(true)))?;
+    *buf = &buf[size..];
+    track!(decoder.finish_decoding())
+}
+
+fn read_value<'a>(tag: Tag, buf: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let start = *buf;
+    let size = match tag.wire_type {
+        WireType::Varint => {
+            track!(read_varint(buf))?;
+            return Ok(&start[..start.len() - buf.len()]);
+        }
+        WireType::Bit32 => 4,
+        WireType::Bit64 => 8,
+        WireType::LengthDelimited => track!(read_varint(buf))?,
+        WireType::StartGroup => {
+            // Nested groups are skipped iteratively to avoid deep recursion
+            let mut groups = vec![tag.field_num];
+            while let Some(&num) = groups.last() {
+                let before = *buf;
+                let t = track!(read_tag(buf))?;
+                match t.wire_type {
+                    WireType::StartGroup => groups.push(t.field_num),
+                    WireType::EndGroup => {
+                        track_assert_eq!(t.field_num, num, ErrorKind::InvalidInput; t);
+                        groups.pop();
+                        if groups.is_empty() {
+                            return Ok(&start[..start.len() - before.len()]);
+                        }
+                    }
+                    _ => {
+                        track!(read_value(t, buf))?;
+                    }
+                }
+            }
+            unreachable!()
+        }
+        WireType::EndGroup => track_panic!(ErrorKind::InvalidInput; tag),
+    };
+    track_assert!(size <= buf.len() as u64, ErrorKind::UnexpectedEos; tag, size);
+    let (value, rest) = buf.split_at(size as usize);
+    *buf = rest;
+    Ok(value)
+}
 
 #[cfg(test)]
 mod tests {
@@ -396,4 +838,37 @@ mod tests {
         assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut buf)), 300);
         assert_eq!(buf, []);
     }
+
+    #[test]
+    fn borrowed_fields_work() {
+        // 1: 150, 2: {1: "a"}, 3: 0x01020304 (fixed32), 4 (group) {5: 1}, 6: 0 (fixed64)
+        let input = [
+            8, 150, 1, 18, 3, 10, 1, 97, 29, 4, 3, 2, 1, 35, 40, 1, 36, 49, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        let fields = track_try_unwrap!(BorrowedFields::new(&input).collect::<Result<Vec<_>>>());
+        assert_eq!(fields.len(), 5);
+        assert_eq!(track_try_unwrap!(fields[0].as_varint()), 150);
+        assert!(fields[0].as_bytes().is_err());
+
+        let mut nested = track_try_unwrap!(fields[1].as_message());
+        let field = track_try_unwrap!(nested.next().unwrap());
+        assert_eq!(track_try_unwrap!(field.as_str()), "a");
+        assert_eq!(field.value.as_ptr(), input[7..].as_ptr());
+        assert!(nested.next().is_none());
+
+        assert_eq!(track_try_unwrap!(fields[2].as_bit32()), 0x0102_0304);
+
+        let mut group = track_try_unwrap!(fields[3].as_message());
+        let field = track_try_unwrap!(group.next().unwrap());
+        assert_eq!(track_try_unwrap!(field.as_varint()), 1);
+        assert!(group.next().is_none());
+
+        assert_eq!(track_try_unwrap!(fields[4].as_bit64()), 0);
+
+        // Truncated
+        let mut fields = BorrowedFields::new(&input[..6]);
+        assert!(fields.next().unwrap().is_ok());
+        assert!(fields.next().unwrap().is_err());
+        assert!(fields.next().is_none());
+    }
 }